@@ -0,0 +1,74 @@
+//! Performance measurement for contradiction detection as the grid grows
+
+// Criterion macros generate undocumented functions
+#![allow(missing_docs)]
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use greedytile::algorithm::cache::ViableTilesCache;
+use greedytile::algorithm::executor::GreedyStochastic;
+use greedytile::algorithm::propagation::check_for_contradiction;
+use greedytile::io::configuration::CONTRADICTION_CHECK_RADIUS;
+use std::hint::black_box;
+
+/// Compares the per-step incremental check against rechecking every cell
+///
+/// The incremental cost should stay flat across grid sizes while the full
+/// rescan grows with the grid area
+fn bench_contradiction_check(c: &mut Criterion) {
+    let mut group = c.benchmark_group("contradiction_check");
+
+    for size in &[64, 128, 256, 512] {
+        let Ok(mut executor) = GreedyStochastic::new(12345) else {
+            group.finish();
+            return;
+        };
+
+        for _ in 0..100 {
+            if executor.run_iteration().is_err() {
+                group.finish();
+                return;
+            }
+        }
+
+        let (offset, _) =
+            executor
+                .grid_state
+                .extend_if_needed(executor.system_offset, &[0, 0], size / 2);
+        executor.system_offset = offset;
+
+        group.bench_with_input(BenchmarkId::new("incremental", size), size, |b, _| {
+            let mut cache = ViableTilesCache::new();
+            b.iter(|| {
+                executor
+                    .grid_state
+                    .mark_dirty(black_box([0, 0]), CONTRADICTION_CHECK_RADIUS);
+                let result = check_for_contradiction(
+                    &mut executor.grid_state,
+                    executor.system_offset,
+                    &executor.step_data,
+                    &mut cache,
+                );
+                black_box(result);
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("full_rescan", size), size, |b, &size| {
+            let mut cache = ViableTilesCache::new();
+            b.iter(|| {
+                executor.grid_state.mark_dirty(black_box([0, 0]), size / 2);
+                let result = check_for_contradiction(
+                    &mut executor.grid_state,
+                    executor.system_offset,
+                    &executor.step_data,
+                    &mut cache,
+                );
+                black_box(result);
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_contradiction_check);
+criterion_main!(benches);
@@ -0,0 +1,74 @@
+//! Performance measurement for weighted position selection as the grid grows
+
+// Criterion macros generate undocumented functions
+#![allow(missing_docs)]
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use greedytile::algorithm::executor::GreedyStochastic;
+use greedytile::algorithm::positioning::{PositionContext, PositionSelector, WeightedSelector};
+use greedytile::algorithm::selection::ADJACENCY_CANDIDATES_CONSIDERED;
+use greedytile::analysis::weights::{calculate_position_selection, top_k_valid_indices};
+use std::hint::black_box;
+
+/// Compares frontier-indexed selection against weighting the whole grid
+///
+/// Indexed selection should stay flat across grid sizes while the full scan
+/// grows with the grid area
+fn bench_position_selection(c: &mut Criterion) {
+    let mut group = c.benchmark_group("position_selection");
+
+    for size in &[64, 128, 256, 512] {
+        let Ok(mut executor) = GreedyStochastic::new(12345) else {
+            group.finish();
+            return;
+        };
+
+        for _ in 0..100 {
+            if executor.run_iteration().is_err() {
+                group.finish();
+                return;
+            }
+        }
+
+        let (offset, _) =
+            executor
+                .grid_state
+                .extend_if_needed(executor.system_offset, &[0, 0], size / 2);
+        executor.system_offset = offset;
+
+        let context = PositionContext {
+            grid_state: &executor.grid_state,
+            selection_tally: &executor.selection_tally,
+            step_data: &executor.step_data,
+            system_offset: executor.system_offset,
+        };
+
+        group.bench_with_input(BenchmarkId::new("frontier_index", size), size, |b, _| {
+            let mut selector = WeightedSelector::default();
+            b.iter(|| {
+                black_box(selector.select(&context, &mut executor.random_selector));
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("full_scan", size), size, |b, _| {
+            b.iter(|| {
+                let weights = calculate_position_selection(
+                    context.grid_state,
+                    context.selection_tally,
+                    context.step_data,
+                    context.system_offset,
+                );
+                black_box(top_k_valid_indices(
+                    &weights.adjacency_matrix,
+                    &weights.validity_matrix,
+                    ADJACENCY_CANDIDATES_CONSIDERED,
+                ));
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_position_selection);
+criterion_main!(benches);
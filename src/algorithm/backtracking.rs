@@ -0,0 +1,241 @@
+//! Chronological backtracking over random placement decisions
+//!
+//! Every placement is appended to a trail, and each random choice opens a
+//! frame on the decision stack that owns the placements following it. On a
+//! contradiction the stack is unwound to the most recent decision whose
+//! placements lie near the conflict, those placements are undone, and the
+//! choice is banned at its position. A ban lasts as long as the decisions
+//! made before it, so it is dropped when the search backtracks further.
+//! Tiles removed by other means only close the decisions and lift the bans
+//! around them, so the rest of the stack can still be backtracked.
+
+use std::collections::HashSet;
+
+use crate::io::{configuration::CONTRADICTION_CHECK_RADIUS, error::AlgorithmError};
+
+/// Backtracks allowed when a budget is not given
+pub const DEFAULT_BACKTRACK_BUDGET: usize = 10_000;
+
+/// Built-in ways to resolve a contradiction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DeadlockStrategy {
+    /// Unlock a square around the contradiction that grows on repeats
+    #[default]
+    Removal,
+    /// Undo the most recent decision affecting the contradiction and ban it,
+    /// falling back to removal, recorded as a fallback event, once the budget
+    /// of backtracks is spent or no decision affects the contradiction
+    Backtrack(usize),
+}
+
+impl DeadlockStrategy {
+    /// Decision stack for this strategy, if it records decisions
+    pub const fn decision_stack(self) -> Option<DecisionStack> {
+        match self {
+            Self::Removal => None,
+            Self::Backtrack(budget) => Some(DecisionStack::new(budget)),
+        }
+    }
+}
+
+impl std::fmt::Display for DeadlockStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Removal => write!(f, "removal"),
+            Self::Backtrack(budget) => write!(f, "backtrack:{budget}"),
+        }
+    }
+}
+
+impl std::str::FromStr for DeadlockStrategy {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_ascii_lowercase();
+        match lower.as_str() {
+            "removal" => Ok(Self::Removal),
+            "backtrack" => Ok(Self::Backtrack(DEFAULT_BACKTRACK_BUDGET)),
+            other => match other.strip_prefix("backtrack:").map(str::parse::<usize>) {
+                Some(Ok(budget)) => Ok(Self::Backtrack(budget)),
+                _ => Err(crate::io::error::invalid_parameter(
+                    "deadlock strategy",
+                    &s,
+                    &"expected 'removal', 'backtrack' or 'backtrack:<budget>'",
+                )),
+            },
+        }
+    }
+}
+
+/// A random choice and the placements that followed it
+#[derive(Debug, Clone)]
+struct DecisionFrame {
+    /// World position of the choice
+    position: [i32; 2],
+    /// Tile reference chosen
+    tile_reference: usize,
+    /// Index of the choice's own placement in the trail
+    trail_index: usize,
+    /// Choices ruled out beneath this frame
+    bans: Vec<([i32; 2], usize)>,
+}
+
+/// Placements undone by a backtrack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backtrack {
+    /// World positions to unlock, most recent first
+    pub undone: Vec<[i32; 2]>,
+    /// Position and tile reference of the banned choice
+    pub banned: ([i32; 2], usize),
+}
+
+/// Stack of random decisions with the trail of placements they own
+#[derive(Debug, Clone)]
+pub struct DecisionStack {
+    /// Open decisions, oldest first
+    frames: Vec<DecisionFrame>,
+    /// Every recorded placement in order
+    trail: Vec<[i32; 2]>,
+    /// Choices ruled out before any decision
+    root_bans: Vec<([i32; 2], usize)>,
+    /// Backtracks allowed in total
+    budget: usize,
+    /// Backtracks performed so far
+    backtracks: usize,
+}
+
+impl DecisionStack {
+    /// Create an empty stack allowing `budget` backtracks
+    pub const fn new(budget: usize) -> Self {
+        Self {
+            frames: Vec::new(),
+            trail: Vec::new(),
+            root_bans: Vec::new(),
+            budget,
+            backtracks: 0,
+        }
+    }
+
+    /// Record a placement at a world position
+    pub fn record_placement(&mut self, position: [i32; 2]) {
+        self.trail.push(position);
+    }
+
+    /// Open a decision whose placement is recorded next
+    pub fn push_decision(&mut self, position: [i32; 2], tile_reference: usize) {
+        self.frames.push(DecisionFrame {
+            position,
+            tile_reference,
+            trail_index: self.trail.len(),
+            bans: Vec::new(),
+        });
+    }
+
+    /// Whether a tile is ruled out at a position under the open decisions
+    pub fn is_banned(&self, position: [i32; 2], tile_reference: usize) -> bool {
+        self.frames
+            .iter()
+            .flat_map(|frame| &frame.bans)
+            .chain(&self.root_bans)
+            .any(|&ban| ban == (position, tile_reference))
+    }
+
+    /// Number of open decisions
+    pub const fn depth(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Backtracks performed so far
+    pub const fn backtracks(&self) -> usize {
+        self.backtracks
+    }
+
+    /// Whether the backtrack budget is spent
+    pub const fn is_exhausted(&self) -> bool {
+        self.backtracks >= self.budget
+    }
+
+    /// Unwind to the most recent decision with a placement near `conflict`
+    ///
+    /// Returns `None` when the budget is spent or no open decision affects
+    /// the conflict, leaving the stack unchanged.
+    pub fn backtrack(&mut self, conflict: [i32; 2]) -> Option<Backtrack> {
+        if self.is_exhausted() {
+            return None;
+        }
+
+        let mut segment_end = self.trail.len();
+        let mut target = None;
+        for (index, frame) in self.frames.iter().enumerate().rev() {
+            let segment = self.trail.get(frame.trail_index..segment_end)?;
+            if segment
+                .iter()
+                .any(|&position| near(position, conflict, CONTRADICTION_CHECK_RADIUS))
+            {
+                target = Some(index);
+                break;
+            }
+            segment_end = frame.trail_index;
+        }
+
+        let target = target?;
+        let frame = self.frames.get(target)?.clone();
+        self.frames.truncate(target);
+        let mut undone = self.trail.split_off(frame.trail_index);
+        undone.reverse();
+
+        let banned = (frame.position, frame.tile_reference);
+        match self.frames.last_mut() {
+            Some(parent) => parent.bans.push(banned),
+            None => self.root_bans.push(banned),
+        }
+        self.backtracks += 1;
+
+        Some(Backtrack { undone, banned })
+    }
+
+    /// Forget the placements, decisions and bans touching a removed region
+    ///
+    /// Used once tiles are removed by other means, since the trail no longer
+    /// matches the grid there. Removed placements leave the trail, decisions
+    /// whose own tile was removed are closed along with their bans, and bans
+    /// near the region are lifted since their neighbourhood has changed.
+    /// Decisions elsewhere stay open, and the backtrack count is kept.
+    pub fn forget(&mut self, region: &[[i32; 2]]) {
+        let region: HashSet<[i32; 2]> = region.iter().copied().collect();
+        if region.is_empty() {
+            return;
+        }
+
+        // Trail index of each entry once removed placements are dropped
+        let mut kept = 0;
+        let mut reindexed = Vec::with_capacity(self.trail.len());
+        for position in &self.trail {
+            reindexed.push(kept);
+            if !region.contains(position) {
+                kept += 1;
+            }
+        }
+        self.trail.retain(|position| !region.contains(position));
+
+        let lifted = |&(position, _): &([i32; 2], usize)| {
+            region
+                .iter()
+                .any(|&removed| near(position, removed, CONTRADICTION_CHECK_RADIUS))
+        };
+        self.frames.retain_mut(|frame| {
+            if region.contains(&frame.position) {
+                return false;
+            }
+            frame.trail_index = reindexed.get(frame.trail_index).copied().unwrap_or(kept);
+            frame.bans.retain(|ban| !lifted(ban));
+            true
+        });
+        self.root_bans.retain(|ban| !lifted(ban));
+    }
+}
+
+/// Whether two world positions are within `radius` in both axes
+const fn near(a: [i32; 2], b: [i32; 2], radius: i32) -> bool {
+    (a[0] - b[0]).abs() <= radius && (a[1] - b[1]).abs() <= radius
+}
@@ -0,0 +1,200 @@
+//! Stopping rules for a single generation run
+//!
+//! A run normally stops after a fixed number of iterations and keeps whatever
+//! was filled. Running until complete instead continues until the generation
+//! bounds are filled, guarded by a safety cap on iterations and by stall
+//! detection: once removals and backtracks keep unlocking tiles without the
+//! filled count ever reaching a new high, the run is only churning and is
+//! stopped early.
+
+use crate::{
+    algorithm::{backtracking::DecisionStack, executor::GreedyStochastic},
+    io::{
+        configuration::{
+            DEFAULT_MAX_ITERATIONS, DEFAULT_STALL_DEADLOCKS, UNTIL_COMPLETE_ITERATIONS_PER_CELL,
+        },
+        error::{AlgorithmError, Result},
+    },
+};
+
+/// When a run stops and whether stopping short of the bounds is a failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunLimits {
+    /// Iterations allowed before stopping, unless allowed per cell instead
+    pub max_iterations: usize,
+    /// Iterations allowed per cell of the executor's generation bounds,
+    /// replacing `max_iterations` for bounded executors when set
+    pub iterations_per_cell: Option<usize>,
+    /// Removals and backtracks allowed without filling more of the bounds,
+    /// if stalls are detected
+    pub stall_deadlocks: Option<usize>,
+    /// Whether stopping with unfilled bounds is reported as incomplete
+    pub require_complete: bool,
+}
+
+impl RunLimits {
+    /// Run a fixed number of iterations, keeping whatever is filled
+    pub const fn iterations(max_iterations: usize) -> Self {
+        Self {
+            max_iterations,
+            iterations_per_cell: None,
+            stall_deadlocks: None,
+            require_complete: false,
+        }
+    }
+
+    /// Run until the generation bounds are filled
+    ///
+    /// The safety cap allows a fixed number of iterations per cell of the
+    /// bounds the executor ends up with, including any prefill expansion
+    pub const fn until_complete() -> Self {
+        Self {
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            iterations_per_cell: Some(UNTIL_COMPLETE_ITERATIONS_PER_CELL),
+            stall_deadlocks: Some(DEFAULT_STALL_DEADLOCKS),
+            require_complete: true,
+        }
+    }
+
+    /// Set a fixed iteration cap, replacing any cap per cell
+    #[must_use]
+    pub const fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self.iterations_per_cell = None;
+        self
+    }
+
+    /// Set the removals and backtracks allowed without progress, or disable
+    /// stall detection
+    #[must_use]
+    pub const fn with_stall_deadlocks(mut self, stall_deadlocks: Option<usize>) -> Self {
+        self.stall_deadlocks = stall_deadlocks;
+        self
+    }
+
+    /// Iteration cap for bounds of `cells` positions, or for unbounded runs
+    pub const fn cap_for_cells(&self, cells: Option<usize>) -> usize {
+        match (self.iterations_per_cell, cells) {
+            (Some(per_cell), Some(cells)) => cells.saturating_mul(per_cell),
+            _ => self.max_iterations,
+        }
+    }
+
+    /// Iteration cap for an executor, from its current generation bounds
+    pub fn iteration_cap(&self, executor: &GreedyStochastic) -> usize {
+        self.cap_for_cells(executor.cells_in_bounds())
+    }
+}
+
+/// Detects removals and backtracks that no longer lead to more of the bounds
+/// being filled
+#[derive(Debug, Clone)]
+pub struct StallDetector {
+    /// Setbacks allowed since the last new high
+    window: usize,
+    /// Highest number of placed tiles seen
+    most_placed: usize,
+    /// Setback count when that high was reached
+    setbacks_at_most: usize,
+}
+
+impl StallDetector {
+    /// Create a detector allowing `window` setbacks without progress
+    pub const fn new(window: usize) -> Self {
+        Self {
+            window,
+            most_placed: 0,
+            setbacks_at_most: 0,
+        }
+    }
+
+    /// Record the placed tiles and total setbacks, removals plus backtracks,
+    /// after an iteration
+    ///
+    /// Returns whether the run has stalled
+    pub const fn observe(&mut self, placed: usize, setbacks: usize) -> bool {
+        if placed > self.most_placed {
+            self.most_placed = placed;
+            self.setbacks_at_most = setbacks;
+        }
+        setbacks.saturating_sub(self.setbacks_at_most) >= self.window
+    }
+}
+
+/// How a run ended
+#[derive(Debug)]
+pub struct RunOutcome {
+    /// Iterations run
+    pub iterations: usize,
+    /// Generation failure that ended the run, if any
+    pub error: Option<AlgorithmError>,
+}
+
+/// Run an executor within the limits
+///
+/// `on_iteration` is called with each iteration number before it runs.
+/// Generation failures, including stopping short of the bounds when
+/// completion is required, are reported in the outcome.
+///
+/// # Errors
+///
+/// Returns any error other than a generation failure
+pub fn run<F>(
+    executor: &mut GreedyStochastic,
+    limits: &RunLimits,
+    mut on_iteration: F,
+) -> Result<RunOutcome>
+where
+    F: FnMut(usize),
+{
+    let mut stall = limits.stall_deadlocks.map(StallDetector::new);
+    let mut iterations = 0;
+    let mut stalled = false;
+
+    for iteration in 1..=limits.iteration_cap(executor) {
+        on_iteration(iteration);
+        iterations = iteration;
+        match executor.execute_iteration() {
+            Ok(true) => {}
+            Ok(false) => {
+                return Ok(RunOutcome {
+                    iterations,
+                    error: None,
+                });
+            }
+            Err(failure) if failure.is_generation_failure() => {
+                return Ok(RunOutcome {
+                    iterations,
+                    error: Some(failure),
+                });
+            }
+            Err(failure) => return Err(failure),
+        }
+
+        if let Some(detector) = &mut stall
+            && detector.observe(
+                executor.grid_state.placed_in_bounds,
+                executor.deadlock_stats.deadlocks
+                    + executor
+                        .decision_stack
+                        .as_ref()
+                        .map_or(0, DecisionStack::backtracks),
+            )
+        {
+            stalled = true;
+            break;
+        }
+    }
+
+    let error = match executor.unfilled_in_bounds() {
+        Some(unfilled) if limits.require_complete && unfilled > 0 => {
+            Some(AlgorithmError::Incomplete {
+                unfilled,
+                iterations,
+                stalled,
+            })
+        }
+        _ => None,
+    };
+    Ok(RunOutcome { iterations, error })
+}
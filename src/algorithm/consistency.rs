@@ -0,0 +1,382 @@
+//! Arc consistency propagation over the domains of frontier cells
+//!
+//! Every open frontier cell keeps a domain of the tiles still possible there.
+//! A value survives only while each 3x3 window covering the cell contains a
+//! source tile that agrees with the locked cells and with the domains of the
+//! other open cells. Reductions are propagated transitively AC-3 style, so
+//! singletons and empty domains surface before selection reaches them.
+//! Placements only shrink domains, so they persist between placements; any
+//! removal clears them and they are rebuilt lazily.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    algorithm::{
+        bitset::TileBitset,
+        cache::ViableTilesCache,
+        propagation::{ForcedPosition, StepData},
+        selection::compute_viable_tiles_at_position,
+    },
+    io::error::AlgorithmError,
+    spatial::GridState,
+};
+
+/// Distance at which two cells share a 3x3 window
+const WINDOW_REACH: i32 = 2;
+
+/// Built-in ways to find forced positions after a placement
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PropagationMode {
+    /// Check the eight neighbours of the placement for single viable tiles
+    #[default]
+    Neighbours,
+    /// Keep frontier domains arc consistent, propagating reductions transitively
+    ArcConsistency,
+}
+
+impl PropagationMode {
+    /// Domain store for this mode, if it keeps domains
+    pub fn arc_consistency(self) -> Option<ArcConsistency> {
+        match self {
+            Self::Neighbours => None,
+            Self::ArcConsistency => Some(ArcConsistency::new()),
+        }
+    }
+}
+
+impl std::fmt::Display for PropagationMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Neighbours => write!(f, "neighbours"),
+            Self::ArcConsistency => write!(f, "ac3"),
+        }
+    }
+}
+
+impl std::str::FromStr for PropagationMode {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "neighbours" => Ok(Self::Neighbours),
+            "ac3" => Ok(Self::ArcConsistency),
+            _ => Err(crate::io::error::invalid_parameter(
+                "propagation mode",
+                &s,
+                &"expected 'neighbours' or 'ac3'",
+            )),
+        }
+    }
+}
+
+/// Outcome of propagating the pending placements
+#[derive(Debug, Default)]
+pub struct Propagation {
+    /// Cells whose domain shrank or appeared and holds a single tile
+    pub forced: Vec<ForcedPosition>,
+    /// Grid index of the first cell whose domain emptied
+    pub contradiction: Option<[usize; 2]>,
+}
+
+/// Grid and source data read while revising domains
+struct Context<'a> {
+    grid_state: &'a GridState,
+    system_offset: [i32; 2],
+    step_data: &'a StepData,
+}
+
+impl Context<'_> {
+    /// Grid index of a world position, if it lies inside the grid
+    fn index(&self, world: [i32; 2]) -> Option<[usize; 2]> {
+        let row = usize::try_from(world[0] + self.system_offset[0]).ok()?;
+        let col = usize::try_from(world[1] + self.system_offset[1]).ok()?;
+        (row < self.grid_state.rows() && col < self.grid_state.cols()).then_some([row, col])
+    }
+
+    /// Locked value of a world position, reading open past the far edges
+    /// and `None` before the near edges of the grid
+    fn locked(&self, world: [i32; 2]) -> Option<u32> {
+        let row = usize::try_from(world[0] + self.system_offset[0]).ok()?;
+        let col = usize::try_from(world[1] + self.system_offset[1]).ok()?;
+        Some(
+            self.grid_state
+                .locked_tiles
+                .get([row, col])
+                .copied()
+                .unwrap_or(1),
+        )
+    }
+
+    /// Whether a world position is an open frontier cell that will be filled
+    fn keeps_domain(&self, world: [i32; 2]) -> bool {
+        let Some(index) = self.index(world) else {
+            return false;
+        };
+        let open = self
+            .grid_state
+            .locked_tiles
+            .get(index)
+            .copied()
+            .unwrap_or(0)
+            <= 1;
+        let frontier = self
+            .grid_state
+            .adjacency_weights
+            .get(index)
+            .copied()
+            .unwrap_or(0)
+            > 1;
+        open && frontier && self.grid_state.in_generation_bounds(world)
+    }
+}
+
+/// Tile domains of open frontier cells kept arc consistent across placements
+#[derive(Debug, Clone, Default)]
+pub struct ArcConsistency {
+    /// Domain of each open frontier cell by world position
+    domains: HashMap<[i32; 2], TileBitset>,
+    /// Cells awaiting revision, in order
+    queue: VecDeque<[i32; 2]>,
+    /// Cells currently in the queue
+    queued: HashSet<[i32; 2]>,
+}
+
+impl ArcConsistency {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Domain of a cell, if one is kept
+    pub fn domain(&self, position: [i32; 2]) -> Option<&TileBitset> {
+        self.domains.get(&position)
+    }
+
+    /// Number of cells with a domain
+    pub fn len(&self) -> usize {
+        self.domains.len()
+    }
+
+    /// Whether no cell has a domain
+    pub fn is_empty(&self) -> bool {
+        self.domains.is_empty()
+    }
+
+    /// Record a placement, queueing every cell sharing a window with it
+    pub fn record_placement(&mut self, position: [i32; 2]) {
+        self.domains.remove(&position);
+        for neighbour in window_neighbours(position) {
+            self.enqueue(neighbour);
+        }
+    }
+
+    /// Forget every domain and pending revision
+    ///
+    /// Used once tiles are removed, since removals can widen any domain
+    pub fn clear(&mut self) {
+        self.domains.clear();
+        self.queue.clear();
+        self.queued.clear();
+    }
+
+    /// Revise queued cells until no domain shrinks or one empties
+    ///
+    /// Cells without a domain are seeded from their viable tiles the first
+    /// time they are reached. On a contradiction the remaining queue is
+    /// dropped, since resolving it removes tiles and clears the domains.
+    pub fn propagate(
+        &mut self,
+        grid_state: &GridState,
+        system_offset: [i32; 2],
+        step_data: &StepData,
+        cache: &mut ViableTilesCache,
+    ) -> Propagation {
+        let context = Context {
+            grid_state,
+            system_offset,
+            step_data,
+        };
+        let mut changed = Vec::new();
+
+        while let Some(cell) = self.queue.pop_front() {
+            self.queued.remove(&cell);
+            if !context.keeps_domain(cell) {
+                self.domains.remove(&cell);
+                continue;
+            }
+
+            // Cells reached for the first time join the propagation, and
+            // cells that treated them as unconstrained are revised again
+            for neighbour in window_neighbours(cell) {
+                if self.domains.contains_key(&neighbour) || !context.keeps_domain(neighbour) {
+                    continue;
+                }
+                if self.seed_domain(&context, cache, neighbour) {
+                    changed.push(neighbour);
+                }
+                for affected in window_neighbours(neighbour) {
+                    if affected != cell && self.domains.contains_key(&affected) {
+                        self.enqueue(affected);
+                    }
+                }
+            }
+
+            let Some(current) = self.domains.get(&cell) else {
+                continue;
+            };
+            let revised = self.supported(&context, cell, current);
+            if revised.is_empty() {
+                self.queue.clear();
+                self.queued.clear();
+                return Propagation {
+                    forced: Vec::new(),
+                    contradiction: context.index(cell),
+                };
+            }
+            if revised.count() == current.count() {
+                continue;
+            }
+
+            self.domains.insert(cell, revised);
+            changed.push(cell);
+            for neighbour in window_neighbours(cell) {
+                if self.domains.contains_key(&neighbour) {
+                    self.enqueue(neighbour);
+                }
+            }
+        }
+
+        let mut reported = HashSet::new();
+        let forced = changed
+            .into_iter()
+            .filter(|&cell| reported.insert(cell))
+            .filter_map(|cell| {
+                let domain = self.domains.get(&cell)?;
+                match domain.to_vec().as_slice() {
+                    &[tile_reference] => Some(ForcedPosition {
+                        coordinates: cell,
+                        tile_reference,
+                    }),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        Propagation {
+            forced,
+            contradiction: None,
+        }
+    }
+
+    /// Start a cell's domain from its viable tiles
+    ///
+    /// Returns whether the domain is narrower than every tile
+    fn seed_domain(
+        &mut self,
+        context: &Context<'_>,
+        cache: &mut ViableTilesCache,
+        cell: [i32; 2],
+    ) -> bool {
+        let tile_types = context.step_data.unique_cell_count;
+        let mut domain = TileBitset::new(tile_types);
+        for tile in compute_viable_tiles_at_position(
+            context.grid_state,
+            cell,
+            context.system_offset,
+            &context.step_data.source_tiles,
+            context.step_data,
+            cache,
+        ) {
+            domain.insert(tile);
+        }
+        let narrowed = domain.count() < tile_types;
+        self.domains.insert(cell, domain);
+        narrowed
+    }
+
+    /// Values of a cell's domain supported in every window covering it
+    fn supported(&self, context: &Context<'_>, cell: [i32; 2], domain: &TileBitset) -> TileBitset {
+        let mut result = domain.clone();
+        for top in window_neighbours(cell).filter(|&[row, col]| row <= cell[0] && col <= cell[1]) {
+            let Some(window) = self.window_constraints(context, top, cell) else {
+                continue;
+            };
+            let mut support = TileBitset::new(context.step_data.unique_cell_count);
+            for tile in &context.step_data.source_tiles {
+                let matches =
+                    tile.iter().flatten().zip(&window).all(
+                        |(&value, constraint)| match constraint {
+                            Constraint::Locked(locked) => value == *locked,
+                            Constraint::Domain(allowed) => allowed.contains(value),
+                            Constraint::Free => true,
+                        },
+                    );
+                if matches {
+                    let center = [(cell[0] - top[0]) as usize, (cell[1] - top[1]) as usize];
+                    if let Some(&value) = tile.get(center[0]).and_then(|row| row.get(center[1])) {
+                        support.insert(value);
+                    }
+                }
+            }
+            result.intersect_with(&support);
+            if result.is_empty() {
+                break;
+            }
+        }
+        result
+    }
+
+    /// Constraint on each cell of the window whose top left is `top`, row-major
+    ///
+    /// Returns `None` when the window crosses the near edges of the grid,
+    /// matching the windows viable tiles are computed from
+    fn window_constraints<'a>(
+        &'a self,
+        context: &Context<'_>,
+        top: [i32; 2],
+        cell: [i32; 2],
+    ) -> Option<Vec<Constraint<'a>>> {
+        let mut constraints = Vec::with_capacity(9);
+        for row in top[0]..top[0] + 3 {
+            for col in top[1]..top[1] + 3 {
+                let position = [row, col];
+                let locked = context.locked(position)?;
+                constraints.push(if locked > 1 {
+                    Constraint::Locked(locked as usize - 1)
+                } else if position == cell {
+                    Constraint::Free
+                } else {
+                    self.domains
+                        .get(&position)
+                        .map_or(Constraint::Free, Constraint::Domain)
+                });
+            }
+        }
+        Some(constraints)
+    }
+
+    /// Queue a cell for revision unless it is already queued
+    fn enqueue(&mut self, cell: [i32; 2]) {
+        if self.queued.insert(cell) {
+            self.queue.push_back(cell);
+        }
+    }
+}
+
+/// What a window cell allows of a source tile
+enum Constraint<'a> {
+    /// Placed tile reference
+    Locked(usize),
+    /// Open cell limited to its domain
+    Domain(&'a TileBitset),
+    /// Open cell allowing any tile
+    Free,
+}
+
+/// Cells sharing a 3x3 window with a position, the position included,
+/// in row-major order
+fn window_neighbours(position: [i32; 2]) -> impl Iterator<Item = [i32; 2]> {
+    (position[0] - WINDOW_REACH..=position[0] + WINDOW_REACH).flat_map(move |row| {
+        (position[1] - WINDOW_REACH..=position[1] + WINDOW_REACH).map(move |col| [row, col])
+    })
+}
@@ -1,12 +1,281 @@
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
 use crate::{
-    algorithm::{feasibility::FeasibilityCountLayer, propagation::StepData},
+    algorithm::{feasibility::FeasibilityCountLayer, fronts::GrowthFronts, propagation::StepData},
     io::{
-        configuration::{ADJACENCY_LEVELS, BASE_REMOVAL_RADIUS, MAX_REMOVAL_RADIUS},
+        configuration::{
+            ADJACENCY_LEVELS, BASE_REMOVAL_RADIUS, CONTRADICTION_CHECK_RADIUS,
+            DEFAULT_RECENT_PLACEMENTS, MAX_REMOVAL_RADIUS,
+        },
+        error::{AlgorithmError, Result, invalid_parameter},
         visualization::VisualizationCapture,
     },
     spatial::{GridState, grid},
 };
 
+/// Which tiles around a contradiction are unlocked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RemovalShape {
+    /// Every tile within the removal radius in both axes
+    #[default]
+    Square,
+    /// Every tile within the removal radius by Euclidean distance
+    Disk,
+    /// Tiles among the given number of most recent placements that connect
+    /// to the contradiction within the removal radius
+    ///
+    /// Older tiles survive, so only the growth that led into the
+    /// contradiction is undone. Falls back to a square when no recent tile
+    /// touches the contradiction.
+    Recent(usize),
+}
+
+impl RemovalShape {
+    /// Placements remembered for this shape
+    pub const fn history_len(self) -> usize {
+        match self {
+            Self::Square | Self::Disk => 0,
+            Self::Recent(placements) => placements,
+        }
+    }
+}
+
+impl std::fmt::Display for RemovalShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Square => write!(f, "square"),
+            Self::Disk => write!(f, "disk"),
+            Self::Recent(placements) => write!(f, "recent:{placements}"),
+        }
+    }
+}
+
+impl std::str::FromStr for RemovalShape {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let lower = s.to_ascii_lowercase();
+        match lower.as_str() {
+            "square" => Ok(Self::Square),
+            "disk" => Ok(Self::Disk),
+            "recent" => Ok(Self::Recent(DEFAULT_RECENT_PLACEMENTS)),
+            other => match other.strip_prefix("recent:").map(str::parse::<usize>) {
+                Some(Ok(placements)) if placements > 0 => Ok(Self::Recent(placements)),
+                _ => Err(invalid_parameter(
+                    "removal shape",
+                    &s,
+                    &"expected 'square', 'disk', 'recent' or 'recent:<placements>'",
+                )),
+            },
+        }
+    }
+}
+
+/// How the removal radius grows with repeated deadlocks at one position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemovalGrowth {
+    /// Radius stays at the base
+    Fixed,
+    /// Radius grows by the step on every repeat
+    Linear(i32),
+    /// Growth doubles on every repeat: 1, 2, 4, ...
+    Doubling,
+}
+
+impl Default for RemovalGrowth {
+    fn default() -> Self {
+        Self::Linear(1)
+    }
+}
+
+impl RemovalGrowth {
+    /// Radius added after `repeats` deadlocks at a position
+    pub const fn growth(self, repeats: u8) -> i32 {
+        match self {
+            Self::Fixed => 0,
+            Self::Linear(step) => step.saturating_mul(repeats as i32),
+            Self::Doubling => match repeats {
+                0 => 0,
+                1..=31 => 1 << (repeats - 1),
+                _ => i32::MAX,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for RemovalGrowth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fixed => write!(f, "fixed"),
+            Self::Linear(step) => write!(f, "linear:{step}"),
+            Self::Doubling => write!(f, "doubling"),
+        }
+    }
+}
+
+impl std::str::FromStr for RemovalGrowth {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let lower = s.to_ascii_lowercase();
+        match lower.as_str() {
+            "fixed" => Ok(Self::Fixed),
+            "linear" => Ok(Self::default()),
+            "doubling" => Ok(Self::Doubling),
+            other => match other.strip_prefix("linear:").map(str::parse::<i32>) {
+                Some(Ok(step)) if step >= 0 => Ok(Self::Linear(step)),
+                _ => Err(invalid_parameter(
+                    "removal growth",
+                    &s,
+                    &"expected 'fixed', 'linear', 'linear:<step>' or 'doubling'",
+                )),
+            },
+        }
+    }
+}
+
+/// How contradictions are cleared by removal, and when to give up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadlockPolicy {
+    /// Which tiles around a contradiction are unlocked
+    pub shape: RemovalShape,
+    /// How the radius grows with repeated deadlocks at one position
+    pub growth: RemovalGrowth,
+    /// Radius before any growth
+    pub base_radius: i32,
+    /// Largest radius growth may reach
+    pub max_radius: i32,
+    /// Removals allowed in a run before it is declared unsolvable
+    pub max_attempts: Option<usize>,
+    /// Iterations between decrements of every position's removal count
+    pub decay_interval: Option<usize>,
+}
+
+impl Default for DeadlockPolicy {
+    fn default() -> Self {
+        Self {
+            shape: RemovalShape::default(),
+            growth: RemovalGrowth::default(),
+            base_radius: BASE_REMOVAL_RADIUS,
+            max_radius: MAX_REMOVAL_RADIUS,
+            max_attempts: None,
+            decay_interval: None,
+        }
+    }
+}
+
+impl DeadlockPolicy {
+    /// Set which tiles around a contradiction are unlocked
+    #[must_use]
+    pub const fn with_shape(mut self, shape: RemovalShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Set how the radius grows with repeated deadlocks at one position
+    #[must_use]
+    pub const fn with_growth(mut self, growth: RemovalGrowth) -> Self {
+        self.growth = growth;
+        self
+    }
+
+    /// Set the radius before growth and the largest radius growth may reach
+    #[must_use]
+    pub const fn with_radius(mut self, base_radius: i32, max_radius: i32) -> Self {
+        self.base_radius = base_radius;
+        self.max_radius = max_radius;
+        self
+    }
+
+    /// Declare a run unsolvable once this many removals have not resolved it
+    #[must_use]
+    pub const fn with_max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// Lower every position's removal count by one each `interval` iterations
+    #[must_use]
+    pub const fn with_decay_interval(mut self, interval: usize) -> Self {
+        self.decay_interval = Some(interval);
+        self
+    }
+
+    /// Removal radius after `repeats` deadlocks at a position
+    pub const fn radius(&self, repeats: u8) -> i32 {
+        let radius = self.base_radius.saturating_add(self.growth.growth(repeats));
+        if radius < self.max_radius {
+            radius
+        } else {
+            self.max_radius
+        }
+    }
+
+    /// Check the radii and decay interval
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a radius is negative, the base radius exceeds the
+    /// maximum, or the decay interval is zero
+    pub fn validate(&self) -> Result<()> {
+        if self.base_radius < 0 || self.max_radius < self.base_radius {
+            return Err(invalid_parameter(
+                "removal radius",
+                &format!("{}..={}", self.base_radius, self.max_radius),
+                &"expected 0 <= base <= max",
+            ));
+        }
+        if self.decay_interval == Some(0) {
+            return Err(invalid_parameter(
+                "removal decay",
+                &0,
+                &"interval must be at least one iteration",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Deadlocks resolved by removal during a run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeadlockStats {
+    /// Removals performed
+    pub deadlocks: usize,
+    /// Tiles unlocked across all removals
+    pub tiles_removed: usize,
+    /// Removals triggered at each world position
+    pub hotspots: BTreeMap<[i32; 2], usize>,
+}
+
+impl DeadlockStats {
+    /// Record one removal at a world position
+    pub fn record(&mut self, position: [i32; 2], tiles_removed: usize) {
+        self.deadlocks += 1;
+        self.tiles_removed += tiles_removed;
+        *self.hotspots.entry(position).or_default() += 1;
+    }
+
+    /// Add another run's removals to these
+    pub fn merge(&mut self, other: &Self) {
+        self.deadlocks += other.deadlocks;
+        self.tiles_removed += other.tiles_removed;
+        for (&position, &count) in &other.hotspots {
+            *self.hotspots.entry(position).or_default() += count;
+        }
+    }
+
+    /// Up to `count` positions with the most removals, most first
+    ///
+    /// Ties are ordered row-major
+    pub fn top_hotspots(&self, count: usize) -> Vec<([i32; 2], usize)> {
+        let mut hotspots: Vec<([i32; 2], usize)> =
+            self.hotspots.iter().map(|(&p, &n)| (p, n)).collect();
+        hotspots.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        hotspots.truncate(count);
+        hotspots
+    }
+}
+
 /// Summary of tiles affected by deadlock resolution
 #[derive(Debug)]
 pub struct DeadlockResolutionResult {
@@ -14,13 +283,20 @@ pub struct DeadlockResolutionResult {
     pub tiles_unlocked: usize,
     /// Grid positions of all unlocked tiles
     pub unlocked_positions: Vec<[usize; 2]>,
+    /// Radius of the removal around the contradiction
+    pub removal_radius: i32,
 }
 
 /// Resolve a spatial deadlock by unlocking tiles around the contradiction
 ///
 /// Uses an adaptive radius that expands with repeated deadlocks at the same
-/// location. This prevents the algorithm from getting stuck in loops by
-/// progressively clearing larger areas when contradictions persist.
+/// location, following the policy's growth schedule. This prevents the
+/// algorithm from getting stuck in loops by progressively clearing larger
+/// areas when contradictions persist. Only tiles inside the generation bounds
+/// are removed; `recent_placements` lists world positions newest last, for
+/// the recent removal shape. When `fronts` places the contradiction on a seam
+/// between growth fronts, the first removal there clears only the tiles of
+/// one front.
 pub fn resolve_spatial_deadlock(
     grid_state: &mut GridState,
     feasibility_layer: &mut FeasibilityCountLayer,
@@ -29,6 +305,9 @@ pub fn resolve_spatial_deadlock(
     selection_tally: &mut [usize],
     step_data: &StepData,
     probability_influence_matrices: &ndarray::Array4<f64>,
+    policy: &DeadlockPolicy,
+    recent_placements: &VecDeque<[i32; 2]>,
+    fronts: Option<&GrowthFronts>,
     visualization: &mut Option<VisualizationCapture>,
     iteration: usize,
 ) -> DeadlockResolutionResult {
@@ -43,7 +322,6 @@ pub fn resolve_spatial_deadlock(
     ];
 
     let mut unlocked_positions = Vec::new();
-    let mut tiles_unlocked = 0;
 
     // Adaptive radius prevents repeated deadlocks at the same location
     let removal_count = grid_state
@@ -52,33 +330,106 @@ pub fn resolve_spatial_deadlock(
         .copied()
         .unwrap_or(0);
 
-    let removal_radius = (BASE_REMOVAL_RADIUS + removal_count as i32).min(MAX_REMOVAL_RADIUS);
+    let removal_radius = policy.radius(removal_count);
 
-    let (row_span, col_span) =
-        grid::get_region_spans(&system_offset, &contradiction_coords, removal_radius);
+    let mut positions = match policy.shape {
+        RemovalShape::Square | RemovalShape::Recent(_) => {
+            square_positions(contradiction_coords, removal_radius).collect()
+        }
+        RemovalShape::Disk => square_positions(contradiction_coords, removal_radius)
+            .filter(|&[row, col]| {
+                let dr = row - contradiction_coords[0];
+                let dc = col - contradiction_coords[1];
+                dr * dr + dc * dc <= removal_radius * removal_radius
+            })
+            .collect(),
+    };
+    if let RemovalShape::Recent(_) = policy.shape {
+        let recent_region = recent_region(
+            grid_state,
+            system_offset,
+            contradiction_coords,
+            removal_radius,
+            recent_placements,
+        );
+        if !recent_region.is_empty() {
+            positions = recent_region;
+        }
+    }
+    // Clear one side of a seam so it regrows against the other, unless that
+    // already failed here and both sides must go
+    if removal_count <= 1
+        && let Some(fronts) = fronts
+        && let Some(side) = fronts.seam_side(contradiction_coords, &positions)
+    {
+        positions.retain(|&world| fronts.front_at(world) == Some(side));
+    }
 
     let mut tiles_to_unlock = Vec::new();
 
-    for row in row_span {
-        for col in col_span.clone() {
-            let locked_val = grid_state
-                .locked_tiles
-                .get([row, col])
-                .copied()
-                .unwrap_or(0);
-            if locked_val > 1 {
-                let tile_reference = locked_val - 1;
-                tiles_to_unlock.push((row, col, tile_reference));
-                unlocked_positions.push([row, col]);
-                tiles_unlocked += 1;
-            }
+    for world in positions {
+        let Some(index) = grid_index(world, system_offset) else {
+            continue;
+        };
+        let locked_val = grid_state.locked_tiles.get(index).copied().unwrap_or(0);
+        // Tiles outside the generation bounds belong to finished regions
+        if locked_val > 1 && grid_state.in_generation_bounds(world) {
+            tiles_to_unlock.push((index, locked_val - 1));
+            unlocked_positions.push(index);
         }
     }
 
+    unlock_tiles(
+        grid_state,
+        feasibility_layer,
+        &tiles_to_unlock,
+        [
+            contradiction_coords[0] - removal_radius,
+            contradiction_coords[1] - removal_radius,
+        ],
+        [
+            contradiction_coords[0] + removal_radius,
+            contradiction_coords[1] + removal_radius,
+        ],
+        system_offset,
+        selection_tally,
+        step_data,
+        probability_influence_matrices,
+        visualization,
+        iteration,
+    );
+
+    DeadlockResolutionResult {
+        tiles_unlocked: unlocked_positions.len(),
+        unlocked_positions,
+        removal_radius,
+    }
+}
+
+/// Reverse the placement of each listed tile
+///
+/// Takes grid positions with their tile references, and refreshes feasibility
+/// around the world rectangle `min..=max` covering them. Influence is removed
+/// exactly, so probabilities and entropy return to their values before the
+/// tiles were placed.
+pub fn unlock_tiles(
+    grid_state: &mut GridState,
+    feasibility_layer: &mut FeasibilityCountLayer,
+    tiles: &[([usize; 2], u32)],
+    min: [i32; 2],
+    max: [i32; 2],
+    system_offset: [i32; 2],
+    selection_tally: &mut [usize],
+    step_data: &StepData,
+    probability_influence_matrices: &ndarray::Array4<f64>,
+    visualization: &mut Option<VisualizationCapture>,
+    iteration: usize,
+) {
     // Reverse the effects of placing each locked tile
-    for (row, col, tile_reference) in tiles_to_unlock {
+    for &([row, col], tile_reference) in tiles {
         if let Some(tile_matrix) = grid_state.locked_tiles.get_mut([row, col]) {
             *tile_matrix = tile_matrix.saturating_sub(tile_reference);
+            grid_state.placed_in_bounds = grid_state.placed_in_bounds.saturating_sub(1);
 
             if let Some(viz) = visualization {
                 let abs_row = row as i32 - system_offset[0];
@@ -88,14 +439,15 @@ pub fn resolve_spatial_deadlock(
         }
 
         // Decrement tally for non-empty tiles (tile_reference 2+ maps to tally index 0+)
-        if tile_reference >= 1 {
-            if let Some(tally) = selection_tally.get_mut(tile_reference as usize - 1) {
-                *tally = tally.saturating_sub(1);
-            }
+        if tile_reference >= 1
+            && let Some(tally) = selection_tally.get_mut(tile_reference as usize - 1)
+        {
+            *tally = tally.saturating_sub(1);
         }
 
         // Revert adjacency weights for all affected levels
         let coords = [row as i32 - system_offset[0], col as i32 - system_offset[1]];
+        grid_state.mark_dirty(coords, CONTRADICTION_CHECK_RADIUS);
 
         for level in 1..=ADJACENCY_LEVELS {
             let weight_decrement = (1 + ADJACENCY_LEVELS - level) as u32;
@@ -111,7 +463,9 @@ pub fn resolve_spatial_deadlock(
             }
         }
 
-        // Reverse probability mutations by dividing out the influence values
+        grid_state.refresh_frontier(coords, ADJACENCY_LEVELS as i32, system_offset);
+
+        // Reverse probability mutations by subtracting the accumulated influence
         let n_tiles = probability_influence_matrices
             .shape()
             .first()
@@ -128,8 +482,6 @@ pub fn resolve_spatial_deadlock(
         let impact = probability_influence_matrices
             .index_axis(ndarray::Axis(0), tile_reference as usize - 1);
 
-        let impact_shape = impact.shape();
-
         let row_start = prob_row_span.start.min(grid_state.rows());
         let row_end = prob_row_span.end.min(grid_state.rows());
         let col_start = prob_col_span.start.min(grid_state.cols());
@@ -137,101 +489,16 @@ pub fn resolve_spatial_deadlock(
 
         for (i, row_index) in (row_start..row_end).enumerate() {
             for (j, col_index) in (col_start..col_end).enumerate() {
-                if i >= impact_shape.get(1).copied().unwrap_or(0)
-                    || j >= impact_shape.get(2).copied().unwrap_or(0)
-                {
-                    continue;
-                }
-
-                // Divide out the influence for all tile types at this position
-                for color in 0..step_data.unique_cell_count {
-                    let impact_value = impact.get([color, i, j]).copied().unwrap_or(1.0);
-                    if impact_value != 0.0
-                        && color < grid_state.tile_probabilities.len()
-                        && row_index < grid_state.rows()
-                        && col_index < grid_state.cols()
-                    {
-                        if let Some(prob_matrix) = grid_state.tile_probabilities.get_mut(color) {
-                            if let Some(prob) = prob_matrix.get_mut([row_index, col_index]) {
-                                *prob /= impact_value;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // Recalculate entropy in affected region with expanded radius
-    let entropy_radius = step_data.grid_extension_radius + removal_radius;
-    let (entropy_row_span, entropy_col_span) =
-        grid::get_region_spans(&system_offset, &contradiction_coords, entropy_radius);
-
-    for row in entropy_row_span.start..entropy_row_span.end.min(grid_state.rows()) {
-        for col in entropy_col_span.start..entropy_col_span.end.min(grid_state.cols()) {
-            if grid_state
-                .locked_tiles
-                .get([row, col])
-                .copied()
-                .unwrap_or(0)
-                > 1
-            {
-                continue;
-            }
-
-            let mut sum = 0.0;
-            let mut count = 0;
-
-            for color in 0..step_data.unique_cell_count {
-                if let Some(prob) = grid_state
-                    .tile_probabilities
-                    .get(color)
-                    .and_then(|p| p.get([row, col]))
-                    .copied()
-                {
-                    sum += prob;
-                    count += 1;
-                }
-            }
-
-            let mean_prob = if count > 0 { sum / count as f64 } else { 0.0 };
-            let entropy = if mean_prob > 0.0 {
-                let mut entropy_sum = 0.0;
-
-                for color in 0..step_data.unique_cell_count {
-                    if let Some(prob) = grid_state
-                        .tile_probabilities
-                        .get(color)
-                        .and_then(|p| p.get([row, col]))
-                        .copied()
-                    {
-                        let normalized = prob / mean_prob;
-                        if normalized > 0.0 {
-                            entropy_sum += normalized * normalized.ln();
-                        }
-                    }
-                }
-
-                entropy_sum
-            } else {
-                0.0
-            };
-
-            if row < grid_state.rows() && col < grid_state.cols() {
-                if let Some(entropy_val) = grid_state.entropy.get_mut([row, col]) {
-                    *entropy_val = entropy;
-                }
+                let factors = (0..grid_state.unique_cell_count)
+                    .map(|color| impact.get([color, i, j]).copied().unwrap_or(1.0));
+                grid_state.remove_influence([row_index, col_index], factors);
             }
         }
     }
 
     // Update feasibility counts in the extended region
-    let feasibility_update_radius = (ADJACENCY_LEVELS as i32 + 1) + removal_radius;
-    let (feas_row_span, feas_col_span) = grid::get_region_spans(
-        &system_offset,
-        &contradiction_coords,
-        feasibility_update_radius,
-    );
+    let (feas_row_span, feas_col_span) =
+        rectangle_spans(system_offset, min, max, ADJACENCY_LEVELS as i32 + 1);
 
     for source_row in feas_row_span.clone() {
         for source_col in feas_col_span.clone() {
@@ -249,12 +516,11 @@ pub fn resolve_spatial_deadlock(
                                 .get([grid_row, grid_col])
                                 .copied()
                                 .unwrap_or(0);
-                            if locked_val > 0 {
-                                if let Some(tile_ref) =
+                            if locked_val > 0
+                                && let Some(tile_ref) =
                                     tile_grid.get_mut(di).and_then(|row| row.get_mut(dj))
-                                {
-                                    *tile_ref = (locked_val - 1) as i32;
-                                }
+                            {
+                                *tile_ref = (locked_val - 1) as i32;
                             }
                         }
                     }
@@ -291,16 +557,87 @@ pub fn resolve_spatial_deadlock(
                 }
             }
 
-            if count > 0 {
-                if let Some(feas) = grid_state.feasibility.get_mut([target_row, target_col]) {
-                    *feas = feasibility_sum / count as f64;
-                }
+            if count > 0
+                && let Some(feas) = grid_state.feasibility.get_mut([target_row, target_col])
+            {
+                *feas = feasibility_sum / count as f64;
             }
         }
     }
+}
 
-    DeadlockResolutionResult {
-        tiles_unlocked,
-        unlocked_positions,
+/// World positions of the square of `radius` around a center in row-major order
+fn square_positions(center: [i32; 2], radius: i32) -> impl Iterator<Item = [i32; 2]> {
+    (center[0] - radius..=center[0] + radius)
+        .flat_map(move |row| (center[1] - radius..=center[1] + radius).map(move |col| [row, col]))
+}
+
+/// Grid index of a world position, if it lies inside the grid
+fn grid_index(world: [i32; 2], system_offset: [i32; 2]) -> Option<[usize; 2]> {
+    Some([
+        usize::try_from(world[0] + system_offset[0]).ok()?,
+        usize::try_from(world[1] + system_offset[1]).ok()?,
+    ])
+}
+
+/// Recently placed tiles connected to a contradiction within `radius`
+///
+/// Floods outwards from the contradiction through the eight neighbours of
+/// each cell, crossing only locked tiles inside the generation bounds that
+/// are among `recent_placements`. Returned in row-major order.
+fn recent_region(
+    grid_state: &GridState,
+    system_offset: [i32; 2],
+    center: [i32; 2],
+    radius: i32,
+    recent_placements: &VecDeque<[i32; 2]>,
+) -> Vec<[i32; 2]> {
+    let recent: HashSet<[i32; 2]> = recent_placements.iter().copied().collect();
+    let removable = |world: [i32; 2]| {
+        recent.contains(&world)
+            && grid_state.in_generation_bounds(world)
+            && grid_index(world, system_offset)
+                .and_then(|index| grid_state.locked_tiles.get(index))
+                .is_some_and(|&locked| locked > 1)
+    };
+
+    let mut region = Vec::new();
+    let mut visited = HashSet::from([center]);
+    let mut queue = VecDeque::from([center]);
+    while let Some(cell) = queue.pop_front() {
+        for neighbour in square_positions(cell, 1) {
+            let within = (neighbour[0] - center[0]).abs() <= radius
+                && (neighbour[1] - center[1]).abs() <= radius;
+            if within && removable(neighbour) && visited.insert(neighbour) {
+                region.push(neighbour);
+                queue.push_back(neighbour);
+            }
+        }
+    }
+    if removable(center) {
+        region.push(center);
     }
+
+    region.sort_unstable();
+    region
+}
+
+/// Grid index spans of a world rectangle grown by `radius`, clamped at zero
+const fn rectangle_spans(
+    system_offset: [i32; 2],
+    min: [i32; 2],
+    max: [i32; 2],
+    radius: i32,
+) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+    (
+        clamped_index(min[0] + system_offset[0] - radius)
+            ..clamped_index(max[0] + system_offset[0] + radius + 1),
+        clamped_index(min[1] + system_offset[1] - radius)
+            ..clamped_index(max[1] + system_offset[1] + radius + 1),
+    )
+}
+
+/// Grid index, with negative values clamped to zero
+const fn clamped_index(index: i32) -> usize {
+    if index < 0 { 0 } else { index as usize }
 }
@@ -1,27 +1,43 @@
 use crate::{
+    algorithm::backtracking::{DeadlockStrategy, DecisionStack},
     algorithm::cache::ViableTilesCache,
+    algorithm::consistency::{ArcConsistency, PropagationMode},
+    algorithm::deadlock::{DeadlockPolicy, DeadlockStats},
     algorithm::feasibility::FeasibilityCountLayer,
+    algorithm::fronts::{GrowthFronts, GrowthSeeds},
+    algorithm::origin::{BoundsAnchor, StartPosition},
+    algorithm::positioning::{PositionContext, PositionSelector, PositionStrategy},
     algorithm::propagation::StepData,
     algorithm::propagation::{
         ForcedPipeline, check_for_contradiction, detect_forced_positions,
         update_feasibility_counts, update_grid_state, update_probabilities_and_entropy,
     },
     algorithm::selection::{
-        ADJACENCY_CANDIDATES_CONSIDERED, CANDIDATES_CONSIDERED, compute_viable_tiles_at_position,
-        density_corrected_log_tile_weights, get_tile_probabilities_at_position,
+        TileChoiceContext, TileChooser, TileStrategy, compute_viable_tiles_at_position,
+        get_tile_probabilities_at_position,
     },
     analysis::patterns::ImageProcessor,
-    analysis::statistics::Processor,
-    analysis::weights::{calculate_position_selection, top_k_from_indices, top_k_valid_indices},
+    analysis::statistics::{
+        BandwidthSelection, KernelShape, OffsetSymmetry, PairBandwidth, Processor, SpatialMode,
+    },
     io::analysis::AnalysisCapture,
+    io::configuration::{
+        ADJACENCY_CANDIDATES_CONSIDERED, CANDIDATES_CONSIDERED, GRID_EXTENSION_RADIUS,
+        MAX_SELECTION_CONTRADICTIONS, PATTERN_INFLUENCE_DISTANCE, TILE_SIZE,
+    },
+    io::events::{Candidate, DecisionSource, EventLog, GenerationEvent},
+    io::fields::{FieldCapture, FieldSchedule, FieldSnapshot},
     io::prefill::{PrefillData, PrefillPlacement},
+    io::visualization::AnimationOptions,
     io::visualization::VisualizationCapture,
-    math::probability::binomial_normal_approximate_cdf,
     spatial::GridState,
+    spatial::chunked::chunk_of,
     spatial::tiles::TileExtractor,
 };
 use ndarray::Array4;
 use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 
 /// Algorithm parameters controlling pattern extraction and selection behavior
 #[derive(Clone, Copy, Debug)]
@@ -42,6 +58,54 @@ pub struct AlgorithmConfig {
     pub include_reflections: bool,
     /// Optional generation bounds (width, height)
     pub bounds: Option<(usize, usize)>,
+    /// Point of the bounds placed at the world origin
+    pub bounds_anchor: BoundsAnchor,
+    /// Where the first tile is placed
+    pub start_position: StartPosition,
+    /// Points from which separate growth fronts start
+    pub growth_seeds: GrowthSeeds,
+    /// Whether pair statistics keep direction (symmetrized by the tile transformations)
+    pub spatial_mode: SpatialMode,
+    /// Strategy for choosing each pair's kernel density bandwidth
+    pub bandwidth_selection: BandwidthSelection,
+    /// Smoothing kernel shape for pair-distance densities
+    pub kernel: KernelShape,
+    /// Strategy for choosing non-forced placement positions
+    pub position_strategy: PositionStrategy,
+    /// Strategy for choosing among viable tiles at a position
+    pub tile_strategy: TileStrategy,
+    /// Strategy for resolving contradictions
+    pub deadlock_strategy: DeadlockStrategy,
+    /// Shape, growth and limits of removal when resolving contradictions
+    pub deadlock_policy: DeadlockPolicy,
+    /// How forced positions and contradictions are found after a placement
+    pub propagation_mode: PropagationMode,
+}
+
+impl Default for AlgorithmConfig {
+    fn default() -> Self {
+        Self {
+            candidates_considered: CANDIDATES_CONSIDERED,
+            adjacency_candidates_considered: ADJACENCY_CANDIDATES_CONSIDERED,
+            pattern_influence_distance: PATTERN_INFLUENCE_DISTANCE,
+            grid_extension_radius: GRID_EXTENSION_RADIUS,
+            tile_size: TILE_SIZE,
+            include_rotations: false,
+            include_reflections: false,
+            bounds: None,
+            bounds_anchor: BoundsAnchor::default(),
+            start_position: StartPosition::default(),
+            growth_seeds: GrowthSeeds::default(),
+            spatial_mode: SpatialMode::default(),
+            bandwidth_selection: BandwidthSelection::default(),
+            kernel: KernelShape::default(),
+            position_strategy: PositionStrategy::default(),
+            tile_strategy: TileStrategy::default(),
+            deadlock_strategy: DeadlockStrategy::default(),
+            deadlock_policy: DeadlockPolicy::default(),
+            propagation_mode: PropagationMode::default(),
+        }
+    }
 }
 
 /// Load source image and initialize all algorithm data structures
@@ -64,6 +128,7 @@ pub fn load_and_initialize_data(
     [i32; 2],
     Vec<usize>,
     Vec<[u8; 4]>,
+    Vec<PairBandwidth>,
 )> {
     let image_processor = ImageProcessor::from_png_file("data/a.png")?;
 
@@ -100,6 +165,7 @@ pub fn load_and_initialize_data(
 
     let probability_influence_matrices =
         statistics_processor.preprocess_pattern_statistics(&exponential_sample_points)?;
+    let pair_bandwidths = statistics_processor.selected_bandwidths().to_vec();
 
     let mut system_offset = [0, 0];
 
@@ -134,6 +200,7 @@ pub fn load_and_initialize_data(
         selection_coordinates,
         selection_tally,
         color_mapping,
+        pair_bandwidths,
     ))
 }
 
@@ -169,6 +236,14 @@ impl RandomSelector {
         weights.len() - 1
     }
 
+    /// Uniform random index below `len`
+    pub fn uniform_choice(&mut self, len: usize) -> usize {
+        if len == 0 {
+            return 0;
+        }
+        self.rng.random_range(0..len)
+    }
+
     /// Random selection of an index for logarithmic weights
     ///
     /// Converts to probabilities using log-sum-exp trick, then selects based on cumulative probability distribution
@@ -233,12 +308,16 @@ fn select_initial_tile(source_ratios: &[f64], rng: &mut StdRng) -> usize {
 }
 
 /// Placement decision for the current iteration
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 struct PlacementDecision {
     /// World coordinates for placement
     world_position: [i32; 2],
     /// Tile reference (1-based index where 0=uninitialized, 1=empty, 2+=tiles)
     tile_reference: usize,
+    /// Why the tile is placed
+    source: DecisionSource,
+    /// Tiles the choice was made among, listed only while logging events
+    candidates: Vec<Candidate>,
 }
 
 /// Wave function collapse algorithm executor with information-theoretic tile selection
@@ -276,12 +355,36 @@ pub struct GreedyStochastic {
     pub visualization: Option<VisualizationCapture>,
     /// Optional analysis metrics capture
     pub analysis: Option<AnalysisCapture>,
-    /// Pre-allocated buffer to reduce allocations in hot path
-    prob_buffer: Vec<f64>,
+    /// Optional numeric field snapshots
+    pub fields: Option<FieldCapture>,
+    /// Optional log of placements and removals
+    pub events: Option<EventLog>,
+    /// Strategy used to choose the kernel density bandwidths
+    pub bandwidth_selection: BandwidthSelection,
+    /// Kernel density bandwidth chosen for each tile value pair
+    pub pair_bandwidths: Vec<PairBandwidth>,
+    /// Strategy for choosing non-forced placement positions
+    pub position_selector: Box<dyn PositionSelector>,
+    /// Strategy for choosing among viable tiles at a position
+    pub tile_chooser: Box<dyn TileChooser>,
+    /// Decisions recorded for backtracking, when enabled
+    pub decision_stack: Option<DecisionStack>,
+    /// Frontier domains kept arc consistent, when enabled
+    pub arc_consistency: Option<ArcConsistency>,
+    /// Shape, growth and limits of removal when resolving contradictions
+    pub deadlock_policy: DeadlockPolicy,
+    /// Removals performed so far
+    pub deadlock_stats: DeadlockStats,
+    /// Front each tile grew from, when growing from several seeds
+    pub growth_fronts: Option<GrowthFronts>,
+    /// World positions of the latest placements, newest last, for the recent removal shape
+    pub(crate) recent_placements: VecDeque<[i32; 2]>,
     /// Prefill data for predetermined placements
     prefill_data: Option<PrefillData>,
     /// Whether the initial placement has occurred
     initial_placement_done: bool,
+    /// Seed points still to be placed, the first taken by the initial placement
+    pending_seeds: VecDeque<[i32; 2]>,
 }
 
 impl GreedyStochastic {
@@ -302,18 +405,19 @@ impl GreedyStochastic {
             selection_coordinates,
             selection_tally,
             color_mapping,
+            pair_bandwidths,
         ) = load_and_initialize_data(seed, false, false)?;
 
         let feasibility_layer = FeasibilityCountLayer::new(
             grid_state.rows(),
             grid_state.cols(),
             step_data.source_tiles.len(),
-        );
+        )
+        .anchored(system_offset);
 
         let random_selector = RandomSelector::new(seed);
         let forced_pipeline = ForcedPipeline::new();
         let viable_tiles_cache = ViableTilesCache::new();
-        let cell_count = step_data.unique_cell_count;
 
         Ok(Self {
             step_data,
@@ -331,9 +435,21 @@ impl GreedyStochastic {
             viable_tiles_cache,
             visualization: None,
             analysis: None,
-            prob_buffer: Vec::with_capacity(cell_count),
+            fields: None,
+            events: None,
+            bandwidth_selection: BandwidthSelection::default(),
+            pair_bandwidths,
+            position_selector: PositionStrategy::default().selector(),
+            tile_chooser: TileStrategy::default().chooser(),
+            decision_stack: DeadlockStrategy::default().decision_stack(),
+            arc_consistency: PropagationMode::default().arc_consistency(),
+            deadlock_policy: DeadlockPolicy::default(),
+            deadlock_stats: DeadlockStats::default(),
+            growth_fronts: None,
+            recent_placements: VecDeque::new(),
             prefill_data: None,
             initial_placement_done: false,
+            pending_seeds: VecDeque::new(),
         })
     }
 
@@ -342,6 +458,8 @@ impl GreedyStochastic {
     /// # Errors
     ///
     /// Returns an error if:
+    /// - The deadlock policy is invalid
+    /// - The start position or growth seeds need bounds or lie outside them
     /// - Pattern statistics preprocessing fails
     /// - Grid initialization fails
     pub fn from_image_processor(
@@ -349,6 +467,8 @@ impl GreedyStochastic {
         config: AlgorithmConfig,
         seed: u64,
     ) -> crate::io::error::Result<Self> {
+        config.deadlock_policy.validate()?;
+
         let (source_data_2d, source_ratios, unique_cell_count, _, _, color_mapping) =
             image_processor.into_parts();
 
@@ -367,33 +487,54 @@ impl GreedyStochastic {
             config.pattern_influence_distance as f64,
         );
 
-        let mut statistics_processor = Processor::new(
+        let statistics_processor = Processor::new(
             source_data_2d,
             source_ratios.clone(),
             config.pattern_influence_distance,
             config.grid_extension_radius,
-        );
+        )
+        .with_bandwidth_selection(config.bandwidth_selection)
+        .with_kernel(config.kernel.kernel());
+        let mut statistics_processor = match config.spatial_mode {
+            SpatialMode::Isotropic => statistics_processor,
+            SpatialMode::Anisotropic => statistics_processor.with_anisotropy(OffsetSymmetry::new(
+                config.include_rotations,
+                config.include_reflections,
+            )),
+        };
 
         let probability_influence_matrices =
             statistics_processor.preprocess_pattern_statistics(&exponential_sample_points)?;
+        let pair_bandwidths = statistics_processor.selected_bandwidths().to_vec();
 
         let mut system_offset = [0, 0];
 
         // Initial tile selection weighted by source distribution
         let mut rng = StdRng::seed_from_u64(seed);
         let selected_cell_reference = select_initial_tile(&source_ratios, &mut rng);
-        let selection_coordinates = [0, 0];
         let selection_tally = vec![0; unique_cell_count];
         let mut grid_state = GridState::new(1, 1, unique_cell_count);
 
         // Calculate generation bounds if specified
         if let Some((width, height)) = config.bounds {
-            let half_width = width as i32 / 2;
-            let half_height = height as i32 / 2;
-            grid_state.generation_bounds = Some(crate::spatial::grid::BoundingBox {
-                min: [-half_width, -half_height],
-                max: [half_width - 1, half_height - 1],
-            });
+            grid_state.set_generation_bounds(
+                Some(config.bounds_anchor.bounds(width, height)),
+                system_offset,
+            );
+        }
+        let start = config
+            .start_position
+            .world_position(grid_state.generation_bounds.as_ref(), &mut rng)?;
+        let growth_fronts = config.growth_seeds.growth_fronts();
+        let mut pending_seeds: VecDeque<[i32; 2]> = config
+            .growth_seeds
+            .world_positions(start, grid_state.generation_bounds.as_ref(), &mut rng)?
+            .into();
+        // The first seed stays pending until the initial placement takes it,
+        // so a prefill that skips the initial placement still places it
+        let selection_coordinates = pending_seeds.front().copied().unwrap_or(start);
+        if growth_fronts.is_none() {
+            pending_seeds.clear();
         }
 
         let (new_offset, _) = grid_state.extend_if_needed(
@@ -418,12 +559,12 @@ impl GreedyStochastic {
             grid_state.rows(),
             grid_state.cols(),
             step_data.source_tiles.len(),
-        );
+        )
+        .anchored(system_offset);
 
         let random_selector = RandomSelector::new(seed);
         let forced_pipeline = ForcedPipeline::new();
         let viable_tiles_cache = ViableTilesCache::new();
-        let cell_count = step_data.unique_cell_count;
 
         Ok(Self {
             step_data,
@@ -441,9 +582,21 @@ impl GreedyStochastic {
             viable_tiles_cache,
             visualization: None,
             analysis: None,
-            prob_buffer: Vec::with_capacity(cell_count),
+            fields: None,
+            events: None,
+            bandwidth_selection: config.bandwidth_selection,
+            pair_bandwidths,
+            position_selector: config.position_strategy.selector(),
+            tile_chooser: config.tile_strategy.chooser(),
+            decision_stack: config.deadlock_strategy.decision_stack(),
+            arc_consistency: config.propagation_mode.arc_consistency(),
+            deadlock_policy: config.deadlock_policy,
+            deadlock_stats: DeadlockStats::default(),
+            growth_fronts,
+            recent_placements: VecDeque::new(),
             prefill_data: None,
             initial_placement_done: false,
+            pending_seeds,
         })
     }
 
@@ -452,6 +605,11 @@ impl GreedyStochastic {
         &self.grid_state
     }
 
+    /// Removals performed so far
+    pub const fn deadlock_stats(&self) -> &DeadlockStats {
+        &self.deadlock_stats
+    }
+
     /// Access tile color mapping
     pub fn color_mapping(&self) -> &[[u8; 4]] {
         &self.color_mapping
@@ -484,16 +642,19 @@ impl GreedyStochastic {
         }
 
         // Update generation bounds if necessary
-        if let Some(gen_bounds) = &mut self.grid_state.generation_bounds {
-            if !gen_bounds.contains(min_coords) || !gen_bounds.contains(max_coords) {
-                // Expand bounds to include prefill
-                gen_bounds.min[0] = gen_bounds.min[0].min(min_coords[0]);
-                gen_bounds.min[1] = gen_bounds.min[1].min(min_coords[1]);
-                gen_bounds.max[0] = gen_bounds.max[0].max(max_coords[0]);
-                gen_bounds.max[1] = gen_bounds.max[1].max(max_coords[1]);
-
-                eprintln!("Warning: Generation bounds expanded to accommodate prefill image");
-            }
+        if let Some(gen_bounds) = &self.grid_state.generation_bounds
+            && (!gen_bounds.contains(min_coords) || !gen_bounds.contains(max_coords))
+        {
+            // Expand bounds to include prefill
+            let mut expanded = gen_bounds.clone();
+            expanded.min[0] = expanded.min[0].min(min_coords[0]);
+            expanded.min[1] = expanded.min[1].min(min_coords[1]);
+            expanded.max[0] = expanded.max[0].max(max_coords[0]);
+            expanded.max[1] = expanded.max[1].max(max_coords[1]);
+            self.grid_state
+                .set_generation_bounds(Some(expanded), self.system_offset);
+
+            eprintln!("Warning: Generation bounds expanded to accommodate prefill image");
         }
 
         self.prefill_data = Some(prefill_data);
@@ -512,20 +673,77 @@ impl GreedyStochastic {
 
     /// Enable metrics recording for analysis
     pub fn enable_analysis(&mut self) {
-        self.analysis = Some(AnalysisCapture::new(
+        let mut analysis = AnalysisCapture::new(
             self.color_mapping.clone(),
             self.step_data.grid_extension_radius,
-        ));
+        );
+        analysis.set_bandwidths(self.bandwidth_selection, self.pair_bandwidths.clone());
+        self.analysis = Some(analysis);
+    }
+
+    /// Enable numeric field snapshots on a schedule, written as they are
+    /// taken into a staging directory owned by this run
+    pub fn enable_field_capture(&mut self, schedule: FieldSchedule, staging: PathBuf) {
+        self.fields = Some(FieldCapture::new(schedule, staging));
+    }
+
+    /// Enable logging of every placement, removal and backtrack
+    pub fn enable_event_log(&mut self) {
+        self.events = Some(EventLog::new());
     }
 
-    /// Export visualization as GIF if enabled
+    /// Export the event log as JSON Lines
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The event log was not enabled
+    /// - The file cannot be written
+    pub fn export_events(&self, output_path: &str) -> crate::io::error::Result<()> {
+        self.events.as_ref().map_or_else(
+            || {
+                Err(crate::io::error::AlgorithmError::InvalidParameter {
+                    parameter: "events",
+                    value: "disabled".to_string(),
+                    reason: "Event logging was not enabled for this run".to_string(),
+                })
+            },
+            |events| events.export_jsonl(output_path),
+        )
+    }
+
+    /// Export scheduled field snapshots and the current fields to a directory
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Field capture was not enabled
+    /// - The directory or a file cannot be written
+    pub fn export_fields(&self, directory: &Path) -> crate::io::error::Result<()> {
+        let fields = self.fields.as_ref().ok_or_else(|| {
+            crate::io::error::AlgorithmError::InvalidParameter {
+                parameter: "fields",
+                value: "disabled".to_string(),
+                reason: "Field capture was not enabled for this run".to_string(),
+            }
+        })?;
+        let final_snapshot =
+            FieldSnapshot::capture(&self.grid_state, self.system_offset, self.iteration);
+        fields.export(&final_snapshot, &self.color_mapping, directory)
+    }
+
+    /// Export visualization as an animation or frame sequence if enabled
     ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - Visualization was not enabled
-    /// - GIF export fails
-    pub fn export_visualization(&self, output_path: &str) -> crate::io::error::Result<()> {
+    /// - Animation export fails
+    pub fn export_visualization(
+        &self,
+        output_path: &str,
+        options: &AnimationOptions,
+    ) -> crate::io::error::Result<()> {
         self.visualization.as_ref().map_or_else(
             || {
                 Err(crate::io::error::AlgorithmError::InvalidParameter {
@@ -534,7 +752,7 @@ impl GreedyStochastic {
                     reason: "Visualization was not enabled for this run".to_string(),
                 })
             },
-            |viz| viz.export_gif(output_path, crate::io::configuration::GIF_FRAME_DELAY_MS),
+            |viz| viz.export_animation(output_path, options),
         )
     }
 
@@ -544,7 +762,8 @@ impl GreedyStochastic {
     ///
     /// Returns an error if:
     /// - No valid positions are found for tile placement
-    /// - No viable tiles exist at the selected position
+    /// - Selection reaches its contradiction retry cap without a viable tile
+    /// - Removals exceed the deadlock policy's limit
     pub fn execute_iteration(&mut self) -> crate::io::error::Result<bool> {
         self.run_iteration()
     }
@@ -555,9 +774,11 @@ impl GreedyStochastic {
     ///
     /// Returns an error if:
     /// - No valid positions are found for tile placement
-    /// - No viable tiles exist at the selected position
+    /// - Selection reaches its contradiction retry cap without a viable tile
+    /// - Removals exceed the deadlock policy's limit
     pub fn run_iteration(&mut self) -> crate::io::error::Result<bool> {
         self.iteration += 1;
+        self.decay_removal_counts();
 
         // Phase 1: Check if we're already complete
         if self.check_completion() {
@@ -571,7 +792,9 @@ impl GreedyStochastic {
         self.place_tile(decision);
 
         // Phase 4: Post-placement updates
-        self.post_placement_updates();
+        self.post_placement_updates()?;
+
+        self.record_fields()?;
 
         Ok(true)
     }
@@ -581,9 +804,23 @@ impl GreedyStochastic {
         // Special case: first iteration with no prefill
         if !self.initial_placement_done && self.prefill_data.is_none() {
             self.initial_placement_done = true;
+            self.pending_seeds.pop_front();
+            if let Some(fronts) = &mut self.growth_fronts {
+                fronts.start_front(self.selection_coordinates);
+            }
+            let candidates = if self.events.is_some() {
+                (1..)
+                    .zip(&self.step_data.source_ratios)
+                    .map(|(tile, &weight)| Candidate { tile, weight })
+                    .collect()
+            } else {
+                Vec::new()
+            };
             return Ok(PlacementDecision {
                 world_position: self.selection_coordinates,
                 tile_reference: self.selected_cell_reference,
+                source: DecisionSource::Initial,
+                candidates,
             });
         }
 
@@ -609,12 +846,21 @@ impl GreedyStochastic {
                     return Ok(PlacementDecision {
                         world_position: placement.world_position,
                         tile_reference: placement.tile_reference,
+                        source: DecisionSource::Prefill,
+                        candidates: Vec::new(),
                     });
                 }
                 // If not valid, skip this prefill position and try the next one
             }
         }
 
+        // Start the remaining fronts before growing any of them
+        while let Some(seed) = self.pending_seeds.pop_front() {
+            if let Some(decision) = self.seed_decision(seed) {
+                return Ok(decision);
+            }
+        }
+
         // Check forced pipeline
         while let Some(forced) = self.forced_pipeline.take_next() {
             // Validate that the forced position is still empty
@@ -632,10 +878,23 @@ impl GreedyStochastic {
                 false
             };
 
+            // A forced tile ruled out by backtracking is a contradiction
+            if is_valid
+                && self
+                    .decision_stack
+                    .as_ref()
+                    .is_some_and(|stack| stack.is_banned(forced.coordinates, forced.tile_reference))
+            {
+                self.resolve_contradiction([row, col])?;
+                break;
+            }
+
             if is_valid {
                 return Ok(PlacementDecision {
                     world_position: forced.coordinates,
                     tile_reference: forced.tile_reference,
+                    source: DecisionSource::Forced,
+                    candidates: Vec::new(),
                 });
             }
             // If not valid, skip this forced position and try the next one
@@ -647,133 +906,191 @@ impl GreedyStochastic {
 
     /// Select a position using the stochastic algorithm
     fn select_random_position(&mut self) -> crate::io::error::Result<PlacementDecision> {
-        let weight_result = calculate_position_selection(
-            &self.grid_state,
-            &self.selection_tally,
-            &self.step_data,
-            self.system_offset,
-        );
+        let mut contradictions = 0;
+        let (world_position, viable_tiles) = loop {
+            let position_context = PositionContext {
+                grid_state: &self.grid_state,
+                selection_tally: &self.selection_tally,
+                step_data: &self.step_data,
+                system_offset: self.system_offset,
+            };
 
-        let adjacency_candidates = top_k_valid_indices(
-            &weight_result.adjacency_matrix,
-            &weight_result.validity_matrix,
-            ADJACENCY_CANDIDATES_CONSIDERED,
-        );
+            let Some(selected_pos) = self
+                .position_selector
+                .select(&position_context, &mut self.random_selector)
+            else {
+                return Err(crate::io::error::AlgorithmError::NoValidPositions {
+                    iteration: self.iteration,
+                    grid_dimensions: (self.grid_state.rows(), self.grid_state.cols()),
+                });
+            };
 
-        let selection_candidates = top_k_from_indices(
-            &weight_result.weight_matrix,
-            &adjacency_candidates,
-            CANDIDATES_CONSIDERED,
-        );
+            let world_position = [
+                selected_pos[0] as i32 - self.system_offset[0],
+                selected_pos[1] as i32 - self.system_offset[1],
+            ];
 
-        let candidate_weights: Vec<f64> = selection_candidates
-            .iter()
-            .map(|&[i, j]| {
-                weight_result
-                    .weight_matrix
-                    .get([i, j])
-                    .copied()
-                    .unwrap_or(0.0)
-            })
-            .collect();
+            let mut viable_tiles = compute_viable_tiles_at_position(
+                &self.grid_state,
+                world_position,
+                self.system_offset,
+                &self.step_data.source_tiles,
+                &self.step_data,
+                &mut self.viable_tiles_cache,
+            );
+            if let Some(stack) = &self.decision_stack {
+                viable_tiles.retain(|&tile| !stack.is_banned(world_position, tile));
+            }
 
-        if selection_candidates.is_empty() {
-            return Err(crate::io::error::AlgorithmError::NoValidPositions {
-                iteration: self.iteration,
-                grid_dimensions: (self.grid_state.rows(), self.grid_state.cols()),
-            });
-        }
+            if !viable_tiles.is_empty() {
+                break (world_position, viable_tiles);
+            }
 
-        let selected_index = self.random_selector.weighted_choice(&candidate_weights);
-        let selected_pos = selection_candidates
-            .get(selected_index)
-            .copied()
-            .unwrap_or([0, 0]);
+            // Resolve the contradiction, then retry selection, giving up when
+            // resolutions keep leaving every selected position without a tile
+            if contradictions == MAX_SELECTION_CONTRADICTIONS {
+                return Err(crate::io::error::AlgorithmError::SelectionExhausted {
+                    contradictions,
+                    position: world_position,
+                    iteration: self.iteration,
+                });
+            }
+            contradictions += 1;
+            self.resolve_contradiction(selected_pos)?;
+        };
+
+        Ok(self.choose_tile(world_position, &viable_tiles, DecisionSource::Selected))
+    }
 
-        let world_position = [
-            selected_pos[0] as i32 - self.system_offset[0],
-            selected_pos[1] as i32 - self.system_offset[1],
+    /// Start a front at a seed point, unless a front already reached it
+    ///
+    /// The seed's tile is chosen like any random placement, so it agrees
+    /// with any tiles already near it. Returns `None` when the point is
+    /// filled or no tile fits there.
+    fn seed_decision(&mut self, seed: [i32; 2]) -> Option<PlacementDecision> {
+        self.extend_grid(seed, self.step_data.grid_extension_radius);
+        let index = [
+            usize::try_from(seed[0] + self.system_offset[0]).ok()?,
+            usize::try_from(seed[1] + self.system_offset[1]).ok()?,
         ];
+        if self
+            .grid_state
+            .locked_tiles
+            .get(index)
+            .is_none_or(|&locked| locked > 1)
+        {
+            return None;
+        }
 
         let viable_tiles = compute_viable_tiles_at_position(
             &self.grid_state,
-            world_position,
+            seed,
             self.system_offset,
             &self.step_data.source_tiles,
             &self.step_data,
             &mut self.viable_tiles_cache,
         );
-
         if viable_tiles.is_empty() {
-            // Trigger deadlock resolution
-            self.resolve_deadlock(selected_pos, self.iteration);
-            self.forced_pipeline = ForcedPipeline::default();
+            return None;
+        }
 
-            // Retry selection after deadlock resolution
-            return self.select_random_position();
+        if let Some(fronts) = &mut self.growth_fronts {
+            fronts.start_front(seed);
         }
+        Some(self.choose_tile(seed, &viable_tiles, DecisionSource::Seed))
+    }
 
+    /// Choose among the viable tiles at a position and record the decision
+    fn choose_tile(
+        &mut self,
+        world_position: [i32; 2],
+        viable_tiles: &[usize],
+        source: DecisionSource,
+    ) -> PlacementDecision {
         let probabilities = get_tile_probabilities_at_position(
             &self.grid_state,
             world_position,
             self.system_offset,
         );
 
-        let total_placed = self.selection_tally.iter().sum::<usize>();
-
-        // Calculate density correction factors
-        self.prob_buffer.clear();
-        for i in 0..self.step_data.unique_cell_count {
-            let p = self.step_data.source_ratios.get(i).copied().unwrap_or(0.0);
-            let k = self.selection_tally.get(i).copied().unwrap_or(0);
-            let n = total_placed;
-            let cdf_value = binomial_normal_approximate_cdf(n, p, k);
-            self.prob_buffer.push(cdf_value - 0.5);
-        }
-
-        let log_corrected_weights = density_corrected_log_tile_weights(
-            &viable_tiles,
-            &probabilities,
-            &self.selection_tally,
-            &self.step_data.source_ratios,
-            total_placed,
-            &self.prob_buffer,
-        );
-
+        let choice_context = TileChoiceContext {
+            viable_tiles,
+            probabilities: &probabilities,
+            selection_tally: &self.selection_tally,
+            source_ratios: &self.step_data.source_ratios,
+        };
         let tile_idx = self
-            .random_selector
-            .log_weighted_choice(&log_corrected_weights);
+            .tile_chooser
+            .choose(&choice_context, &mut self.random_selector);
         let tile_reference = viable_tiles.get(tile_idx).copied().unwrap_or(1);
 
-        Ok(PlacementDecision {
+        if let Some(stack) = &mut self.decision_stack {
+            stack.push_decision(world_position, tile_reference);
+        }
+
+        let candidates = if self.events.is_some() {
+            viable_tiles
+                .iter()
+                .map(|&tile| Candidate {
+                    tile,
+                    weight: probabilities.get(tile - 1).copied().unwrap_or(0.0),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        PlacementDecision {
             world_position,
             tile_reference,
-        })
+            source,
+            candidates,
+        }
     }
 
     /// Place a tile and update all state
     fn place_tile(&mut self, decision: PlacementDecision) {
+        if let Some(events) = &mut self.events {
+            events.record(GenerationEvent::Placement {
+                iteration: self.iteration,
+                position: decision.world_position,
+                tile: decision.tile_reference,
+                source: decision.source,
+                candidates: decision.candidates,
+            });
+        }
+
         // Update tally
         if let Some(tally) = self.selection_tally.get_mut(decision.tile_reference - 1) {
             *tally += 1;
         }
 
+        if let Some(stack) = &mut self.decision_stack {
+            stack.record_placement(decision.world_position);
+        }
+        if let Some(arc_consistency) = &mut self.arc_consistency {
+            arc_consistency.record_placement(decision.world_position);
+        }
+        if let Some(fronts) = &mut self.growth_fronts {
+            fronts.record_placement(decision.world_position);
+        }
+        let history_len = self.deadlock_policy.shape.history_len();
+        if history_len > 0 {
+            if self.recent_placements.len() >= history_len {
+                self.recent_placements.pop_front();
+            }
+            self.recent_placements.push_back(decision.world_position);
+        }
+
         // Set current selection state
         self.selected_cell_reference = decision.tile_reference;
         self.selection_coordinates = decision.world_position;
 
         // Extend grid if needed
-        let (new_offset, extended) = self.grid_state.extend_if_needed(
-            self.system_offset,
-            &decision.world_position,
+        self.extend_grid(
+            decision.world_position,
             self.step_data.grid_extension_radius,
         );
-        self.system_offset = new_offset;
-
-        if extended {
-            self.feasibility_layer
-                .extend_to(self.grid_state.rows(), self.grid_state.cols());
-        }
 
         // Update all state matrices
         update_probabilities_and_entropy(
@@ -802,6 +1119,7 @@ impl GreedyStochastic {
             self.system_offset,
             &mut self.visualization,
             self.iteration,
+            decision.source,
         );
 
         update_feasibility_counts(
@@ -813,49 +1131,239 @@ impl GreedyStochastic {
         );
     }
 
+    /// Place a tile chosen outside the normal decision pipeline
+    ///
+    /// Counts as an iteration and replaces the pending initial placement
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if removals exceed the deadlock policy's limit
+    pub(crate) fn place_seed(
+        &mut self,
+        world_position: [i32; 2],
+        tile_reference: usize,
+    ) -> crate::io::error::Result<()> {
+        self.iteration += 1;
+        self.skip_initial_placement();
+        self.place_tile(PlacementDecision {
+            world_position,
+            tile_reference,
+            source: DecisionSource::Seed,
+            candidates: Vec::new(),
+        });
+        self.post_placement_updates()
+    }
+
+    /// Place a known tile without forced detection or contradiction checks
+    ///
+    /// Used to copy tiles generated elsewhere; replaces the pending initial placement
+    pub(crate) fn place_fixed(&mut self, world_position: [i32; 2], tile_reference: usize) {
+        self.skip_initial_placement();
+        self.place_tile(PlacementDecision {
+            world_position,
+            tile_reference,
+            source: DecisionSource::Fixed,
+            candidates: Vec::new(),
+        });
+    }
+
+    /// Drop the pending placement at the origin
+    pub(crate) const fn skip_initial_placement(&mut self) {
+        self.initial_placement_done = true;
+    }
+
+    /// Place a tile recorded in an event log, without looking for forced
+    /// positions or contradictions, which the log already resolved
+    pub(crate) fn replay_placement(
+        &mut self,
+        world_position: [i32; 2],
+        tile_reference: usize,
+        source: DecisionSource,
+        candidates: Vec<Candidate>,
+    ) {
+        self.skip_initial_placement();
+        self.place_tile(PlacementDecision {
+            world_position,
+            tile_reference,
+            source,
+            candidates,
+        });
+    }
+
+    /// Write scheduled field snapshots for the current iteration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a snapshot cannot be written
+    pub(crate) fn record_fields(&mut self) -> crate::io::error::Result<()> {
+        match &mut self.fields {
+            Some(fields) => fields.record(&self.grid_state, self.system_offset, self.iteration),
+            None => Ok(()),
+        }
+    }
+
+    /// Extend the grid and feasibility layer to cover a world position plus radius
+    pub(crate) fn extend_grid(&mut self, position: [i32; 2], radius: i32) {
+        let previous_offset = self.system_offset;
+        let (new_offset, extended) =
+            self.grid_state
+                .extend_if_needed(self.system_offset, &position, radius);
+        self.system_offset = new_offset;
+
+        if extended {
+            self.feasibility_layer.extend_with_shift(
+                self.grid_state.rows(),
+                self.grid_state.cols(),
+                [
+                    (new_offset[0] - previous_offset[0]) as usize,
+                    (new_offset[1] - previous_offset[1]) as usize,
+                ],
+            );
+        }
+    }
+
     /// Perform post-placement updates
-    fn post_placement_updates(&mut self) {
+    fn post_placement_updates(&mut self) -> crate::io::error::Result<()> {
         // Detect new forced positions
-        let new_forced = detect_forced_positions(
-            &self.grid_state,
-            self.selection_coordinates,
-            self.system_offset,
-            &self.step_data.source_tiles,
-            &self.step_data,
-            &mut self.viable_tiles_cache,
-        );
+        let new_forced = match &mut self.arc_consistency {
+            Some(arc_consistency) => {
+                let propagation = arc_consistency.propagate(
+                    &self.grid_state,
+                    self.system_offset,
+                    &self.step_data,
+                    &mut self.viable_tiles_cache,
+                );
+                // Emptied domains are resolved before selection reaches them
+                if let Some(contradiction_pos) = propagation.contradiction {
+                    return self.resolve_contradiction(contradiction_pos);
+                }
+                propagation.forced
+            }
+            None => detect_forced_positions(
+                &self.grid_state,
+                self.selection_coordinates,
+                self.system_offset,
+                &self.step_data.source_tiles,
+                &self.step_data,
+                &mut self.viable_tiles_cache,
+            ),
+        };
 
         self.forced_pipeline.add_positions(new_forced);
 
         // Check for contradictions
         if let Some(contradiction_pos) = check_for_contradiction(
-            &self.grid_state,
+            &mut self.grid_state,
             self.system_offset,
             &self.step_data,
             &mut self.viable_tiles_cache,
         ) {
-            self.resolve_deadlock(contradiction_pos, self.iteration);
-            self.forced_pipeline = ForcedPipeline::default();
+            self.resolve_contradiction(contradiction_pos)?;
         }
+        Ok(())
     }
 
     /// Check if generation is complete
     fn check_completion(&self) -> bool {
+        self.unfilled_in_bounds() == Some(0)
+    }
+
+    /// Number of positions inside the generation bounds, if any
+    pub fn cells_in_bounds(&self) -> Option<usize> {
+        self.grid_state.generation_bounds.as_ref().map(|bounds| {
+            let width = (bounds.max[0] - bounds.min[0] + 1) as usize;
+            let height = (bounds.max[1] - bounds.min[1] + 1) as usize;
+            width * height
+        })
+    }
+
+    /// Number of positions inside the generation bounds still without a tile
+    pub fn unfilled_in_bounds(&self) -> Option<usize> {
+        self.cells_in_bounds()
+            .map(|cells| cells.saturating_sub(self.grid_state.placed_in_bounds))
+    }
+
+    /// Tile reference placed at a world position, or `None` if it is open or off the grid
+    pub fn tile_at(&self, world: [i32; 2]) -> Option<u32> {
+        let row = usize::try_from(world[0] + self.system_offset[0]).ok()?;
+        let col = usize::try_from(world[1] + self.system_offset[1]).ok()?;
         self.grid_state
-            .generation_bounds
-            .as_ref()
-            .is_some_and(|bounds| {
-                let filled_positions = self.selection_tally.iter().sum::<usize>();
-                let width = (bounds.max[0] - bounds.min[0] + 1) as usize;
-                let height = (bounds.max[1] - bounds.min[1] + 1) as usize;
-                let positions_in_bounds = width * height;
-
-                filled_positions >= positions_in_bounds
-            })
+            .locked_tiles
+            .get([row, col])
+            .filter(|&&locked| locked > 1)
+            .map(|&locked| locked - 1)
+    }
+
+    /// Resolve a contradiction by backtracking when enabled and possible,
+    /// otherwise by unlocking tiles around it
+    fn resolve_contradiction(
+        &mut self,
+        contradiction_pos: [usize; 2],
+    ) -> crate::io::error::Result<()> {
+        let conflict = [
+            contradiction_pos[0] as i32 - self.system_offset[0],
+            contradiction_pos[1] as i32 - self.system_offset[1],
+        ];
+        let backtrack = self
+            .decision_stack
+            .as_mut()
+            .and_then(|stack| stack.backtrack(conflict));
+
+        if let Some(backtrack) = backtrack {
+            let removed = self.undo_placements(&backtrack.undone);
+            if let Some(events) = &mut self.events {
+                events.record(GenerationEvent::Backtrack {
+                    iteration: self.iteration,
+                    position: conflict,
+                    removed,
+                    banned: backtrack.banned,
+                });
+            }
+        } else {
+            if let (Some(stack), Some(events)) = (&self.decision_stack, &mut self.events) {
+                events.record(GenerationEvent::Fallback {
+                    iteration: self.iteration,
+                    position: conflict,
+                    backtracks: stack.backtracks(),
+                    exhausted: stack.is_exhausted(),
+                });
+            }
+            self.resolve_deadlock(contradiction_pos, self.iteration)?;
+        }
+        self.forced_pipeline = ForcedPipeline::default();
+        Ok(())
     }
 
     /// Unlock tiles around a contradiction to allow algorithm progression
-    pub fn resolve_deadlock(&mut self, contradiction_pos: [usize; 2], iteration: usize) {
+    ///
+    /// Recorded decisions and bans around the removed tiles are forgotten,
+    /// since the removal breaks their trail, and consistent domains are
+    /// cleared since removed tiles can widen any domain
+    ///
+    /// # Errors
+    ///
+    /// Returns `Unsolvable` once the deadlock policy's removal limit is reached
+    pub fn resolve_deadlock(
+        &mut self,
+        contradiction_pos: [usize; 2],
+        iteration: usize,
+    ) -> crate::io::error::Result<()> {
+        let position = [
+            contradiction_pos[0] as i32 - self.system_offset[0],
+            contradiction_pos[1] as i32 - self.system_offset[1],
+        ];
+        if self
+            .deadlock_policy
+            .max_attempts
+            .is_some_and(|max_attempts| self.deadlock_stats.deadlocks >= max_attempts)
+        {
+            return Err(crate::io::error::AlgorithmError::Unsolvable {
+                deadlocks: self.deadlock_stats.deadlocks,
+                position,
+                iteration,
+            });
+        }
+
         let result = crate::algorithm::deadlock::resolve_spatial_deadlock(
             &mut self.grid_state,
             &mut self.feasibility_layer,
@@ -864,13 +1372,167 @@ impl GreedyStochastic {
             &mut self.selection_tally,
             &self.step_data,
             &self.probability_influence_matrices,
+            &self.deadlock_policy,
+            &self.recent_placements,
+            self.growth_fronts.as_ref(),
             &mut self.visualization,
             iteration,
         );
+        self.deadlock_stats.record(position, result.tiles_unlocked);
+        self.forget_fronts(&result.unlocked_positions);
+        let removed: Vec<[i32; 2]> = result
+            .unlocked_positions
+            .iter()
+            .map(|&[row, col]| {
+                [
+                    row as i32 - self.system_offset[0],
+                    col as i32 - self.system_offset[1],
+                ]
+            })
+            .collect();
+
+        // The contradiction's own bans are lifted even when nothing was removed
+        if let Some(stack) = &mut self.decision_stack {
+            stack.forget(&[removed.as_slice(), &[position]].concat());
+        }
+        if let Some(events) = &mut self.events {
+            events.record(GenerationEvent::Removal {
+                iteration,
+                position,
+                radius: result.removal_radius,
+                removed,
+            });
+        }
+        // Removed tiles can widen any domain
+        if let Some(arc_consistency) = &mut self.arc_consistency {
+            arc_consistency.clear();
+        }
+        self.requeue_prefill(&result.unlocked_positions);
+        Ok(())
+    }
+
+    /// Lower the removal count at every deadlock position when the policy's
+    /// decay interval elapses, so old hotspots shrink back to the base radius
+    pub(crate) fn decay_removal_counts(&mut self) {
+        let Some(interval) = self.deadlock_policy.decay_interval else {
+            return;
+        };
+        if !self.iteration.is_multiple_of(interval) {
+            return;
+        }
+        for position in self.deadlock_stats.hotspots.keys() {
+            // Evicted chunks keep their counts until restored
+            if self
+                .grid_state
+                .evicted_chunks
+                .contains(&chunk_of(*position))
+            {
+                continue;
+            }
+            let (Ok(row), Ok(col)) = (
+                usize::try_from(position[0] + self.system_offset[0]),
+                usize::try_from(position[1] + self.system_offset[1]),
+            ) else {
+                continue;
+            };
+            if self
+                .grid_state
+                .removal_count
+                .get([row, col])
+                .is_some_and(|&count| count > 0)
+                && let Some(count) = self.grid_state.removal_count.get_mut([row, col])
+            {
+                *count -= 1;
+            }
+        }
+    }
+
+    /// Unlock the tiles at world positions undone by a backtrack
+    ///
+    /// Returns the world positions that held tiles, in the order given
+    pub(crate) fn undo_placements(&mut self, positions: &[[i32; 2]]) -> Vec<[i32; 2]> {
+        let (Some(min), Some(max)) = (
+            positions
+                .iter()
+                .copied()
+                .reduce(|a, b| [a[0].min(b[0]), a[1].min(b[1])]),
+            positions
+                .iter()
+                .copied()
+                .reduce(|a, b| [a[0].max(b[0]), a[1].max(b[1])]),
+        ) else {
+            return Vec::new();
+        };
+        self.unlock_positions(positions, min, max)
+    }
+
+    /// Unlock the tiles at world positions, refreshing feasibility over the
+    /// world rectangle `min..=max`
+    ///
+    /// Returns the world positions that held tiles, in the order given
+    pub(crate) fn unlock_positions(
+        &mut self,
+        positions: &[[i32; 2]],
+        min: [i32; 2],
+        max: [i32; 2],
+    ) -> Vec<[i32; 2]> {
+        let tiles: Vec<([usize; 2], u32)> = positions
+            .iter()
+            .filter_map(|&position| {
+                let row = usize::try_from(position[0] + self.system_offset[0]).ok()?;
+                let col = usize::try_from(position[1] + self.system_offset[1]).ok()?;
+                let locked = *self.grid_state.locked_tiles.get([row, col])?;
+                (locked > 1).then_some(([row, col], locked - 1))
+            })
+            .collect();
+
+        crate::algorithm::deadlock::unlock_tiles(
+            &mut self.grid_state,
+            &mut self.feasibility_layer,
+            &tiles,
+            min,
+            max,
+            self.system_offset,
+            &mut self.selection_tally,
+            &self.step_data,
+            &self.probability_influence_matrices,
+            &mut self.visualization,
+            self.iteration,
+        );
+
+        if let Some(arc_consistency) = &mut self.arc_consistency {
+            arc_consistency.clear();
+        }
+        let unlocked: Vec<[usize; 2]> = tiles.iter().map(|&(index, _)| index).collect();
+        self.forget_fronts(&unlocked);
+        self.requeue_prefill(&unlocked);
+        unlocked
+            .iter()
+            .map(|&[row, col]| {
+                [
+                    row as i32 - self.system_offset[0],
+                    col as i32 - self.system_offset[1],
+                ]
+            })
+            .collect()
+    }
+
+    /// Drop the front labels of unlocked grid positions
+    fn forget_fronts(&mut self, unlocked_positions: &[[usize; 2]]) {
+        if let Some(fronts) = &mut self.growth_fronts {
+            for &[row, col] in unlocked_positions {
+                fronts.record_removal([
+                    row as i32 - self.system_offset[0],
+                    col as i32 - self.system_offset[1],
+                ]);
+            }
+        }
+    }
 
-        // Queue replacements for any removed protected positions
+    /// Queue replacements for any removed protected positions
+    fn requeue_prefill(&mut self, unlocked_positions: &[[usize; 2]]) {
         if let Some(prefill) = &mut self.prefill_data {
-            for &[row, col] in &result.unlocked_positions {
+            for &[row, col] in unlocked_positions {
                 let world_pos = [
                     row as i32 - self.system_offset[0],
                     col as i32 - self.system_offset[1],
@@ -1,10 +1,16 @@
-use crate::spatial::tiles::{Tile, convert_tile_to_membership_booleans};
-use ndarray::Array2;
+use crate::spatial::{
+    chunked::ChunkedGrid,
+    extension::ExtensionInfo,
+    tiles::{Tile, convert_tile_to_membership_booleans},
+};
 use std::collections::HashMap;
 
 /// Stores counts of tiles that can legally match each 3x3 region for feasibility scoring
+///
+/// Counts use the same chunked storage as the grid state, so growing the
+/// layer moves its bounds in place instead of copying existing counts
 pub struct FeasibilityCountLayer {
-    counts: Array2<usize>,
+    counts: ChunkedGrid<usize>,
     tile_count: usize,
 }
 
@@ -12,11 +18,20 @@ impl FeasibilityCountLayer {
     /// Create a count layer initialized with all tiles feasible at each position
     pub fn new(rows: usize, cols: usize, tile_count: usize) -> Self {
         Self {
-            counts: Array2::from_elem((rows, cols), tile_count),
+            counts: ChunkedGrid::new(rows, cols, tile_count, tile_count),
             tile_count,
         }
     }
 
+    /// Anchor the count storage to world coordinates
+    ///
+    /// Lets chunks be evicted and restored alongside the grid state
+    #[must_use]
+    pub fn anchored(mut self, system_offset: [i32; 2]) -> Self {
+        self.counts = self.counts.anchored(system_offset);
+        self
+    }
+
     /// Update the feasible tile count for a 3x3 region centered at (row, col)
     ///
     /// Matches the `tile_grid` pattern against source tiles using dispatch rules
@@ -86,24 +101,45 @@ impl FeasibilityCountLayer {
         }
     }
 
-    /// Resize the count array while preserving existing data
+    /// Grow the count array while preserving existing data
     ///
-    /// New positions are initialized with full feasibility (all tiles viable)
-    pub fn extend_to(&mut self, new_rows: usize, new_cols: usize) {
-        if new_rows == self.counts.nrows() && new_cols == self.counts.ncols() {
-            return;
-        }
+    /// New positions are initialized with full feasibility (all tiles viable).
+    /// Existing counts keep their indices; use `extend_with_shift` when the
+    /// grid also grows towards negative coordinates.
+    pub const fn extend_to(&mut self, new_rows: usize, new_cols: usize) {
+        self.extend_with_shift(new_rows, new_cols, [0, 0]);
+    }
 
-        let mut new_counts = Array2::from_elem((new_rows, new_cols), self.tile_count);
-        for i in 0..self.counts.nrows().min(new_rows) {
-            for j in 0..self.counts.ncols().min(new_cols) {
-                if let Some(count) = self.counts.get([i, j]).copied() {
-                    if let Some(new_count) = new_counts.get_mut([i, j]) {
-                        *new_count = count;
-                    }
-                }
-            }
-        }
-        self.counts = new_counts;
+    /// Grow the count array, moving existing counts down and right by `origin_shift`
+    ///
+    /// Matches a grid extension whose system offset grew by `origin_shift`
+    pub const fn extend_with_shift(
+        &mut self,
+        new_rows: usize,
+        new_cols: usize,
+        origin_shift: [usize; 2],
+    ) {
+        let (rows, cols) = self.counts.dim();
+        let info = ExtensionInfo {
+            pad_left: origin_shift[0],
+            pad_right: new_rows.saturating_sub(rows + origin_shift[0]),
+            pad_top: origin_shift[1],
+            pad_bottom: new_cols.saturating_sub(cols + origin_shift[1]),
+            new_offset: [0, 0],
+            needs_extension: new_rows > rows || new_cols > cols,
+        };
+        self.counts.extend(&info);
+    }
+
+    /// Remove the counts of a world chunk, which then read as fully feasible
+    pub fn take_chunk(&mut self, chunk: [i32; 2]) -> Option<Box<[usize]>> {
+        self.counts.take_chunk(chunk)
+    }
+
+    /// Store the counts of a world chunk
+    ///
+    /// Returns `false` if `counts` does not cover a whole chunk
+    pub fn insert_chunk(&mut self, chunk: [i32; 2], counts: Box<[usize]>) -> bool {
+        self.counts.insert_chunk(chunk, counts)
     }
 }
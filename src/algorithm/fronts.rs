@@ -0,0 +1,337 @@
+//! Growth from several seed points at once
+//!
+//! Each seed point starts its own growth front, placed over the first
+//! iterations before any other selection. Fronts then spread independently
+//! until they meet. Every placed tile is labelled with the front it grew
+//! from, so a contradiction on the seam between two fronts can be cleared by
+//! removing tiles of one side only: the other side is left intact and the
+//! removed side regrows against it, rather than both sides being carved back.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use rand::rngs::StdRng;
+
+use crate::{
+    algorithm::origin::StartPosition,
+    io::{
+        configuration::{DEFAULT_SEED_SPACING, MAX_SEED_PIXELS},
+        error::{AlgorithmError, Result, invalid_parameter},
+    },
+    spatial::grid::BoundingBox,
+};
+
+/// Random draws allowed per seed point when sampling with minimum spacing
+const SAMPLING_ATTEMPTS_PER_SEED: usize = 1000;
+
+/// Output pixels given as growth seeds, at most `MAX_SEED_PIXELS` of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeedPixels {
+    /// (row, column) pixels, unused slots left at zero
+    pixels: [[usize; 2]; MAX_SEED_PIXELS],
+    len: usize,
+}
+
+impl SeedPixels {
+    /// Collect the given (row, column) pixels
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are more than `MAX_SEED_PIXELS` pixels
+    pub fn new(pixels: &[[usize; 2]]) -> Result<Self> {
+        let mut seed_pixels = Self {
+            pixels: [[0; 2]; MAX_SEED_PIXELS],
+            len: pixels.len(),
+        };
+        seed_pixels
+            .pixels
+            .get_mut(..pixels.len())
+            .ok_or_else(|| {
+                invalid_parameter(
+                    "growth seeds",
+                    &pixels.len(),
+                    &format!("at most {MAX_SEED_PIXELS} pixels can be given"),
+                )
+            })?
+            .copy_from_slice(pixels);
+        Ok(seed_pixels)
+    }
+
+    /// The pixels in the order given
+    pub fn as_slice(&self) -> &[[usize; 2]] {
+        self.pixels.get(..self.len).unwrap_or_default()
+    }
+}
+
+/// Points from which generation grows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GrowthSeeds {
+    /// A single front from the start position
+    #[default]
+    Single,
+    /// The start position plus random points, all at least `min_spacing`
+    /// cells apart by Euclidean distance
+    Sampled {
+        /// Total number of seed points
+        count: usize,
+        /// Smallest distance between any two seed points
+        min_spacing: usize,
+    },
+    /// The given (row, column) pixels of the output, replacing the start position
+    Pixels(SeedPixels),
+}
+
+impl GrowthSeeds {
+    /// Seeds at the given (row, column) pixels of the output
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are more than `MAX_SEED_PIXELS` pixels
+    pub fn pixels(pixels: &[[usize; 2]]) -> Result<Self> {
+        SeedPixels::new(pixels).map(Self::Pixels)
+    }
+
+    /// World positions of the seed points, starting with the first to place
+    ///
+    /// `start` is the resolved start position. Only sampling draws from `rng`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if several seeds are requested without bounds, a
+    /// pixel lies outside the bounds, or the sampled points cannot be spaced
+    /// within them
+    pub fn world_positions(
+        self,
+        start: [i32; 2],
+        bounds: Option<&BoundingBox>,
+        rng: &mut StdRng,
+    ) -> Result<Vec<[i32; 2]>> {
+        let bounds = match (&self, bounds) {
+            (Self::Single, _) => return Ok(vec![start]),
+            (_, Some(bounds)) => bounds,
+            (_, None) => {
+                return Err(invalid_parameter(
+                    "growth seeds",
+                    &self,
+                    &"several seeds require generation bounds",
+                ));
+            }
+        };
+
+        match self {
+            Self::Single => Ok(vec![start]),
+            Self::Pixels(pixels) => {
+                let mut positions = Vec::with_capacity(pixels.as_slice().len());
+                for &pixel in pixels.as_slice() {
+                    let position = StartPosition::Pixel(pixel).world_position(Some(bounds), rng)?;
+                    if !positions.contains(&position) {
+                        positions.push(position);
+                    }
+                }
+                Ok(positions)
+            }
+            Self::Sampled { count, min_spacing } => {
+                let spacing = (min_spacing * min_spacing) as i64;
+                let mut positions = vec![start];
+                for _ in 0..count.saturating_mul(SAMPLING_ATTEMPTS_PER_SEED) {
+                    if positions.len() >= count {
+                        break;
+                    }
+                    let candidate = StartPosition::Random.world_position(Some(bounds), rng)?;
+                    let spaced = positions.iter().all(|position| {
+                        let dr = i64::from(candidate[0] - position[0]);
+                        let dc = i64::from(candidate[1] - position[1]);
+                        dr * dr + dc * dc >= spacing
+                    });
+                    if spaced {
+                        positions.push(candidate);
+                    }
+                }
+
+                if positions.len() < count {
+                    return Err(invalid_parameter(
+                        "growth seeds",
+                        &self,
+                        &format!(
+                            "only {} seeds fit {min_spacing} cells apart in the bounds",
+                            positions.len()
+                        ),
+                    ));
+                }
+                Ok(positions)
+            }
+        }
+    }
+
+    /// Fronts tracker for these seeds, if there is more than one
+    pub fn growth_fronts(&self) -> Option<GrowthFronts> {
+        match self {
+            Self::Single => None,
+            Self::Sampled { .. } | Self::Pixels(_) => Some(GrowthFronts::new()),
+        }
+    }
+}
+
+impl std::fmt::Display for GrowthSeeds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Single => write!(f, "single"),
+            Self::Sampled { count, min_spacing } => write!(f, "{count}:{min_spacing}"),
+            Self::Pixels(pixels) => {
+                let points: Vec<String> = pixels
+                    .as_slice()
+                    .iter()
+                    .map(|[row, col]| format!("{row},{col}"))
+                    .collect();
+                write!(f, "{}", points.join(";"))
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for GrowthSeeds {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            invalid_parameter(
+                "growth seeds",
+                &s,
+                &"expected 'single', '<count>', '<count>:<spacing>' or '<row>,<col>;<row>,<col>...'",
+            )
+        };
+        let trimmed = s.trim();
+
+        if trimmed.eq_ignore_ascii_case("single") || trimmed == "1" {
+            return Ok(Self::Single);
+        }
+        if trimmed.contains(',') {
+            let pixels = trimmed
+                .split(';')
+                .map(|point| match point.parse::<StartPosition>() {
+                    Ok(StartPosition::Pixel(pixel)) => Ok(pixel),
+                    _ => Err(invalid()),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            return Self::pixels(&pixels);
+        }
+
+        let (count, min_spacing) = match trimmed.split_once(':') {
+            Some((count, spacing)) => (count.parse(), spacing.parse()),
+            None => (trimmed.parse(), Ok(DEFAULT_SEED_SPACING)),
+        };
+        match (count, min_spacing) {
+            (Ok(1), Ok(_)) => Ok(Self::Single),
+            (Ok(count), Ok(min_spacing)) if count > 1 => Ok(Self::Sampled { count, min_spacing }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Front each placed tile grew from
+///
+/// Fronts are numbered in the order their seeds were placed. A tile joins the
+/// front holding most of its eight neighbours, the lowest numbered on a tie;
+/// tiles with no labelled neighbour, such as prefill, belong to no front.
+#[derive(Debug, Clone, Default)]
+pub struct GrowthFronts {
+    /// Front of each labelled tile by world position
+    labels: HashMap<[i32; 2], usize>,
+    /// Fronts started so far
+    fronts: usize,
+    /// Pairs of fronts that have met, lower first
+    merged: BTreeSet<(usize, usize)>,
+}
+
+impl GrowthFronts {
+    /// Create a tracker with no fronts
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new front at a seed position, returning its number
+    pub fn start_front(&mut self, position: [i32; 2]) -> usize {
+        let front = self.fronts;
+        self.fronts += 1;
+        self.labels.insert(position, front);
+        front
+    }
+
+    /// Label a placed tile from its neighbours and record any fronts it joins
+    ///
+    /// Seed positions keep the front they started.
+    pub fn record_placement(&mut self, position: [i32; 2]) {
+        let around = self.neighbour_counts(position);
+        let front = self.labels.get(&position).copied().or_else(|| {
+            around
+                .iter()
+                .max_by_key(|&(&front, &count)| (count, std::cmp::Reverse(front)))
+                .map(|(&front, _)| front)
+        });
+        let Some(front) = front else {
+            return;
+        };
+        self.labels.insert(position, front);
+        for &other in around.keys() {
+            if other != front {
+                self.merged.insert((front.min(other), front.max(other)));
+            }
+        }
+    }
+
+    /// Forget the label of a removed tile
+    pub fn record_removal(&mut self, position: [i32; 2]) {
+        self.labels.remove(&position);
+    }
+
+    /// Front of the tile at a world position, if labelled
+    pub fn front_at(&self, position: [i32; 2]) -> Option<usize> {
+        self.labels.get(&position).copied()
+    }
+
+    /// Distinct fronts among the eight neighbours of a position, lowest first
+    pub fn fronts_around(&self, position: [i32; 2]) -> Vec<usize> {
+        self.neighbour_counts(position).into_keys().collect()
+    }
+
+    /// Labelled tiles of each front among the eight neighbours of a position
+    fn neighbour_counts(&self, position: [i32; 2]) -> BTreeMap<usize, usize> {
+        let mut counts = BTreeMap::new();
+        for front in (-1..=1)
+            .flat_map(|dr| (-1..=1).map(move |dc| [position[0] + dr, position[1] + dc]))
+            .filter(|&neighbour| neighbour != position)
+            .filter_map(|neighbour| self.front_at(neighbour))
+        {
+            *counts.entry(front).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Front to remove when clearing a contradiction on a seam
+    ///
+    /// A contradiction touching two or more fronts lies on a seam. Of those
+    /// fronts, the one with the fewest tiles among `positions` is removed,
+    /// the most recently started on a tie. Returns `None` off seams.
+    pub fn seam_side(&self, contradiction: [i32; 2], positions: &[[i32; 2]]) -> Option<usize> {
+        let around = self.fronts_around(contradiction);
+        if around.len() < 2 {
+            return None;
+        }
+        around.into_iter().min_by_key(|&front| {
+            let tiles = positions
+                .iter()
+                .filter(|&&position| self.front_at(position) == Some(front))
+                .count();
+            (tiles, std::cmp::Reverse(front))
+        })
+    }
+
+    /// Fronts started so far
+    pub const fn fronts(&self) -> usize {
+        self.fronts
+    }
+
+    /// Pairs of fronts that have met
+    pub fn merges(&self) -> usize {
+        self.merged.len()
+    }
+}
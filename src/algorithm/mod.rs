@@ -1,14 +1,34 @@
+/// Backtracking over random placement decisions
+pub mod backtracking;
 /// Efficient bitset implementation for tile compatibility tracking
 pub mod bitset;
 /// Caching system for tile pattern computations
 pub mod cache;
+/// Stopping rules for running until the bounds are filled
+pub mod completion;
+/// Arc consistency propagation over frontier cell domains
+pub mod consistency;
 /// Deadlock detection and resolution mechanisms
 pub mod deadlock;
 /// Main algorithm executor and orchestration
 pub mod executor;
 /// Feasibility tracking for tile placement
 pub mod feasibility;
+/// Growth from several seed points with seam-aware removal
+pub mod fronts;
+/// Placement of the bounds and first tile around the origin
+pub mod origin;
+/// Position selection strategies for non-forced placements
+pub mod positioning;
 /// Wave propagation and forced position detection
 pub mod propagation;
+/// Rebuilding generation state from a recorded event log
+pub mod replay;
+/// Restarting failed generations with derived seeds
+pub mod restart;
+/// Order-independent world generation from per-chunk seeds
+pub mod seeded;
 /// Tile selection strategies with density correction
 pub mod selection;
+/// On-demand region generation with chunk eviction
+pub mod streaming;
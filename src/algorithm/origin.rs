@@ -0,0 +1,202 @@
+//! Placement of the generation bounds and the first tile around the origin
+//!
+//! The bounds are anchored so that the world origin sits at their centre or
+//! at one of their corners, which also fixes where prefill images land. The
+//! first tile goes at the origin by default, but can start from a point of
+//! the bounds, a given pixel of the output, or a random cell inside them.
+//! Growth spreads from the first tile, so starting in a corner gives outputs
+//! that sweep across the canvas rather than growing outwards from its middle.
+
+use rand::{Rng, rngs::StdRng};
+
+use crate::{
+    io::error::{AlgorithmError, Result, invalid_parameter},
+    spatial::grid::BoundingBox,
+};
+
+/// Point of the bounds placed at the world origin
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BoundsAnchor {
+    /// Origin at the middle, with any odd cell after it
+    #[default]
+    Center,
+    /// Origin at the first row and column
+    TopLeft,
+    /// Origin at the first row and last column
+    TopRight,
+    /// Origin at the last row and first column
+    BottomLeft,
+    /// Origin at the last row and column
+    BottomRight,
+}
+
+impl BoundsAnchor {
+    /// Every anchor, in command line order
+    pub const ALL: [Self; 5] = [
+        Self::Center,
+        Self::TopLeft,
+        Self::TopRight,
+        Self::BottomLeft,
+        Self::BottomRight,
+    ];
+
+    /// Cells from the top left of `rows` by `cols` bounds to the anchor
+    pub const fn offset(self, rows: usize, cols: usize) -> [i32; 2] {
+        let last = [rows.saturating_sub(1) as i32, cols.saturating_sub(1) as i32];
+        match self {
+            Self::Center => [(rows / 2) as i32, (cols / 2) as i32],
+            Self::TopLeft => [0, 0],
+            Self::TopRight => [0, last[1]],
+            Self::BottomLeft => [last[0], 0],
+            Self::BottomRight => last,
+        }
+    }
+
+    /// Bounds of `rows` by `cols` cells with the anchor at the origin
+    pub const fn bounds(self, rows: usize, cols: usize) -> BoundingBox {
+        let offset = self.offset(rows, cols);
+        let min = [-offset[0], -offset[1]];
+        BoundingBox {
+            min,
+            max: [min[0] + rows as i32 - 1, min[1] + cols as i32 - 1],
+        }
+    }
+
+    /// World position of the anchor within existing bounds
+    pub const fn position_in(self, bounds: &BoundingBox) -> [i32; 2] {
+        let rows = (bounds.max[0] - bounds.min[0] + 1) as usize;
+        let cols = (bounds.max[1] - bounds.min[1] + 1) as usize;
+        let offset = self.offset(rows, cols);
+        [bounds.min[0] + offset[0], bounds.min[1] + offset[1]]
+    }
+}
+
+impl std::fmt::Display for BoundsAnchor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Center => write!(f, "center"),
+            Self::TopLeft => write!(f, "top-left"),
+            Self::TopRight => write!(f, "top-right"),
+            Self::BottomLeft => write!(f, "bottom-left"),
+            Self::BottomRight => write!(f, "bottom-right"),
+        }
+    }
+}
+
+impl std::str::FromStr for BoundsAnchor {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lower = s.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|anchor| anchor.to_string() == lower)
+            .ok_or_else(|| {
+                invalid_parameter(
+                    "bounds anchor",
+                    &s,
+                    &"expected 'center', 'top-left', 'top-right', 'bottom-left' or 'bottom-right'",
+                )
+            })
+    }
+}
+
+/// Where the first tile is placed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StartPosition {
+    /// At the world origin
+    #[default]
+    Origin,
+    /// At a point of the bounds
+    Anchor(BoundsAnchor),
+    /// At a (row, column) pixel of the output, counted from its top left
+    Pixel([usize; 2]),
+    /// At a uniformly random cell of the bounds
+    Random,
+}
+
+impl StartPosition {
+    /// World position of the first tile
+    ///
+    /// Only the random start draws from `rng`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a start other than the origin is used without
+    /// bounds, or a pixel lies outside them
+    pub fn world_position(
+        self,
+        bounds: Option<&BoundingBox>,
+        rng: &mut StdRng,
+    ) -> Result<[i32; 2]> {
+        let Some(bounds) = bounds else {
+            return match self {
+                Self::Origin => Ok([0, 0]),
+                _ => Err(invalid_parameter(
+                    "start position",
+                    &self,
+                    &"requires generation bounds",
+                )),
+            };
+        };
+
+        match self {
+            Self::Origin => Ok([0, 0]),
+            Self::Anchor(anchor) => Ok(anchor.position_in(bounds)),
+            Self::Pixel([row, col]) => {
+                let position = [bounds.min[0] + row as i32, bounds.min[1] + col as i32];
+                if bounds.contains(position) {
+                    Ok(position)
+                } else {
+                    Err(invalid_parameter(
+                        "start position",
+                        &self,
+                        &"pixel lies outside the bounds",
+                    ))
+                }
+            }
+            Self::Random => Ok([
+                rng.random_range(bounds.min[0]..=bounds.max[0]),
+                rng.random_range(bounds.min[1]..=bounds.max[1]),
+            ]),
+        }
+    }
+}
+
+impl std::fmt::Display for StartPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Origin => write!(f, "origin"),
+            Self::Anchor(anchor) => write!(f, "{anchor}"),
+            Self::Pixel([row, col]) => write!(f, "{row},{col}"),
+            Self::Random => write!(f, "random"),
+        }
+    }
+}
+
+impl std::str::FromStr for StartPosition {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lower = s.to_ascii_lowercase();
+        match lower.as_str() {
+            "origin" => return Ok(Self::Origin),
+            "random" => return Ok(Self::Random),
+            _ => {}
+        }
+        if let Ok(anchor) = lower.parse::<BoundsAnchor>() {
+            return Ok(Self::Anchor(anchor));
+        }
+        match lower
+            .split_once(',')
+            .map(|(row, col)| (row.trim().parse(), col.trim().parse()))
+        {
+            Some((Ok(row), Ok(col))) => Ok(Self::Pixel([row, col])),
+            _ => Err(invalid_parameter(
+                "start position",
+                &s,
+                &"expected 'origin', 'random', a bounds anchor or '<row>,<col>'",
+            )),
+        }
+    }
+}
@@ -0,0 +1,416 @@
+//! Position selection strategies for choosing the next cell to fill
+
+use crate::{
+    algorithm::executor::RandomSelector,
+    algorithm::propagation::StepData,
+    algorithm::selection::{ADJACENCY_CANDIDATES_CONSIDERED, CANDIDATES_CONSIDERED},
+    analysis::weights::{
+        DensityBias, calculate_position_selection, position_weight, top_k_from_indices,
+        top_k_frontier_indices, top_k_valid_indices, top_k_weighted,
+    },
+    io::error::AlgorithmError,
+    spatial::GridState,
+};
+
+/// Relative tolerance when comparing entropies for ties
+const ENTROPY_TIE_TOLERANCE: f64 = 1e-9;
+
+/// Grid state visible to a position selector
+pub struct PositionContext<'a> {
+    /// Current grid state with tile placements and probabilities
+    pub grid_state: &'a GridState,
+    /// Count of each tile type selected
+    pub selection_tally: &'a [usize],
+    /// Algorithm parameters and source pattern data
+    pub step_data: &'a StepData,
+    /// Offset from world coordinates to grid indices
+    pub system_offset: [i32; 2],
+}
+
+impl PositionContext<'_> {
+    /// World coordinates of a grid index
+    pub const fn world_position(&self, index: [usize; 2]) -> [i32; 2] {
+        [
+            index[0] as i32 - self.system_offset[0],
+            index[1] as i32 - self.system_offset[1],
+        ]
+    }
+
+    /// Whether a grid index is unplaced and inside the generation bounds
+    pub fn is_open(&self, index: [usize; 2]) -> bool {
+        let unplaced = self
+            .grid_state
+            .locked_tiles
+            .get(index)
+            .is_some_and(|&locked| locked <= 1);
+        let in_bounds = self
+            .grid_state
+            .generation_bounds
+            .as_ref()
+            .is_none_or(|bounds| bounds.contains(self.world_position(index)));
+
+        unplaced && in_bounds
+    }
+
+    /// Grid indices of the frontier index's open positions in row-major order
+    ///
+    /// These are the open positions next to a placed tile, read without
+    /// scanning the grid
+    pub fn frontier_positions(&self) -> Vec<[usize; 2]> {
+        let mut positions: Vec<[usize; 2]> = self
+            .grid_state
+            .frontier
+            .iter_descending()
+            .filter_map(|(position, _)| self.grid_index(position))
+            .collect();
+        positions.sort_unstable();
+        positions
+    }
+
+    /// Grid index of a world position, if it lies on the grid
+    pub fn grid_index(&self, position: [i32; 2]) -> Option<[usize; 2]> {
+        let row = usize::try_from(position[0] + self.system_offset[0]).ok()?;
+        let col = usize::try_from(position[1] + self.system_offset[1]).ok()?;
+        (row < self.grid_state.rows() && col < self.grid_state.cols()).then_some([row, col])
+    }
+
+    /// Grid indices of every open position in row-major order
+    pub fn open_positions(&self) -> impl Iterator<Item = [usize; 2]> + '_ {
+        self.grid_state
+            .locked_tiles
+            .indexed_iter()
+            .filter(|&(_, &locked)| locked <= 1)
+            .map(|(index, _)| <[usize; 2]>::from(index))
+            .filter(|&index| {
+                self.grid_state
+                    .generation_bounds
+                    .as_ref()
+                    .is_none_or(|bounds| bounds.contains(self.world_position(index)))
+            })
+    }
+}
+
+/// Strategy for choosing the grid position of the next non-forced placement
+///
+/// Implementations return a grid index, or `None` when no position can be
+/// chosen. Forced and prefilled placements bypass the selector.
+pub trait PositionSelector: std::fmt::Debug {
+    /// Choose the next grid index to fill
+    fn select(
+        &mut self,
+        context: &PositionContext<'_>,
+        random: &mut RandomSelector,
+    ) -> Option<[usize; 2]>;
+}
+
+/// Adjacency and density weighted choice among the top candidates
+///
+/// Takes the positions with the most placed neighbours, keeps those with the
+/// highest combined weight, and samples one in proportion to its weight.
+/// Candidates come from the grid's frontier index, so only they are weighted.
+#[derive(Debug, Clone)]
+pub struct WeightedSelector {
+    /// Number of top adjacency candidates to consider
+    pub adjacency_candidates: usize,
+    /// Number of top weighted candidates to sample from
+    pub candidates: usize,
+}
+
+impl Default for WeightedSelector {
+    fn default() -> Self {
+        Self {
+            adjacency_candidates: ADJACENCY_CANDIDATES_CONSIDERED,
+            candidates: CANDIDATES_CONSIDERED,
+        }
+    }
+}
+
+impl PositionSelector for WeightedSelector {
+    fn select(
+        &mut self,
+        context: &PositionContext<'_>,
+        random: &mut RandomSelector,
+    ) -> Option<[usize; 2]> {
+        let adjacency_candidates = top_k_frontier_indices(
+            context.grid_state,
+            context.system_offset,
+            self.adjacency_candidates,
+        );
+
+        let density = DensityBias::new(context.selection_tally, context.step_data);
+        let selection_candidates = top_k_weighted(
+            adjacency_candidates
+                .into_iter()
+                .map(|index| (index, position_weight(context.grid_state, index, &density))),
+            self.candidates,
+        );
+
+        if selection_candidates.is_empty() {
+            return None;
+        }
+
+        let candidate_weights: Vec<f64> = selection_candidates
+            .iter()
+            .map(|&(_, weight)| weight)
+            .collect();
+        let selected_index = random.weighted_choice(&candidate_weights);
+        selection_candidates
+            .get(selected_index)
+            .map(|&(index, _)| index)
+    }
+}
+
+impl WeightedSelector {
+    /// Weight every grid position and select among the top candidates
+    ///
+    /// Reference implementation of `select`, which makes the same choice
+    /// from the same random state without building full-grid arrays
+    pub fn select_by_full_scan(
+        &self,
+        context: &PositionContext<'_>,
+        random: &mut RandomSelector,
+    ) -> Option<[usize; 2]> {
+        let weight_result = calculate_position_selection(
+            context.grid_state,
+            context.selection_tally,
+            context.step_data,
+            context.system_offset,
+        );
+
+        let adjacency_candidates = top_k_valid_indices(
+            &weight_result.adjacency_matrix,
+            &weight_result.validity_matrix,
+            self.adjacency_candidates,
+        );
+
+        let selection_candidates = top_k_from_indices(
+            &weight_result.weight_matrix,
+            &adjacency_candidates,
+            self.candidates,
+        );
+
+        if selection_candidates.is_empty() {
+            return None;
+        }
+
+        let candidate_weights: Vec<f64> = selection_candidates
+            .iter()
+            .map(|&[i, j]| {
+                weight_result
+                    .weight_matrix
+                    .get([i, j])
+                    .copied()
+                    .unwrap_or(0.0)
+            })
+            .collect();
+
+        let selected_index = random.weighted_choice(&candidate_weights);
+        selection_candidates.get(selected_index).copied()
+    }
+}
+
+/// Classic wave function collapse: the open position with the lowest entropy
+///
+/// Entropy is the Shannon entropy of each cell's normalized tile
+/// probabilities, so a cell dominated by one tile is chosen before a cell
+/// where every tile is equally likely. Cells where no tile has any
+/// probability are skipped. Ties are broken uniformly at random.
+/// Candidates are the frontier positions next to placed tiles; the whole
+/// grid is considered only while the frontier is empty
+#[derive(Debug, Clone, Default)]
+pub struct MinimumEntropySelector;
+
+impl PositionSelector for MinimumEntropySelector {
+    fn select(
+        &mut self,
+        context: &PositionContext<'_>,
+        random: &mut RandomSelector,
+    ) -> Option<[usize; 2]> {
+        let mut candidates = context.frontier_positions();
+        if candidates.is_empty() {
+            candidates = context.open_positions().collect();
+        }
+
+        let mut lowest = f64::INFINITY;
+        let mut ties = Vec::new();
+
+        for index in candidates {
+            let Some(entropy) = context.grid_state.shannon_entropy(index) else {
+                continue;
+            };
+
+            let tolerance = ENTROPY_TIE_TOLERANCE * lowest.abs().min(entropy.abs());
+            if entropy < lowest - tolerance {
+                lowest = entropy;
+                ties.clear();
+                ties.push(index);
+            } else if entropy <= lowest + tolerance {
+                ties.push(index);
+            }
+        }
+
+        if ties.is_empty() {
+            return None;
+        }
+        ties.get(random.uniform_choice(ties.len())).copied()
+    }
+}
+
+/// Row-major order over the generation bounds
+///
+/// Without bounds the scan covers the smallest square around the origin
+/// that can hold one more tile than has been placed, so rows stay finite
+#[derive(Debug, Clone, Default)]
+pub struct ScanlineSelector;
+
+impl PositionSelector for ScanlineSelector {
+    fn select(
+        &mut self,
+        context: &PositionContext<'_>,
+        _random: &mut RandomSelector,
+    ) -> Option<[usize; 2]> {
+        let (min, max) = context.grid_state.generation_bounds.as_ref().map_or_else(
+            || {
+                let placed = context.grid_state.placed_in_bounds;
+                let half_width = ((placed + 1) as f64).sqrt().ceil() as i32 / 2;
+                ([-half_width; 2], [half_width; 2])
+            },
+            |bounds| (bounds.min, bounds.max),
+        );
+
+        (min[0]..=max[0])
+            .flat_map(|row| (min[1]..=max[1]).map(move |col| [row, col]))
+            .filter_map(|position| context.grid_index(position))
+            .find(|&index| context.is_open(index))
+    }
+}
+
+/// Square rings around the origin, filled clockwise from the top
+#[derive(Debug, Clone, Default)]
+pub struct SpiralSelector;
+
+impl PositionSelector for SpiralSelector {
+    fn select(
+        &mut self,
+        context: &PositionContext<'_>,
+        _random: &mut RandomSelector,
+    ) -> Option<[usize; 2]> {
+        // Clockwise angle from straight up, in [0, 2π)
+        let angle = |[row, col]: [i32; 2]| {
+            f64::from(col)
+                .atan2(-f64::from(row))
+                .rem_euclid(std::f64::consts::TAU)
+        };
+
+        // Rings beyond the farthest grid edge hold no positions
+        let grid_min = context.world_position([0, 0]);
+        let grid_max = context.world_position([
+            context.grid_state.rows().saturating_sub(1),
+            context.grid_state.cols().saturating_sub(1),
+        ]);
+        let last_ring = grid_min
+            .into_iter()
+            .chain(grid_max)
+            .map(i32::abs)
+            .max()
+            .unwrap_or(0);
+
+        (0..=last_ring).find_map(|ring| {
+            (-ring..=ring)
+                .flat_map(|row| (-ring..=ring).map(move |col| [row, col]))
+                .filter(|&[row, col]| row.abs() == ring || col.abs() == ring)
+                .filter(|&position| {
+                    context
+                        .grid_index(position)
+                        .is_some_and(|index| context.is_open(index))
+                })
+                .min_by(|&a, &b| angle(a).total_cmp(&angle(b)))
+                .and_then(|position| context.grid_index(position))
+        })
+    }
+}
+
+/// Uniform choice among open positions next to a placed tile
+///
+/// Falls back to any open position when nothing has been placed
+#[derive(Debug, Clone, Default)]
+pub struct RandomFrontierSelector;
+
+impl PositionSelector for RandomFrontierSelector {
+    fn select(
+        &mut self,
+        context: &PositionContext<'_>,
+        random: &mut RandomSelector,
+    ) -> Option<[usize; 2]> {
+        let mut candidates = context.frontier_positions();
+        if candidates.is_empty() {
+            candidates = context.open_positions().collect();
+        }
+        if candidates.is_empty() {
+            return None;
+        }
+        candidates
+            .get(random.uniform_choice(candidates.len()))
+            .copied()
+    }
+}
+
+/// Built-in position selection strategies
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PositionStrategy {
+    /// Adjacency and density weighted sampling among top candidates
+    #[default]
+    Weighted,
+    /// Lowest entropy first, as in classic wave function collapse
+    MinimumEntropy,
+    /// Row-major order
+    Scanline,
+    /// Rings outward from the origin
+    Spiral,
+    /// Uniform choice among positions next to placed tiles
+    RandomFrontier,
+}
+
+impl PositionStrategy {
+    /// Selector implementation for this strategy
+    pub fn selector(self) -> Box<dyn PositionSelector> {
+        match self {
+            Self::Weighted => Box::new(WeightedSelector::default()),
+            Self::MinimumEntropy => Box::new(MinimumEntropySelector),
+            Self::Scanline => Box::new(ScanlineSelector),
+            Self::Spiral => Box::new(SpiralSelector),
+            Self::RandomFrontier => Box::new(RandomFrontierSelector),
+        }
+    }
+}
+
+impl std::fmt::Display for PositionStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Weighted => write!(f, "weighted"),
+            Self::MinimumEntropy => write!(f, "min-entropy"),
+            Self::Scanline => write!(f, "scanline"),
+            Self::Spiral => write!(f, "spiral"),
+            Self::RandomFrontier => write!(f, "frontier"),
+        }
+    }
+}
+
+impl std::str::FromStr for PositionStrategy {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "weighted" => Ok(Self::Weighted),
+            "min-entropy" | "entropy" => Ok(Self::MinimumEntropy),
+            "scanline" => Ok(Self::Scanline),
+            "spiral" => Ok(Self::Spiral),
+            "frontier" | "random-frontier" => Ok(Self::RandomFrontier),
+            _ => Err(crate::io::error::invalid_parameter(
+                "position strategy",
+                &s,
+                &"expected 'weighted', 'min-entropy', 'scanline', 'spiral' or 'frontier'",
+            )),
+        }
+    }
+}
@@ -2,7 +2,8 @@ use crate::{
     algorithm::cache::ViableTilesCache,
     algorithm::feasibility::FeasibilityCountLayer,
     algorithm::selection::compute_viable_tiles_at_position,
-    io::configuration::ADJACENCY_LEVELS,
+    io::configuration::{ADJACENCY_LEVELS, CONTRADICTION_CHECK_RADIUS},
+    io::events::DecisionSource,
     io::visualization::VisualizationCapture,
     spatial::tiles::Tile,
     spatial::{GridState, grid},
@@ -89,57 +90,19 @@ pub fn update_probabilities_and_entropy(
 
 /// Update probabilities and entropy in a single pass over the affected region
 ///
-/// Applies the influence matrix to tile probabilities and immediately
-/// recalculates entropy using mean normalization to avoid separate traversals
+/// Applies the influence matrix to each cell's accumulated influence and
+/// immediately recalculates its entropy to avoid separate traversals
 pub fn update_probabilities_and_entropy_fused(
     grid_state: &mut GridState,
     impact: &ArrayView3<'_, f64>,
     region: &Region,
 ) {
+    let unique_cell_count = grid_state.unique_cell_count;
     for (i, row) in region.rows().enumerate() {
         for (j, col) in region.cols().enumerate() {
-            let mut sum = 0.0;
-
-            for color in 0..grid_state.unique_cell_count {
-                let impact_val = impact.get([color, i, j]).copied().unwrap_or(1.0);
-                if let Some(tile_probs) = grid_state.tile_probabilities.get_mut(color) {
-                    if let Some(prob) = tile_probs.get_mut([row, col]) {
-                        *prob *= impact_val;
-                    }
-                }
-            }
-
-            for color in 0..grid_state.unique_cell_count {
-                if let Some(tile_probs) = grid_state.tile_probabilities.get(color) {
-                    if let Some(prob) = tile_probs.get([row, col]) {
-                        sum += prob;
-                    }
-                }
-            }
-
-            // Mean normalization prevents numerical instability in entropy calculation
-            let mean_prob = sum / grid_state.unique_cell_count as f64;
-            let entropy = if mean_prob > 0.0 {
-                let mut entropy_sum = 0.0;
-                for color in 0..grid_state.unique_cell_count {
-                    let p = grid_state
-                        .tile_probabilities
-                        .get(color)
-                        .and_then(|probs| probs.get([row, col]))
-                        .copied()
-                        .unwrap_or(0.0)
-                        / mean_prob;
-                    if p > 0.0 {
-                        entropy_sum += p * p.ln();
-                    }
-                }
-                entropy_sum
-            } else {
-                0.0
-            };
-            if let Some(entropy_val) = grid_state.entropy.get_mut([row, col]) {
-                *entropy_val = entropy;
-            }
+            let factors = (0..unique_cell_count)
+                .map(|color| impact.get([color, i, j]).copied().unwrap_or(1.0));
+            grid_state.apply_influence([row, col], factors);
         }
     }
 }
@@ -147,7 +110,9 @@ pub fn update_probabilities_and_entropy_fused(
 /// Mark the selected tile position as locked and update adjacency weights
 ///
 /// Adjacency weights decrease with distance to guide future tile selection
-/// toward positions near already-placed tiles
+/// toward positions near already-placed tiles. Cells whose viability or
+/// frontier membership may have changed are marked for contradiction checks,
+/// and the frontier index is refreshed over the adjacency region.
 pub fn update_grid_state(
     grid_state: &mut GridState,
     selected_cell_reference: usize,
@@ -155,6 +120,7 @@ pub fn update_grid_state(
     system_offset: [i32; 2],
     visualization: &mut Option<VisualizationCapture>,
     iteration: usize,
+    source: DecisionSource,
 ) {
     for level in 1..=ADJACENCY_LEVELS {
         let weight_increment = (1 + ADJACENCY_LEVELS - level) as u32;
@@ -179,11 +145,22 @@ pub fn update_grid_state(
                 if let Some(viz) = visualization {
                     let abs_row = row as i32 - system_offset[0];
                     let abs_col = col as i32 - system_offset[1];
-                    viz.record_placement(abs_row, abs_col, *locked, iteration);
+                    viz.record_placement(abs_row, abs_col, *locked, iteration, source);
                 }
             }
         }
     }
+
+    if grid_state.in_generation_bounds(selection_coordinates) {
+        grid_state.placed_in_bounds += 1;
+    }
+
+    grid_state.mark_dirty(selection_coordinates, CONTRADICTION_CHECK_RADIUS);
+    grid_state.refresh_frontier(
+        selection_coordinates,
+        ADJACENCY_LEVELS as i32,
+        system_offset,
+    );
 }
 
 /// Position with exactly one compatible tile based on surrounding constraints
@@ -218,10 +195,10 @@ pub fn detect_forced_positions(
             let check_pos = [position[0] + di, position[1] + dj];
 
             // Skip positions outside bounds
-            if let Some(bounds) = &grid_state.generation_bounds {
-                if !bounds.contains(check_pos) {
-                    continue;
-                }
+            if let Some(bounds) = &grid_state.generation_bounds
+                && !bounds.contains(check_pos)
+            {
+                continue;
             }
 
             let row = (check_pos[0] + system_offset[0]) as usize;
@@ -248,13 +225,13 @@ pub fn detect_forced_positions(
                 cache,
             );
 
-            if viable.len() == 1 {
-                if let Some(&tile_ref) = viable.first() {
-                    forced.push(ForcedPosition {
-                        coordinates: check_pos,
-                        tile_reference: tile_ref,
-                    });
-                }
+            if viable.len() == 1
+                && let Some(&tile_ref) = viable.first()
+            {
+                forced.push(ForcedPosition {
+                    coordinates: check_pos,
+                    tile_reference: tile_ref,
+                });
             }
         }
     }
@@ -311,27 +288,38 @@ impl ForcedPipeline {
 /// Detect positions that have adjacent tiles but no compatible options
 ///
 /// Returns the first contradiction found, which indicates the algorithm
-/// has reached an unsolvable state requiring backtracking or restart
+/// has reached an unsolvable state requiring backtracking or restart.
+/// Only cells marked dirty by placements and removals are rechecked, so the
+/// cost per step follows the changed neighbourhood rather than the grid area;
+/// the contradiction and any unchecked cells stay marked for the next call.
+/// Cells outside the generation bounds are never filled, so they are skipped.
 pub fn check_for_contradiction(
-    grid_state: &GridState,
+    grid_state: &mut GridState,
     system_offset: [i32; 2],
     step_data: &StepData,
     cache: &mut ViableTilesCache,
 ) -> Option<[usize; 2]> {
-    for i in 0..grid_state.rows() {
-        for j in 0..grid_state.cols() {
-            if grid_state.locked_tiles.get([i, j]).copied().unwrap_or(0) > 1 {
-                continue;
-            }
-
-            if grid_state
+    let pending: Vec<[i32; 2]> = grid_state.dirty_cells.iter().copied().collect();
+
+    for coords in pending {
+        let i = coords[0] + system_offset[0];
+        let j = coords[1] + system_offset[1];
+        let in_grid = i >= 0
+            && j >= 0
+            && (i as usize) < grid_state.rows()
+            && (j as usize) < grid_state.cols();
+
+        if in_grid {
+            let index = [i as usize, j as usize];
+            let open = grid_state.locked_tiles.get(index).copied().unwrap_or(0) <= 1;
+            let frontier = grid_state
                 .adjacency_weights
-                .get([i, j])
+                .get(index)
                 .copied()
                 .unwrap_or(0)
-                > 1
-            {
-                let coords = [i as i32 - system_offset[0], j as i32 - system_offset[1]];
+                > 1;
+
+            if open && frontier && grid_state.in_generation_bounds(coords) {
                 let viable = compute_viable_tiles_at_position(
                     grid_state,
                     coords,
@@ -342,10 +330,12 @@ pub fn check_for_contradiction(
                 );
 
                 if viable.is_empty() {
-                    return Some([i, j]);
+                    return Some(index);
                 }
             }
         }
+
+        grid_state.dirty_cells.remove(&coords);
     }
     None
 }
@@ -383,12 +373,11 @@ pub fn update_feasibility_counts(
                                 .get([grid_row, grid_col])
                                 .copied()
                                 .unwrap_or(0);
-                            if locked_val > 0 {
-                                if let Some(tile_ref) =
+                            if locked_val > 0
+                                && let Some(tile_ref) =
                                     tile_grid.get_mut(di).and_then(|row| row.get_mut(dj))
-                                {
-                                    *tile_ref = (locked_val - 1) as i32;
-                                }
+                            {
+                                *tile_ref = (locked_val - 1) as i32;
                             }
                         }
                     }
@@ -429,13 +418,12 @@ pub fn update_feasibility_counts(
                 }
             }
 
-            if count > 0 {
-                if let Some(feas) = grid_state
+            if count > 0
+                && let Some(feas) = grid_state
                     .feasibility
                     .get_mut([target_row + 1, target_col + 1])
-                {
-                    *feas = feasibility_sum / count as f64;
-                }
+            {
+                *feas = feasibility_sum / count as f64;
             }
         }
     }
@@ -0,0 +1,175 @@
+//! Rebuilding generation state from a recorded event log
+//!
+//! An executor built from the same source and configuration as the recorded
+//! run applies its placements, removals and backtracks in order, without
+//! drawing random numbers or detecting contradictions. Probabilities,
+//! entropy, feasibility, adjacency, removal counts and the selection tally
+//! follow from the applied events, and enabled visualization, analysis, field
+//! and event captures record them as during the original run, so stopping
+//! after any iteration reproduces the state the run had then.
+
+use crate::{
+    algorithm::executor::GreedyStochastic,
+    io::{
+        error::{AlgorithmError, Result, invalid_parameter},
+        events::GenerationEvent,
+    },
+};
+
+impl GreedyStochastic {
+    /// Apply recorded events up to and including an iteration, or all of them
+    ///
+    /// Events must come from a run with the same source and configuration, and
+    /// start from a fresh executor. Returns the number of events applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an event does not fit the state built so far:
+    /// - A tile reference outside the tile set
+    /// - A placement on an occupied position
+    /// - A removal of a position without a tile
+    /// - An iteration earlier than the one before it
+    pub fn replay_events(
+        &mut self,
+        events: &[GenerationEvent],
+        until_iteration: Option<usize>,
+    ) -> Result<usize> {
+        let mut applied = 0;
+        for event in events {
+            let iteration = event.iteration();
+            if until_iteration.is_some_and(|until| iteration > until) {
+                break;
+            }
+            if iteration < self.iteration {
+                return Err(invalid_parameter(
+                    "events",
+                    &iteration,
+                    &format!("recorded after iteration {}", self.iteration),
+                ));
+            }
+            self.advance_to(iteration)?;
+            self.replay_event(event)?;
+            applied += 1;
+        }
+        if applied > 0 {
+            self.record_fields()?;
+        }
+        Ok(applied)
+    }
+
+    // Finish the current iteration and start the given one, decaying removal
+    // counts for every iteration passed as a run would
+    fn advance_to(&mut self, iteration: usize) -> Result<()> {
+        while self.iteration < iteration {
+            if self.iteration > 0 {
+                self.record_fields()?;
+            }
+            self.iteration += 1;
+            self.decay_removal_counts();
+        }
+        Ok(())
+    }
+
+    fn replay_event(&mut self, event: &GenerationEvent) -> Result<()> {
+        match event {
+            GenerationEvent::Placement {
+                position,
+                tile,
+                source,
+                candidates,
+                ..
+            } => {
+                if *tile == 0 || *tile > self.color_mapping.len() {
+                    return Err(AlgorithmError::InvalidTileIndex {
+                        index: *tile,
+                        max_tiles: self.color_mapping.len(),
+                    });
+                }
+                if self.tile_at(*position).is_some() {
+                    return Err(self.mismatch(*position, "placed on an occupied position"));
+                }
+                self.replay_placement(*position, *tile, *source, candidates.clone());
+            }
+            GenerationEvent::Removal {
+                position,
+                radius,
+                removed,
+                ..
+            } => {
+                self.check_removable(removed)?;
+                if let Some(count) = self
+                    .grid_index(*position)
+                    .and_then(|index| self.grid_state.removal_count.get_mut(index))
+                {
+                    *count = count.saturating_add(1);
+                }
+                let unlocked = self.unlock_positions(
+                    removed,
+                    [position[0] - radius, position[1] - radius],
+                    [position[0] + radius, position[1] + radius],
+                );
+                self.deadlock_stats.record(*position, unlocked.len());
+                if let Some(stack) = &mut self.decision_stack {
+                    stack.forget(&[removed.as_slice(), &[*position]].concat());
+                }
+                if let Some(events) = &mut self.events {
+                    events.record(event.clone());
+                }
+            }
+            GenerationEvent::Backtrack { removed, .. } => {
+                self.check_removable(removed)?;
+                self.undo_placements(removed);
+                if let Some(events) = &mut self.events {
+                    events.record(event.clone());
+                }
+            }
+            GenerationEvent::Fallback { .. } => {
+                if let Some(events) = &mut self.events {
+                    events.record(event.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_removable(&self, removed: &[[i32; 2]]) -> Result<()> {
+        match removed
+            .iter()
+            .find(|&&position| self.tile_at(position).is_none())
+        {
+            Some(&position) => Err(self.mismatch(position, "removed from an open position")),
+            None => Ok(()),
+        }
+    }
+
+    fn grid_index(&self, world: [i32; 2]) -> Option<[usize; 2]> {
+        Some([
+            usize::try_from(world[0] + self.system_offset[0]).ok()?,
+            usize::try_from(world[1] + self.system_offset[1]).ok()?,
+        ])
+    }
+
+    fn mismatch(&self, position: [i32; 2], reason: &str) -> AlgorithmError {
+        invalid_parameter(
+            "events",
+            &format!("[{}, {}]", position[0], position[1]),
+            &format!(
+                "{reason} at iteration {}; the log comes from another source or configuration",
+                self.iteration
+            ),
+        )
+    }
+}
+
+/// Index of the first event where two logs differ, or where the shorter one
+/// ends, or None when they are identical
+///
+/// Comparing the log of a fresh run against a recorded one checks that
+/// generation is deterministic for a seed and configuration
+pub fn first_divergence(recorded: &[GenerationEvent], fresh: &[GenerationEvent]) -> Option<usize> {
+    recorded
+        .iter()
+        .zip(fresh)
+        .position(|(recorded_event, fresh_event)| recorded_event != fresh_event)
+        .or_else(|| (recorded.len() != fresh.len()).then(|| recorded.len().min(fresh.len())))
+}
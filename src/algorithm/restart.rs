@@ -0,0 +1,224 @@
+//! Restarting failed generations with derived seeds
+//!
+//! Each attempt builds a fresh executor from a seed derived from the base
+//! seed and runs it to completion, to the iteration cap, or to a generation
+//! failure. Attempts are scored by how much of the output they filled, how
+//! closely their tile proportions follow the source, and how many deadlocks
+//! they hit, and the best one is kept. The first attempt that completes ends
+//! the run, so a run that succeeds first time behaves exactly as without
+//! restarts.
+
+use std::cmp::Ordering;
+
+use crate::{
+    algorithm::{
+        completion::{RunLimits, run},
+        executor::GreedyStochastic,
+        seeded::mix,
+    },
+    io::error::{AlgorithmError, Result},
+};
+
+/// Seed for an attempt, the base seed itself for the first attempt
+pub const fn attempt_seed(seed: u64, attempt: usize) -> u64 {
+    if attempt == 0 {
+        seed
+    } else {
+        mix(mix(seed) ^ attempt as u64)
+    }
+}
+
+/// Quality of one attempt
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttemptScore {
+    /// Fraction of the bounds filled, or of the iteration cap run when unbounded
+    pub completeness: f64,
+    /// Total variation distance between placed and source tile proportions
+    pub ratio_deviation: f64,
+    /// Deadlocks resolved during the attempt
+    pub deadlocks: usize,
+}
+
+impl AttemptScore {
+    /// Score an executor after `iterations` of at most `max_iterations`
+    ///
+    /// Unbounded runs are complete once they reach the cap without failing.
+    pub fn of(
+        executor: &GreedyStochastic,
+        iterations: usize,
+        max_iterations: usize,
+        failed: bool,
+    ) -> Self {
+        let completeness = match executor.cells_in_bounds() {
+            Some(cells) => executor.grid_state.placed_in_bounds as f64 / cells.max(1) as f64,
+            None if failed => iterations as f64 / max_iterations.max(1) as f64,
+            None => 1.0,
+        };
+
+        Self {
+            completeness: completeness.min(1.0),
+            ratio_deviation: ratio_deviation(
+                &executor.selection_tally,
+                &executor.step_data.source_ratios,
+            ),
+            deadlocks: executor.deadlock_stats.deadlocks,
+        }
+    }
+
+    /// Whether the attempt filled everything it was asked to
+    pub fn is_complete(&self) -> bool {
+        self.completeness >= 1.0
+    }
+
+    /// Order attempts from worst to best
+    ///
+    /// More complete attempts win, then those closer to the source
+    /// proportions, then those with fewer deadlocks.
+    pub fn rank(&self, other: &Self) -> Ordering {
+        self.completeness
+            .total_cmp(&other.completeness)
+            .then_with(|| other.ratio_deviation.total_cmp(&self.ratio_deviation))
+            .then_with(|| other.deadlocks.cmp(&self.deadlocks))
+    }
+}
+
+/// Total variation distance between the tally's proportions and the source ratios
+///
+/// An empty tally is as far from the source as possible.
+pub fn ratio_deviation(tally: &[usize], source_ratios: &[f64]) -> f64 {
+    let total: usize = tally.iter().sum();
+    if total == 0 {
+        return 1.0;
+    }
+    let distance: f64 = source_ratios
+        .iter()
+        .enumerate()
+        .map(|(index, &ratio)| {
+            let placed = tally.get(index).copied().unwrap_or(0) as f64 / total as f64;
+            (placed - ratio).abs()
+        })
+        .sum();
+    distance / 2.0
+}
+
+/// Best attempt of a restarted run
+pub struct Attempt {
+    /// Executor left by the attempt
+    pub executor: GreedyStochastic,
+    /// Seed the attempt ran with
+    pub seed: u64,
+    /// Index of the attempt, 0 for the base seed
+    pub attempt: usize,
+    /// Attempts run in total
+    pub attempts: usize,
+    /// Quality of the attempt
+    pub score: AttemptScore,
+    /// Generation failure that ended the attempt, if any
+    pub error: Option<AlgorithmError>,
+}
+
+impl Attempt {
+    /// Whether the attempt completed without a generation failure
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none() && self.score.is_complete()
+    }
+
+    /// Save the winning seed and score as JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parent directory or the file cannot be written
+    pub fn export_metadata(&self, output_path: &str) -> Result<()> {
+        let metadata = serde_json::json!({
+            "seed": self.seed,
+            "attempt": self.attempt,
+            "attempts": self.attempts,
+            "complete": self.succeeded(),
+            "completeness": self.score.completeness,
+            "ratio_deviation": self.score.ratio_deviation,
+            "deadlocks": self.score.deadlocks,
+        });
+
+        if let Some(parent) = std::path::Path::new(output_path).parent() {
+            std::fs::create_dir_all(parent).map_err(|e| AlgorithmError::FileSystem {
+                path: parent.to_path_buf(),
+                operation: "create directory",
+                source: e,
+            })?;
+        }
+
+        let write_error = |e: std::io::Error| AlgorithmError::FileSystem {
+            path: output_path.into(),
+            operation: "write file",
+            source: e,
+        };
+        let file = std::fs::File::create(output_path).map_err(write_error)?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), &metadata)
+            .map_err(|e| write_error(e.into()))
+    }
+}
+
+/// Run up to `restarts + 1` attempts, keeping the best
+///
+/// Each attempt runs within `limits`. `build` creates the executor for a
+/// seed and `on_iteration` is called with the attempt and iteration before
+/// each iteration. Attempts stop at the
+/// first success; otherwise the best scoring attempt is returned with the
+/// failure that ended it.
+///
+/// # Errors
+///
+/// Returns an error if building an executor fails or an attempt fails for a
+/// reason other than generation itself
+pub fn run_with_restarts<B, F>(
+    seed: u64,
+    restarts: usize,
+    limits: &RunLimits,
+    mut build: B,
+    mut on_iteration: F,
+) -> Result<Attempt>
+where
+    B: FnMut(u64) -> Result<GreedyStochastic>,
+    F: FnMut(usize, usize),
+{
+    let mut best: Option<Attempt> = None;
+
+    for attempt in 0..=restarts {
+        let attempt_seed = attempt_seed(seed, attempt);
+        let mut executor = build(attempt_seed)?;
+
+        let outcome = run(&mut executor, limits, |iteration| {
+            on_iteration(attempt, iteration);
+        })?;
+
+        let score = AttemptScore::of(
+            &executor,
+            outcome.iterations,
+            limits.iteration_cap(&executor),
+            outcome.error.is_some(),
+        );
+        let candidate = Attempt {
+            executor,
+            seed: attempt_seed,
+            attempt,
+            attempts: attempt + 1,
+            score,
+            error: outcome.error,
+        };
+        if candidate.succeeded() {
+            return Ok(candidate);
+        }
+
+        best = match best {
+            Some(mut current) if current.score.rank(&candidate.score) != Ordering::Less => {
+                current.attempts = candidate.attempts;
+                Some(current)
+            }
+            _ => Some(candidate),
+        };
+    }
+
+    best.ok_or_else(|| {
+        crate::io::error::invalid_parameter("restarts", &restarts, &"no attempt ran")
+    })
+}
@@ -0,0 +1,350 @@
+//! Order-independent world generation from per-chunk seeds
+//!
+//! Chunks are classed by coordinate parity, so any two neighbouring chunks,
+//! diagonals included, belong to different classes. A chunk is generated
+//! after its lower-class neighbours and before its higher-class ones, which
+//! fixes the tiles around it no matter which chunk was requested first. The
+//! chunk is then generated in isolation from a seed derived from the global
+//! seed and its coordinate, after replaying those border tiles in row-major
+//! order, so its content depends only on the seed, the coordinate and the
+//! borders.
+//!
+//! A chunk may also regenerate a band of `SEAM_REPAIR_WIDTH` cells inside
+//! its generated neighbours, since a seam can be unsolvable against a finished
+//! neighbour. Chunks are wide enough that only mutual neighbours touch a cell,
+//! so those repairs also happen in class order, and a chunk's edge is final
+//! once all eight of its neighbours are generated.
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::{
+    algorithm::{
+        consistency::ArcConsistency,
+        deadlock::DeadlockStats,
+        executor::{GreedyStochastic, RandomSelector},
+        feasibility::FeasibilityCountLayer,
+        propagation::ForcedPipeline,
+    },
+    io::{
+        analysis::AnalysisCapture,
+        configuration::SEAM_REPAIR_WIDTH,
+        error::{Result, invalid_parameter},
+        visualization::VisualizationCapture,
+    },
+    spatial::{GridState, grid::BoundingBox},
+};
+
+/// Iterations allowed for one attempt at a chunk
+const DEFAULT_CHUNK_ITERATIONS: usize = 2000;
+
+/// Attempts with successive derived seeds before a chunk is reported unfilled
+const DEFAULT_CHUNK_ATTEMPTS: u32 = 4;
+
+/// `SplitMix64` finalizer
+pub(crate) const fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Seed for one attempt at generating a chunk
+pub const fn chunk_seed(seed: u64, chunk: [i32; 2], attempt: u32) -> u64 {
+    let coordinate = ((chunk[0] as u32 as u64) << 32) | chunk[1] as u32 as u64;
+    mix(mix(mix(seed) ^ coordinate) ^ attempt as u64)
+}
+
+/// Generation class of a chunk, from 0 to 3
+///
+/// Neighbouring chunks always differ in class
+pub const fn chunk_class(chunk: [i32; 2]) -> i32 {
+    chunk[0].rem_euclid(2) * 2 + chunk[1].rem_euclid(2)
+}
+
+/// Neighbouring chunks that must be generated before `chunk`
+pub fn chunk_dependencies(chunk: [i32; 2]) -> impl Iterator<Item = [i32; 2]> {
+    let class = chunk_class(chunk);
+    neighbourhood(chunk).filter(move |&neighbour| chunk_class(neighbour) < class)
+}
+
+/// Executor state that would carry history from one chunk to the next
+struct ChunkState {
+    grid_state: GridState,
+    feasibility_layer: FeasibilityCountLayer,
+    system_offset: [i32; 2],
+    selection_tally: Vec<usize>,
+    random_selector: RandomSelector,
+    forced_pipeline: ForcedPipeline,
+    iteration: usize,
+    visualization: Option<VisualizationCapture>,
+    analysis: Option<AnalysisCapture>,
+    deadlock_stats: DeadlockStats,
+    recent_placements: VecDeque<[i32; 2]>,
+    arc_consistency: Option<ArcConsistency>,
+}
+
+impl ChunkState {
+    /// Empty state with its own random sequence
+    fn fresh(executor: &GreedyStochastic, seed: u64) -> Self {
+        let tile_types = executor.step_data.unique_cell_count;
+        Self {
+            grid_state: GridState::new(1, 1, tile_types),
+            feasibility_layer: FeasibilityCountLayer::new(
+                1,
+                1,
+                executor.step_data.source_tiles.len(),
+            ),
+            system_offset: [0, 0],
+            selection_tally: vec![0; tile_types],
+            random_selector: RandomSelector::new(seed),
+            forced_pipeline: ForcedPipeline::new(),
+            iteration: 0,
+            visualization: None,
+            analysis: None,
+            deadlock_stats: DeadlockStats::default(),
+            recent_placements: VecDeque::new(),
+            arc_consistency: executor
+                .arc_consistency
+                .as_ref()
+                .map(|_| ArcConsistency::new()),
+        }
+    }
+
+    /// Exchange this state with the executor's
+    const fn swap(&mut self, executor: &mut GreedyStochastic) {
+        std::mem::swap(&mut self.grid_state, &mut executor.grid_state);
+        std::mem::swap(&mut self.feasibility_layer, &mut executor.feasibility_layer);
+        std::mem::swap(&mut self.system_offset, &mut executor.system_offset);
+        std::mem::swap(&mut self.selection_tally, &mut executor.selection_tally);
+        std::mem::swap(&mut self.random_selector, &mut executor.random_selector);
+        std::mem::swap(&mut self.forced_pipeline, &mut executor.forced_pipeline);
+        std::mem::swap(&mut self.iteration, &mut executor.iteration);
+        std::mem::swap(&mut self.visualization, &mut executor.visualization);
+        std::mem::swap(&mut self.analysis, &mut executor.analysis);
+        std::mem::swap(&mut self.deadlock_stats, &mut executor.deadlock_stats);
+        std::mem::swap(&mut self.recent_placements, &mut executor.recent_placements);
+        std::mem::swap(&mut self.arc_consistency, &mut executor.arc_consistency);
+    }
+}
+
+/// World whose chunks are generated independently of request order
+pub struct SeededWorld {
+    /// Executor used to generate each chunk from an empty state
+    executor: GreedyStochastic,
+    /// Global seed shared by every client
+    seed: u64,
+    /// Side length of a chunk in cells
+    chunk_size: i32,
+    /// Iterations allowed for one attempt at a chunk
+    max_iterations: usize,
+    /// Attempts per chunk before it is reported unfilled
+    max_attempts: u32,
+    /// Chunks already generated
+    generated: BTreeSet<[i32; 2]>,
+    /// Tile reference at each generated world position
+    tiles: HashMap<[i32; 2], usize>,
+}
+
+impl SeededWorld {
+    /// Wrap an executor with no placed tiles
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `chunk_size` is below the executor's seam margin
+    /// plus both repair bands, since non-neighbouring chunks would then
+    /// influence each other
+    pub fn new(executor: GreedyStochastic, seed: u64, chunk_size: usize) -> Result<Self> {
+        let minimum = executor.seam_margin() + 2 * SEAM_REPAIR_WIDTH;
+        let size = i32::try_from(chunk_size).unwrap_or(i32::MAX);
+        if size < minimum {
+            return Err(invalid_parameter(
+                "chunk_size",
+                &chunk_size,
+                &format!("must be at least {minimum}"),
+            ));
+        }
+
+        Ok(Self {
+            executor,
+            seed,
+            chunk_size: size,
+            max_iterations: DEFAULT_CHUNK_ITERATIONS,
+            max_attempts: DEFAULT_CHUNK_ATTEMPTS,
+            generated: BTreeSet::new(),
+            tiles: HashMap::new(),
+        })
+    }
+
+    /// Set the iterations allowed for one attempt at a chunk
+    #[must_use]
+    pub const fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Set the attempts per chunk, each with its own derived seed
+    #[must_use]
+    pub const fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Executor used for generation, holding the source data
+    pub const fn executor(&self) -> &GreedyStochastic {
+        &self.executor
+    }
+
+    /// Tile reference generated at a world position
+    pub fn tile(&self, position: [i32; 2]) -> Option<usize> {
+        self.tiles.get(&position).copied()
+    }
+
+    /// Whether a chunk has been generated
+    pub fn is_generated(&self, chunk: [i32; 2]) -> bool {
+        self.generated.contains(&chunk)
+    }
+
+    /// Whether a chunk and all of its neighbours are generated
+    ///
+    /// Only a settled chunk's tiles are final; an unsettled chunk's edge may
+    /// still be repaired by a neighbour.
+    pub fn is_settled(&self, chunk: [i32; 2]) -> bool {
+        neighbourhood(chunk).all(|neighbour| self.generated.contains(&neighbour))
+    }
+
+    /// Chunk containing a world position
+    pub const fn chunk_containing(&self, position: [i32; 2]) -> [i32; 2] {
+        [
+            position[0].div_euclid(self.chunk_size),
+            position[1].div_euclid(self.chunk_size),
+        ]
+    }
+
+    /// World rectangle covered by a chunk
+    pub const fn chunk_bounds(&self, chunk: [i32; 2]) -> BoundingBox {
+        BoundingBox {
+            min: [chunk[0] * self.chunk_size, chunk[1] * self.chunk_size],
+            max: [
+                (chunk[0] + 1) * self.chunk_size - 1,
+                (chunk[1] + 1) * self.chunk_size - 1,
+            ],
+        }
+    }
+
+    /// Generate a chunk and any neighbours it depends on
+    ///
+    /// Returns whether the chunk is now generated; a chunk that cannot be
+    /// filled within the attempt budget stays empty, as does everything that
+    /// depends on it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an iteration fails
+    pub fn generate_chunk(&mut self, chunk: [i32; 2]) -> Result<bool> {
+        if self.generated.contains(&chunk) {
+            return Ok(true);
+        }
+
+        for dependency in chunk_dependencies(chunk) {
+            if !self.generate_chunk(dependency)? {
+                return Ok(false);
+            }
+        }
+
+        let Some(tiles) = self.generate_isolated(chunk)? else {
+            return Ok(false);
+        };
+        self.generated.insert(chunk);
+        for (position, tile_reference) in tiles {
+            // Band cells in chunks not yet generated are left to those chunks
+            if self.generated.contains(&self.chunk_containing(position)) {
+                self.tiles.insert(position, tile_reference);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Generate a chunk and all of its neighbours so its tiles are final
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an iteration fails
+    pub fn settle_chunk(&mut self, chunk: [i32; 2]) -> Result<bool> {
+        for neighbour in neighbourhood(chunk) {
+            if !self.generate_chunk(neighbour)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Tiles of a chunk and its repair band generated apart from the world
+    fn generate_isolated(&mut self, chunk: [i32; 2]) -> Result<Option<Vec<([i32; 2], usize)>>> {
+        let bounds = self.chunk_bounds(chunk);
+        let reach = self.executor.seam_margin() + SEAM_REPAIR_WIDTH;
+        let border: Vec<_> = positions_in(
+            [bounds.min[0] - reach, bounds.min[1] - reach],
+            [bounds.max[0] + reach, bounds.max[1] + reach],
+        )
+        .filter_map(|position| Some((position, *self.tiles.get(&position)?)))
+        .collect();
+
+        for attempt in 0..self.max_attempts {
+            let mut state =
+                ChunkState::fresh(&self.executor, chunk_seed(self.seed, chunk, attempt));
+            state.swap(&mut self.executor);
+
+            for &(position, tile_reference) in &border {
+                self.executor.place_fixed(position, tile_reference);
+            }
+            let filled = self
+                .executor
+                .fill_region(&bounds, SEAM_REPAIR_WIDTH, self.max_iterations);
+            let tiles = placed_tiles(
+                &self.executor,
+                [
+                    bounds.min[0] - SEAM_REPAIR_WIDTH,
+                    bounds.min[1] - SEAM_REPAIR_WIDTH,
+                ],
+                [
+                    bounds.max[0] + SEAM_REPAIR_WIDTH,
+                    bounds.max[1] + SEAM_REPAIR_WIDTH,
+                ],
+            );
+
+            state.swap(&mut self.executor);
+            self.executor.deadlock_stats.merge(&state.deadlock_stats);
+            if filled? {
+                return Ok(Some(tiles));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// A chunk and its eight neighbours
+fn neighbourhood(chunk: [i32; 2]) -> impl Iterator<Item = [i32; 2]> {
+    positions_in([chunk[0] - 1, chunk[1] - 1], [chunk[0] + 1, chunk[1] + 1])
+}
+
+/// Positions of a rectangle in row-major order
+fn positions_in(min: [i32; 2], max: [i32; 2]) -> impl Iterator<Item = [i32; 2]> {
+    (min[0]..=max[0]).flat_map(move |row| (min[1]..=max[1]).map(move |col| [row, col]))
+}
+
+/// Placed tiles in a world rectangle in row-major order
+fn placed_tiles(
+    executor: &GreedyStochastic,
+    min: [i32; 2],
+    max: [i32; 2],
+) -> Vec<([i32; 2], usize)> {
+    positions_in(min, max)
+        .filter_map(|position| {
+            let row = usize::try_from(position[0] + executor.system_offset[0]).ok()?;
+            let col = usize::try_from(position[1] + executor.system_offset[1]).ok()?;
+            let locked = *executor.grid_state.locked_tiles.get([row, col])?;
+            (locked > 1).then(|| (position, locked as usize - 1))
+        })
+        .collect()
+}
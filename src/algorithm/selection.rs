@@ -2,9 +2,11 @@ use crate::{
     algorithm::{
         bitset::TileBitset,
         cache::{PatternKey, ViableTilesCache},
+        executor::RandomSelector,
         propagation::StepData,
     },
-    math::probability::erf,
+    io::error::AlgorithmError,
+    math::probability::{binomial_normal_approximate_cdf, erf},
     spatial::tiles::{Tile, convert_tile_to_membership_booleans},
     spatial::{GridState, grid},
 };
@@ -142,12 +144,11 @@ fn find_compatible_values_at_offset_bitset(
                         )
                     });
 
-            if matches {
-                if let Some(row) = source_tile.get(target_row) {
-                    if let Some(&val) = row.get(target_col) {
-                        result.insert(val);
-                    }
-                }
+            if matches
+                && let Some(row) = source_tile.get(target_row)
+                && let Some(&val) = row.get(target_col)
+            {
+                result.insert(val);
             }
         }
     }
@@ -211,7 +212,7 @@ pub fn density_corrected_log_tile_weights(
 
     viable_log_corrected
         .iter()
-        .map(|&log_prob| (log_prob - mean_log_prob))
+        .map(|&log_prob| log_prob - mean_log_prob)
         .collect()
 }
 
@@ -329,3 +330,161 @@ pub fn calculate_deviation_derivative(
         })
         .sum()
 }
+
+/// Inputs available when choosing a tile at a selected position
+pub struct TileChoiceContext<'a> {
+    /// Tile references that can legally be placed
+    pub viable_tiles: &'a [usize],
+    /// Probability of each tile type at the position
+    pub probabilities: &'a [f64],
+    /// Count of each tile type selected
+    pub selection_tally: &'a [usize],
+    /// Target ratio of each tile type in the source
+    pub source_ratios: &'a [f64],
+}
+
+/// Strategy for choosing one of the viable tiles at a position
+///
+/// Implementations return an index into `viable_tiles`
+pub trait TileChooser: std::fmt::Debug {
+    /// Choose a viable tile
+    fn choose(&mut self, context: &TileChoiceContext<'_>, random: &mut RandomSelector) -> usize;
+}
+
+/// Probability-weighted sampling with density correction toward source ratios
+#[derive(Debug, Clone, Default)]
+pub struct DensityCorrectedChooser {
+    /// Pre-allocated deviation buffer to reduce allocations in hot path
+    deviations: Vec<f64>,
+}
+
+impl TileChooser for DensityCorrectedChooser {
+    fn choose(&mut self, context: &TileChoiceContext<'_>, random: &mut RandomSelector) -> usize {
+        let total_placed = context.selection_tally.iter().sum::<usize>();
+
+        self.deviations.clear();
+        for (i, &p) in context.source_ratios.iter().enumerate() {
+            let k = context.selection_tally.get(i).copied().unwrap_or(0);
+            let cdf_value = binomial_normal_approximate_cdf(total_placed, p, k);
+            self.deviations.push(cdf_value - 0.5);
+        }
+
+        let log_corrected_weights = density_corrected_log_tile_weights(
+            context.viable_tiles,
+            context.probabilities,
+            context.selection_tally,
+            context.source_ratios,
+            total_placed,
+            &self.deviations,
+        );
+
+        random.log_weighted_choice(&log_corrected_weights)
+    }
+}
+
+/// Softmax over log probabilities scaled by a temperature
+///
+/// Temperature 1 samples in proportion to probability, 0 always takes the
+/// most probable tile, and large temperatures approach a uniform choice
+#[derive(Debug, Clone)]
+pub struct SoftmaxChooser {
+    /// Sharpness of the distribution, where lower is sharper
+    pub temperature: f64,
+}
+
+impl TileChooser for SoftmaxChooser {
+    fn choose(&mut self, context: &TileChoiceContext<'_>, random: &mut RandomSelector) -> usize {
+        let log_probabilities: Vec<f64> = context
+            .viable_tiles
+            .iter()
+            .map(|&tile_ref| {
+                context
+                    .probabilities
+                    .get(tile_ref - 1)
+                    .copied()
+                    .unwrap_or(0.0)
+                    .max(f64::MIN_POSITIVE)
+                    .ln()
+            })
+            .collect();
+
+        if self.temperature <= 0.0 {
+            return log_probabilities
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map_or(0, |(i, _)| i);
+        }
+
+        let scaled: Vec<f64> = log_probabilities
+            .iter()
+            .map(|log_p| log_p / self.temperature)
+            .collect();
+        random.log_weighted_choice(&scaled)
+    }
+}
+
+/// Uniform choice among viable tiles, ignoring learned probabilities
+#[derive(Debug, Clone, Default)]
+pub struct UniformChooser;
+
+impl TileChooser for UniformChooser {
+    fn choose(&mut self, context: &TileChoiceContext<'_>, random: &mut RandomSelector) -> usize {
+        random.uniform_choice(context.viable_tiles.len())
+    }
+}
+
+/// Built-in tile choice strategies
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TileStrategy {
+    /// Probability-weighted sampling with density correction
+    #[default]
+    DensityCorrected,
+    /// Temperature-scaled softmax over tile probabilities
+    Softmax(f64),
+    /// Uniform among viable tiles
+    Uniform,
+}
+
+impl TileStrategy {
+    /// Chooser implementation for this strategy
+    pub fn chooser(self) -> Box<dyn TileChooser> {
+        match self {
+            Self::DensityCorrected => Box::new(DensityCorrectedChooser::default()),
+            Self::Softmax(temperature) => Box::new(SoftmaxChooser { temperature }),
+            Self::Uniform => Box::new(UniformChooser),
+        }
+    }
+}
+
+impl std::fmt::Display for TileStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DensityCorrected => write!(f, "density"),
+            Self::Softmax(temperature) => write!(f, "softmax:{temperature}"),
+            Self::Uniform => write!(f, "uniform"),
+        }
+    }
+}
+
+impl std::str::FromStr for TileStrategy {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_ascii_lowercase();
+        match lower.as_str() {
+            "density" => Ok(Self::DensityCorrected),
+            "uniform" => Ok(Self::Uniform),
+            "softmax" => Ok(Self::Softmax(1.0)),
+            "argmax" => Ok(Self::Softmax(0.0)),
+            other => match other.strip_prefix("softmax:").map(str::parse::<f64>) {
+                Some(Ok(t)) if t.is_finite() && t >= 0.0 => Ok(Self::Softmax(t)),
+                _ => Err(crate::io::error::invalid_parameter(
+                    "tile strategy",
+                    &s,
+                    &"expected 'density', 'uniform', 'argmax', 'softmax' or 'softmax:<temperature>'",
+                )),
+            },
+        }
+    }
+}
@@ -0,0 +1,484 @@
+//! On-demand generation of world regions with chunk eviction
+//!
+//! An open world is generated one rectangle at a time around a moving
+//! viewpoint. Each region is filled against the tiles already placed around
+//! it, so seams between regions follow the same adjacency rules as the
+//! interior. Chunks far from the viewpoint can be evicted to bytes and
+//! restored later; a region may only be generated once every chunk its
+//! placements could touch is loaded.
+
+use crate::{
+    algorithm::executor::GreedyStochastic,
+    io::{
+        configuration::{ADJACENCY_LEVELS, SEAM_REPAIR_WIDTH},
+        error::{AlgorithmError, Result, invalid_parameter},
+    },
+    spatial::{
+        chunked::{CHUNK_AREA, CHUNK_SIZE, chunk_of},
+        grid::{BoundingBox, GridChunk, chunk_world_min},
+    },
+};
+
+/// Leading bytes identifying a serialized chunk
+const SNAPSHOT_MAGIC: &[u8; 4] = b"GTCK";
+
+/// Serialized chunk format version
+const SNAPSHOT_VERSION: u8 = 2;
+
+/// One evicted world chunk with everything needed to restore it
+#[derive(Debug, Clone)]
+pub struct ChunkSnapshot {
+    /// Grid state layers of the chunk
+    pub grid: GridChunk,
+    /// Feasibility pattern counts of the chunk
+    pub feasibility_counts: Option<Box<[usize]>>,
+}
+
+impl ChunkSnapshot {
+    /// Chunk coordinate (world position divided by `CHUNK_SIZE`)
+    pub const fn chunk(&self) -> [i32; 2] {
+        self.grid.chunk
+    }
+
+    /// Encode the snapshot as little-endian bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(SNAPSHOT_MAGIC);
+        bytes.push(SNAPSHOT_VERSION);
+        bytes.extend_from_slice(&self.grid.chunk[0].to_le_bytes());
+        bytes.extend_from_slice(&self.grid.chunk[1].to_le_bytes());
+        bytes.extend_from_slice(&(CHUNK_SIZE as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.grid.tile_probabilities.len() as u32).to_le_bytes());
+
+        for layer in &self.grid.tile_probabilities {
+            write_layer(&mut bytes, layer.as_deref(), f64::to_le_bytes);
+        }
+        for layer in &self.grid.log_influence {
+            write_layer(&mut bytes, layer.as_deref(), i128::to_le_bytes);
+        }
+        write_layer(
+            &mut bytes,
+            self.grid.influence_coverage.as_deref(),
+            u32::to_le_bytes,
+        );
+        write_layer(&mut bytes, self.grid.entropy.as_deref(), f64::to_le_bytes);
+        write_layer(
+            &mut bytes,
+            self.grid.adjacency_weights.as_deref(),
+            u32::to_le_bytes,
+        );
+        write_layer(
+            &mut bytes,
+            self.grid.locked_tiles.as_deref(),
+            u32::to_le_bytes,
+        );
+        write_layer(
+            &mut bytes,
+            self.grid.feasibility.as_deref(),
+            f64::to_le_bytes,
+        );
+        write_layer(
+            &mut bytes,
+            self.grid.removal_count.as_deref(),
+            u8::to_le_bytes,
+        );
+        write_layer(&mut bytes, self.feasibility_counts.as_deref(), |v| {
+            (v as u64).to_le_bytes()
+        });
+        bytes
+    }
+
+    /// Decode a snapshot produced by `to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are truncated, were written by another
+    /// format version, or use a different chunk size
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = ByteReader { bytes };
+
+        if reader.take(SNAPSHOT_MAGIC.len())? != SNAPSHOT_MAGIC {
+            return Err(snapshot_error(&"missing chunk snapshot header"));
+        }
+        let version = reader.array::<1>()?[0];
+        if version != SNAPSHOT_VERSION {
+            return Err(snapshot_error(&format!(
+                "unsupported snapshot version {version}"
+            )));
+        }
+
+        let chunk = [
+            i32::from_le_bytes(reader.array()?),
+            i32::from_le_bytes(reader.array()?),
+        ];
+        let chunk_size = u32::from_le_bytes(reader.array()?) as usize;
+        if chunk_size != CHUNK_SIZE {
+            return Err(snapshot_error(&format!(
+                "chunk size {chunk_size} does not match {CHUNK_SIZE}"
+            )));
+        }
+        let layer_count = u32::from_le_bytes(reader.array()?) as usize;
+
+        let mut tile_probabilities = Vec::with_capacity(layer_count);
+        for _ in 0..layer_count {
+            tile_probabilities.push(reader.layer(f64::from_le_bytes)?);
+        }
+        let mut log_influence = Vec::with_capacity(layer_count);
+        for _ in 0..layer_count {
+            log_influence.push(reader.layer(i128::from_le_bytes)?);
+        }
+        let grid = GridChunk {
+            chunk,
+            tile_probabilities,
+            log_influence,
+            influence_coverage: reader.layer(u32::from_le_bytes)?,
+            entropy: reader.layer(f64::from_le_bytes)?,
+            adjacency_weights: reader.layer(u32::from_le_bytes)?,
+            locked_tiles: reader.layer(u32::from_le_bytes)?,
+            feasibility: reader.layer(f64::from_le_bytes)?,
+            removal_count: reader.layer(u8::from_le_bytes)?,
+        };
+        let feasibility_counts = reader.layer(|v| u64::from_le_bytes(v) as usize)?;
+
+        if !reader.bytes.is_empty() {
+            return Err(snapshot_error(&"trailing bytes after chunk snapshot"));
+        }
+
+        Ok(Self {
+            grid,
+            feasibility_counts,
+        })
+    }
+}
+
+/// Append a presence flag and, if present, every cell of a layer
+fn write_layer<T: Copy, const N: usize>(
+    bytes: &mut Vec<u8>,
+    layer: Option<&[T]>,
+    encode: impl Fn(T) -> [u8; N],
+) {
+    match layer {
+        Some(cells) => {
+            bytes.push(1);
+            for &cell in cells {
+                bytes.extend_from_slice(&encode(cell));
+            }
+        }
+        None => bytes.push(0),
+    }
+}
+
+/// Sequential reader over snapshot bytes
+struct ByteReader<'a> {
+    /// Bytes not yet consumed
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    /// Consume the next `len` bytes
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let Some((head, tail)) = self.bytes.split_at_checked(len) else {
+            return Err(snapshot_error(&"chunk snapshot is truncated"));
+        };
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    /// Consume the next `N` bytes as an array
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    /// Consume a presence flag and, if present, every cell of a layer
+    fn layer<T, const N: usize>(
+        &mut self,
+        decode: impl Fn([u8; N]) -> T,
+    ) -> Result<Option<Box<[T]>>> {
+        if self.array::<1>()?[0] == 0 {
+            return Ok(None);
+        }
+        (0..CHUNK_AREA)
+            .map(|_| self.array().map(&decode))
+            .collect::<Result<Box<[T]>>>()
+            .map(Some)
+    }
+}
+
+/// Error for malformed snapshot bytes
+fn snapshot_error(reason: &impl ToString) -> AlgorithmError {
+    invalid_parameter("chunk snapshot", &"bytes", reason)
+}
+
+/// Inclusive chunk range covering a world rectangle
+fn chunks_covering(min: [i32; 2], max: [i32; 2]) -> impl Iterator<Item = [i32; 2]> {
+    let low = chunk_of(min);
+    let high = chunk_of(max);
+    (low[0]..=high[0]).flat_map(move |row| (low[1]..=high[1]).map(move |col| [row, col]))
+}
+
+impl GreedyStochastic {
+    /// Distance around the generation bounds within which placements can change cells
+    ///
+    /// Placements and removals stay inside the bounds, but their adjacency,
+    /// probability and feasibility updates reach this far beyond them
+    pub const fn seam_margin(&self) -> i32 {
+        if self.step_data.grid_extension_radius > ADJACENCY_LEVELS as i32 + 1 {
+            self.step_data.grid_extension_radius
+        } else {
+            ADJACENCY_LEVELS as i32 + 1
+        }
+    }
+
+    /// Chunks holding placed tiles
+    pub fn loaded_chunks(&self) -> Vec<[i32; 2]> {
+        let mut chunks: Vec<[i32; 2]> = self.grid_state.locked_tiles.chunk_coordinates().collect();
+        chunks.sort_unstable();
+        chunks
+    }
+
+    /// Fill every cell of a world rectangle, continuing from its neighbours
+    ///
+    /// The rectangle plus a band of `SEAM_REPAIR_WIDTH` cells becomes the
+    /// generation bounds. Tiles further out are never placed or removed, while
+    /// tiles in the band may be removed and regenerated to resolve a seam
+    /// contradiction with an earlier region. A region with no placed tiles
+    /// nearby is started from a tile drawn from the source distribution at
+    /// its centre.
+    ///
+    /// Returns whether the region and its band were completely filled within
+    /// `max_iterations`; calling again continues from the current state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the region is empty, if a chunk within the seam
+    /// margin is evicted, or if an iteration fails
+    pub fn generate_region(&mut self, region: &BoundingBox, max_iterations: usize) -> Result<bool> {
+        self.fill_region(region, SEAM_REPAIR_WIDTH, max_iterations)
+    }
+
+    /// Fill a world rectangle plus a repair band of `band` cells around it
+    ///
+    /// With a band of zero every tile outside the rectangle stays fixed.
+    pub(crate) fn fill_region(
+        &mut self,
+        region: &BoundingBox,
+        band: i32,
+        max_iterations: usize,
+    ) -> Result<bool> {
+        if region.min[0] > region.max[0] || region.min[1] > region.max[1] {
+            return Err(invalid_parameter(
+                "region",
+                &format!("{:?}..={:?}", region.min, region.max),
+                &"minimum must not exceed maximum",
+            ));
+        }
+
+        let working = BoundingBox {
+            min: [region.min[0] - band, region.min[1] - band],
+            max: [region.max[0] + band, region.max[1] + band],
+        };
+        let margin = self.seam_margin();
+        let margin_min = [working.min[0] - margin, working.min[1] - margin];
+        let margin_max = [working.max[0] + margin, working.max[1] + margin];
+        if let Some(chunk) = chunks_covering(margin_min, margin_max)
+            .find(|chunk| self.grid_state.evicted_chunks.contains(chunk))
+        {
+            return Err(invalid_parameter(
+                "region",
+                &format!("{:?}..={:?}", region.min, region.max),
+                &format!("chunk {chunk:?} within the seam margin is evicted"),
+            ));
+        }
+
+        // Cover the region and its margin before bounds constrain extension
+        self.grid_state.generation_bounds = None;
+        self.extend_grid(margin_min, 0);
+        self.extend_grid(margin_max, 0);
+        self.grid_state
+            .set_generation_bounds(Some(working.clone()), self.system_offset);
+        self.forced_pipeline = crate::algorithm::propagation::ForcedPipeline::default();
+        // Domains were kept for the previous bounds
+        if let Some(arc_consistency) = &mut self.arc_consistency {
+            arc_consistency.clear();
+        }
+        self.skip_initial_placement();
+
+        let area = ((working.max[0] - working.min[0] + 1) * (working.max[1] - working.min[1] + 1))
+            as usize;
+
+        for _ in 0..max_iterations {
+            if self.grid_state.placed_in_bounds >= area {
+                return Ok(true);
+            }
+
+            let isolated = self.forced_pipeline.is_empty() && self.grid_state.frontier.is_empty();
+            if isolated && let Some(position) = self.region_seed_position(&working) {
+                let tile_reference = self
+                    .random_selector
+                    .weighted_choice(&self.step_data.source_ratios)
+                    + 1;
+                self.place_seed(position, tile_reference)?;
+                continue;
+            }
+
+            self.run_iteration()?;
+        }
+
+        Ok(self.grid_state.placed_in_bounds >= area)
+    }
+
+    /// Open cell nearest the centre of a region, preferring the centre itself
+    fn region_seed_position(&self, region: &BoundingBox) -> Option<[i32; 2]> {
+        let centre = [
+            region.min[0] + (region.max[0] - region.min[0]) / 2,
+            region.min[1] + (region.max[1] - region.min[1]) / 2,
+        ];
+        let is_open = |position: [i32; 2]| {
+            let index = [
+                (position[0] + self.system_offset[0]) as usize,
+                (position[1] + self.system_offset[1]) as usize,
+            ];
+            self.grid_state
+                .locked_tiles
+                .get(index)
+                .is_some_and(|&locked| locked <= 1)
+        };
+
+        (region.min[0]..=region.max[0])
+            .flat_map(|row| (region.min[1]..=region.max[1]).map(move |col| [row, col]))
+            .filter(|&position| is_open(position))
+            .min_by_key(|&position| {
+                (position[0] - centre[0]).abs() + (position[1] - centre[1]).abs()
+            })
+    }
+
+    /// Remove a chunk from memory and return it as bytes
+    ///
+    /// Placed tiles in the chunk leave the selection tally until the chunk is
+    /// restored. Neighbouring chunks keep the adjacency and probability
+    /// updates the evicted tiles made, so restoring reproduces the seam.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chunk is already evicted or lies within the
+    /// seam margin of the current generation bounds
+    pub fn evict_chunk(&mut self, chunk: [i32; 2]) -> Result<Vec<u8>> {
+        if self.grid_state.evicted_chunks.contains(&chunk) {
+            return Err(invalid_parameter(
+                "chunk",
+                &format!("{chunk:?}"),
+                &"chunk is already evicted",
+            ));
+        }
+
+        if let Some(bounds) = &self.grid_state.generation_bounds {
+            let margin = self.seam_margin();
+            let active = chunks_covering(
+                [bounds.min[0] - margin, bounds.min[1] - margin],
+                [bounds.max[0] + margin, bounds.max[1] + margin],
+            )
+            .any(|active| active == chunk);
+            if active {
+                return Err(invalid_parameter(
+                    "chunk",
+                    &format!("{chunk:?}"),
+                    &"chunk lies within the seam margin of the generation bounds",
+                ));
+            }
+        }
+
+        let grid = self.grid_state.evict_chunk(chunk, self.system_offset);
+        for (_, locked) in grid.placed_tiles() {
+            if let Some(tally) = self.selection_tally.get_mut(locked as usize - 2) {
+                *tally = tally.saturating_sub(1);
+            }
+        }
+
+        let snapshot = ChunkSnapshot {
+            grid,
+            feasibility_counts: self.feasibility_layer.take_chunk(chunk),
+        };
+        Ok(snapshot.to_bytes())
+    }
+
+    /// Restore a chunk evicted by `evict_chunk`, returning its coordinate
+    ///
+    /// The chunk may also be restored into another executor built from the
+    /// same source and configuration, provided it holds no tiles there.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are malformed, do not match this
+    /// executor's tile set, or the chunk already holds placed tiles
+    pub fn restore_chunk(&mut self, bytes: &[u8]) -> Result<[i32; 2]> {
+        let snapshot = ChunkSnapshot::from_bytes(bytes)?;
+        let chunk = snapshot.chunk();
+
+        let min = chunk_world_min(chunk);
+        let max = [
+            min[0] + CHUNK_SIZE as i32 - 1,
+            min[1] + CHUNK_SIZE as i32 - 1,
+        ];
+        let occupied = (min[0]..=max[0])
+            .flat_map(|row| (min[1]..=max[1]).map(move |col| [row, col]))
+            .any(|[row, col]| {
+                let (Ok(i), Ok(j)) = (
+                    usize::try_from(row + self.system_offset[0]),
+                    usize::try_from(col + self.system_offset[1]),
+                ) else {
+                    return false;
+                };
+                self.grid_state
+                    .locked_tiles
+                    .get([i, j])
+                    .is_some_and(|&locked| locked > 1)
+            });
+        if occupied {
+            return Err(invalid_parameter(
+                "chunk",
+                &format!("{chunk:?}"),
+                &"chunk already holds placed tiles",
+            ));
+        }
+
+        // Cover the chunk without letting the generation bounds clip it
+        let bounds = self.grid_state.generation_bounds.take();
+        self.extend_grid(min, 0);
+        self.extend_grid(max, 0);
+        self.grid_state.generation_bounds = bounds;
+
+        let placed: Vec<u32> = snapshot
+            .grid
+            .placed_tiles()
+            .map(|(_, locked)| locked)
+            .collect();
+        if !self
+            .grid_state
+            .restore_chunk(snapshot.grid, self.system_offset)
+        {
+            return Err(snapshot_error(&format!(
+                "chunk layers do not match {} tile types",
+                self.step_data.unique_cell_count
+            )));
+        }
+
+        match snapshot.feasibility_counts {
+            Some(counts) => {
+                self.feasibility_layer.insert_chunk(chunk, counts);
+            }
+            None => {
+                self.feasibility_layer.take_chunk(chunk);
+            }
+        }
+
+        for locked in placed {
+            if let Some(tally) = self.selection_tally.get_mut(locked as usize - 2) {
+                *tally += 1;
+            }
+        }
+
+        Ok(chunk)
+    }
+}
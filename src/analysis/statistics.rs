@@ -1,11 +1,405 @@
 //! Pattern statistics preprocessing using kernel density estimation and spatial analysis
 
+use crate::io::configuration::{
+    DEFAULT_KDE_BANDWIDTH, KDE_CROSS_VALIDATION_CANDIDATES, KDE_CROSS_VALIDATION_MAX_POINTS,
+    MAX_KDE_BANDWIDTH, MIN_KDE_BANDWIDTH,
+};
 use crate::io::error::AlgorithmError;
 use crate::math::interpolation::Cubic;
+use crate::math::kernel::{
+    DiscreteKernel, EpanechnikovKernel, GaussianKernel, Kernel, TriangularKernel,
+};
 use crate::math::probability::erf;
 use ndarray::{Array2, Array4};
+use std::borrow::Borrow;
+use std::rc::Rc;
+use std::sync::Arc;
 
 type TaperedInterpolationFn = Box<dyn Fn(f64) -> f64>;
+type LogRatioFn = Box<dyn Fn(f64) -> f64>;
+
+/// How pair relationships are summarised before building influence matrices
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SpatialMode {
+    /// Offsets collapse to Euclidean distance, giving radially symmetric influence
+    #[default]
+    Isotropic,
+    /// Offsets keep their direction, giving direction-aware influence
+    Anisotropic,
+}
+
+/// Offset symmetries applied to anisotropic statistics
+///
+/// Mirrors the tile transformation settings so that directional statistics
+/// agree with the rotated and reflected tiles available to the generator
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OffsetSymmetry {
+    /// Include 90°, 180° and 270° rotations of every offset
+    pub rotations: bool,
+    /// Include horizontal reflections of every offset
+    pub reflections: bool,
+}
+
+impl OffsetSymmetry {
+    /// Create a symmetry matching the tile transformation settings
+    pub const fn new(rotations: bool, reflections: bool) -> Self {
+        Self {
+            rotations,
+            reflections,
+        }
+    }
+
+    /// Return every image of an offset under the symmetry group, including the offset itself
+    ///
+    /// Images are returned with multiplicity so that each offset contributes
+    /// equal total weight regardless of its own symmetries
+    pub fn images(&self, offset: [i32; 2]) -> Vec<[i32; 2]> {
+        let mut images = vec![offset];
+
+        if self.rotations {
+            let mut current = offset;
+            for _ in 0..3 {
+                current = [current[1], -current[0]];
+                images.push(current);
+            }
+        }
+
+        if self.reflections {
+            let current_len = images.len();
+            for i in 0..current_len {
+                if let Some(&[di, dj]) = images.get(i) {
+                    images.push([di, -dj]);
+                }
+            }
+        }
+
+        images
+    }
+}
+
+/// Strategy for choosing the kernel bandwidth of each pair distribution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BandwidthSelection {
+    /// Use the same bandwidth for every pair
+    Fixed(f64),
+    /// Silverman's rule of thumb, robust to heavy tails through the interquartile range
+    Silverman,
+    /// Scott's rule of thumb, based on the standard deviation alone
+    Scott,
+    /// Maximise the leave-one-out log-likelihood over a grid of candidates
+    LikelihoodCrossValidation,
+}
+
+impl Default for BandwidthSelection {
+    fn default() -> Self {
+        Self::Fixed(DEFAULT_KDE_BANDWIDTH)
+    }
+}
+
+impl std::fmt::Display for BandwidthSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fixed(h) => write!(f, "{h}"),
+            Self::Silverman => write!(f, "silverman"),
+            Self::Scott => write!(f, "scott"),
+            Self::LikelihoodCrossValidation => write!(f, "lcv"),
+        }
+    }
+}
+
+impl std::str::FromStr for BandwidthSelection {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "silverman" => Ok(Self::Silverman),
+            "scott" => Ok(Self::Scott),
+            "lcv" | "cv" => Ok(Self::LikelihoodCrossValidation),
+            other => match other.parse::<f64>() {
+                Ok(h) if h.is_finite() && h > 0.0 => Ok(Self::Fixed(h)),
+                _ => Err(crate::io::error::invalid_parameter(
+                    "bandwidth",
+                    &s,
+                    &"expected a positive number, 'silverman', 'scott' or 'lcv'",
+                )),
+            },
+        }
+    }
+}
+
+impl BandwidthSelection {
+    /// Choose a bandwidth for weighted one-dimensional distance data
+    ///
+    /// Weights are treated as observation counts, so the effective sample
+    /// size is the total weight rather than the number of distinct points
+    pub fn select_1d(&self, data_points: &[f64], weights: &[f64], kernel: &dyn Kernel) -> f64 {
+        match self {
+            Self::Fixed(h) => *h,
+            Self::Silverman => {
+                let (sigma, iqr, n) = weighted_spread(data_points, weights);
+                let spread = robust_spread(sigma, iqr);
+                clamp_bandwidth(0.9 * spread * n.powf(-0.2))
+            }
+            Self::Scott => {
+                let (sigma, _, n) = weighted_spread(data_points, weights);
+                clamp_bandwidth(1.06 * sigma * n.powf(-0.2))
+            }
+            Self::LikelihoodCrossValidation => cross_validated_bandwidth(weights, |i, j, h| {
+                let (Some(&x_i), Some(&x_j)) = (data_points.get(i), data_points.get(j)) else {
+                    return 0.0;
+                };
+                // Same reflection at zero as SmoothKernelDistribution::pdf
+                kernel.density(x_i - x_j, h) + kernel.density(x_i + x_j, h)
+            }),
+        }
+    }
+
+    /// Choose a bandwidth for weighted two-dimensional offset data
+    ///
+    /// Rules of thumb use the pooled spread of both axes with the
+    /// two-dimensional sample size exponent of -1/6
+    pub fn select_2d(&self, data_points: &[[f64; 2]], weights: &[f64], kernel: &dyn Kernel) -> f64 {
+        match self {
+            Self::Fixed(h) => *h,
+            Self::Silverman | Self::Scott => {
+                let rows: Vec<f64> = data_points.iter().map(|p| p[0]).collect();
+                let cols: Vec<f64> = data_points.iter().map(|p| p[1]).collect();
+                let (sigma_r, iqr_r, n) = weighted_spread(&rows, weights);
+                let (sigma_c, iqr_c, _) = weighted_spread(&cols, weights);
+                let (spread_r, spread_c) = if *self == Self::Silverman {
+                    (robust_spread(sigma_r, iqr_r), robust_spread(sigma_c, iqr_c))
+                } else {
+                    (sigma_r, sigma_c)
+                };
+                let pooled = f64::midpoint(spread_r * spread_r, spread_c * spread_c).sqrt();
+                clamp_bandwidth(pooled * n.powf(-1.0 / 6.0))
+            }
+            Self::LikelihoodCrossValidation => cross_validated_bandwidth(weights, |i, j, h| {
+                let (Some(x_i), Some(x_j)) = (data_points.get(i), data_points.get(j)) else {
+                    return 0.0;
+                };
+                kernel.density_2d([x_i[0] - x_j[0], x_i[1] - x_j[1]], h)
+            }),
+        }
+    }
+}
+
+/// Built-in kernel shapes for pair-distance density estimation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KernelShape {
+    /// Smooth Gaussian kernel
+    #[default]
+    Gaussian,
+    /// Parabolic kernel with compact support
+    Epanechnikov,
+    /// Linearly decaying kernel with compact support
+    Triangular,
+    /// Empirical histogram of lattice distances without smoothing
+    Discrete,
+}
+
+impl KernelShape {
+    /// Kernel implementation for this shape
+    pub fn kernel(self) -> Arc<dyn Kernel> {
+        match self {
+            Self::Gaussian => Arc::new(GaussianKernel),
+            Self::Epanechnikov => Arc::new(EpanechnikovKernel),
+            Self::Triangular => Arc::new(TriangularKernel),
+            Self::Discrete => Arc::new(DiscreteKernel),
+        }
+    }
+}
+
+impl std::fmt::Display for KernelShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Gaussian => write!(f, "gaussian"),
+            Self::Epanechnikov => write!(f, "epanechnikov"),
+            Self::Triangular => write!(f, "triangular"),
+            Self::Discrete => write!(f, "discrete"),
+        }
+    }
+}
+
+impl std::str::FromStr for KernelShape {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "gaussian" => Ok(Self::Gaussian),
+            "epanechnikov" => Ok(Self::Epanechnikov),
+            "triangular" => Ok(Self::Triangular),
+            "discrete" => Ok(Self::Discrete),
+            _ => Err(crate::io::error::invalid_parameter(
+                "kernel",
+                &s,
+                &"expected 'gaussian', 'epanechnikov', 'triangular' or 'discrete'",
+            )),
+        }
+    }
+}
+
+/// Weighted standard deviation, interquartile range and total weight
+fn weighted_spread(data_points: &[f64], weights: &[f64]) -> (f64, f64, f64) {
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 {
+        return (0.0, 0.0, 0.0);
+    }
+
+    let mean = data_points
+        .iter()
+        .zip(weights)
+        .map(|(x, w)| x * w)
+        .sum::<f64>()
+        / total;
+    let variance = data_points
+        .iter()
+        .zip(weights)
+        .map(|(x, w)| w * (x - mean).powi(2))
+        .sum::<f64>()
+        / total;
+
+    let mut sorted: Vec<(f64, f64)> = data_points
+        .iter()
+        .copied()
+        .zip(weights.iter().copied())
+        .collect();
+    sorted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let quantile = |q: f64| {
+        let target = q * total;
+        let mut cumulative = 0.0;
+        for &(x, w) in &sorted {
+            cumulative += w;
+            if cumulative >= target {
+                return x;
+            }
+        }
+        sorted.last().map_or(0.0, |&(x, _)| x)
+    };
+
+    (variance.sqrt(), quantile(0.75) - quantile(0.25), total)
+}
+
+/// Silverman's spread estimate, falling back to whichever measure is non-zero
+fn robust_spread(sigma: f64, iqr: f64) -> f64 {
+    let iqr_sigma = iqr / 1.34;
+    if iqr_sigma > 0.0 {
+        sigma.min(iqr_sigma)
+    } else {
+        sigma
+    }
+}
+
+fn clamp_bandwidth(h: f64) -> f64 {
+    if h.is_finite() && h > 0.0 {
+        h.clamp(MIN_KDE_BANDWIDTH, MAX_KDE_BANDWIDTH)
+    } else {
+        DEFAULT_KDE_BANDWIDTH
+    }
+}
+
+/// Pick the candidate bandwidth with the highest leave-one-out log-likelihood
+///
+/// `kernel(i, j, h)` evaluates the kernel centred on point j at point i. Each
+/// distinct point is left out with all of its weight: pair distances lie on a
+/// lattice and repeat exactly, so removing a single copy would always favour
+/// the narrowest candidate.
+///
+/// The cost grows with the square of the point count, so beyond
+/// `KDE_CROSS_VALIDATION_MAX_POINTS` points every k-th point is kept for
+/// both the left-out points and the densities they are scored against.
+/// Points arrive sorted by distance or offset, so the sample spans their
+/// whole range.
+fn cross_validated_bandwidth(weights: &[f64], kernel: impl Fn(usize, usize, f64) -> f64) -> f64 {
+    let stride = weights
+        .len()
+        .div_ceil(KDE_CROSS_VALIDATION_MAX_POINTS.max(1))
+        .max(1);
+    let sample: Vec<(usize, f64)> = weights
+        .iter()
+        .copied()
+        .enumerate()
+        .step_by(stride)
+        .collect();
+
+    let total: f64 = sample.iter().map(|&(_, w)| w).sum();
+    if sample.len() < 2 || total <= 0.0 {
+        return DEFAULT_KDE_BANDWIDTH;
+    }
+
+    let steps = KDE_CROSS_VALIDATION_CANDIDATES.max(2) - 1;
+    let ratio = (MAX_KDE_BANDWIDTH / MIN_KDE_BANDWIDTH).powf(1.0 / steps as f64);
+
+    let mut best = (f64::NEG_INFINITY, DEFAULT_KDE_BANDWIDTH);
+    for step in 0..=steps {
+        let h = MIN_KDE_BANDWIDTH * ratio.powi(step as i32);
+
+        let mut log_likelihood = 0.0;
+        for &(i, w_i) in &sample {
+            let remaining = total - w_i;
+            if remaining <= 0.0 {
+                continue;
+            }
+            let density: f64 = sample
+                .iter()
+                .filter(|&&(j, _)| j != i)
+                .map(|&(j, w_j)| w_j * kernel(i, j, h))
+                .sum();
+            let loo_density = (density / remaining).max(f64::MIN_POSITIVE);
+            log_likelihood += w_i * loo_density.ln();
+        }
+
+        if log_likelihood > best.0 {
+            best = (log_likelihood, h);
+        }
+    }
+
+    best.1
+}
+
+/// Distributions grouped by source value, in ascending source order
+fn group_by_source(
+    distributions: &[SmoothKernelDistribution],
+) -> Vec<Vec<&SmoothKernelDistribution>> {
+    let mut grouped: std::collections::BTreeMap<usize, Vec<&SmoothKernelDistribution>> =
+        std::collections::BTreeMap::new();
+    for dist in distributions {
+        grouped.entry(dist.pair.0).or_default().push(dist);
+    }
+    grouped.into_values().collect()
+}
+
+/// Log-ratio of a pair's density at `x` against the mixture of its source group
+///
+/// Pairs with no density at `x` fall back to the uniform share of all pairs
+fn log_density_ratio<D: Borrow<SmoothKernelDistribution>>(
+    dist: &SmoothKernelDistribution,
+    group: &[D],
+    total_distributions: f64,
+    x: f64,
+) -> f64 {
+    let pdf_single = dist.pdf(x);
+    let n = group.len() as f64;
+    let pdf_mixture: f64 = group.iter().map(|d| d.borrow().pdf(x)).sum::<f64>() / n;
+
+    if pdf_mixture > 0.0 && pdf_single > 0.0 {
+        (pdf_single * n / pdf_mixture).ln() - total_distributions.ln()
+    } else {
+        0.0 - total_distributions.ln()
+    }
+}
+
+/// Bandwidth chosen for a single tile value pair
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PairBandwidth {
+    /// Source tile value
+    pub from_value: usize,
+    /// Target tile value
+    pub to_value: usize,
+    /// Selected kernel bandwidth
+    pub bandwidth: f64,
+}
 
 /// Distance-frequency pair for spatial relationship analysis
 #[derive(Debug, Clone)]
@@ -27,6 +421,26 @@ pub struct IntegerPairDistances {
     pub distances: Vec<DistanceFrequency>,
 }
 
+/// Offset-frequency pair for directional spatial relationship analysis
+#[derive(Debug, Clone)]
+pub struct OffsetFrequency {
+    /// Signed (row, column) offset from the source tile to the target tile
+    pub offset: [i32; 2],
+    /// Number of occurrences at this offset
+    pub frequency: usize,
+}
+
+/// Aggregated offset statistics for a specific tile value pair
+#[derive(Debug, Clone)]
+pub struct IntegerPairOffsets {
+    /// Source tile value
+    pub from_value: usize,
+    /// Target tile value
+    pub to_value: usize,
+    /// All observed offsets and their frequencies
+    pub offsets: Vec<OffsetFrequency>,
+}
+
 /// Kernel density estimator for tile pair spatial relationships
 #[derive(Debug, Clone)]
 pub struct SmoothKernelDistribution {
@@ -36,8 +450,10 @@ pub struct SmoothKernelDistribution {
     pub data_points: Vec<f64>,
     /// Frequency weights for each data point
     pub weights: Vec<f64>,
-    /// Gaussian kernel bandwidth parameter
+    /// Kernel bandwidth parameter
     pub bandwidth: f64,
+    /// Smoothing kernel placed on every data point
+    pub kernel: Arc<dyn Kernel>,
 }
 
 impl SmoothKernelDistribution {
@@ -49,10 +465,25 @@ impl SmoothKernelDistribution {
             pair,
             data_points,
             weights,
-            bandwidth: 1.0,
+            bandwidth: DEFAULT_KDE_BANDWIDTH,
+            kernel: Arc::new(GaussianKernel),
         }
     }
 
+    /// Replace the kernel bandwidth
+    #[must_use]
+    pub const fn with_bandwidth(mut self, bandwidth: f64) -> Self {
+        self.bandwidth = bandwidth;
+        self
+    }
+
+    /// Replace the smoothing kernel
+    #[must_use]
+    pub fn with_kernel(mut self, kernel: Arc<dyn Kernel>) -> Self {
+        self.kernel = kernel;
+        self
+    }
+
     /// Calculate PDF at point x using reflection at x=0 boundary to handle edge effects
     pub fn pdf(&self, x: f64) -> f64 {
         if x < 0.0 {
@@ -64,17 +495,72 @@ impl SmoothKernelDistribution {
 
         let mut sum = 0.0;
         for (x_i, w_i) in self.data_points.iter().zip(self.weights.iter()) {
-            let u = (x - x_i) / h;
-            let gaussian = (-0.5 * u.powi(2)).exp() / (2.0 * std::f64::consts::PI).sqrt();
+            sum += w_i * (self.kernel.density(x - x_i, h) + self.kernel.density(x + x_i, h));
+        }
+
+        sum / total_weight
+    }
+}
+
+/// Two-dimensional kernel density estimator over signed pair offsets
+#[derive(Debug, Clone)]
+pub struct SmoothKernelDistribution2d {
+    /// Source and target tile value pair
+    pub pair: (usize, usize),
+    /// Offset values from the source data
+    pub data_points: Vec<[f64; 2]>,
+    /// Frequency weights for each data point
+    pub weights: Vec<f64>,
+    /// Kernel bandwidth parameter, shared by both axes
+    pub bandwidth: f64,
+    /// Smoothing kernel placed on every data point as a product over both axes
+    pub kernel: Arc<dyn Kernel>,
+}
+
+impl SmoothKernelDistribution2d {
+    /// Create a new kernel density estimator from weighted offset data
+    pub fn new(pair: (usize, usize), weighted_data: Vec<([f64; 2], f64)>) -> Self {
+        let (data_points, weights): (Vec<[f64; 2]>, Vec<f64>) = weighted_data.into_iter().unzip();
+
+        Self {
+            pair,
+            data_points,
+            weights,
+            bandwidth: DEFAULT_KDE_BANDWIDTH,
+            kernel: Arc::new(GaussianKernel),
+        }
+    }
+
+    /// Replace the kernel bandwidth
+    #[must_use]
+    pub const fn with_bandwidth(mut self, bandwidth: f64) -> Self {
+        self.bandwidth = bandwidth;
+        self
+    }
+
+    /// Replace the smoothing kernel
+    #[must_use]
+    pub fn with_kernel(mut self, kernel: Arc<dyn Kernel>) -> Self {
+        self.kernel = kernel;
+        self
+    }
 
-            let u_reflected = (x + x_i) / h;
-            let gaussian_reflected =
-                (-0.5 * u_reflected.powi(2)).exp() / (2.0 * std::f64::consts::PI).sqrt();
+    /// Calculate PDF at offset x using a product kernel
+    ///
+    /// Offsets are signed, so no boundary reflection is required
+    pub fn pdf(&self, x: [f64; 2]) -> f64 {
+        let h = self.bandwidth;
+        let total_weight = self.weights.iter().sum::<f64>();
+        if total_weight <= 0.0 {
+            return 0.0;
+        }
 
-            sum += w_i * (gaussian + gaussian_reflected);
+        let mut sum = 0.0;
+        for (x_i, w_i) in self.data_points.iter().zip(self.weights.iter()) {
+            sum += w_i * self.kernel.density_2d([x[0] - x_i[0], x[1] - x_i[1]], h);
         }
 
-        sum / (total_weight * h)
+        sum / total_weight
     }
 }
 
@@ -88,6 +574,16 @@ pub struct Processor {
     pattern_influence_distance: usize,
     /// Radius for grid extension operations
     grid_extension_radius: usize,
+    /// Whether pair statistics keep their direction
+    spatial_mode: SpatialMode,
+    /// Offset symmetries applied in anisotropic mode
+    symmetry: OffsetSymmetry,
+    /// Strategy for choosing each pair's kernel bandwidth
+    bandwidth_selection: BandwidthSelection,
+    /// Bandwidths chosen during the last preprocessing run
+    selected_bandwidths: Vec<PairBandwidth>,
+    /// Smoothing kernel for pair-distance densities, Gaussian when unset
+    kernel: Option<Arc<dyn Kernel>>,
 }
 
 impl Processor {
@@ -103,9 +599,53 @@ impl Processor {
             source_ratios,
             pattern_influence_distance,
             grid_extension_radius,
+            spatial_mode: SpatialMode::Isotropic,
+            symmetry: OffsetSymmetry::new(false, false),
+            bandwidth_selection: BandwidthSelection::Fixed(DEFAULT_KDE_BANDWIDTH),
+            selected_bandwidths: Vec::new(),
+            kernel: None,
         }
     }
 
+    /// Use the given kernel for pair-distance density estimation
+    #[must_use]
+    pub fn with_kernel(mut self, kernel: Arc<dyn Kernel>) -> Self {
+        self.kernel = Some(kernel);
+        self
+    }
+
+    /// Smoothing kernel for pair-distance densities
+    fn kernel(&self) -> Arc<dyn Kernel> {
+        self.kernel
+            .as_ref()
+            .map_or_else(|| Arc::new(GaussianKernel), Arc::clone)
+    }
+
+    /// Use the given strategy to choose each pair's kernel bandwidth
+    #[must_use]
+    pub const fn with_bandwidth_selection(mut self, selection: BandwidthSelection) -> Self {
+        self.bandwidth_selection = selection;
+        self
+    }
+
+    /// Bandwidths chosen for each tile value pair during preprocessing
+    pub fn selected_bandwidths(&self) -> &[PairBandwidth] {
+        &self.selected_bandwidths
+    }
+
+    /// Switch to anisotropic statistics with the given offset symmetry
+    #[must_use]
+    pub const fn with_anisotropy(mut self, symmetry: OffsetSymmetry) -> Self {
+        self.spatial_mode = SpatialMode::Anisotropic;
+        self.symmetry = symmetry;
+        self
+    }
+
+    /// Get the spatial statistics mode
+    pub const fn spatial_mode(&self) -> SpatialMode {
+        self.spatial_mode
+    }
+
     /// Extract all pairwise tile distances from the source pattern
     pub fn calculate_integer_pair_distances(&self) -> Vec<IntegerPairDistances> {
         let (rows, cols) = self.source_data.dim();
@@ -181,6 +721,107 @@ impl Processor {
         result
     }
 
+    /// Extract all signed pairwise tile offsets from the source pattern
+    ///
+    /// Unlike `calculate_integer_pair_distances`, offsets keep their direction
+    /// so that oriented structure such as stripes survives preprocessing
+    pub fn calculate_integer_pair_offsets(&self) -> Vec<IntegerPairOffsets> {
+        let (rows, cols) = self.source_data.dim();
+
+        let mut coordinates_by_value: std::collections::HashMap<usize, Vec<(i32, i32)>> =
+            std::collections::HashMap::new();
+
+        for i in 0..rows {
+            for j in 0..cols {
+                let value = self.source_data.get([i, j]).copied().unwrap_or(0);
+                coordinates_by_value
+                    .entry(value)
+                    .or_default()
+                    .push((i as i32, j as i32));
+            }
+        }
+
+        let mut result = Vec::new();
+
+        for (&from_value, from_coords) in &coordinates_by_value {
+            for (&to_value, to_coords) in &coordinates_by_value {
+                let mut offset_counts: std::collections::HashMap<[i32; 2], usize> =
+                    std::collections::HashMap::new();
+
+                for &(i1, j1) in from_coords {
+                    for &(i2, j2) in to_coords {
+                        if (i1, j1) != (i2, j2) {
+                            *offset_counts.entry([i2 - i1, j2 - j1]).or_insert(0) += 1;
+                        }
+                    }
+                }
+
+                if offset_counts.is_empty() {
+                    continue;
+                }
+
+                let mut offsets: Vec<OffsetFrequency> = offset_counts
+                    .into_iter()
+                    .map(|(offset, frequency)| OffsetFrequency { offset, frequency })
+                    .collect();
+                offsets.sort_by_key(|of| of.offset);
+
+                result.push(IntegerPairOffsets {
+                    from_value,
+                    to_value,
+                    offsets,
+                });
+            }
+        }
+
+        result.sort_by_key(|item| (item.from_value, item.to_value));
+
+        result
+    }
+
+    /// Convert offset statistics into symmetrized two-dimensional density distributions
+    ///
+    /// Each offset is expanded into its images under the configured symmetry,
+    /// matching the transformations applied to the extracted tiles
+    pub fn create_smooth_kernel_distributions_2d(
+        &self,
+        pair_offsets: &[IntegerPairOffsets],
+    ) -> Vec<SmoothKernelDistribution2d> {
+        let mut distributions = Vec::new();
+
+        for pair_data in pair_offsets {
+            if pair_data.offsets.is_empty() {
+                continue;
+            }
+
+            let weighted_data: Vec<([f64; 2], f64)> = pair_data
+                .offsets
+                .iter()
+                .flat_map(|of| {
+                    self.symmetry
+                        .images(of.offset)
+                        .into_iter()
+                        .map(move |[di, dj]| ([f64::from(di), f64::from(dj)], of.frequency as f64))
+                })
+                .collect();
+
+            let dist = SmoothKernelDistribution2d::new(
+                (pair_data.from_value, pair_data.to_value),
+                weighted_data,
+            )
+            .with_kernel(self.kernel());
+            let bandwidth = self.bandwidth_selection.select_2d(
+                &dist.data_points,
+                &dist.weights,
+                dist.kernel.as_ref(),
+            );
+
+            distributions.push(dist.with_bandwidth(bandwidth));
+        }
+
+        distributions
+    }
+
     /// Convert distance statistics into smooth kernel density distributions
     pub fn create_smooth_kernel_distributions(
         &self,
@@ -202,9 +843,15 @@ impl Processor {
             let dist = SmoothKernelDistribution::new(
                 (pair_data.from_value, pair_data.to_value),
                 weighted_data,
+            )
+            .with_kernel(self.kernel());
+            let bandwidth = self.bandwidth_selection.select_1d(
+                &dist.data_points,
+                &dist.weights,
+                dist.kernel.as_ref(),
             );
 
-            distributions.push(dist);
+            distributions.push(dist.with_bandwidth(bandwidth));
         }
 
         distributions
@@ -225,19 +872,9 @@ impl Processor {
     ) -> Result<Vec<Vec<Cubic>>, AlgorithmError> {
         let total_distributions = distributions.len() as f64;
 
-        let mut grouped: std::collections::HashMap<usize, Vec<&SmoothKernelDistribution>> =
-            std::collections::HashMap::new();
-        for dist in distributions {
-            grouped.entry(dist.pair.0).or_default().push(dist);
-        }
-
-        let mut sorted_groups: Vec<(usize, Vec<&SmoothKernelDistribution>)> =
-            grouped.into_iter().collect();
-        sorted_groups.sort_by_key(|(k, _)| *k);
-
         let mut all_interpolations = Vec::new();
 
-        for (_source_value, group) in sorted_groups {
+        for group in group_by_source(distributions) {
             let mut group_interpolations = Vec::new();
 
             for dist in &group {
@@ -245,20 +882,8 @@ impl Processor {
                 let mut y_values = Vec::new();
 
                 for &x in exponential_sample_points {
-                    let pdf_single = dist.pdf(x);
-
-                    let pdf_mixture: f64 =
-                        group.iter().map(|d| d.pdf(x)).sum::<f64>() / group.len() as f64;
-
-                    let n = group.len() as f64;
-                    let ratio = if pdf_mixture > 0.0 && pdf_single > 0.0 {
-                        (pdf_single * n / pdf_mixture).ln() - total_distributions.ln()
-                    } else {
-                        0.0 - total_distributions.ln()
-                    };
-
                     x_values.push(x);
-                    y_values.push(ratio);
+                    y_values.push(log_density_ratio(dist, &group, total_distributions, x));
                 }
 
                 let interpolation =
@@ -275,31 +900,59 @@ impl Processor {
         Ok(all_interpolations)
     }
 
-    /// Apply locality tapering to density interpolations
+    /// Create exact density log-ratios from distributions with a discrete kernel
+    ///
+    /// Evaluates the same log-ratios as `create_density_interpolations` at
+    /// each requested distance instead of interpolating between sample
+    /// points, so every lattice distance keeps its own histogram value
+    pub fn create_lattice_log_ratios(
+        distributions: &[SmoothKernelDistribution],
+    ) -> Vec<Vec<LogRatioFn>> {
+        let total_distributions = distributions.len() as f64;
+
+        group_by_source(distributions)
+            .into_iter()
+            .map(|group| {
+                let group: Rc<Vec<SmoothKernelDistribution>> =
+                    Rc::new(group.into_iter().cloned().collect());
+                (0..group.len())
+                    .map(|target_index| {
+                        let group = Rc::clone(&group);
+                        Box::new(move |x: f64| {
+                            group.get(target_index).map_or(0.0, |dist| {
+                                log_density_ratio(dist, &group, total_distributions, x)
+                            })
+                        }) as LogRatioFn
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Apply locality tapering to density log-ratios
     ///
     /// Transitions from source statistics to uniform distribution beyond
     /// the pattern influence distance using error function-based tapering
     fn apply_locality_tapering(
         &self,
-        density_interpolations: &[Vec<Cubic>],
+        density_log_ratios: Vec<Vec<LogRatioFn>>,
     ) -> Vec<Vec<TaperedInterpolationFn>> {
         let pattern_influence_distance = self.pattern_influence_distance as f64;
         let log_ratios: Vec<f64> = self.source_ratios.iter().map(|r| r.ln()).collect();
 
         let mut tapered_interpolations: Vec<Vec<TaperedInterpolationFn>> = Vec::new();
 
-        for interpolations in density_interpolations {
+        for log_ratio_fns in density_log_ratios {
             let mut tapered_group: Vec<TaperedInterpolationFn> = Vec::new();
 
-            for (target_index, interpolation) in interpolations.iter().enumerate() {
-                let interp_clone = interpolation.clone();
+            for (target_index, log_ratio_fn) in log_ratio_fns.into_iter().enumerate() {
                 let log_ratio = log_ratios.get(target_index).copied().unwrap_or(0.0);
                 let source_min = pattern_influence_distance;
 
                 let tapered_fn = move |x: f64| -> f64 {
                     if x <= source_min {
                         let taper = Self::locality_taper_static(x, source_min);
-                        let source_val = interp_clone.evaluate(x).unwrap_or(0.0);
+                        let source_val = log_ratio_fn(x);
                         source_val.mul_add(1.0 - taper, taper * log_ratio)
                     } else {
                         log_ratio
@@ -407,6 +1060,101 @@ impl Processor {
         Ok(probability_influence_matrices)
     }
 
+    /// Build direction-aware 4D probability influence matrices
+    ///
+    /// Each element [from][to][di][dj] is the log-ratio of the pair density at
+    /// offset (di, dj) against the mixture of all targets for the same source,
+    /// tapered towards the source ratio with distance exactly as the isotropic path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the grid extension radius does not fit in an i32
+    pub fn compute_anisotropic_influence_matrices(
+        &self,
+        distributions: &[SmoothKernelDistribution2d],
+    ) -> crate::io::error::Result<Array4<f64>> {
+        let unique_cell_count = self.source_ratios.len();
+        let matrix_size = 2 * self.grid_extension_radius + 1;
+        let radius = i32::try_from(self.grid_extension_radius).map_err(|_e| {
+            crate::io::error::computation_error(
+                "matrix computation",
+                &format!(
+                    "grid extension radius {} too large for i32",
+                    self.grid_extension_radius
+                ),
+            )
+        })?;
+        let pattern_influence_distance = self.pattern_influence_distance as f64;
+        let total_distributions = distributions.len() as f64;
+
+        let mut grouped: std::collections::HashMap<usize, Vec<&SmoothKernelDistribution2d>> =
+            std::collections::HashMap::new();
+        for dist in distributions {
+            grouped.entry(dist.pair.0).or_default().push(dist);
+        }
+
+        let mut probability_influence_matrices = Array4::<f64>::zeros((
+            unique_cell_count,
+            unique_cell_count,
+            matrix_size,
+            matrix_size,
+        ));
+
+        for from_value in 1..=unique_cell_count {
+            let group = grouped.get(&from_value).map_or(&[][..], Vec::as_slice);
+            let n = group.len() as f64;
+
+            for i in 0..matrix_size {
+                for j in 0..matrix_size {
+                    let di = i as i32 - radius;
+                    let dj = j as i32 - radius;
+                    let offset = [f64::from(di), f64::from(dj)];
+                    let dist = f64::from(di * di + dj * dj).sqrt();
+
+                    let pdf_mixture = if group.is_empty() {
+                        0.0
+                    } else {
+                        group.iter().map(|d| d.pdf(offset)).sum::<f64>() / n
+                    };
+                    let taper = Self::locality_taper_static(dist, pattern_influence_distance);
+
+                    for to_value in 1..=unique_cell_count {
+                        // Pairs absent from the source behave as zero density
+                        let pdf_single = group
+                            .iter()
+                            .find(|d| d.pair.1 == to_value)
+                            .map_or(0.0, |d| d.pdf(offset));
+                        let log_ratio =
+                            self.source_ratios.get(to_value - 1).map_or(0.0, |r| r.ln());
+
+                        let ratio = if pdf_mixture > 0.0 && pdf_single > 0.0 {
+                            (pdf_single * n / pdf_mixture).ln() - total_distributions.ln()
+                        } else {
+                            0.0 - total_distributions.ln()
+                        };
+
+                        let tapered = if dist <= pattern_influence_distance {
+                            ratio.mul_add(1.0 - taper, taper * log_ratio)
+                        } else {
+                            log_ratio
+                        };
+
+                        if let Some(prob_val) = probability_influence_matrices.get_mut([
+                            from_value - 1,
+                            to_value - 1,
+                            i,
+                            j,
+                        ]) {
+                            *prob_val = tapered.exp() / dist.max(1.0);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(probability_influence_matrices)
+    }
+
     /// Preprocess source pattern into probability influence matrices
     ///
     /// This is the main entry point that orchestrates the full statistical
@@ -419,11 +1167,47 @@ impl Processor {
         &mut self,
         exponential_sample_points: &[f64],
     ) -> crate::io::error::Result<Array4<f64>> {
+        if self.spatial_mode == SpatialMode::Anisotropic {
+            let pair_offsets = self.calculate_integer_pair_offsets();
+            let distributions = self.create_smooth_kernel_distributions_2d(&pair_offsets);
+            self.selected_bandwidths = distributions
+                .iter()
+                .map(|d| PairBandwidth {
+                    from_value: d.pair.0,
+                    to_value: d.pair.1,
+                    bandwidth: d.bandwidth,
+                })
+                .collect();
+            return self.compute_anisotropic_influence_matrices(&distributions);
+        }
+
         let pair_distances = self.calculate_integer_pair_distances();
         let distributions = self.create_smooth_kernel_distributions(&pair_distances);
-        let density_interpolations =
-            self.create_density_interpolations(&distributions, exponential_sample_points)?;
-        let tapered_interpolations = self.apply_locality_tapering(&density_interpolations);
+        self.selected_bandwidths = distributions
+            .iter()
+            .map(|d| PairBandwidth {
+                from_value: d.pair.0,
+                to_value: d.pair.1,
+                bandwidth: d.bandwidth,
+            })
+            .collect();
+        // Discrete kernels have no density between lattice distances to interpolate
+        let density_log_ratios = if self.kernel().is_discrete() {
+            Self::create_lattice_log_ratios(&distributions)
+        } else {
+            self.create_density_interpolations(&distributions, exponential_sample_points)?
+                .into_iter()
+                .map(|group| {
+                    group
+                        .into_iter()
+                        .map(|cubic| {
+                            Box::new(move |x: f64| cubic.evaluate(x).unwrap_or(0.0)) as LogRatioFn
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+        let tapered_interpolations = self.apply_locality_tapering(density_log_ratios);
         let probability_influence_matrices =
             self.compute_probability_influence_matrices(&tapered_interpolations)?;
         Ok(probability_influence_matrices)
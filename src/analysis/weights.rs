@@ -29,102 +29,41 @@ pub fn calculate_position_selection(
     step_data: &StepData,
     system_offset: [i32; 2],
 ) -> WeightCalculationResult {
-    let total_placed = selection_tally.iter().sum::<usize>();
-
-    let mut deviations = Vec::with_capacity(step_data.unique_cell_count);
-    for i in 0..step_data.unique_cell_count {
-        let p = step_data.source_ratios.get(i).copied().unwrap_or(0.0);
-        let k = selection_tally.get(i).copied().unwrap_or(0);
-        let n = total_placed;
-
-        let cdf_value = binomial_normal_approximate_cdf(n, p, k);
-        deviations.push(cdf_value - 0.5);
-    }
-
-    let max_deviation: f64 = step_data
-        .source_ratios
-        .iter()
-        .zip(&deviations)
-        .map(|(ratio, dev)| ratio * dev.abs())
-        .sum::<f64>()
-        * 200.0;
-
-    let density_bias_strength = 1.0
-        / (1.0
-            + (-step_data.density_correction_steepness
-                * (max_deviation - step_data.density_correction_threshold))
-                .exp());
-    let density_bias_strength = density_bias_strength.max(step_data.density_minimum_strength);
-
-    let mut density_bias = Array2::<f64>::ones((grid_state.rows(), grid_state.cols()));
-
-    for i in 0..grid_state.rows() {
-        for j in 0..grid_state.cols() {
-            let mut dot_product = 0.0;
-            for (k, deviation) in deviations
-                .iter()
-                .enumerate()
-                .take(step_data.unique_cell_count)
-            {
-                let sign_dev = if deviation >= &0.0 { 1.0 } else { -1.0 };
-                let exp_abs_dev = deviation.abs().exp();
-                let matrix_val = grid_state
-                    .tile_probabilities
-                    .get(k)
-                    .and_then(|probs| probs.get([i, j]))
-                    .copied()
-                    .unwrap_or(0.0);
-                dot_product += sign_dev * exp_abs_dev * matrix_val;
-            }
-            if let Some(bias) = density_bias.get_mut([i, j]) {
-                *bias = density_bias_strength.mul_add(dot_product.exp(), 1.0);
-            }
-        }
-    }
+    let density = DensityBias::new(selection_tally, step_data);
+    let density_bias = density.grid(grid_state);
 
+    // Layers are read in row-major order, matching the standard layout of the arrays
     let mut adjacency_weight_matrix = Array2::<f64>::zeros((grid_state.rows(), grid_state.cols()));
     let mut validity_matrix =
         Array2::<bool>::from_elem((grid_state.rows(), grid_state.cols()), true);
 
-    for i in 0..grid_state.rows() {
-        for j in 0..grid_state.cols() {
-            let adj_val = grid_state
-                .adjacency_weights
-                .get([i, j])
-                .copied()
-                .unwrap_or(0)
-                .saturating_sub(1) as f64;
-            let locked_val = grid_state.locked_tiles.get([i, j]).copied().unwrap_or(0);
-
-            // Set validity to false for locked positions
-            if locked_val > 1 {
-                if let Some(valid) = validity_matrix.get_mut([i, j]) {
-                    *valid = false;
-                }
-            }
+    for (((adj_weight, valid), &adjacency), &locked_val) in adjacency_weight_matrix
+        .iter_mut()
+        .zip(validity_matrix.iter_mut())
+        .zip(grid_state.adjacency_weights.iter())
+        .zip(grid_state.locked_tiles.iter())
+    {
+        // Set validity to false for locked positions
+        if locked_val > 1 {
+            *valid = false;
+        }
 
-            if adj_val > 0.0 {
-                let weight = adj_val.powi(2);
-                if let Some(adj_weight) = adjacency_weight_matrix.get_mut([i, j]) {
-                    *adj_weight = weight;
-                }
-            }
+        let adj_val = adjacency.saturating_sub(1) as f64;
+        if adj_val > 0.0 {
+            *adj_weight = adj_val.powi(2);
         }
     }
 
     let mut weight_matrix = Array2::<f64>::zeros((grid_state.rows(), grid_state.cols()));
-    for i in 0..grid_state.rows() {
-        for j in 0..grid_state.cols() {
-            let entropy = grid_state.entropy.get([i, j]).copied().unwrap_or(0.0);
-            let feasibility = grid_state.feasibility.get([i, j]).copied().unwrap_or(0.0);
-
-            if entropy > 0.0 && feasibility > 0.0 {
-                let adj_weight = adjacency_weight_matrix.get([i, j]).copied().unwrap_or(0.0);
-                let dens_bias = density_bias.get([i, j]).copied().unwrap_or(1.0);
-                if let Some(weight) = weight_matrix.get_mut([i, j]) {
-                    *weight = adj_weight * dens_bias / (feasibility * entropy);
-                }
-            }
+    for ((((weight, &entropy), &feasibility), &adj_weight), &dens_bias) in weight_matrix
+        .iter_mut()
+        .zip(grid_state.entropy.iter())
+        .zip(grid_state.feasibility.iter())
+        .zip(adjacency_weight_matrix.iter())
+        .zip(density_bias.iter())
+    {
+        if entropy > 0.0 && feasibility > 0.0 {
+            *weight = adj_weight * dens_bias / (feasibility * entropy);
         }
     }
 
@@ -146,6 +85,163 @@ pub fn calculate_position_selection(
     }
 }
 
+/// Density correction shared by every position within one selection step
+///
+/// Positions whose probabilities favour under-represented tiles are boosted,
+/// with a strength that rises as the placed distribution drifts from the source
+pub struct DensityBias {
+    /// Signed exponential deviation of each tile from its source ratio
+    factors: Vec<f64>,
+    /// Overall strength of the correction
+    strength: f64,
+}
+
+impl DensityBias {
+    /// Compute the correction from the current selection tally
+    pub fn new(selection_tally: &[usize], step_data: &StepData) -> Self {
+        let total_placed = selection_tally.iter().sum::<usize>();
+
+        let mut deviations = Vec::with_capacity(step_data.unique_cell_count);
+        for i in 0..step_data.unique_cell_count {
+            let p = step_data.source_ratios.get(i).copied().unwrap_or(0.0);
+            let k = selection_tally.get(i).copied().unwrap_or(0);
+            let n = total_placed;
+
+            let cdf_value = binomial_normal_approximate_cdf(n, p, k);
+            deviations.push(cdf_value - 0.5);
+        }
+
+        let max_deviation: f64 = step_data
+            .source_ratios
+            .iter()
+            .zip(&deviations)
+            .map(|(ratio, dev)| ratio * dev.abs())
+            .sum::<f64>()
+            * 200.0;
+
+        let strength = 1.0
+            / (1.0
+                + (-step_data.density_correction_steepness
+                    * (max_deviation - step_data.density_correction_threshold))
+                    .exp());
+        let strength = strength.max(step_data.density_minimum_strength);
+
+        let factors = deviations
+            .iter()
+            .map(|deviation| {
+                let sign_dev = if deviation >= &0.0 { 1.0 } else { -1.0 };
+                sign_dev * deviation.abs().exp()
+            })
+            .collect();
+
+        Self { factors, strength }
+    }
+
+    /// Density bias multiplier at every grid index, reading each tile layer
+    /// in one pass rather than every layer at each index
+    pub fn grid(&self, grid_state: &GridState) -> Array2<f64> {
+        let mut dot_products = Array2::<f64>::zeros((grid_state.rows(), grid_state.cols()));
+        for (factor, probabilities) in self.factors.iter().zip(&grid_state.tile_probabilities) {
+            for (dot_product, &probability) in dot_products.iter_mut().zip(probabilities.iter()) {
+                *dot_product += factor * probability;
+            }
+        }
+        dot_products.mapv_into(|dot_product| self.strength.mul_add(dot_product.exp(), 1.0))
+    }
+
+    /// Density bias multiplier at a grid index
+    pub fn at(&self, grid_state: &GridState, index: [usize; 2]) -> f64 {
+        let mut dot_product = 0.0;
+        for (k, factor) in self.factors.iter().enumerate() {
+            let matrix_val = grid_state
+                .tile_probabilities
+                .get(k)
+                .and_then(|probs| probs.get(index))
+                .copied()
+                .unwrap_or(0.0);
+            dot_product += factor * matrix_val;
+        }
+        self.strength.mul_add(dot_product.exp(), 1.0)
+    }
+}
+
+/// Combined selection weight of a single grid index
+///
+/// Equal to the corresponding `weight_matrix` entry of
+/// `calculate_position_selection`, without building any full-grid arrays
+pub fn position_weight(grid_state: &GridState, index: [usize; 2], density: &DensityBias) -> f64 {
+    let entropy = grid_state.entropy.get(index).copied().unwrap_or(0.0);
+    let feasibility = grid_state.feasibility.get(index).copied().unwrap_or(0.0);
+    if entropy <= 0.0 || feasibility <= 0.0 {
+        return 0.0;
+    }
+
+    adjacency_score(grid_state, index) * density.at(grid_state, index) / (feasibility * entropy)
+}
+
+/// Grid indices of the `k` open positions with the highest adjacency
+///
+/// Returns exactly what `top_k_valid_indices` returns for the adjacency and
+/// validity matrices of `calculate_position_selection`, in the same order.
+/// The heap is fed the same row-major sequence: every open cell until it holds
+/// `k` entries, then only frontier cells, since a cell with no adjacency can
+/// never displace the minimum. The scan therefore stops at the `k`-th open
+/// cell and the remainder reads the frontier index.
+pub fn top_k_frontier_indices(
+    grid_state: &GridState,
+    system_offset: [i32; 2],
+    k: usize,
+) -> Vec<[usize; 2]> {
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    let mut scanned_through = None;
+
+    for (index, &locked) in grid_state.locked_tiles.indexed_iter() {
+        if heap.len() >= k {
+            break;
+        }
+        let index = <[usize; 2]>::from(index);
+        scanned_through = Some(index);
+
+        let world_pos = [
+            index[0] as i32 - system_offset[0],
+            index[1] as i32 - system_offset[1],
+        ];
+        if locked > 1 || !grid_state.in_generation_bounds(world_pos) {
+            continue;
+        }
+        offer_top_k(&mut heap, index, adjacency_score(grid_state, index), k);
+    }
+
+    let mut remaining: Vec<[usize; 2]> = grid_state
+        .frontier
+        .iter_descending()
+        .filter_map(|(position, _)| {
+            let row = usize::try_from(position[0] + system_offset[0]).ok()?;
+            let col = usize::try_from(position[1] + system_offset[1]).ok()?;
+            (row < grid_state.rows() && col < grid_state.cols()).then_some([row, col])
+        })
+        .filter(|&index| scanned_through.is_none_or(|scanned| index > scanned))
+        .collect();
+    remaining.sort_unstable();
+
+    for index in remaining {
+        offer_top_k(&mut heap, index, adjacency_score(grid_state, index), k);
+    }
+
+    heap.into_iter().map(|Reverse(iv)| iv.index).collect()
+}
+
+/// Adjacency score of a grid index as it appears in the adjacency matrix
+fn adjacency_score(grid_state: &GridState, index: [usize; 2]) -> f64 {
+    let adj_val = grid_state
+        .adjacency_weights
+        .get(index)
+        .copied()
+        .unwrap_or(0)
+        .saturating_sub(1) as f64;
+    if adj_val > 0.0 { adj_val.powi(2) } else { 0.0 }
+}
+
 /// Apply boundary mask to mark positions outside bounds as invalid
 fn apply_boundary_mask(
     _weight_matrix: &mut Array2<f64>,
@@ -211,22 +307,7 @@ pub fn top_k_valid_indices(
                 continue;
             }
 
-            let value = matrix[[i, j]];
-
-            if heap.len() < k {
-                heap.push(Reverse(IndexValue {
-                    index: [i, j],
-                    value,
-                }));
-            } else if let Some(Reverse(min_elem)) = heap.peek() {
-                if value > min_elem.value {
-                    heap.pop();
-                    heap.push(Reverse(IndexValue {
-                        index: [i, j],
-                        value,
-                    }));
-                }
-            }
+            offer_top_k(&mut heap, [i, j], matrix[[i, j]], k);
         }
     }
 
@@ -239,26 +320,46 @@ pub fn top_k_from_indices(
     indices: &[[usize; 2]],
     k: usize,
 ) -> Vec<[usize; 2]> {
+    top_k_weighted(indices.iter().map(|&[i, j]| ([i, j], matrix[[i, j]])), k)
+        .into_iter()
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Returns the top K weighted indices with their weights
+///
+/// Selects and orders exactly as `top_k_from_indices` does for a matrix
+/// holding the same weights
+pub fn top_k_weighted(
+    weighted: impl IntoIterator<Item = ([usize; 2], f64)>,
+    k: usize,
+) -> Vec<([usize; 2], f64)> {
     let mut heap = BinaryHeap::with_capacity(k + 1);
 
-    for &[i, j] in indices {
-        let value = matrix[[i, j]];
-
-        if heap.len() < k {
-            heap.push(Reverse(IndexValue {
-                index: [i, j],
-                value,
-            }));
-        } else if let Some(Reverse(min_elem)) = heap.peek() {
-            if value > min_elem.value {
-                heap.pop();
-                heap.push(Reverse(IndexValue {
-                    index: [i, j],
-                    value,
-                }));
-            }
-        }
+    for (index, value) in weighted {
+        offer_top_k(&mut heap, index, value, k);
     }
 
-    heap.into_iter().map(|Reverse(iv)| iv.index).collect()
+    heap.into_iter()
+        .map(|Reverse(iv)| (iv.index, iv.value))
+        .collect()
+}
+
+/// Offer a value to a min-heap holding the top K seen so far
+///
+/// Equal values never displace the minimum, so earlier entries win ties
+fn offer_top_k(
+    heap: &mut BinaryHeap<Reverse<IndexValue>>,
+    index: [usize; 2],
+    value: f64,
+    k: usize,
+) {
+    if heap.len() < k {
+        heap.push(Reverse(IndexValue { index, value }));
+    } else if let Some(Reverse(min_elem)) = heap.peek()
+        && value > min_elem.value
+    {
+        heap.pop();
+        heap.push(Reverse(IndexValue { index, value }));
+    }
 }
@@ -1,16 +1,28 @@
 //! Captures and exports algorithm metrics as animated visualization
-
-use crate::io::error::Result;
-use crate::io::visualization::VisualizationCapture;
+//!
+//! Cells recorded in the same iteration are gathered into one frame, keeping
+//! only the latest values of each cell. When the iteration moves on, the frame
+//! is appended to a spool file in the temporary directory, so memory holds a
+//! single frame however long the run. Export reads the spool back in order,
+//! encoding each frame as soon as it is rendered, and the spool is removed
+//! with the capture.
+
+use crate::analysis::statistics::{BandwidthSelection, PairBandwidth};
+use crate::io::configuration::FINAL_FRAME_HOLD;
+use crate::io::error::{AlgorithmError, Result};
+use crate::io::render::{CellOrigin, FrameCanvas, RenderOptions};
+use crate::io::visualization::{TilePlacement, VisualizationCapture};
 use crate::spatial::GridState;
 use crate::spatial::grid;
-use std::collections::HashMap;
+use image::RgbaImage;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-struct RgbaFrame {
-    width: u32,
-    height: u32,
-    pixels: Vec<u8>,
-}
+/// Spools created by this process, naming each one uniquely
+static SPOOLS_CREATED: AtomicUsize = AtomicUsize::new(0);
 
 /// Analysis data captured at a specific grid position during algorithm execution
 #[derive(Debug, Clone)]
@@ -29,25 +41,139 @@ pub struct AnalysisEvent {
     pub weighted_color: [u8; 4],
 }
 
+impl AnalysisEvent {
+    // Size of an event in the spool file
+    const RECORD_BYTES: usize = 36;
+
+    fn write_to(&self, out: &mut impl Write) -> std::io::Result<()> {
+        out.write_all(&self.row.to_le_bytes())?;
+        out.write_all(&self.col.to_le_bytes())?;
+        out.write_all(&(self.iteration as u64).to_le_bytes())?;
+        out.write_all(&self.entropy.to_le_bytes())?;
+        out.write_all(&self.feasibility.to_le_bytes())?;
+        out.write_all(&self.weighted_color)
+    }
+
+    fn read_from(input: &mut impl Read) -> std::io::Result<Self> {
+        let mut record = [0; Self::RECORD_BYTES];
+        input.read_exact(&mut record)?;
+        let fields = || -> Option<Self> {
+            let (row, rest) = record.split_first_chunk::<4>()?;
+            let (col, rest) = rest.split_first_chunk::<4>()?;
+            let (iteration, rest) = rest.split_first_chunk::<8>()?;
+            let (entropy, rest) = rest.split_first_chunk::<8>()?;
+            let (feasibility, rest) = rest.split_first_chunk::<8>()?;
+            let (weighted_color, _) = rest.split_first_chunk::<4>()?;
+            Some(Self {
+                row: i32::from_le_bytes(*row),
+                col: i32::from_le_bytes(*col),
+                iteration: u64::from_le_bytes(*iteration) as usize,
+                entropy: f64::from_le_bytes(*entropy),
+                feasibility: f64::from_le_bytes(*feasibility),
+                weighted_color: *weighted_color,
+            })
+        };
+        fields().ok_or_else(|| std::io::ErrorKind::InvalidData.into())
+    }
+}
+
 /// Captures and visualizes algorithm metrics in a 2x2 grid layout:
 /// - Top-left: Weighted color probabilities
 /// - Top-right: Actual tile placements
 /// - Bottom-left: Entropy (information content)
 /// - Bottom-right: Feasibility (placement viability)
 pub struct AnalysisCapture {
-    events: Vec<AnalysisEvent>,
+    /// Cells recorded in the current iteration, by absolute position
+    frame: BTreeMap<(i32, i32), AnalysisEvent>,
+    frame_iteration: usize,
+    /// Earlier frames, created with the first one written
+    spool: Option<BufWriter<File>>,
+    spool_path: Option<PathBuf>,
+    spooled: usize,
+    spool_error: Option<std::io::Error>,
+    /// Minimum and maximum row, then column, of every recorded cell
+    extent: Option<[i32; 4]>,
+    max_entropy: f64,
+    max_iteration: usize,
     color_mapping: Vec<[u8; 4]>,
     capture_radius: i32,
+    bandwidth_selection: BandwidthSelection,
+    pair_bandwidths: Vec<PairBandwidth>,
 }
 
 impl AnalysisCapture {
     /// Create a new analysis capture with color mapping and grid parameters
     pub fn new(color_mapping: Vec<[u8; 4]>, grid_extension_radius: i32) -> Self {
         Self {
-            events: Vec::with_capacity(10000),
+            frame: BTreeMap::new(),
+            frame_iteration: 0,
+            spool: None,
+            spool_path: None,
+            spooled: 0,
+            spool_error: None,
+            extent: None,
+            max_entropy: 0.0,
+            max_iteration: 0,
             color_mapping,
             capture_radius: grid_extension_radius,
+            bandwidth_selection: BandwidthSelection::default(),
+            pair_bandwidths: Vec::new(),
+        }
+    }
+
+    /// Record the kernel density bandwidths chosen during preprocessing
+    pub fn set_bandwidths(
+        &mut self,
+        selection: BandwidthSelection,
+        pair_bandwidths: Vec<PairBandwidth>,
+    ) {
+        self.bandwidth_selection = selection;
+        self.pair_bandwidths = pair_bandwidths;
+    }
+
+    /// Kernel density bandwidths chosen for each tile value pair
+    pub fn pair_bandwidths(&self) -> &[PairBandwidth] {
+        &self.pair_bandwidths
+    }
+
+    /// Export run metadata, including the chosen bandwidths, as JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parent directory cannot be created or the file cannot be written
+    pub fn export_metadata(&self, output_path: &str) -> Result<()> {
+        let bandwidths = self
+            .pair_bandwidths
+            .iter()
+            .map(|pb| {
+                serde_json::json!({
+                    "from": pb.from_value,
+                    "to": pb.to_value,
+                    "bandwidth": pb.bandwidth,
+                })
+            })
+            .collect::<Vec<_>>();
+        let metadata = serde_json::json!({
+            "bandwidth_selection": self.bandwidth_selection.to_string(),
+            "bandwidths": bandwidths,
+        });
+
+        if let Some(parent) = std::path::Path::new(output_path).parent() {
+            std::fs::create_dir_all(parent).map_err(|e| AlgorithmError::FileSystem {
+                path: parent.to_path_buf(),
+                operation: "create directory",
+                source: e,
+            })?;
         }
+
+        let write_error = |e: std::io::Error| AlgorithmError::FileSystem {
+            path: output_path.into(),
+            operation: "write file",
+            source: e,
+        };
+        let file = File::create(output_path).map_err(write_error)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), &metadata)
+            .map_err(|e| write_error(e.into()))
     }
 
     /// Calculates the weighted average color from cell probabilities
@@ -60,14 +186,15 @@ impl AnalysisCapture {
 
         // Cells are 1 indexed, conversion to 0 index for the color lookup here
         for (tile_idx, &prob) in probabilities.iter().enumerate() {
-            if prob > 0.0 && tile_idx > 0 {
-                if let Some(color) = self.color_mapping.get(tile_idx - 1) {
-                    weighted_r += color[0] as f64 * prob;
-                    weighted_g += color[1] as f64 * prob;
-                    weighted_b += color[2] as f64 * prob;
-                    weighted_a += color[3] as f64 * prob;
-                    total_weight += prob;
-                }
+            if prob > 0.0
+                && tile_idx > 0
+                && let Some(color) = self.color_mapping.get(tile_idx - 1)
+            {
+                weighted_r += color[0] as f64 * prob;
+                weighted_g += color[1] as f64 * prob;
+                weighted_b += color[2] as f64 * prob;
+                weighted_a += color[3] as f64 * prob;
+                total_weight += prob;
             }
         }
 
@@ -83,9 +210,9 @@ impl AnalysisCapture {
         }
     }
 
-    /// Get the number of events captured
-    pub const fn event_count(&self) -> usize {
-        self.events.len()
+    /// Get the number of events captured, counting each cell once per iteration
+    pub fn event_count(&self) -> usize {
+        self.spooled + self.frame.len()
     }
 
     /// Records analysis data for a region around a placement
@@ -117,10 +244,10 @@ impl AnalysisCapture {
 
                 let mut probs = vec![0.0; grid_state.unique_cell_count + 1];
                 for (i, prob_matrix) in grid_state.tile_probabilities.iter().enumerate() {
-                    if let Some(prob_value) = prob_matrix.get([row, col]) {
-                        if let Some(prob_slot) = probs.get_mut(i + 1) {
-                            *prob_slot = *prob_value;
-                        }
+                    if let Some(prob_value) = prob_matrix.get([row, col])
+                        && let Some(prob_slot) = probs.get_mut(i + 1)
+                    {
+                        *prob_slot = *prob_value;
                     }
                 }
                 let weighted_color = self.calculate_weighted_color(&probs);
@@ -128,7 +255,7 @@ impl AnalysisCapture {
                 let abs_row = row as i32 - system_offset[0];
                 let abs_col = col as i32 - system_offset[1];
 
-                self.events.push(AnalysisEvent {
+                self.record(AnalysisEvent {
                     row: abs_row,
                     col: abs_col,
                     iteration,
@@ -140,6 +267,50 @@ impl AnalysisCapture {
         }
     }
 
+    fn record(&mut self, event: AnalysisEvent) {
+        if event.iteration != self.frame_iteration {
+            self.flush_frame();
+            self.frame_iteration = event.iteration;
+        }
+
+        self.extent = Some(self.extent.map_or(
+            [event.row, event.row, event.col, event.col],
+            |[min_row, max_row, min_col, max_col]| {
+                [
+                    min_row.min(event.row),
+                    max_row.max(event.row),
+                    min_col.min(event.col),
+                    max_col.max(event.col),
+                ]
+            },
+        ));
+        self.max_entropy = self.max_entropy.max(event.entropy);
+        self.max_iteration = self.max_iteration.max(event.iteration);
+        self.frame.insert((event.row, event.col), event);
+    }
+
+    // Appends the current frame to the spool, keeping the first write error
+    // to report on export
+    fn flush_frame(&mut self) {
+        if self.frame.is_empty() || self.spool_error.is_some() {
+            self.frame.clear();
+            return;
+        }
+        let frame = std::mem::take(&mut self.frame);
+        let written = match &mut self.spool {
+            Some(spool) => Ok(spool),
+            None => create_spool().map(|(path, file)| {
+                self.spool_path = Some(path);
+                self.spool.insert(BufWriter::new(file))
+            }),
+        }
+        .and_then(|spool| frame.values().try_for_each(|event| event.write_to(spool)));
+        match written {
+            Ok(()) => self.spooled += frame.len(),
+            Err(error) => self.spool_error = Some(error),
+        }
+    }
+
     /// Calculate unified bounds across all events and visualization placements
     ///
     /// Determines the minimal bounding box that contains all analysis events
@@ -147,337 +318,348 @@ impl AnalysisCapture {
     fn calculate_unified_bounds(
         &self,
         visualization: &VisualizationCapture,
-    ) -> (i32, i32, usize, usize) {
-        let mut min_row = i32::MAX;
-        let mut max_row = i32::MIN;
-        let mut min_col = i32::MAX;
-        let mut max_col = i32::MIN;
-
-        for event in &self.events {
-            min_row = min_row.min(event.row);
-            max_row = max_row.max(event.row);
-            min_col = min_col.min(event.col);
-            max_col = max_col.max(event.col);
-        }
-
-        let placements = visualization.get_placements();
-        for placement in placements {
-            min_row = min_row.min(placement.row);
-            max_row = max_row.max(placement.row);
-            min_col = min_col.min(placement.col);
-            max_col = max_col.max(placement.col);
-        }
+    ) -> Option<(i32, i32, usize, usize)> {
+        let [min_row, max_row, min_col, max_col] =
+            visualization
+                .get_placements()
+                .iter()
+                .fold(self.extent, |extent, placement| {
+                    Some(extent.map_or(
+                        [placement.row, placement.row, placement.col, placement.col],
+                        |[min_row, max_row, min_col, max_col]| {
+                            [
+                                min_row.min(placement.row),
+                                max_row.max(placement.row),
+                                min_col.min(placement.col),
+                                max_col.max(placement.col),
+                            ]
+                        },
+                    ))
+                })?;
 
         let total_rows = (max_row - min_row + 1) as usize;
         let total_cols = (max_col - min_col + 1) as usize;
 
-        (min_row, min_col, total_rows, total_cols)
+        Some((min_row, min_col, total_rows, total_cols))
     }
 
-    fn create_entropy_grid(
-        &self,
-        bounds: (i32, i32, usize, usize),
-        up_to_iteration: usize,
-    ) -> Vec<Vec<f64>> {
-        let (min_row, min_col, rows, cols) = bounds;
-        let mut grid = vec![vec![0.0; cols]; rows];
-
-        for event in &self.events {
-            if event.iteration <= up_to_iteration {
-                let row_idx = (event.row - min_row) as usize;
-                let col_idx = (event.col - min_col) as usize;
-                if let Some(row) = grid.get_mut(row_idx) {
-                    if let Some(cell) = row.get_mut(col_idx) {
-                        *cell = event.entropy;
-                    }
-                }
-            }
+    /// Export analysis as animated GIF with 2x2 layout
+    ///
+    /// Creates an animated visualization showing the algorithm's decision-making
+    /// process through entropy, feasibility, and probability metrics. Frames
+    /// are rendered from the recorded cells in order and written one at a
+    /// time; recording may continue after export. The render options apply
+    /// to every panel, with overlays tinting the tile placements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if nothing was captured, the render options cannot be
+    /// drawn, recorded cells could not be kept in the temporary file, or image
+    /// creation or file operations fail
+    pub fn export_analysis(
+        &mut self,
+        visualization: &VisualizationCapture,
+        output_path: &str,
+        frame_delay_ms: u32,
+        options: RenderOptions,
+    ) -> Result<()> {
+        let spool_failure = |source| AlgorithmError::FileSystem {
+            path: std::env::temp_dir(),
+            operation: "spool analysis events",
+            source,
+        };
+
+        options.validate()?;
+        self.flush_frame();
+        if let Some(error) = self.spool_error.take() {
+            return Err(spool_failure(error));
         }
+        let bounds = self
+            .calculate_unified_bounds(visualization)
+            .ok_or_else(|| AlgorithmError::InvalidSourceData {
+                reason: "No analysis events or tile placements captured".to_string(),
+            })?;
 
-        grid
-    }
+        let max_iteration = self.max_iteration.max(
+            visualization
+                .get_placements()
+                .iter()
+                .map(|p| p.iteration)
+                .max()
+                .unwrap_or(0),
+        );
 
-    fn create_feasibility_grid(
-        &self,
-        bounds: (i32, i32, usize, usize),
-        up_to_iteration: usize,
-    ) -> Vec<Vec<f64>> {
-        let (min_row, min_col, rows, cols) = bounds;
-        let mut grid = vec![vec![0.0; cols]; rows];
-
-        for event in &self.events {
-            if event.iteration <= up_to_iteration {
-                let row_idx = (event.row - min_row) as usize;
-                let col_idx = (event.col - min_col) as usize;
-                if let Some(row) = grid.get_mut(row_idx) {
-                    if let Some(cell) = row.get_mut(col_idx) {
-                        *cell = event.feasibility;
-                    }
+        if let Some(parent) = std::path::Path::new(output_path).parent() {
+            std::fs::create_dir_all(parent).map_err(|e| AlgorithmError::FileSystem {
+                path: parent.to_path_buf(),
+                operation: "create directory",
+                source: e,
+            })?;
+        }
+
+        let file = File::create(output_path).map_err(|e| AlgorithmError::FileSystem {
+            path: output_path.into(),
+            operation: "create file",
+            source: e,
+        })?;
+        let mut encoder = image::codecs::gif::GifEncoder::new(BufWriter::new(file));
+        let mut encode = |image: RgbaImage, delay_ms: u32| {
+            encoder
+                .encode_frame(image::Frame::from_parts(
+                    image,
+                    0,
+                    0,
+                    image::Delay::from_numer_denom_ms(delay_ms, 1),
+                ))
+                .map_err(|e| AlgorithmError::ImageExport {
+                    path: output_path.into(),
+                    source: e,
+                })
+        };
+
+        let mut events = match &mut self.spool {
+            Some(spool) => {
+                spool
+                    .flush()
+                    .and_then(|()| spool.get_mut().rewind())
+                    .map_err(spool_failure)?;
+                Some(BufReader::new(spool.get_mut()))
+            }
+            None => None,
+        };
+        let mut remaining = self.spooled;
+        let mut next_event = || -> Result<Option<AnalysisEvent>> {
+            match &mut events {
+                Some(reader) if remaining > 0 => {
+                    remaining -= 1;
+                    AnalysisEvent::read_from(reader)
+                        .map(Some)
+                        .map_err(spool_failure)
                 }
+                _ => Ok(None),
             }
-        }
+        };
 
-        grid
-    }
+        let mut canvas = AnalysisCanvas::new(bounds);
+        let mut placements = visualization.get_placements().iter().peekable();
+        let mut pending = next_event()?;
+        for iteration in 0..=max_iteration {
+            while let Some(event) = pending.take_if(|event| event.iteration <= iteration) {
+                canvas.record(&event);
+                pending = next_event()?;
+            }
+            while let Some(placement) = placements.next_if(|p| p.iteration <= iteration) {
+                canvas.place(placement);
+            }
 
-    fn reconstruct_grid_at_iteration(
-        visualization: &VisualizationCapture,
-        iteration: usize,
-    ) -> HashMap<(i32, i32), usize> {
-        let mut grid = HashMap::new();
-        for placement in visualization.get_placements() {
-            if placement.iteration <= iteration {
-                if let Some(tile_ref) = placement.tile_ref {
-                    grid.insert((placement.row, placement.col), tile_ref as usize);
-                } else {
-                    grid.remove(&(placement.row, placement.col));
-                }
+            // max_entropy ensures consistent normalization across all frames
+            let image = canvas.render(
+                &self.color_mapping,
+                self.max_entropy,
+                (iteration, max_iteration),
+                options,
+            );
+            if iteration == max_iteration {
+                // Final frame repeated with a longer delay for viewing
+                encode(image.clone(), frame_delay_ms)?;
+                encode(image, frame_delay_ms * FINAL_FRAME_HOLD)?;
+            } else {
+                encode(image, frame_delay_ms)?;
             }
         }
-        grid
-    }
 
-    fn create_weighted_color_grid(
-        &self,
-        bounds: (i32, i32, usize, usize),
-        up_to_iteration: usize,
-    ) -> Vec<Vec<[u8; 4]>> {
-        let (min_row, min_col, rows, cols) = bounds;
-        let mut grid = vec![vec![[0, 0, 0, 255]; cols]; rows];
-
-        for event in &self.events {
-            if event.iteration <= up_to_iteration {
-                let row_idx = (event.row - min_row) as usize;
-                let col_idx = (event.col - min_col) as usize;
-                if let Some(row) = grid.get_mut(row_idx) {
-                    if let Some(cell) = row.get_mut(col_idx) {
-                        *cell = event.weighted_color;
-                    }
-                }
-            }
+        if let Some(spool) = &mut self.spool {
+            spool
+                .get_mut()
+                .seek(SeekFrom::End(0))
+                .map_err(spool_failure)?;
         }
 
-        grid
+        Ok(())
     }
+}
 
-    fn render_combined_frame(
-        &self,
-        visualization: &VisualizationCapture,
-        bounds: (i32, i32, usize, usize),
-        iteration: usize,
-        _delay_ms: u32,
-        max_entropy: f64,
-    ) -> RgbaFrame {
-        let (min_row, min_col, grid_rows, grid_cols) = bounds;
-
-        let entropy_grid = self.create_entropy_grid(bounds, iteration);
-        let feasibility_grid = self.create_feasibility_grid(bounds, iteration);
-        let weighted_color_grid = self.create_weighted_color_grid(bounds, iteration);
-
-        let tile_grid = Self::reconstruct_grid_at_iteration(visualization, iteration);
-
-        // max_entropy ensures consistent normalization across all frames
+impl Drop for AnalysisCapture {
+    fn drop(&mut self) {
+        if let Some(path) = &self.spool_path {
+            std::fs::remove_file(path).ok();
+        }
+    }
+}
 
-        let padding = 2;
-        let total_width = grid_cols * 2 + padding;
-        let total_height = grid_rows * 2 + padding;
+// Creates an empty spool file in the temporary directory, named by process
+// and creation order so concurrent captures never share one
+fn create_spool() -> std::io::Result<(PathBuf, File)> {
+    let path = std::env::temp_dir().join(format!(
+        "greedytile-analysis-{}-{}.spool",
+        std::process::id(),
+        SPOOLS_CREATED.fetch_add(1, Ordering::Relaxed)
+    ));
+    let file = File::options()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    Ok((path, file))
+}
 
-        let mut pixels = vec![0u8; total_width * total_height * 4];
+// Latest metrics and tiles of every cell within the unified bounds, as of the
+// iteration being rendered
+struct AnalysisCanvas {
+    min_row: i32,
+    min_col: i32,
+    rows: usize,
+    cols: usize,
+    entropy: Vec<f64>,
+    feasibility: Vec<f64>,
+    weighted_colors: Vec<[u8; 4]>,
+    tiles: Vec<Option<usize>>,
+    origins: Vec<Option<CellOrigin>>,
+    latest: Option<[usize; 2]>,
+}
 
-        for row in 0..grid_rows {
-            for col in 0..grid_cols {
-                let pixel_idx = (row * total_width + col) * 4;
-                if let Some(grid_row) = weighted_color_grid.get(row) {
-                    if let Some(color) = grid_row.get(col) {
-                        if let Some(pixel_slice) = pixels.get_mut(pixel_idx..pixel_idx + 4) {
-                            pixel_slice.copy_from_slice(color);
-                        }
-                    }
-                }
-            }
+impl AnalysisCanvas {
+    fn new(bounds: (i32, i32, usize, usize)) -> Self {
+        let (min_row, min_col, rows, cols) = bounds;
+        let cells = rows * cols;
+        Self {
+            min_row,
+            min_col,
+            rows,
+            cols,
+            entropy: vec![0.0; cells],
+            feasibility: vec![0.0; cells],
+            weighted_colors: vec![[0, 0, 0, 255]; cells],
+            tiles: vec![None; cells],
+            origins: vec![None; cells],
+            latest: None,
         }
+    }
 
-        for row in 0..grid_rows {
-            for col in 0..grid_cols {
-                let pixel_idx = (row * total_width + col + grid_cols + padding) * 4;
-                let abs_row = row as i32 + min_row;
-                let abs_col = col as i32 + min_col;
-
-                if let Some(&tile_idx) = tile_grid.get(&(abs_row, abs_col)) {
-                    if tile_idx > 1 {
-                        if let Some(color) = self.color_mapping.get(tile_idx - 2) {
-                            if let Some(pixel_slice) = pixels.get_mut(pixel_idx..pixel_idx + 4) {
-                                pixel_slice.copy_from_slice(color);
-                            }
-                        }
-                    }
-                } else if let Some(pixel_slice) = pixels.get_mut(pixel_idx..pixel_idx + 4) {
-                    pixel_slice.copy_from_slice(&[0, 0, 0, 255]);
-                }
-            }
-        }
+    fn cell(&self, row: i32, col: i32) -> Option<[usize; 2]> {
+        let row = usize::try_from(row - self.min_row).ok()?;
+        let col = usize::try_from(col - self.min_col).ok()?;
+        (row < self.rows && col < self.cols).then_some([row, col])
+    }
 
-        for row in 0..grid_rows {
-            for col in 0..grid_cols {
-                let pixel_idx = ((row + grid_rows + padding) * total_width + col) * 4;
-                if let Some(grid_row) = entropy_grid.get(row) {
-                    if let Some(&entropy) = grid_row.get(col) {
-                        let normalized = if max_entropy > 0.0 {
-                            (entropy / max_entropy * 255.0) as u8
-                        } else {
-                            0
-                        };
-                        if let Some(pixel_slice) = pixels.get_mut(pixel_idx..pixel_idx + 4) {
-                            pixel_slice.copy_from_slice(&[normalized, normalized, normalized, 255]);
-                        }
-                    }
-                }
-            }
-        }
+    fn index(&self, row: i32, col: i32) -> Option<usize> {
+        self.cell(row, col).map(|[row, col]| row * self.cols + col)
+    }
 
-        for row in 0..grid_rows {
-            for col in 0..grid_cols {
-                let pixel_idx =
-                    ((row + grid_rows + padding) * total_width + col + grid_cols + padding) * 4;
-                if let Some(grid_row) = feasibility_grid.get(row) {
-                    if let Some(&feasibility) = grid_row.get(col) {
-                        let normalized = (feasibility * 255.0) as u8;
-                        if let Some(pixel_slice) = pixels.get_mut(pixel_idx..pixel_idx + 4) {
-                            pixel_slice.copy_from_slice(&[normalized, normalized, normalized, 255]);
-                        }
-                    }
-                }
-            }
+    fn record(&mut self, event: &AnalysisEvent) {
+        if let Some(index) = self.index(event.row, event.col)
+            && let (Some(entropy), Some(feasibility), Some(color)) = (
+                self.entropy.get_mut(index),
+                self.feasibility.get_mut(index),
+                self.weighted_colors.get_mut(index),
+            )
+        {
+            *entropy = event.entropy;
+            *feasibility = event.feasibility;
+            *color = event.weighted_color;
         }
+    }
 
-        let gray = [128u8, 128, 128, 255];
-        for row in 0..grid_rows {
-            for p in 0..padding {
-                let pixel_idx = (row * total_width + grid_cols + p) * 4;
-                if let Some(pixel_slice) = pixels.get_mut(pixel_idx..pixel_idx + 4) {
-                    pixel_slice.copy_from_slice(&gray);
-                }
-                let bottom_pixel_idx =
-                    ((row + grid_rows + padding) * total_width + grid_cols + p) * 4;
-                if let Some(pixel_slice) = pixels.get_mut(bottom_pixel_idx..bottom_pixel_idx + 4) {
-                    pixel_slice.copy_from_slice(&gray);
-                }
-            }
-        }
-        for col in 0..total_width {
-            for p in 0..padding {
-                let pixel_idx = ((grid_rows + p) * total_width + col) * 4;
-                if let Some(pixel_slice) = pixels.get_mut(pixel_idx..pixel_idx + 4) {
-                    pixel_slice.copy_from_slice(&gray);
-                }
-            }
+    fn place(&mut self, placement: &TilePlacement) {
+        let Some(index) = self.index(placement.row, placement.col) else {
+            return;
+        };
+        if let (Some(tile), Some(origin)) = (self.tiles.get_mut(index), self.origins.get_mut(index))
+        {
+            *tile = placement.tile_ref.map(|tile_ref| tile_ref as usize);
+            *origin = match placement.tile_ref {
+                Some(_) => placement.source.map(CellOrigin::of),
+                None => Some(CellOrigin::Removed),
+            };
         }
-
-        RgbaFrame {
-            width: total_width as u32,
-            height: total_height as u32,
-            pixels,
+        if placement.tile_ref.is_some() {
+            self.latest = self.cell(placement.row, placement.col);
         }
     }
 
-    /// Export analysis as animated GIF with 2x2 layout
-    ///
-    /// Creates an animated visualization showing the algorithm's decision-making
-    /// process through entropy, feasibility, and probability metrics
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if image creation or file operations fail
-    pub fn export_analysis(
+    fn render(
         &self,
-        visualization: &VisualizationCapture,
-        output_path: &str,
-        frame_delay_ms: u32,
-    ) -> Result<()> {
-        use image::{Frame, RgbaImage};
-
-        let bounds = self.calculate_unified_bounds(visualization);
-
-        let max_iteration = self
-            .events
-            .iter()
-            .map(|e| e.iteration)
-            .max()
-            .unwrap_or(0)
-            .max(
-                visualization
-                    .get_placements()
-                    .iter()
-                    .map(|p| p.iteration)
-                    .max()
-                    .unwrap_or(0),
-            );
-
-        // Calculate global max entropy for consistent normalization
-        let max_entropy = self.events.iter().map(|e| e.entropy).fold(0.0, f64::max);
+        color_mapping: &[[u8; 4]],
+        max_entropy: f64,
+        (iteration, max_iteration): (usize, usize),
+        options: RenderOptions,
+    ) -> RgbaImage {
+        let scale = options.scale;
+        let padding = 2 * scale;
+        let panel_width = self.cols as u32 * scale;
+        let panel_height = self.rows as u32 * scale;
+        let right = panel_width + padding;
+        let lower = panel_height + padding;
+        let total_width = panel_width * 2 + padding;
+
+        let mut canvas = FrameCanvas::new(
+            total_width,
+            panel_height * 2 + padding,
+            max_iteration,
+            options,
+        );
 
-        let mut frames = Vec::new();
-        for iteration in 0..=max_iteration {
-            let rgba_frame = self.render_combined_frame(
-                visualization,
-                bounds,
-                iteration,
-                frame_delay_ms,
-                max_entropy,
-            );
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let index = row * self.cols + col;
+                let cell = [row, col];
 
-            let img = RgbaImage::from_raw(rgba_frame.width, rgba_frame.height, rgba_frame.pixels)
-                .ok_or_else(|| crate::io::error::AlgorithmError::InvalidSourceData {
-                reason: "Failed to create image from frame data".to_string(),
-            })?;
+                if let Some(&color) = self.weighted_colors.get(index) {
+                    canvas.cell(0, 0, cell, color, None);
+                }
 
-            frames.push(Frame::from_parts(
-                img,
-                0,
-                0,
-                image::Delay::from_numer_denom_ms(frame_delay_ms, 1),
-            ));
-        }
+                let origin = self.origins.get(index).copied().flatten();
+                match self.tiles.get(index).copied().flatten() {
+                    Some(tile_idx) => {
+                        if tile_idx > 1
+                            && let Some(&color) = color_mapping.get(tile_idx - 2)
+                        {
+                            canvas.cell(right, 0, cell, color, origin);
+                        }
+                    }
+                    None => canvas.cell(right, 0, cell, [0, 0, 0, 255], origin),
+                }
 
-        // Add final frame with 25x longer delay for viewing
-        if !frames.is_empty() {
-            let final_frame_delay = frame_delay_ms * 25;
-            if let Some(last_frame_img) = frames.last().map(|f| f.buffer().clone()) {
-                frames.push(Frame::from_parts(
-                    last_frame_img,
-                    0,
-                    0,
-                    image::Delay::from_numer_denom_ms(final_frame_delay, 1),
-                ));
-            }
-        }
+                if let Some(&entropy) = self.entropy.get(index) {
+                    let normalized = if max_entropy > 0.0 {
+                        (entropy / max_entropy * 255.0) as u8
+                    } else {
+                        0
+                    };
+                    canvas.cell(
+                        0,
+                        lower,
+                        cell,
+                        [normalized, normalized, normalized, 255],
+                        None,
+                    );
+                }
 
-        if let Some(parent) = std::path::Path::new(output_path).parent() {
-            std::fs::create_dir_all(parent).map_err(|e| {
-                crate::io::error::AlgorithmError::FileSystem {
-                    path: parent.to_path_buf(),
-                    operation: "create directory",
-                    source: e,
+                if let Some(&feasibility) = self.feasibility.get(index) {
+                    let normalized = (feasibility * 255.0) as u8;
+                    canvas.cell(
+                        right,
+                        lower,
+                        cell,
+                        [normalized, normalized, normalized, 255],
+                        None,
+                    );
                 }
-            })?;
+            }
         }
 
-        let file = std::fs::File::create(output_path).map_err(|e| {
-            crate::io::error::AlgorithmError::FileSystem {
-                path: output_path.into(),
-                operation: "create file",
-                source: e,
-            }
-        })?;
+        let gray = [128u8, 128, 128, 255];
+        canvas.fill(panel_width, 0, padding, panel_height, gray);
+        canvas.fill(panel_width, lower, padding, panel_height, gray);
+        canvas.fill(0, panel_height, total_width, padding, gray);
 
-        let mut encoder = image::codecs::gif::GifEncoder::new(file);
-        encoder.encode_frames(frames).map_err(|e| {
-            crate::io::error::AlgorithmError::ImageExport {
-                path: output_path.into(),
-                source: e,
+        if let Some(latest) = self.latest {
+            for (left, top) in [(0, 0), (right, 0), (0, lower), (right, lower)] {
+                canvas.highlight(left, top, latest);
             }
-        })?;
+        }
+        canvas.caption(iteration);
 
-        Ok(())
+        canvas.into_image()
     }
 }
@@ -1,15 +1,31 @@
 //! Command-line interface for batch processing PNG files with pattern generation
 
+use crate::algorithm::backtracking::DeadlockStrategy;
+use crate::algorithm::completion::RunLimits;
+use crate::algorithm::consistency::PropagationMode;
+use crate::algorithm::deadlock::{DeadlockPolicy, RemovalGrowth, RemovalShape};
 use crate::algorithm::executor::{AlgorithmConfig, GreedyStochastic};
+use crate::algorithm::fronts::GrowthSeeds;
+use crate::algorithm::origin::{BoundsAnchor, StartPosition};
+use crate::algorithm::positioning::PositionStrategy;
+use crate::algorithm::replay::first_divergence;
+use crate::algorithm::restart::run_with_restarts;
+use crate::algorithm::selection::TileStrategy;
 use crate::analysis::patterns::ImageProcessor;
+use crate::analysis::statistics::{BandwidthSelection, KernelShape, SpatialMode};
 use crate::io::configuration::{
-    ADJACENCY_CANDIDATES_CONSIDERED, CANDIDATES_CONSIDERED, DEFAULT_MAX_ITERATIONS, DEFAULT_SEED,
-    GRID_EXTENSION_RADIUS, OUTPUT_SUFFIX, PATTERN_INFLUENCE_DISTANCE, TILE_SIZE,
+    ADJACENCY_CANDIDATES_CONSIDERED, BASE_REMOVAL_RADIUS, CANDIDATES_CONSIDERED,
+    DEFAULT_MAX_ITERATIONS, DEFAULT_SEED, GIF_FRAME_DELAY_MS, GRID_EXTENSION_RADIUS,
+    MAX_REMOVAL_RADIUS, OUTPUT_SUFFIX, PATTERN_INFLUENCE_DISTANCE, TILE_SIZE,
 };
 use crate::io::error::Result;
-use crate::io::image::export_grid_as_png;
+use crate::io::events::EventLog;
+use crate::io::fields::FieldSchedule;
+use crate::io::image::{UnfilledFill, export_bounds_as_png, export_grid_as_png};
 use crate::io::prefill::PrefillData;
 use crate::io::progress::ProgressManager;
+use crate::io::render::RenderOptions;
+use crate::io::visualization::{AnimationFormat, AnimationOptions};
 use clap::Parser;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
@@ -53,11 +69,11 @@ pub struct Cli {
     #[arg(short, long)]
     pub analysis: bool,
 
-    /// Maximum width in pixels (implies square if height not specified)
+    /// Output width in pixels (implies square if height not specified)
     #[arg(short = 'w', long)]
     pub width: Option<usize>,
 
-    /// Maximum height in pixels
+    /// Output height in pixels
     #[arg(short = 'H', long)]
     pub height: Option<usize>,
 
@@ -72,6 +88,160 @@ pub struct Cli {
     /// Enable tile mirroring transformations (horizontal reflection)
     #[arg(short = 'm', long)]
     pub mirror: bool,
+
+    /// Keep pair statistics directional to preserve oriented structure (stripes, grain)
+    #[arg(long)]
+    pub anisotropic: bool,
+
+    /// Kernel bandwidth for pair statistics: a number, silverman, scott or lcv
+    #[arg(long, default_value_t = BandwidthSelection::default())]
+    pub bandwidth: BandwidthSelection,
+
+    /// Kernel shape for pair statistics: gaussian, epanechnikov, triangular or discrete
+    #[arg(long, default_value_t = KernelShape::default())]
+    pub kernel: KernelShape,
+
+    /// Position strategy: weighted, min-entropy, scanline, spiral or frontier
+    #[arg(long, default_value_t = PositionStrategy::default())]
+    pub position: PositionStrategy,
+
+    /// Tile choice: density, uniform, argmax, softmax or softmax:<temperature>
+    #[arg(long, default_value_t = TileStrategy::default())]
+    pub tile_choice: TileStrategy,
+
+    /// Contradiction resolution: removal, backtrack or backtrack:<budget>
+    #[arg(long, default_value_t = DeadlockStrategy::default())]
+    pub deadlock: DeadlockStrategy,
+
+    /// Tiles unlocked on removal: square, disk, recent or recent:<placements>
+    #[arg(long, default_value_t = RemovalShape::default())]
+    pub removal_shape: RemovalShape,
+
+    /// Removal radius growth on repeats: fixed, linear, linear:<step> or doubling
+    #[arg(long, default_value_t = RemovalGrowth::default())]
+    pub removal_growth: RemovalGrowth,
+
+    /// Removal radius before any growth
+    #[arg(long, default_value_t = BASE_REMOVAL_RADIUS)]
+    pub removal_radius: i32,
+
+    /// Largest radius removal growth may reach
+    #[arg(long, default_value_t = MAX_REMOVAL_RADIUS)]
+    pub max_removal_radius: i32,
+
+    /// Fail as unsolvable after this many removals
+    #[arg(long)]
+    pub max_deadlocks: Option<usize>,
+
+    /// Shrink each position's removal radius by one step every this many iterations
+    #[arg(long)]
+    pub removal_decay: Option<usize>,
+
+    /// Forced position detection: neighbours, or ac3 for transitive arc consistency
+    #[arg(long, default_value_t = PropagationMode::default())]
+    pub propagation: PropagationMode,
+
+    /// Point of the bounds at the world origin, which prefill images share:
+    /// center, top-left, top-right, bottom-left or bottom-right
+    #[arg(long, default_value_t = BoundsAnchor::default())]
+    pub anchor: BoundsAnchor,
+
+    /// First tile position: origin, random, a bounds anchor such as
+    /// top-left, or an output pixel as <row>,<col>
+    #[arg(long, default_value_t = StartPosition::default())]
+    pub start: StartPosition,
+
+    /// Growth fronts: single, a count of seeds sampled at least
+    /// <spacing> cells apart as <count> or <count>:<spacing>, or up to 8
+    /// output pixels as <row>,<col>;<row>,<col>...
+    #[arg(long, default_value_t = GrowthSeeds::default())]
+    pub seeds: GrowthSeeds,
+
+    /// Color of unfilled cells in bounded outputs: transparent, probable,
+    /// #rrggbb or #rrggbbaa
+    #[arg(long, default_value_t = UnfilledFill::default())]
+    pub fill: UnfilledFill,
+
+    /// Run until the bounds are filled rather than for a fixed number of
+    /// iterations, failing when stalled on deadlocks or past a safety cap
+    #[arg(long)]
+    pub until_complete: bool,
+
+    /// Iteration cap with --until-complete, replacing the default cap of a
+    /// fixed number of iterations per cell of the final bounds
+    #[arg(long)]
+    pub max_iterations: Option<usize>,
+
+    /// Removals and backtracks allowed with --until-complete without filling
+    /// more of the bounds before the run stalls, or 0 to never stall
+    #[arg(long)]
+    pub stall_deadlocks: Option<usize>,
+
+    /// Export probability, entropy, feasibility, adjacency and tile fields as
+    /// NPY arrays with JSON metadata into <name>_fields/, at the end of the
+    /// run and optionally every:<n> iterations or at <n>,<n>,...
+    #[arg(long)]
+    pub fields: Option<FieldSchedule>,
+
+    /// Log every placement with its decision source and candidates, and every
+    /// removal with its radius and cleared cells, to <name>_events.jsonl
+    #[arg(long)]
+    pub events: bool,
+
+    /// Rebuild the output from <name>_events.jsonl instead of generating,
+    /// using the options of the recorded run
+    #[arg(long)]
+    pub replay: bool,
+
+    /// Stop replaying after this iteration (implies --replay)
+    #[arg(long)]
+    pub replay_until: Option<usize>,
+
+    /// Check the run against <name>_events.jsonl, failing at the first event
+    /// that differs
+    #[arg(long)]
+    pub verify_events: bool,
+
+    /// Delay between visualization frames in milliseconds
+    #[arg(long, default_value_t = GIF_FRAME_DELAY_MS)]
+    pub frame_delay: u32,
+
+    /// Visualization format: gif, apng, webp, or frames for numbered PNGs
+    /// in <name>_visualization/
+    #[arg(long, default_value_t = AnimationFormat::default())]
+    pub animation: AnimationFormat,
+
+    /// Visualization and analysis pixels per tile along each side
+    #[arg(long, default_value_t = 1)]
+    pub scale: u32,
+
+    /// Draw lines between the cells of visualization and analysis frames,
+    /// needing --scale of at least 2
+    #[arg(long)]
+    pub gridlines: bool,
+
+    /// Outline the most recently placed cell in each frame
+    #[arg(long)]
+    pub highlight: bool,
+
+    /// Tint tiles by whether they were chosen at random, forced or prefilled,
+    /// and cells emptied by removals
+    #[arg(long)]
+    pub overlays: bool,
+
+    /// Show the iteration in a strip below each frame
+    #[arg(long)]
+    pub caption: bool,
+
+    /// Play the visualization over this many milliseconds instead of using
+    /// --frame-delay
+    #[arg(long)]
+    pub duration: Option<u32>,
+
+    /// Retry a failed generation up to this many times with derived seeds,
+    /// keeping the best attempt and recording its seed in <name>_result.json
+    #[arg(long, default_value_t = 0)]
+    pub restarts: usize,
 }
 
 impl Cli {
@@ -80,6 +250,54 @@ impl Cli {
         !self.no_skip
     }
 
+    /// Generation bounds as (height, width), square when only one side is given
+    pub const fn bounds(&self) -> Option<(usize, usize)> {
+        match (self.height, self.width) {
+            (Some(h), Some(w)) => Some((h, w)),
+            (Some(h), None) => Some((h, h)),
+            (None, Some(w)) => Some((w, w)),
+            (None, None) => None,
+        }
+    }
+
+    /// Format, size and timing of the visualization
+    pub const fn animation_options(&self) -> AnimationOptions {
+        AnimationOptions {
+            format: self.animation,
+            render: self.render_options(),
+            frame_delay_ms: self.frame_delay,
+            duration_ms: self.duration,
+        }
+    }
+
+    /// Scale and annotations of visualization and analysis frames
+    pub const fn render_options(&self) -> RenderOptions {
+        RenderOptions {
+            scale: self.scale,
+            gridlines: self.gridlines,
+            highlight_latest: self.highlight,
+            overlays: self.overlays,
+            caption: self.caption,
+        }
+    }
+
+    /// Removal shape, growth, radii and limits for resolving contradictions
+    pub const fn deadlock_policy(&self) -> DeadlockPolicy {
+        DeadlockPolicy {
+            shape: self.removal_shape,
+            growth: self.removal_growth,
+            base_radius: self.removal_radius,
+            max_radius: self.max_removal_radius,
+            max_attempts: self.max_deadlocks,
+            decay_interval: self.removal_decay,
+        }
+    }
+
+    /// Check if outputs are rebuilt from a recorded event log
+    pub const fn replaying(&self) -> bool {
+        self.replay || self.replay_until.is_some()
+    }
+
     /// Check if progress should be displayed
     pub const fn should_show_progress(&self) -> bool {
         !self.quiet
@@ -186,97 +404,105 @@ impl FileProcessor {
         let start_time = Instant::now();
         let output_path = Self::get_output_path(input_path);
 
+        let limits = self.run_limits()?;
+        let planned_iterations = limits.cap_for_cells(self.cli.bounds().map(|(h, w)| h * w));
         if let Some(ref mut pm) = self.progress_manager {
-            pm.start_file(index, input_path, self.cli.iterations);
+            pm.start_file(index, input_path, planned_iterations);
         }
 
-        let image_processor = ImageProcessor::from_png_path(input_path)?;
-
-        let bounds = match (self.cli.height, self.cli.width) {
-            (Some(h), Some(w)) => Some((h, w)),
-            (Some(h), None) => Some((h, h)),
-            (None, Some(w)) => Some((w, w)),
-            (None, None) => None,
-        };
-
-        let config = AlgorithmConfig {
-            candidates_considered: CANDIDATES_CONSIDERED,
-            adjacency_candidates_considered: ADJACENCY_CANDIDATES_CONSIDERED,
-            pattern_influence_distance: PATTERN_INFLUENCE_DISTANCE,
-            grid_extension_radius: GRID_EXTENSION_RADIUS,
-            tile_size: TILE_SIZE,
-            include_rotations: self.cli.rotate,
-            include_reflections: self.cli.mirror,
-            bounds,
-        };
-
-        let mut executor =
-            GreedyStochastic::from_image_processor(image_processor, config, self.cli.seed)?;
-
         // Apply prefill if requested
+        let mut prefill_path = None;
         if self.cli.prefill {
-            let prefill_path = Self::get_prefill_path(input_path);
-            if prefill_path.exists() {
-                let prefill_data = PrefillData::from_png(&prefill_path, executor.color_mapping())?;
-                executor.apply_prefill(prefill_data)?;
+            let path = Self::get_prefill_path(input_path);
+            if path.exists() {
+                prefill_path = Some(path);
             } else if !self.cli.quiet {
                 eprintln!(
                     "No prefill found at: {} (continuing without prefill)",
-                    prefill_path.display()
+                    path.display()
                 );
             }
         }
 
-        // Enable visualization if requested or if analysis is requested
-        if self.cli.visualize || self.cli.analysis {
-            executor.enable_visualization(self.cli.iterations);
-        }
+        let mut executor = if self.cli.replaying() {
+            Self::replay_file(
+                &self.cli,
+                input_path,
+                prefill_path.as_deref(),
+                planned_iterations,
+            )?
+        } else {
+            self.generate_file(
+                input_path,
+                index,
+                start_time,
+                prefill_path.as_deref(),
+                &limits,
+            )?
+        };
 
-        if self.cli.analysis {
-            executor.enable_analysis();
+        if self.cli.verify_events && !self.cli.replaying() {
+            Self::verify_events(&executor, input_path)?;
         }
 
-        for iteration in 1..=self.cli.iterations {
-            if let Some(ref mut pm) = self.progress_manager {
-                pm.update_iteration(index, iteration, start_time.elapsed());
-            }
-
-            let should_continue = executor.execute_iteration()?;
-            if !should_continue {
-                break;
-            }
+        let output = output_path
+            .to_str()
+            .ok_or_else(|| crate::io::error::io_error("Invalid output path"))?;
+        match &executor.grid_state().generation_bounds {
+            Some(bounds) => export_bounds_as_png(
+                executor.grid_state(),
+                executor.system_offset,
+                bounds,
+                executor.color_mapping(),
+                self.cli.fill,
+                output,
+            )?,
+            None => export_grid_as_png(executor.grid_state(), executor.color_mapping(), output)?,
         }
 
-        export_grid_as_png(
-            executor.grid_state(),
-            executor.color_mapping(),
-            output_path
-                .to_str()
-                .ok_or_else(|| crate::io::error::io_error("Invalid output path"))?,
-        )?;
-
         if self.cli.visualize {
-            let viz_path = Self::get_visualization_path(input_path);
+            let viz_path = Self::get_visualization_path(input_path, self.cli.animation);
             executor.export_visualization(
                 viz_path
                     .to_str()
                     .ok_or_else(|| crate::io::error::io_error("Invalid visualization path"))?,
+                &self.cli.animation_options(),
             )?;
         }
 
         if self.cli.analysis {
             let analysis_path = Self::get_analysis_path(input_path);
-            if let (Some(viz), Some(analysis)) = (&executor.visualization, &executor.analysis) {
+            if let (Some(viz), Some(analysis)) = (&executor.visualization, &mut executor.analysis) {
                 analysis.export_analysis(
                     viz,
                     analysis_path
                         .to_str()
                         .ok_or_else(|| crate::io::error::io_error("Invalid analysis path"))?,
-                    crate::io::configuration::GIF_FRAME_DELAY_MS,
+                    self.cli.frame_delay,
+                    self.cli.render_options(),
+                )?;
+                let metadata_path = Self::get_analysis_metadata_path(input_path);
+                analysis.export_metadata(
+                    metadata_path
+                        .to_str()
+                        .ok_or_else(|| crate::io::error::io_error("Invalid metadata path"))?,
                 )?;
             }
         }
 
+        if self.cli.fields.is_some() {
+            executor.export_fields(&Self::get_fields_path(input_path))?;
+        }
+
+        if self.cli.events && !self.cli.replaying() {
+            let events_path = Self::get_events_path(input_path);
+            executor.export_events(
+                events_path
+                    .to_str()
+                    .ok_or_else(|| crate::io::error::io_error("Invalid events path"))?,
+            )?;
+        }
+
         if let Some(ref mut pm) = self.progress_manager {
             pm.complete_file(index, start_time.elapsed());
         }
@@ -284,6 +510,228 @@ impl FileProcessor {
         Ok(())
     }
 
+    /// Best attempt at generating a file, reporting an incomplete one
+    // Allow print for user feedback for failed attempts
+    #[allow(clippy::print_stderr)]
+    fn generate_file(
+        &mut self,
+        input_path: &Path,
+        index: usize,
+        start_time: Instant,
+        prefill_path: Option<&Path>,
+        limits: &RunLimits,
+    ) -> Result<GreedyStochastic> {
+        let cli = &self.cli;
+        let progress_manager = &mut self.progress_manager;
+        let mut best = run_with_restarts(
+            cli.seed,
+            cli.restarts,
+            limits,
+            |seed| {
+                Self::build_executor(
+                    cli,
+                    input_path,
+                    prefill_path,
+                    limits.cap_for_cells(cli.bounds().map(|(h, w)| h * w)),
+                    seed,
+                )
+            },
+            |_, iteration| {
+                if let Some(pm) = progress_manager.as_mut() {
+                    pm.update_iteration(index, iteration, start_time.elapsed());
+                }
+            },
+        )?;
+
+        if self.cli.restarts == 0
+            && let Some(error) = best.error.take()
+        {
+            return Err(error);
+        }
+
+        if self.cli.restarts > 0 {
+            let result_path = Self::get_result_path(input_path);
+            best.export_metadata(
+                result_path
+                    .to_str()
+                    .ok_or_else(|| crate::io::error::io_error("Invalid result path"))?,
+            )?;
+        }
+
+        if !best.succeeded() && !self.cli.quiet {
+            let reason = best.error.as_ref().map_or_else(
+                || {
+                    format!(
+                        "iteration limit of {} reached with {} cells unfilled",
+                        limits.iteration_cap(&best.executor),
+                        best.executor.unfilled_in_bounds().unwrap_or(0)
+                    )
+                },
+                ToString::to_string,
+            );
+            if self.cli.restarts > 0 {
+                eprintln!(
+                    "No attempt completed for {} ({reason}); keeping attempt {} of {} with seed {} at {:.1}% complete",
+                    input_path.display(),
+                    best.attempt + 1,
+                    best.attempts,
+                    best.seed,
+                    best.score.completeness * 100.0
+                );
+            } else {
+                eprintln!("Incomplete output for {}: {reason}", input_path.display());
+            }
+        }
+
+        Ok(best.executor)
+    }
+
+    /// Executor rebuilt from the file's event log, up to the requested iteration
+    fn replay_file(
+        cli: &Cli,
+        input_path: &Path,
+        prefill_path: Option<&Path>,
+        max_iterations: usize,
+    ) -> Result<GreedyStochastic> {
+        let events_path = Self::get_events_path(input_path);
+        let log = EventLog::load_jsonl(
+            events_path
+                .to_str()
+                .ok_or_else(|| crate::io::error::io_error("Invalid events path"))?,
+        )?;
+        let mut executor =
+            Self::build_executor(cli, input_path, prefill_path, max_iterations, cli.seed)?;
+        executor.replay_events(log.events(), cli.replay_until)?;
+        Ok(executor)
+    }
+
+    /// Compare the run's events with the file's recorded log
+    fn verify_events(executor: &GreedyStochastic, input_path: &Path) -> Result<()> {
+        let events_path = Self::get_events_path(input_path);
+        let recorded = EventLog::load_jsonl(
+            events_path
+                .to_str()
+                .ok_or_else(|| crate::io::error::io_error("Invalid events path"))?,
+        )?;
+        let fresh = executor.events.as_ref().map_or(&[][..], EventLog::events);
+        first_divergence(recorded.events(), fresh).map_or(Ok(()), |divergence| {
+            Err(crate::io::error::invalid_parameter(
+                "verify-events",
+                &events_path.display(),
+                &format!(
+                    "Run differs from the recorded log at event {} of {}",
+                    divergence + 1,
+                    recorded.events().len()
+                ),
+            ))
+        })
+    }
+
+    /// Stopping rules for each attempt, running until the bounds are filled
+    /// when requested
+    fn run_limits(&self) -> Result<RunLimits> {
+        if !self.cli.until_complete {
+            for (name, value) in [
+                ("max-iterations", self.cli.max_iterations),
+                ("stall-deadlocks", self.cli.stall_deadlocks),
+            ] {
+                if let Some(value) = value {
+                    return Err(crate::io::error::invalid_parameter(
+                        name,
+                        &value,
+                        &"requires --until-complete",
+                    ));
+                }
+            }
+            return Ok(RunLimits::iterations(self.cli.iterations));
+        }
+        if self.cli.bounds().is_none() {
+            return Err(crate::io::error::invalid_parameter(
+                "until-complete",
+                &true,
+                &"requires --width or --height",
+            ));
+        }
+
+        let mut limits = RunLimits::until_complete();
+        if let Some(max_iterations) = self.cli.max_iterations {
+            limits = limits.with_max_iterations(max_iterations);
+        }
+        if let Some(window) = self.cli.stall_deadlocks {
+            limits = limits.with_stall_deadlocks((window > 0).then_some(window));
+        }
+        Ok(limits)
+    }
+
+    /// Executor for one attempt at a file, with prefill, visualization and analysis applied
+    fn build_executor(
+        cli: &Cli,
+        input_path: &Path,
+        prefill_path: Option<&Path>,
+        max_iterations: usize,
+        seed: u64,
+    ) -> Result<GreedyStochastic> {
+        let image_processor = ImageProcessor::from_png_path(input_path)?;
+        let bounds = cli.bounds();
+
+        let config = AlgorithmConfig {
+            candidates_considered: CANDIDATES_CONSIDERED,
+            adjacency_candidates_considered: ADJACENCY_CANDIDATES_CONSIDERED,
+            pattern_influence_distance: PATTERN_INFLUENCE_DISTANCE,
+            grid_extension_radius: GRID_EXTENSION_RADIUS,
+            tile_size: TILE_SIZE,
+            include_rotations: cli.rotate,
+            include_reflections: cli.mirror,
+            bounds,
+            bounds_anchor: cli.anchor,
+            start_position: cli.start,
+            growth_seeds: cli.seeds,
+            spatial_mode: if cli.anisotropic {
+                SpatialMode::Anisotropic
+            } else {
+                SpatialMode::Isotropic
+            },
+            bandwidth_selection: cli.bandwidth,
+            kernel: cli.kernel,
+            position_strategy: cli.position,
+            tile_strategy: cli.tile_choice,
+            deadlock_strategy: cli.deadlock,
+            deadlock_policy: cli.deadlock_policy(),
+            propagation_mode: cli.propagation,
+        };
+
+        let mut executor = GreedyStochastic::from_image_processor(image_processor, config, seed)?;
+
+        if let Some(prefill_path) = prefill_path {
+            let prefill_data =
+                PrefillData::from_png_anchored(prefill_path, executor.color_mapping(), cli.anchor)?;
+            executor.apply_prefill(prefill_data)?;
+        }
+
+        // Enable visualization if requested or if analysis is requested
+        if cli.visualize || cli.analysis {
+            executor.enable_visualization(max_iterations);
+        }
+
+        if cli.analysis {
+            executor.enable_analysis();
+        }
+
+        if let Some(schedule) = &cli.fields {
+            // Each attempt stages its own snapshots until the best is exported
+            executor.enable_field_capture(
+                schedule.clone(),
+                Self::get_fields_path(input_path).join(format!(".seed_{seed}")),
+            );
+        }
+
+        if (cli.events && !cli.replaying()) || cli.verify_events {
+            executor.enable_event_log();
+        }
+
+        Ok(executor)
+    }
+
     fn get_prefill_path(input_path: &Path) -> PathBuf {
         let stem = input_path.file_stem().unwrap_or_default();
         let prefill_name = format!("{}_pre.png", stem.to_string_lossy());
@@ -312,9 +760,12 @@ impl FileProcessor {
         }
     }
 
-    fn get_visualization_path(input_path: &Path) -> PathBuf {
+    fn get_visualization_path(input_path: &Path, format: AnimationFormat) -> PathBuf {
         let stem = input_path.file_stem().unwrap_or_default();
-        let viz_name = format!("{}_visualization.gif", stem.to_string_lossy());
+        let viz_name = format.extension().map_or_else(
+            || format!("{}_visualization", stem.to_string_lossy()),
+            |extension| format!("{}_visualization.{extension}", stem.to_string_lossy()),
+        );
 
         if let Some(parent) = input_path.parent() {
             parent.join(viz_name)
@@ -333,4 +784,48 @@ impl FileProcessor {
             PathBuf::from(analysis_name)
         }
     }
+
+    fn get_fields_path(input_path: &Path) -> PathBuf {
+        let stem = input_path.file_stem().unwrap_or_default();
+        let fields_name = format!("{}_fields", stem.to_string_lossy());
+
+        if let Some(parent) = input_path.parent() {
+            parent.join(fields_name)
+        } else {
+            PathBuf::from(fields_name)
+        }
+    }
+
+    fn get_events_path(input_path: &Path) -> PathBuf {
+        let stem = input_path.file_stem().unwrap_or_default();
+        let events_name = format!("{}_events.jsonl", stem.to_string_lossy());
+
+        if let Some(parent) = input_path.parent() {
+            parent.join(events_name)
+        } else {
+            PathBuf::from(events_name)
+        }
+    }
+
+    fn get_result_path(input_path: &Path) -> PathBuf {
+        let stem = input_path.file_stem().unwrap_or_default();
+        let result_name = format!("{}_result.json", stem.to_string_lossy());
+
+        if let Some(parent) = input_path.parent() {
+            parent.join(result_name)
+        } else {
+            PathBuf::from(result_name)
+        }
+    }
+
+    fn get_analysis_metadata_path(input_path: &Path) -> PathBuf {
+        let stem = input_path.file_stem().unwrap_or_default();
+        let metadata_name = format!("{}_analysis.json", stem.to_string_lossy());
+
+        if let Some(parent) = input_path.parent() {
+            parent.join(metadata_name)
+        } else {
+            PathBuf::from(metadata_name)
+        }
+    }
 }
@@ -15,6 +15,18 @@ pub const PATTERN_INFLUENCE_DISTANCE: usize = 6;
 /// Radius for grid extension operations
 pub const GRID_EXTENSION_RADIUS: usize = 6;
 
+// Kernel density estimation settings for pair statistics
+/// Default Gaussian kernel bandwidth for pair densities
+pub const DEFAULT_KDE_BANDWIDTH: f64 = 1.0;
+/// Smallest bandwidth automatic selection may choose, keeping densities smooth between lattice points
+pub const MIN_KDE_BANDWIDTH: f64 = 0.25;
+/// Largest bandwidth automatic selection may choose
+pub const MAX_KDE_BANDWIDTH: f64 = 4.0;
+/// Number of geometrically spaced bandwidths evaluated by likelihood cross-validation
+pub const KDE_CROSS_VALIDATION_CANDIDATES: usize = 12;
+/// Most points likelihood cross-validation scores, larger inputs are subsampled evenly
+pub const KDE_CROSS_VALIDATION_MAX_POINTS: usize = 400;
+
 // Safety limit to prevent excessive memory allocation
 /// Maximum allowed grid dimension
 pub const MAX_GRID_DIMENSION: usize = 10_000;
@@ -26,10 +38,31 @@ pub const BASE_REMOVAL_RADIUS: i32 = 0;
 /// Maximum radius for deadlock resolution
 pub const MAX_REMOVAL_RADIUS: i32 = 6;
 
+/// Placements remembered by the recent removal shape when no count is given
+pub const DEFAULT_RECENT_PLACEMENTS: usize = 64;
+
+// Seam cells can be individually viable yet jointly unsolvable against a finished
+// neighbour, so each region keeps a band of the neighbour open to repair
+/// Width of the band around a requested region that is generated with it
+pub const SEAM_REPAIR_WIDTH: i32 = 2;
+
 // Determines influence distance for pattern matching
 /// Number of adjacency levels to check
 pub const ADJACENCY_LEVELS: usize = 2;
 
+// Viability reads every 3x3 window covering a cell (two cells out), and frontier
+// membership follows the adjacency levels
+/// Radius around a changed cell whose contradiction status must be rechecked
+pub const CONTRADICTION_CHECK_RADIUS: i32 = if ADJACENCY_LEVELS > 2 {
+    ADJACENCY_LEVELS as i32
+} else {
+    2
+};
+
+// Bounds the retries when bans or removals keep every selected position empty
+/// Contradictions resolved while selecting one position before selection fails
+pub const MAX_SELECTION_CONTRADICTIONS: usize = 100;
+
 // Progress bar display settings
 /// Threshold for switching to batch progress mode
 pub const MAX_INDIVIDUAL_PROGRESS_BARS: usize = 5;
@@ -40,9 +73,23 @@ pub const PROGRESS_BAR_WIDTH: u16 = 50;
 /// Fixed seed for reproducible generation
 pub const DEFAULT_SEED: u64 = 42;
 
+/// Default spacing of sampled growth seeds, far enough apart that no seed's
+/// tile influences another's
+pub const DEFAULT_SEED_SPACING: usize = 2 * GRID_EXTENSION_RADIUS;
+
+/// Most growth seeds that can be given as output pixels, kept inline so
+/// algorithm configurations stay `Copy`
+pub const MAX_SEED_PIXELS: usize = 8;
+
 /// Default maximum iterations before stopping
 pub const DEFAULT_MAX_ITERATIONS: usize = 1000;
 
+/// Safety cap on iterations per bounded cell when running until complete
+pub const UNTIL_COMPLETE_ITERATIONS_PER_CELL: usize = 20;
+
+/// Removals and backtracks allowed without filling more of the bounds before a run counts as stalled
+pub const DEFAULT_STALL_DEADLOCKS: usize = 100;
+
 // Output settings
 /// Suffix added to output filenames
 pub const OUTPUT_SUFFIX: &str = "_result";
@@ -50,3 +97,21 @@ pub const OUTPUT_SUFFIX: &str = "_result";
 pub const GIF_FRAME_DELAY_MS: u32 = 5;
 /// Minimum frame delay that viewers reliably support (in milliseconds)
 pub const VIEWER_MIN_FRAME_DELAY_MS: u32 = 50;
+/// Times longer the final frame of an animation is shown than the others
+pub const FINAL_FRAME_HOLD: u32 = 25;
+/// Frame rate exported image sequences are sampled at, to pass to video encoders
+pub const SEQUENCE_FRAME_RATE: u32 = 60;
+/// Color of the lines drawn between cells of upscaled frames
+pub const GRIDLINE_COLOR: [u8; 4] = [96, 96, 96, 255];
+/// Outline color of the most recently placed cell
+pub const HIGHLIGHT_COLOR: [u8; 4] = [255, 255, 255, 255];
+/// Overlay color of tiles chosen at random among viable candidates
+pub const RANDOM_OVERLAY_COLOR: [u8; 4] = [255, 200, 0, 255];
+/// Overlay color of tiles that were the only one fitting their position
+pub const FORCED_OVERLAY_COLOR: [u8; 4] = [0, 96, 255, 255];
+/// Overlay color of tiles given before generation by a prefill image or neighbouring chunk
+pub const PREFILL_OVERLAY_COLOR: [u8; 4] = [0, 200, 80, 255];
+/// Overlay color of cells emptied by deadlock removal or backtracking
+pub const REMOVED_OVERLAY_COLOR: [u8; 4] = [255, 0, 0, 255];
+/// Share of an overlay color mixed into the cell beneath it
+pub const OVERLAY_OPACITY: f64 = 0.5;
@@ -32,6 +32,36 @@ pub enum AlgorithmError {
         grid_dimensions: (usize, usize),
     },
 
+    /// Deadlock removals exceeded the limit set by the deadlock policy
+    Unsolvable {
+        /// Removals performed before giving up
+        deadlocks: usize,
+        /// World coordinates of the contradiction left unresolved
+        position: [i32; 2],
+        /// Algorithm iteration when this occurred
+        iteration: usize,
+    },
+
+    /// Selection reached its retry cap with every selected position left without a tile
+    SelectionExhausted {
+        /// Contradictions resolved during selection before giving up
+        contradictions: usize,
+        /// World coordinates of the last position selected
+        position: [i32; 2],
+        /// Algorithm iteration when this occurred
+        iteration: usize,
+    },
+
+    /// Generation stopped before filling its bounds
+    Incomplete {
+        /// Positions inside the bounds left without a tile
+        unfilled: usize,
+        /// Iterations run before stopping
+        iterations: usize,
+        /// Whether deadlocks stopped making progress, rather than the cap being reached
+        stalled: bool,
+    },
+
     /// Algorithm parameter validation failed
     InvalidParameter {
         /// Name of the invalid parameter
@@ -77,6 +107,19 @@ pub enum AlgorithmError {
     },
 }
 
+impl AlgorithmError {
+    /// Whether generation itself failed, so another seed may succeed
+    pub const fn is_generation_failure(&self) -> bool {
+        matches!(
+            self,
+            Self::NoValidPositions { .. }
+                | Self::Unsolvable { .. }
+                | Self::SelectionExhausted { .. }
+                | Self::Incomplete { .. }
+        )
+    }
+}
+
 impl fmt::Display for AlgorithmError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -96,6 +139,43 @@ impl fmt::Display for AlgorithmError {
                     grid_dimensions.0, grid_dimensions.1
                 )
             }
+            Self::Unsolvable {
+                deadlocks,
+                position,
+                iteration,
+            } => {
+                write!(
+                    f,
+                    "Unsolvable after {deadlocks} deadlock removals: contradiction at ({}, {}) in iteration {iteration}",
+                    position[0], position[1]
+                )
+            }
+            Self::SelectionExhausted {
+                contradictions,
+                position,
+                iteration,
+            } => {
+                write!(
+                    f,
+                    "Selection retry cap reached after {contradictions} contradictions: no viable tile at ({}, {}) in iteration {iteration}",
+                    position[0], position[1]
+                )
+            }
+            Self::Incomplete {
+                unfilled,
+                iterations,
+                stalled,
+            } => {
+                let cause = if *stalled {
+                    "stalled on repeated deadlocks"
+                } else {
+                    "iteration cap reached"
+                };
+                write!(
+                    f,
+                    "Bounds incomplete after {iterations} iterations ({cause}): {unfilled} cells unfilled"
+                )
+            }
             Self::InvalidParameter {
                 parameter,
                 value,
@@ -182,10 +262,10 @@ where
         self.map_err(|e| {
             let mut error = e.into();
             // Only certain error types benefit from positional context
-            if let AlgorithmError::NoValidPositions { iteration, .. } = &mut error {
-                if let Some(iter) = context.iteration {
-                    *iteration = iter;
-                }
+            if let AlgorithmError::NoValidPositions { iteration, .. } = &mut error
+                && let Some(iter) = context.iteration
+            {
+                *iteration = iter;
             }
             error
         })
@@ -0,0 +1,305 @@
+//! Structured log of generation decisions as JSON Lines
+//!
+//! Every placement records why its tile was chosen and, for chosen tiles, the
+//! candidates with their weights; every removal records what triggered it and
+//! the cells it cleared, preceded by a fallback event when backtracking could
+//! not clear the contradiction. Each event is written as one JSON object per line,
+//! in the order the executor applied them. Positions are world coordinates
+//! and tiles are references counting from 1, so tile `n` has palette entry
+//! `n - 1`. Logs read back into events for replay, with null weights read as
+//! NaN.
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::io::error::{AlgorithmError, Result, invalid_parameter};
+
+/// Why a tile was placed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DecisionSource {
+    /// The first tile, drawn from the source ratios
+    Initial,
+    /// A tile of the prefill image
+    Prefill,
+    /// The first tile of a growth front or isolated region
+    Seed,
+    /// The only tile that fits its position
+    Forced,
+    /// A tile chosen at a selected position
+    Selected,
+    /// A tile copied from generation elsewhere
+    Fixed,
+}
+
+impl std::fmt::Display for DecisionSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Initial => write!(f, "initial"),
+            Self::Prefill => write!(f, "prefill"),
+            Self::Seed => write!(f, "seed"),
+            Self::Forced => write!(f, "forced"),
+            Self::Selected => write!(f, "selected"),
+            Self::Fixed => write!(f, "fixed"),
+        }
+    }
+}
+
+/// A tile considered for a placement
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Candidate {
+    /// Tile reference
+    pub tile: usize,
+    /// Weight the tile was chosen with: its probability at the position, or
+    /// its source ratio for the initial tile
+    #[serde(deserialize_with = "weight_or_nan")]
+    pub weight: f64,
+}
+
+/// One step of generation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum GenerationEvent {
+    /// A tile was placed
+    Placement {
+        /// Iteration of the placement
+        iteration: usize,
+        /// World position of the tile
+        position: [i32; 2],
+        /// Tile reference placed
+        tile: usize,
+        /// Why the tile was placed
+        source: DecisionSource,
+        /// Tiles that could have been chosen, empty unless the tile was chosen
+        candidates: Vec<Candidate>,
+    },
+    /// Tiles were removed around a contradiction
+    Removal {
+        /// Iteration of the removal
+        iteration: usize,
+        /// World position of the contradiction
+        position: [i32; 2],
+        /// Radius of the removal
+        radius: i32,
+        /// World positions of the removed tiles
+        removed: Vec<[i32; 2]>,
+    },
+    /// Recent decisions were undone to clear a contradiction
+    Backtrack {
+        /// Iteration of the backtrack
+        iteration: usize,
+        /// World position of the contradiction
+        position: [i32; 2],
+        /// World positions of the removed tiles, latest first
+        removed: Vec<[i32; 2]>,
+        /// World position and tile reference of the choice banned by the backtrack
+        #[serde(with = "banned_choice")]
+        banned: ([i32; 2], usize),
+    },
+    /// Backtracking could not clear a contradiction, so a removal follows
+    Fallback {
+        /// Iteration of the fallback
+        iteration: usize,
+        /// World position of the contradiction
+        position: [i32; 2],
+        /// Backtracks performed so far
+        backtracks: usize,
+        /// Whether the budget of backtracks is spent, rather than no open
+        /// decision affecting the contradiction
+        exhausted: bool,
+    },
+}
+
+impl GenerationEvent {
+    /// Iteration at which the event happened
+    pub const fn iteration(&self) -> usize {
+        match self {
+            Self::Placement { iteration, .. }
+            | Self::Removal { iteration, .. }
+            | Self::Backtrack { iteration, .. }
+            | Self::Fallback { iteration, .. } => *iteration,
+        }
+    }
+
+    /// Read an event from a single line of JSON written by [`Self::to_json`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the line is not valid JSON or does not describe an event
+    pub fn from_json(line: &str) -> Result<Self> {
+        serde_json::from_str(line).map_err(|e| invalid_parameter("event", &line, &e))
+    }
+
+    /// The event as a single line of JSON
+    pub fn to_json(&self) -> String {
+        let mut json = Vec::new();
+        let mut serializer = serde_json::Serializer::with_formatter(&mut json, EventFormatter);
+        // Events hold only numbers, booleans and fixed names, so writing to
+        // memory cannot fail
+        match self.serialize(&mut serializer) {
+            Ok(()) => String::from_utf8(json).unwrap_or_default(),
+            Err(_never) => String::new(),
+        }
+    }
+}
+
+/// Events recorded during a run, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct EventLog {
+    events: Vec<GenerationEvent>,
+}
+
+impl EventLog {
+    /// Create an empty log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read a log from JSON Lines, skipping blank lines
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first line that is not an event
+    pub fn from_jsonl(jsonl: &str) -> Result<Self> {
+        let mut log = Self::new();
+        for (number, line) in jsonl.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event = serde_json::from_str(line)
+                .map_err(|e| invalid_parameter("events", &format!("line {}", number + 1), &e))?;
+            log.record(event);
+        }
+        Ok(log)
+    }
+
+    /// Load a log from a JSON Lines file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or holds a line that is not an event
+    pub fn load_jsonl(path: &str) -> Result<Self> {
+        let jsonl = std::fs::read_to_string(path).map_err(|e| AlgorithmError::FileSystem {
+            path: path.into(),
+            operation: "read file",
+            source: e,
+        })?;
+        Self::from_jsonl(&jsonl)
+    }
+
+    /// Append an event
+    pub fn record(&mut self, event: GenerationEvent) {
+        self.events.push(event);
+    }
+
+    /// Events recorded so far
+    pub fn events(&self) -> &[GenerationEvent] {
+        &self.events
+    }
+
+    /// The log as JSON Lines, one event per line
+    pub fn to_jsonl(&self) -> String {
+        let mut jsonl = String::new();
+        for event in &self.events {
+            jsonl.push_str(&event.to_json());
+            jsonl.push('\n');
+        }
+        jsonl
+    }
+
+    /// Export the log as a JSON Lines file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parent directory cannot be created or the file cannot be written
+    pub fn export_jsonl(&self, output_path: &str) -> Result<()> {
+        if let Some(parent) = std::path::Path::new(output_path).parent() {
+            std::fs::create_dir_all(parent).map_err(|e| AlgorithmError::FileSystem {
+                path: parent.to_path_buf(),
+                operation: "create directory",
+                source: e,
+            })?;
+        }
+
+        std::fs::write(output_path, self.to_jsonl()).map_err(|e| AlgorithmError::FileSystem {
+            path: output_path.into(),
+            operation: "write file",
+            source: e,
+        })
+    }
+}
+
+// Writes events on one line with a space after every separator, and
+// numbers in exponent form when very small or large
+struct EventFormatter;
+
+impl serde_json::ser::Formatter for EventFormatter {
+    fn begin_array_value<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> std::io::Result<()> {
+        if first {
+            Ok(())
+        } else {
+            writer.write_all(b", ")
+        }
+    }
+
+    fn begin_object_key<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> std::io::Result<()> {
+        self.begin_array_value(writer, first)
+    }
+
+    fn begin_object_value<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        writer.write_all(b": ")
+    }
+
+    // Only finite values reach the formatter; others are written as null
+    fn write_f64<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+        value: f64,
+    ) -> std::io::Result<()> {
+        if value == 0.0 || (1e-6..1e15).contains(&value.abs()) {
+            write!(writer, "{value}")
+        } else {
+            write!(writer, "{value:e}")
+        }
+    }
+}
+
+// Reads a weight written as null, for a value JSON cannot hold, as NaN
+fn weight_or_nan<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<f64, D::Error> {
+    Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::NAN))
+}
+
+// Banned choices are written as an object naming the position and tile
+mod banned_choice {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct BannedChoice {
+        position: [i32; 2],
+        tile: usize,
+    }
+
+    pub(super) fn serialize<S: Serializer>(
+        &(position, tile): &([i32; 2], usize),
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        BannedChoice { position, tile }.serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<([i32; 2], usize), D::Error> {
+        let BannedChoice { position, tile } = BannedChoice::deserialize(deserializer)?;
+        Ok((position, tile))
+    }
+}
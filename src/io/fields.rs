@@ -0,0 +1,496 @@
+//! Numeric export of grid state fields for offline analysis
+//!
+//! Probabilities, entropy, feasibility, adjacency weights and locked tiles are
+//! written as NPY arrays, readable with `numpy.load`, alongside a JSON file
+//! giving each snapshot's iteration, shape and world offset plus the tile
+//! palette. Snapshots cover the generation bounds when set and the whole grid
+//! otherwise; cells outside the grid read as NaN, or 0 in integer fields.
+//!
+//! Scheduled snapshots are written as soon as they are taken, into a staging
+//! directory of their run, so memory holds one snapshot however many are
+//! scheduled. Export moves them into the output directory beside the final
+//! state; a run that is never exported, such as a discarded restart attempt,
+//! has its staging directory removed with its capture.
+
+use crate::io::error::{AlgorithmError, Result, invalid_parameter};
+use crate::spatial::{GridState, chunked::ChunkedGrid};
+use std::path::{Path, PathBuf};
+
+/// Name of the metadata file written beside the arrays
+pub const FIELDS_METADATA_FILE: &str = "fields.json";
+
+/// Element types that can be written to NPY arrays
+pub trait NpyElement: Copy {
+    /// `NumPy` type descriptor, little endian
+    const DESCR: &'static str;
+
+    /// Append the little endian bytes of the value
+    fn write_le(self, out: &mut Vec<u8>);
+}
+
+impl NpyElement for f64 {
+    const DESCR: &'static str = "<f8";
+
+    fn write_le(self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl NpyElement for u32 {
+    const DESCR: &'static str = "<u4";
+
+    fn write_le(self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+}
+
+/// Encode a C-ordered array in NPY format version 1.0
+///
+/// # Errors
+///
+/// Returns an error if the data length does not match the shape
+pub fn encode_npy<T: NpyElement>(shape: &[usize], data: &[T]) -> Result<Vec<u8>> {
+    if shape.iter().product::<usize>() != data.len() {
+        return Err(invalid_parameter(
+            "npy shape",
+            &format!("{shape:?}"),
+            &format!("does not hold {} elements", data.len()),
+        ));
+    }
+
+    let dims = match shape {
+        [single] => format!("{single},"),
+        _ => shape
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': ({dims}), }}",
+        T::DESCR
+    );
+    // Magic, version and length take 10 bytes; the header ends in a newline
+    // and pads the data to a 64 byte boundary
+    let unpadded = 10 + header.len() + 1;
+    header.extend(std::iter::repeat_n(
+        ' ',
+        unpadded.next_multiple_of(64) - unpadded,
+    ));
+    header.push('\n');
+
+    let header_len = u16::try_from(header.len()).map_err(|_overflow| {
+        invalid_parameter("npy shape", &format!("{shape:?}"), &"header too long")
+    })?;
+    let mut out = Vec::with_capacity(10 + header.len() + data.len() * 8);
+    out.extend_from_slice(b"\x93NUMPY\x01\x00");
+    out.extend_from_slice(&header_len.to_le_bytes());
+    out.extend_from_slice(header.as_bytes());
+    for &value in data {
+        value.write_le(&mut out);
+    }
+    Ok(out)
+}
+
+/// Iterations at which fields are captured, besides the end of the run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FieldSchedule {
+    /// Only the end of the run
+    #[default]
+    Final,
+    /// Every multiple of this many iterations
+    Every(usize),
+    /// The listed iterations
+    Iterations(Vec<usize>),
+}
+
+impl FieldSchedule {
+    /// Whether fields are captured after this iteration
+    pub fn includes(&self, iteration: usize) -> bool {
+        match self {
+            Self::Final => false,
+            Self::Every(interval) => iteration.is_multiple_of(*interval),
+            Self::Iterations(iterations) => iterations.contains(&iteration),
+        }
+    }
+}
+
+impl std::fmt::Display for FieldSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Final => write!(f, "final"),
+            Self::Every(interval) => write!(f, "every:{interval}"),
+            Self::Iterations(iterations) => {
+                let listed: Vec<String> = iterations.iter().map(ToString::to_string).collect();
+                write!(f, "{}", listed.join(","))
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for FieldSchedule {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            invalid_parameter(
+                "fields",
+                &s,
+                &"expected 'final', 'every:<n>' or '<n>,<n>,...' with n > 0",
+            )
+        };
+        let trimmed = s.trim();
+
+        if trimmed.eq_ignore_ascii_case("final") {
+            return Ok(Self::Final);
+        }
+        if let Some(interval) = trimmed.strip_prefix("every:") {
+            return match interval.trim().parse() {
+                Ok(interval) if interval > 0 => Ok(Self::Every(interval)),
+                _ => Err(invalid()),
+            };
+        }
+
+        let mut iterations = trimmed
+            .split(',')
+            .map(|iteration| match iteration.trim().parse() {
+                Ok(iteration) if iteration > 0 => Ok(iteration),
+                _ => Err(invalid()),
+            })
+            .collect::<Result<Vec<usize>>>()?;
+        iterations.sort_unstable();
+        iterations.dedup();
+        Ok(Self::Iterations(iterations))
+    }
+}
+
+/// Every field of a grid state over one region at one iteration
+///
+/// Arrays are row-major, with element `[0, 0]` at world position `offset`
+#[derive(Debug, Clone)]
+pub struct FieldSnapshot {
+    /// Iteration after which the fields were read
+    pub iteration: usize,
+    /// World position of the first element
+    pub offset: [i32; 2],
+    /// Rows in each field
+    pub rows: usize,
+    /// Columns in each field
+    pub cols: usize,
+    /// Probability of each tile type, shaped (tiles, rows, cols)
+    pub tile_probabilities: Vec<f64>,
+    /// Shannon entropy
+    pub entropy: Vec<f64>,
+    /// Feasibility score
+    pub feasibility: Vec<f64>,
+    /// Count of locked adjacent positions
+    pub adjacency_weights: Vec<u32>,
+    /// Locked tile values: 0 outside the grid, 1 open, n > 1 palette entry n - 2
+    pub locked_tiles: Vec<u32>,
+}
+
+impl FieldSnapshot {
+    /// Read every field over the generation bounds, or the whole grid without them
+    pub fn capture(grid_state: &GridState, system_offset: [i32; 2], iteration: usize) -> Self {
+        let (offset, rows, cols) = grid_state.generation_bounds.as_ref().map_or_else(
+            || {
+                (
+                    [-system_offset[0], -system_offset[1]],
+                    grid_state.rows(),
+                    grid_state.cols(),
+                )
+            },
+            |bounds| {
+                (
+                    bounds.min,
+                    (bounds.max[0] - bounds.min[0] + 1).max(0) as usize,
+                    (bounds.max[1] - bounds.min[1] + 1).max(0) as usize,
+                )
+            },
+        );
+
+        let cells = rows * cols;
+        let mut snapshot = Self {
+            iteration,
+            offset,
+            rows,
+            cols,
+            tile_probabilities: Vec::with_capacity(grid_state.unique_cell_count * cells),
+            entropy: Vec::with_capacity(cells),
+            feasibility: Vec::with_capacity(cells),
+            adjacency_weights: Vec::with_capacity(cells),
+            locked_tiles: Vec::with_capacity(cells),
+        };
+
+        let indices: Vec<Option<[usize; 2]>> = (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| [row, col]))
+            .map(|[row, col]| {
+                match (
+                    usize::try_from(offset[0] + row as i32 + system_offset[0]),
+                    usize::try_from(offset[1] + col as i32 + system_offset[1]),
+                ) {
+                    (Ok(r), Ok(c)) if r < grid_state.rows() && c < grid_state.cols() => {
+                        Some([r, c])
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
+
+        for probabilities in &grid_state.tile_probabilities {
+            snapshot.tile_probabilities.extend(
+                indices
+                    .iter()
+                    .map(|&index| read(probabilities, index, f64::NAN)),
+            );
+        }
+        for &index in &indices {
+            snapshot
+                .entropy
+                .push(read(&grid_state.entropy, index, f64::NAN));
+            snapshot
+                .feasibility
+                .push(read(&grid_state.feasibility, index, f64::NAN));
+            snapshot
+                .adjacency_weights
+                .push(read(&grid_state.adjacency_weights, index, 0));
+            snapshot
+                .locked_tiles
+                .push(read(&grid_state.locked_tiles, index, 0));
+        }
+
+        snapshot
+    }
+
+    /// Number of tile types in the probability field
+    pub const fn tiles(&self) -> usize {
+        match self
+            .tile_probabilities
+            .len()
+            .checked_div(self.rows * self.cols)
+        {
+            Some(tiles) => tiles,
+            None => 0,
+        }
+    }
+
+    /// Write each field as `<prefix>_<field>.npy` in a directory
+    ///
+    /// Returns the field names and the file names written
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be written
+    pub fn export(&self, directory: &Path, prefix: &str) -> Result<Vec<(&'static str, String)>> {
+        let plane = [self.rows, self.cols];
+        let arrays = [
+            (
+                "tile_probabilities",
+                encode_npy(
+                    &[self.tiles(), self.rows, self.cols],
+                    &self.tile_probabilities,
+                )?,
+            ),
+            ("entropy", encode_npy(&plane, &self.entropy)?),
+            ("feasibility", encode_npy(&plane, &self.feasibility)?),
+            (
+                "adjacency_weights",
+                encode_npy(&plane, &self.adjacency_weights)?,
+            ),
+            ("locked_tiles", encode_npy(&plane, &self.locked_tiles)?),
+        ];
+
+        let mut files = Vec::with_capacity(arrays.len());
+        for (field, bytes) in arrays {
+            let file_name = format!("{prefix}_{field}.npy");
+            write_file(&directory.join(&file_name), &bytes)?;
+            files.push((field, file_name));
+        }
+        Ok(files)
+    }
+}
+
+/// When and where a written snapshot was taken, and the files holding its fields
+#[derive(Debug, Clone)]
+pub struct SnapshotRecord {
+    /// Iteration after which the fields were read
+    pub iteration: usize,
+    /// World position of the first element
+    pub offset: [i32; 2],
+    /// Rows in each field
+    pub rows: usize,
+    /// Columns in each field
+    pub cols: usize,
+    /// Field names and the file names holding them
+    pub files: Vec<(&'static str, String)>,
+}
+
+impl SnapshotRecord {
+    // Metadata entry of the snapshot
+    fn to_json(&self, is_final: bool) -> serde_json::Value {
+        let files = self
+            .files
+            .iter()
+            .map(|(field, file_name)| ((*field).to_owned(), file_name.as_str().into()))
+            .collect::<serde_json::Map<_, _>>();
+        serde_json::json!({
+            "iteration": self.iteration,
+            "final": is_final,
+            "offset": self.offset,
+            "shape": [self.rows, self.cols],
+            "files": files,
+        })
+    }
+}
+
+/// Field snapshots written during a run on a schedule
+///
+/// Snapshots go to the staging directory as they are taken, prefixed
+/// `iteration_<n>`. The staging directory is removed when the capture is
+/// dropped, so it should belong to this capture alone.
+#[derive(Debug)]
+pub struct FieldCapture {
+    schedule: FieldSchedule,
+    staging: PathBuf,
+    snapshots: Vec<SnapshotRecord>,
+}
+
+impl FieldCapture {
+    /// Create a capture writing snapshots on a schedule into a staging directory
+    pub const fn new(schedule: FieldSchedule, staging: PathBuf) -> Self {
+        Self {
+            schedule,
+            staging,
+            snapshots: Vec::new(),
+        }
+    }
+
+    /// Snapshot and write the fields if the schedule includes this iteration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the staging directory cannot be created or a file
+    /// cannot be written
+    pub fn record(
+        &mut self,
+        grid_state: &GridState,
+        system_offset: [i32; 2],
+        iteration: usize,
+    ) -> Result<()> {
+        if !self.schedule.includes(iteration) {
+            return Ok(());
+        }
+        create_directory(&self.staging)?;
+        let snapshot = FieldSnapshot::capture(grid_state, system_offset, iteration);
+        let files = snapshot.export(&self.staging, &format!("iteration_{iteration}"))?;
+        self.snapshots.push(SnapshotRecord {
+            iteration,
+            offset: snapshot.offset,
+            rows: snapshot.rows,
+            cols: snapshot.cols,
+            files,
+        });
+        Ok(())
+    }
+
+    /// Snapshots written so far, oldest first
+    pub fn snapshots(&self) -> &[SnapshotRecord] {
+        &self.snapshots
+    }
+
+    /// Move every snapshot and write the final state to a directory with JSON metadata
+    ///
+    /// Scheduled snapshots keep their `iteration_<n>` prefix and the final
+    /// state is prefixed `final`. The palette lists the RGBA color of each
+    /// tile type, in the order of the probability field.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be created or a file cannot
+    /// be moved or written
+    pub fn export(
+        &self,
+        final_snapshot: &FieldSnapshot,
+        color_mapping: &[[u8; 4]],
+        directory: &Path,
+    ) -> Result<()> {
+        create_directory(directory)?;
+
+        let mut entries = Vec::with_capacity(self.snapshots.len() + 1);
+        for snapshot in &self.snapshots {
+            for (_, file_name) in &snapshot.files {
+                let staged = self.staging.join(file_name);
+                std::fs::rename(&staged, directory.join(file_name)).map_err(|e| {
+                    AlgorithmError::FileSystem {
+                        path: staged,
+                        operation: "move file",
+                        source: e,
+                    }
+                })?;
+            }
+            entries.push(snapshot.to_json(false));
+        }
+        let final_record = SnapshotRecord {
+            iteration: final_snapshot.iteration,
+            offset: final_snapshot.offset,
+            rows: final_snapshot.rows,
+            cols: final_snapshot.cols,
+            files: final_snapshot.export(directory, "final")?,
+        };
+        entries.push(final_record.to_json(true));
+
+        let metadata = serde_json::json!({
+            "schedule": self.schedule.to_string(),
+            "palette": color_mapping,
+            "locked_tiles": "0 outside the grid, 1 open, n > 1 palette entry n - 2",
+            "snapshots": entries,
+        });
+        write_json(&directory.join(FIELDS_METADATA_FILE), &metadata)
+    }
+}
+
+impl Drop for FieldCapture {
+    fn drop(&mut self) {
+        // Snapshots not moved out by an export belong to no output
+        if self.staging.exists() {
+            std::fs::remove_dir_all(&self.staging).ok();
+        }
+    }
+}
+
+// Reads a cell of a field, or `outside` for positions beyond the grid
+fn read<T: Copy + PartialEq>(grid: &ChunkedGrid<T>, index: Option<[usize; 2]>, outside: T) -> T {
+    index
+        .and_then(|index| grid.get(index).copied())
+        .unwrap_or(outside)
+}
+
+// Creates a directory and its parents, reporting the path on failure
+fn create_directory(path: &Path) -> Result<()> {
+    std::fs::create_dir_all(path).map_err(|e| AlgorithmError::FileSystem {
+        path: path.to_path_buf(),
+        operation: "create directory",
+        source: e,
+    })
+}
+
+// Writes bytes to a file, reporting the path on failure
+fn write_file(path: &Path, bytes: &[u8]) -> Result<()> {
+    std::fs::write(path, bytes).map_err(|e| AlgorithmError::FileSystem {
+        path: path.to_path_buf(),
+        operation: "write file",
+        source: e,
+    })
+}
+
+// Writes a value as pretty printed JSON, reporting the path on failure
+fn write_json(path: &Path, value: &serde_json::Value) -> Result<()> {
+    let write_error = |e: std::io::Error| AlgorithmError::FileSystem {
+        path: path.to_path_buf(),
+        operation: "write file",
+        source: e,
+    };
+    let file = std::fs::File::create(path).map_err(write_error)?;
+    serde_json::to_writer_pretty(std::io::BufWriter::new(file), value)
+        .map_err(|e| write_error(e.into()))
+}
@@ -1,7 +1,95 @@
 //! PNG export with automatic cropping and transparency handling
+//!
+//! Unbounded grids are cropped to their placed tiles. Bounded grids are
+//! exported at exactly the size of their generation bounds, with the top
+//! left pixel at the bounds minimum, so unfilled cells keep their place and
+//! are rendered with a chosen fill.
 
-use crate::spatial::GridState;
-use image::{ImageBuffer, Rgba};
+use crate::io::error::{AlgorithmError, invalid_parameter};
+use crate::spatial::{GridState, grid};
+use image::{ImageBuffer, Rgba, RgbaImage};
+
+/// How cells without a tile are rendered in bounded exports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnfilledFill {
+    /// Leave unfilled cells fully transparent
+    #[default]
+    Transparent,
+    /// Paint unfilled cells a fixed RGBA color
+    Color([u8; 4]),
+    /// Use the color of the tile with the highest probability at the cell,
+    /// ties going to the lower tile
+    MostProbable,
+}
+
+impl UnfilledFill {
+    /// Color of an unfilled cell at a grid index, if the cell is inside the grid
+    fn color(
+        self,
+        grid_state: &GridState,
+        index: Option<[usize; 2]>,
+        color_mapping: &[[u8; 4]],
+    ) -> [u8; 4] {
+        match (self, index) {
+            (Self::Transparent, _) | (Self::MostProbable, None) => [0, 0, 0, 0],
+            (Self::Color(color), _) => color,
+            (Self::MostProbable, Some(index)) => {
+                let mut best: Option<(usize, f64)> = None;
+                for (tile, probabilities) in grid_state.tile_probabilities.iter().enumerate() {
+                    let probability = probabilities.get(index).copied().unwrap_or(0.0);
+                    if probability > best.map_or(0.0, |(_, most)| most) {
+                        best = Some((tile, probability));
+                    }
+                }
+                best.and_then(|(tile, _)| color_mapping.get(tile).copied())
+                    .unwrap_or([0, 0, 0, 0])
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for UnfilledFill {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Transparent => write!(f, "transparent"),
+            Self::Color([r, g, b, a]) => write!(f, "#{r:02x}{g:02x}{b:02x}{a:02x}"),
+            Self::MostProbable => write!(f, "probable"),
+        }
+    }
+}
+
+impl std::str::FromStr for UnfilledFill {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_ascii_lowercase();
+        match lower.as_str() {
+            "transparent" => return Ok(Self::Transparent),
+            "probable" => return Ok(Self::MostProbable),
+            _ => {}
+        }
+
+        let error = || {
+            invalid_parameter(
+                "fill",
+                &s,
+                &"expected 'transparent', 'probable', '#rrggbb' or '#rrggbbaa'",
+            )
+        };
+        let hex = lower.strip_prefix('#').ok_or_else(error)?;
+        if !matches!(hex.len(), 6 | 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+        let mut color = [0, 0, 0, 255];
+        for (channel, value) in color.iter_mut().enumerate() {
+            let Some(digits) = hex.get(channel * 2..channel * 2 + 2) else {
+                break;
+            };
+            *value = u8::from_str_radix(digits, 16).ok().ok_or_else(error)?;
+        }
+        Ok(Self::Color(color))
+    }
+}
 
 #[derive(Debug)]
 struct BoundingBox {
@@ -19,21 +107,13 @@ fn calculate_bounding_box(grid_state: &GridState) -> Option<BoundingBox> {
     let mut max_col = 0;
     let mut found_tiles = false;
 
-    for row in 0..grid_state.rows() {
-        for col in 0..grid_state.cols() {
-            if grid_state
-                .locked_tiles
-                .get([row, col])
-                .copied()
-                .unwrap_or(0)
-                > 1
-            {
-                found_tiles = true;
-                min_row = min_row.min(row);
-                max_row = max_row.max(row);
-                min_col = min_col.min(col);
-                max_col = max_col.max(col);
-            }
+    for ((row, col), &locked) in grid_state.locked_tiles.indexed_iter() {
+        if locked > 1 {
+            found_tiles = true;
+            min_row = min_row.min(row);
+            max_row = max_row.max(row);
+            min_col = min_col.min(col);
+            max_col = max_col.max(col);
         }
     }
 
@@ -59,7 +139,6 @@ pub fn export_grid_as_png(
     color_mapping: &[[u8; 4]],
     output_path: &str,
 ) -> crate::io::error::Result<()> {
-    use crate::io::error::AlgorithmError;
     let bbox = calculate_bounding_box(grid_state).ok_or(AlgorithmError::InvalidSourceData {
         reason: "No tiles have been placed in the grid".to_string(),
     })?;
@@ -101,6 +180,80 @@ pub fn export_grid_as_png(
         }
     }
 
+    save_image(&img, output_path)
+}
+
+/// Render the generation bounds at their exact size
+///
+/// Cells outside the grid or without a tile are painted with `fill`
+///
+/// # Errors
+///
+/// Returns an error if a tile value is out of bounds for the color mapping
+pub fn render_bounds(
+    grid_state: &GridState,
+    system_offset: [i32; 2],
+    bounds: &grid::BoundingBox,
+    color_mapping: &[[u8; 4]],
+    fill: UnfilledFill,
+) -> crate::io::error::Result<RgbaImage> {
+    let height = (bounds.max[0] - bounds.min[0] + 1).max(0) as u32;
+    let width = (bounds.max[1] - bounds.min[1] + 1).max(0) as u32;
+    let mut img = ImageBuffer::new(width, height);
+
+    for (pixel_y, row) in (bounds.min[0]..=bounds.max[0]).enumerate() {
+        for (pixel_x, col) in (bounds.min[1]..=bounds.max[1]).enumerate() {
+            let index = match (
+                usize::try_from(row + system_offset[0]),
+                usize::try_from(col + system_offset[1]),
+            ) {
+                (Ok(r), Ok(c)) if r < grid_state.rows() && c < grid_state.cols() => Some([r, c]),
+                _ => None,
+            };
+            let tile_value = index
+                .and_then(|index| grid_state.locked_tiles.get(index).copied())
+                .unwrap_or(0);
+
+            let color = if tile_value > 1 {
+                color_mapping
+                    .get((tile_value - 2) as usize)
+                    .copied()
+                    .ok_or(AlgorithmError::InvalidTileIndex {
+                        index: tile_value as usize,
+                        max_tiles: color_mapping.len() + 1,
+                    })?
+            } else {
+                fill.color(grid_state, index, color_mapping)
+            };
+            img.put_pixel(pixel_x as u32, pixel_y as u32, Rgba(color));
+        }
+    }
+
+    Ok(img)
+}
+
+/// Export the generation bounds as a PNG image at their exact size
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A tile value is out of bounds for the color mapping
+/// - The parent directory cannot be created
+/// - The image cannot be saved to the specified path
+pub fn export_bounds_as_png(
+    grid_state: &GridState,
+    system_offset: [i32; 2],
+    bounds: &grid::BoundingBox,
+    color_mapping: &[[u8; 4]],
+    fill: UnfilledFill,
+    output_path: &str,
+) -> crate::io::error::Result<()> {
+    let img = render_bounds(grid_state, system_offset, bounds, color_mapping, fill)?;
+    save_image(&img, output_path)
+}
+
+// Saves an image, creating its parent directory if needed
+fn save_image(img: &RgbaImage, output_path: &str) -> crate::io::error::Result<()> {
     if let Some(parent) = std::path::Path::new(output_path).parent() {
         std::fs::create_dir_all(parent).map_err(|e| AlgorithmError::FileSystem {
             path: parent.to_path_buf(),
@@ -4,7 +4,10 @@ pub mod analysis;
 pub mod cli;
 pub mod configuration;
 pub mod error;
+pub mod events;
+pub mod fields;
 pub mod image;
 pub mod prefill;
 pub mod progress;
+pub mod render;
 pub mod visualization;
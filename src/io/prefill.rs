@@ -1,5 +1,6 @@
 //! Prefill image parsing and queue management for predetermined tile placement
 
+use crate::algorithm::origin::BoundsAnchor;
 use crate::io::error::{AlgorithmError, Result};
 use crate::spatial::grid::BoundingBox;
 use std::collections::{HashMap, VecDeque};
@@ -36,6 +37,22 @@ impl PrefillData {
     /// - The PNG file cannot be loaded
     /// - The prefill image contains no colors from the source palette
     pub fn from_png(path: &Path, color_mapping: &[[u8; 4]]) -> Result<Self> {
+        Self::from_png_anchored(path, color_mapping, BoundsAnchor::Center)
+    }
+
+    /// Parse prefill PNG with `anchor` of the image at the world origin,
+    /// matching bounds of the same size and anchor pixel for pixel
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The PNG file cannot be loaded
+    /// - The prefill image contains no colors from the source palette
+    pub fn from_png_anchored(
+        path: &Path,
+        color_mapping: &[[u8; 4]],
+        anchor: BoundsAnchor,
+    ) -> Result<Self> {
         let img = image::open(path).map_err(|e| AlgorithmError::ImageLoad {
             path: path.to_path_buf(),
             source: e,
@@ -59,9 +76,8 @@ impl PrefillData {
         let mut min_col = i32::MAX;
         let mut max_col = i32::MIN;
 
-        // Center the prefill image at origin
-        let offset_x = width as i32 / 2;
-        let offset_y = height as i32 / 2;
+        // Place the anchor of the prefill image at origin
+        let [offset_y, offset_x] = anchor.offset(height as usize, width as usize);
 
         for (x, y, pixel) in rgba_img.enumerate_pixels() {
             let color = [pixel[0], pixel[1], pixel[2], pixel[3]];
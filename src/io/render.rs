@@ -0,0 +1,286 @@
+//! Upscaled, gridlined and annotated frame rendering
+//!
+//! Frames draw each grid cell as a square block of pixels. Options add lines
+//! between cells, an outline around the most recent placement, tints showing
+//! how each tile was placed or that a removal emptied the cell, and a strip
+//! below the frame giving its iteration.
+
+use crate::io::configuration::{
+    FORCED_OVERLAY_COLOR, GRIDLINE_COLOR, HIGHLIGHT_COLOR, OVERLAY_OPACITY, PREFILL_OVERLAY_COLOR,
+    RANDOM_OVERLAY_COLOR, REMOVED_OVERLAY_COLOR,
+};
+use crate::io::error::{Result, invalid_parameter};
+use crate::io::events::DecisionSource;
+use image::{Rgba, RgbaImage};
+
+/// Presentation of visualization and analysis frames
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Output pixels per cell along each side, upscaled by nearest neighbour
+    pub scale: u32,
+    /// Draw a line along the bottom and right edge of every cell
+    pub gridlines: bool,
+    /// Outline the cell of the most recent placement
+    pub highlight_latest: bool,
+    /// Tint cells by how their tile was placed, or that a removal emptied them
+    pub overlays: bool,
+    /// Show the iteration of each frame in a strip below it
+    pub caption: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            scale: 1,
+            gridlines: false,
+            highlight_latest: false,
+            overlays: false,
+            caption: false,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Check the options can be drawn
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the scale is zero, or gridlines are requested at
+    /// a scale that leaves no pixels of the cell beside them
+    pub fn validate(self) -> Result<()> {
+        if self.scale == 0 {
+            return Err(invalid_parameter("scale", &0, &"must be at least 1"));
+        }
+        if self.gridlines && self.scale < 2 {
+            return Err(invalid_parameter(
+                "scale",
+                &self.scale,
+                &"must be at least 2 to draw gridlines",
+            ));
+        }
+        Ok(())
+    }
+
+    // Output pixels per pixel of a caption glyph
+    fn glyph_scale(self) -> u32 {
+        (self.scale / 4).max(1)
+    }
+
+    // Height of the caption strip, zero without a caption
+    fn caption_height(self) -> u32 {
+        if self.caption {
+            (GLYPH_HEIGHT + 2) * self.glyph_scale()
+        } else {
+            0
+        }
+    }
+
+    /// Size in pixels of frames with content of the given size, widened when
+    /// needed to caption iterations up to `max_iteration`
+    pub(crate) fn frame_size(
+        self,
+        content_width: u32,
+        content_height: u32,
+        max_iteration: usize,
+    ) -> (u32, u32) {
+        (
+            content_width.max(self.caption_width(max_iteration)),
+            content_height + self.caption_height(),
+        )
+    }
+
+    // Width the caption strip needs to show an iteration
+    fn caption_width(self, iteration: usize) -> u32 {
+        if self.caption {
+            (digits(iteration).len() as u32 * (GLYPH_WIDTH + 1) + 1) * self.glyph_scale()
+        } else {
+            0
+        }
+    }
+}
+
+/// How the content of a cell came to be, shown by overlays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellOrigin {
+    /// Chosen at random among viable tiles, including the first tile and seeds
+    Random,
+    /// The only tile fitting its position
+    Forced,
+    /// Given before generation, by a prefill image or a neighbouring chunk
+    Prefill,
+    /// Emptied by deadlock removal or backtracking
+    Removed,
+}
+
+impl CellOrigin {
+    /// Origin of a tile placed for the given reason
+    pub const fn of(source: DecisionSource) -> Self {
+        match source {
+            DecisionSource::Initial | DecisionSource::Seed | DecisionSource::Selected => {
+                Self::Random
+            }
+            DecisionSource::Forced => Self::Forced,
+            DecisionSource::Prefill | DecisionSource::Fixed => Self::Prefill,
+        }
+    }
+
+    const fn overlay_color(self) -> [u8; 4] {
+        match self {
+            Self::Random => RANDOM_OVERLAY_COLOR,
+            Self::Forced => FORCED_OVERLAY_COLOR,
+            Self::Prefill => PREFILL_OVERLAY_COLOR,
+            Self::Removed => REMOVED_OVERLAY_COLOR,
+        }
+    }
+}
+
+const GLYPH_WIDTH: u32 = 3;
+const GLYPH_HEIGHT: u32 = 5;
+
+// Digits 0 to 9 as 3x5 bitmaps, rows from the top, most significant bit leftmost
+const DIGIT_GLYPHS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+fn digits(value: usize) -> Vec<usize> {
+    value
+        .to_string()
+        .bytes()
+        .map(|digit| usize::from(digit - b'0'))
+        .collect()
+}
+
+/// Frame drawn cell by cell, with the caption strip below its content
+pub(crate) struct FrameCanvas {
+    image: RgbaImage,
+    content_height: u32,
+    options: RenderOptions,
+}
+
+impl FrameCanvas {
+    /// Transparent canvas for content of the given size in pixels, widened
+    /// when needed to caption iterations up to `max_iteration`
+    pub(crate) fn new(
+        content_width: u32,
+        content_height: u32,
+        max_iteration: usize,
+        options: RenderOptions,
+    ) -> Self {
+        let (width, height) = options.frame_size(content_width, content_height, max_iteration);
+        Self {
+            image: RgbaImage::new(width, height),
+            content_height,
+            options,
+        }
+    }
+
+    /// Fill a rectangle of pixels, clipped to the canvas
+    pub(crate) fn fill(&mut self, left: u32, top: u32, width: u32, height: u32, color: [u8; 4]) {
+        let right = (left + width).min(self.image.width());
+        let bottom = (top + height).min(self.image.height());
+        for y in top..bottom {
+            for x in left..right {
+                self.image.put_pixel(x, y, Rgba(color));
+            }
+        }
+    }
+
+    /// Draw a cell of a panel whose top left pixel is at `left`, `top`,
+    /// tinted by its origin when overlays are on
+    pub(crate) fn cell(
+        &mut self,
+        left: u32,
+        top: u32,
+        [row, col]: [usize; 2],
+        color: [u8; 4],
+        origin: Option<CellOrigin>,
+    ) {
+        let scale = self.options.scale;
+        let x = left + col as u32 * scale;
+        let y = top + row as u32 * scale;
+        let shown = match origin {
+            Some(origin) if self.options.overlays => mix(color, origin.overlay_color()),
+            _ => color,
+        };
+        self.fill(x, y, scale, scale, shown);
+        if self.options.gridlines {
+            self.fill(x + scale - 1, y, 1, scale, GRIDLINE_COLOR);
+            self.fill(x, y + scale - 1, scale, 1, GRIDLINE_COLOR);
+        }
+    }
+
+    /// Outline a cell of a panel when highlighting the latest placement
+    pub(crate) fn highlight(&mut self, left: u32, top: u32, [row, col]: [usize; 2]) {
+        if !self.options.highlight_latest {
+            return;
+        }
+        let scale = self.options.scale;
+        let width = (scale / 8).max(1);
+        let x = left + col as u32 * scale;
+        let y = top + row as u32 * scale;
+        self.fill(x, y, scale, width, HIGHLIGHT_COLOR);
+        self.fill(x, y + scale - width, scale, width, HIGHLIGHT_COLOR);
+        self.fill(x, y, width, scale, HIGHLIGHT_COLOR);
+        self.fill(x + scale - width, y, width, scale, HIGHLIGHT_COLOR);
+    }
+
+    /// Write the iteration in the caption strip, when there is one
+    pub(crate) fn caption(&mut self, iteration: usize) {
+        if !self.options.caption {
+            return;
+        }
+        let glyph_scale = self.options.glyph_scale();
+        let (width, height) = (self.image.width(), self.options.caption_height());
+        self.fill(0, self.content_height, width, height, [0, 0, 0, 255]);
+
+        let top = self.content_height + glyph_scale;
+        for (position, digit) in digits(iteration).into_iter().enumerate() {
+            let left = (position as u32 * (GLYPH_WIDTH + 1) + 1) * glyph_scale;
+            let rows = DIGIT_GLYPHS.get(digit).into_iter().flatten();
+            for (glyph_row, &bits) in (0..GLYPH_HEIGHT).zip(rows) {
+                for glyph_col in 0..GLYPH_WIDTH {
+                    if bits >> (GLYPH_WIDTH - 1 - glyph_col) & 1 == 1 {
+                        self.fill(
+                            left + glyph_col * glyph_scale,
+                            top + glyph_row * glyph_scale,
+                            glyph_scale,
+                            glyph_scale,
+                            HIGHLIGHT_COLOR,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn into_image(self) -> RgbaImage {
+        self.image
+    }
+}
+
+// Blends an overlay color into a cell color, keeping the cell's alpha
+fn mix(color: [u8; 4], overlay: [u8; 4]) -> [u8; 4] {
+    let channel = |base: u8, tint: u8| {
+        (f64::from(tint) - f64::from(base))
+            .mul_add(OVERLAY_OPACITY, f64::from(base))
+            .round() as u8
+    };
+    let [r, g, b, a] = color;
+    let [tint_r, tint_g, tint_b, _] = overlay;
+    [
+        channel(r, tint_r),
+        channel(g, tint_g),
+        channel(b, tint_b),
+        a,
+    ]
+}
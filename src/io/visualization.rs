@@ -1,7 +1,108 @@
-//! Frame capture and GIF generation for algorithm visualization
+//! Frame capture and animation export for algorithm visualization
+//!
+//! Frames show the grid after each placement or removal and are written as
+//! a 256 color GIF, a lossless APNG or animated WebP, or a directory of
+//! numbered PNGs for external video encoders. When the placements come faster
+//! than a format can show, frames are skipped so the animation keeps its
+//! speed, and a target duration can replace the per-placement delay.
+
+use crate::io::configuration::{
+    FINAL_FRAME_HOLD, GIF_FRAME_DELAY_MS, SEQUENCE_FRAME_RATE, VIEWER_MIN_FRAME_DELAY_MS,
+};
+use crate::io::error::{AlgorithmError, Result, invalid_parameter};
+use crate::io::events::DecisionSource;
+use crate::io::render::{CellOrigin, FrameCanvas, RenderOptions};
+use image::{Frame, ImageEncoder, ImageFormat, RgbaImage};
+use std::io::Write;
+use std::path::Path;
+
+/// Output format of a visualization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnimationFormat {
+    /// Animated GIF, limited to 256 colors and the viewer minimum delay
+    #[default]
+    Gif,
+    /// Lossless animated PNG
+    Apng,
+    /// Lossless animated WebP
+    Webp,
+    /// Directory of numbered PNGs sampled at `SEQUENCE_FRAME_RATE`
+    Frames,
+}
+
+impl AnimationFormat {
+    /// File extension of the output, or None when frames are written to a directory
+    pub const fn extension(self) -> Option<&'static str> {
+        match self {
+            Self::Gif => Some("gif"),
+            Self::Apng => Some("png"),
+            Self::Webp => Some("webp"),
+            Self::Frames => None,
+        }
+    }
+
+    /// Shortest time a frame is shown for, in milliseconds
+    pub fn min_frame_delay_ms(self) -> f64 {
+        match self {
+            Self::Gif => f64::from(VIEWER_MIN_FRAME_DELAY_MS),
+            Self::Apng | Self::Webp => 1.0,
+            Self::Frames => 1000.0 / f64::from(SEQUENCE_FRAME_RATE),
+        }
+    }
+}
 
-use crate::io::error::{AlgorithmError, Result};
-use image::{Frame, Rgba, RgbaImage};
+impl std::str::FromStr for AnimationFormat {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "gif" => Ok(Self::Gif),
+            "apng" | "png" => Ok(Self::Apng),
+            "webp" => Ok(Self::Webp),
+            "frames" | "sequence" => Ok(Self::Frames),
+            _ => Err(invalid_parameter(
+                "animation format",
+                &s,
+                &"expected 'gif', 'apng', 'webp' or 'frames'",
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for AnimationFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Gif => write!(f, "gif"),
+            Self::Apng => write!(f, "apng"),
+            Self::Webp => write!(f, "webp"),
+            Self::Frames => write!(f, "frames"),
+        }
+    }
+}
+
+/// Format, size and timing of an exported visualization
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnimationOptions {
+    /// Output format
+    pub format: AnimationFormat,
+    /// Scale and annotations of each frame
+    pub render: RenderOptions,
+    /// Time each placement is shown for, in milliseconds
+    pub frame_delay_ms: u32,
+    /// Time to play every placement in, replacing the frame delay when set
+    pub duration_ms: Option<u32>,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        Self {
+            format: AnimationFormat::default(),
+            render: RenderOptions::default(),
+            frame_delay_ms: GIF_FRAME_DELAY_MS,
+            duration_ms: None,
+        }
+    }
+}
 
 /// Represents a single tile placement event
 #[derive(Debug, Clone)]
@@ -12,6 +113,8 @@ pub struct TilePlacement {
     pub col: i32,
     /// Tile reference (None for removals)
     pub tile_ref: Option<u32>,
+    /// Why the tile was placed (None for removals)
+    pub source: Option<DecisionSource>,
     /// Algorithm iteration when placed
     pub iteration: usize,
 }
@@ -68,11 +171,19 @@ impl VisualizationCapture {
     }
 
     /// Records a tile placement at the given position
-    pub fn record_placement(&mut self, row: i32, col: i32, tile_ref: u32, iteration: usize) {
+    pub fn record_placement(
+        &mut self,
+        row: i32,
+        col: i32,
+        tile_ref: u32,
+        iteration: usize,
+        source: DecisionSource,
+    ) {
         self.placements.push(TilePlacement {
             row,
             col,
             tile_ref: Some(tile_ref),
+            source: Some(source),
             iteration,
         });
     }
@@ -83,6 +194,7 @@ impl VisualizationCapture {
             row,
             col,
             tile_ref: None,
+            source: None,
             iteration,
         });
     }
@@ -105,55 +217,159 @@ impl VisualizationCapture {
     /// - File system operations fail
     /// - GIF encoding fails
     pub fn export_gif(&self, output_path: &str, frame_delay_ms: u32) -> Result<()> {
-        use crate::io::configuration::VIEWER_MIN_FRAME_DELAY_MS;
+        self.export_animation(
+            output_path,
+            &AnimationOptions {
+                frame_delay_ms,
+                ..AnimationOptions::default()
+            },
+        )
+    }
 
+    /// Export the captured frames in the requested format, skipping frames
+    /// shown for less than the format's minimum delay
+    ///
+    /// Frame sequences are written into a directory at `output_path` as
+    /// `frame_000000.png` onwards, each frame repeated so the sequence plays
+    /// at `SEQUENCE_FRAME_RATE` for the requested delay or duration, without
+    /// the longer final frame the animated formats end on.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - No tile placements were captured
+    /// - The render options cannot be drawn
+    /// - File system operations fail
+    /// - Encoding fails
+    pub fn export_animation(&self, output_path: &str, options: &AnimationOptions) -> Result<()> {
         if self.placements.is_empty() {
             return Err(AlgorithmError::InvalidSourceData {
                 reason: "No tile placements captured for visualization".to_string(),
             });
         }
-
-        let effective_delay_ms = frame_delay_ms.max(VIEWER_MIN_FRAME_DELAY_MS);
-        let skip_factor = if frame_delay_ms < VIEWER_MIN_FRAME_DELAY_MS {
-            VIEWER_MIN_FRAME_DELAY_MS.div_ceil(frame_delay_ms)
+        options.render.validate()?;
+
+        let placement_delay_ms = options.duration_ms.map_or_else(
+            || f64::from(options.frame_delay_ms),
+            |duration_ms| f64::from(duration_ms) / self.placements.len() as f64,
+        );
+        let min_delay_ms = options.format.min_frame_delay_ms();
+        let skip_factor = if placement_delay_ms < min_delay_ms {
+            (min_delay_ms / placement_delay_ms).ceil() as usize
         } else {
             1
         };
-
-        let (min_row, min_col, final_rows, final_cols) = self.calculate_final_bounds();
-
-        let frames = self.generate_frames(
-            min_row,
-            min_col,
-            final_rows,
-            final_cols,
-            effective_delay_ms,
-            skip_factor as usize,
-        )?;
-
-        if let Some(parent) = std::path::Path::new(output_path).parent() {
-            std::fs::create_dir_all(parent).map_err(|e| AlgorithmError::FileSystem {
-                path: parent.to_path_buf(),
+        let delay_ms = (placement_delay_ms * skip_factor as f64).max(min_delay_ms);
+
+        let bounds = self.calculate_final_bounds();
+        let (_, _, rows, cols) = bounds;
+        let frame_count = self.frame_count(bounds, skip_factor);
+        let hold_ms = delay_ms * f64::from(FINAL_FRAME_HOLD);
+        let frame_delay = |index: usize| {
+            if index + 1 == frame_count {
+                hold_ms
+            } else {
+                delay_ms
+            }
+        };
+        let max_iteration = self
+            .placements
+            .iter()
+            .map(|p| p.iteration)
+            .max()
+            .unwrap_or(0);
+        let (width, height) = options.render.frame_size(
+            cols as u32 * options.render.scale,
+            rows as u32 * options.render.scale,
+            max_iteration,
+        );
+
+        let target = Path::new(output_path);
+        let directory = match options.format {
+            AnimationFormat::Frames => Some(target),
+            _ => target.parent(),
+        };
+        if let Some(directory) = directory {
+            std::fs::create_dir_all(directory).map_err(|e| AlgorithmError::FileSystem {
+                path: directory.to_path_buf(),
                 operation: "create directory",
                 source: e,
             })?;
         }
 
-        let file = std::fs::File::create(output_path).map_err(|e| AlgorithmError::FileSystem {
-            path: output_path.into(),
-            operation: "create file",
-            source: e,
-        })?;
-
-        let mut encoder = image::codecs::gif::GifEncoder::new(file);
-        encoder
-            .encode_frames(frames)
-            .map_err(|e| AlgorithmError::ImageExport {
-                path: output_path.into(),
-                source: e,
-            })?;
-
-        Ok(())
+        // Each frame is encoded as soon as it is rendered, so memory stays
+        // proportional to one frame however long the run was
+        match options.format {
+            AnimationFormat::Gif => {
+                let mut encoder = image::codecs::gif::GifEncoder::new(create_file(target)?);
+                self.render_frames(bounds, skip_factor, options.render, |index, frame| {
+                    let microseconds = (frame_delay(index) * 1000.0).round() as u32;
+                    encoder
+                        .encode_frame(Frame::from_parts(
+                            frame,
+                            0,
+                            0,
+                            image::Delay::from_numer_denom_ms(microseconds, 1000),
+                        ))
+                        .map_err(|e| AlgorithmError::ImageExport {
+                            path: target.to_path_buf(),
+                            source: e,
+                        })
+                })
+            }
+            AnimationFormat::Apng => {
+                let error = |e: png::EncodingError| export_error(target, ImageFormat::Png, e);
+                let frames = u32::try_from(frame_count).map_err(|_overflow| {
+                    invalid_parameter("frames", &frame_count, &"exceeds 32 bits")
+                })?;
+                let mut encoder = png::Encoder::new(create_file(target)?, width, height);
+                encoder.set_color(png::ColorType::Rgba);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder.set_animated(frames, 0).map_err(error)?;
+                let mut writer = encoder.write_header().map_err(error)?;
+                self.render_frames(bounds, skip_factor, options.render, |index, frame| {
+                    let (numerator, denominator) = apng_delay(frame_delay(index));
+                    writer
+                        .set_frame_delay(numerator, denominator)
+                        .and_then(|()| writer.write_image_data(frame.as_raw()))
+                        .map_err(error)
+                })?;
+                writer.finish().map_err(error)
+            }
+            AnimationFormat::Webp => {
+                let mut writer = WebpWriter::create(target, width, height)?;
+                self.render_frames(bounds, skip_factor, options.render, |index, frame| {
+                    writer.write_frame(&frame, frame_delay(index))
+                })?;
+                writer.finish()
+            }
+            AnimationFormat::Frames => {
+                // Each frame is repeated for every sequence frame its delay
+                // covers, so the sequence plays for the requested time
+                let mut written = 0;
+                self.render_frames(bounds, skip_factor, options.render, |index, frame| {
+                    let end = ((index + 1) as f64 * delay_ms / min_delay_ms).round() as usize;
+                    let first = target.join(format!("frame_{written:06}.png"));
+                    frame
+                        .save_with_format(&first, ImageFormat::Png)
+                        .map_err(|e| AlgorithmError::ImageExport {
+                            path: first.clone(),
+                            source: e,
+                        })?;
+                    written += 1;
+                    while written < end {
+                        let path = target.join(format!("frame_{written:06}.png"));
+                        std::fs::copy(&first, &path).map_err(|e| AlgorithmError::FileSystem {
+                            path,
+                            operation: "copy frame",
+                            source: e,
+                        })?;
+                        written += 1;
+                    }
+                    Ok(())
+                })
+            }
+        }
     }
 
     fn calculate_final_bounds(&self) -> (i32, i32, usize, usize) {
@@ -179,99 +395,136 @@ impl VisualizationCapture {
         (min_row, min_col, rows, cols)
     }
 
-    fn generate_frames(
-        &self,
-        min_row: i32,
-        min_col: i32,
-        rows: usize,
-        cols: usize,
-        delay_ms: u32,
-        skip_factor: usize,
-    ) -> Result<Vec<Frame>> {
-        // 0 = removal, 1 = empty, 2+ = tiles
-        let mut grid = vec![vec![1u32; cols]; rows];
-        let mut frames = Vec::new();
+    // Grid cell of a placement, or None when it lies outside the bounds
+    fn grid_cell(
+        placement: &TilePlacement,
+        bounds: (i32, i32, usize, usize),
+    ) -> Option<(usize, usize)> {
+        let (min_row, min_col, rows, cols) = bounds;
+        let row = usize::try_from(placement.row - min_row).ok()?;
+        let col = usize::try_from(placement.col - min_col).ok()?;
+        (row < rows && col < cols).then_some((row, col))
+    }
 
-        frames.push(self.render_frame(&grid, rows, cols, delay_ms)?);
+    // Frames rendered for the placements inside the bounds: the empty grid,
+    // then one per `skip_factor` placements and one for any left over
+    fn frame_count(&self, bounds: (i32, i32, usize, usize), skip_factor: usize) -> usize {
+        let shown = self
+            .placements
+            .iter()
+            .filter(|placement| Self::grid_cell(placement, bounds).is_some())
+            .count();
+        1 + shown.div_ceil(skip_factor)
+    }
 
-        let mut frame_count = 0;
+    // Renders the frames counted by `frame_count` in order, handing each to
+    // `write` with its index before the next is rendered
+    fn render_frames(
+        &self,
+        bounds: (i32, i32, usize, usize),
+        skip_factor: usize,
+        options: RenderOptions,
+        mut write: impl FnMut(usize, RgbaImage) -> Result<()>,
+    ) -> Result<()> {
+        let (_, _, rows, cols) = bounds;
+        let max_iteration = self
+            .placements
+            .iter()
+            .map(|p| p.iteration)
+            .max()
+            .unwrap_or(0);
+        let mut frame = FrameState {
+            // 0 = removal, 1 = empty, 2+ = tiles
+            grid: vec![vec![(1u32, None); cols]; rows],
+            latest: None,
+            iteration: 0,
+        };
+        let mut frame_index = 0;
+        write(
+            frame_index,
+            self.render_frame(&frame, max_iteration, options)?,
+        )?;
 
+        let mut shown = 0;
         for placement in &self.placements {
-            let grid_row = (placement.row - min_row) as usize;
-            let grid_col = (placement.col - min_col) as usize;
-
-            if grid_row < rows && grid_col < cols {
-                if let Some(row) = grid.get_mut(grid_row) {
-                    if let Some(cell) = row.get_mut(grid_col) {
-                        *cell = placement.tile_ref.unwrap_or(0);
-                    }
+            if let Some((grid_row, grid_col)) = Self::grid_cell(placement, bounds) {
+                if let Some(cell) = frame
+                    .grid
+                    .get_mut(grid_row)
+                    .and_then(|row| row.get_mut(grid_col))
+                {
+                    *cell = match (placement.tile_ref, placement.source) {
+                        (Some(tile_ref), source) => (tile_ref, source.map(CellOrigin::of)),
+                        (None, _) => (0, Some(CellOrigin::Removed)),
+                    };
+                }
+                if placement.tile_ref.is_some() {
+                    frame.latest = Some([grid_row, grid_col]);
                 }
+                frame.iteration = placement.iteration;
 
-                frame_count += 1;
+                shown += 1;
 
-                if frame_count % skip_factor == 0 {
-                    frames.push(self.render_frame(&grid, rows, cols, delay_ms)?);
+                if shown % skip_factor == 0 {
+                    frame_index += 1;
+                    write(
+                        frame_index,
+                        self.render_frame(&frame, max_iteration, options)?,
+                    )?;
                 }
             }
         }
 
-        if frame_count % skip_factor != 0 {
-            frames.push(self.render_frame(&grid, rows, cols, delay_ms)?);
+        if shown % skip_factor != 0 {
+            write(
+                frame_index + 1,
+                self.render_frame(&frame, max_iteration, options)?,
+            )?;
         }
 
-        // Final frame displays longer for better visibility
-        if !frames.is_empty() {
-            let final_frame_delay = delay_ms * 25;
-            if let Some(last_frame_img) = frames.last().map(|f| f.buffer().clone()) {
-                frames.push(Frame::from_parts(
-                    last_frame_img,
-                    0,
-                    0,
-                    image::Delay::from_numer_denom_ms(final_frame_delay, 1),
-                ));
-            }
-        }
-
-        Ok(frames)
+        Ok(())
     }
 
     fn render_frame(
         &self,
-        grid: &[Vec<u32>],
-        rows: usize,
-        cols: usize,
-        delay_ms: u32,
-    ) -> Result<Frame> {
-        let mut img = RgbaImage::new(cols as u32, rows as u32);
-
-        for (row, row_data) in grid.iter().enumerate().take(rows) {
-            for (col, &tile_ref) in row_data.iter().enumerate().take(cols) {
+        frame: &FrameState,
+        max_iteration: usize,
+        options: RenderOptions,
+    ) -> Result<RgbaImage> {
+        let rows = frame.grid.len();
+        let cols = frame.grid.first().map_or(0, Vec::len);
+        let mut canvas = FrameCanvas::new(
+            cols as u32 * options.scale,
+            rows as u32 * options.scale,
+            max_iteration,
+            options,
+        );
+
+        for (row, row_data) in frame.grid.iter().enumerate() {
+            for (col, &(tile_ref, origin)) in row_data.iter().enumerate() {
                 let color = match tile_ref {
-                    0 | 1 => Rgba(self.empty_color),
+                    0 | 1 => self.empty_color,
                     _ => {
                         let color_index = (tile_ref - 2) as usize;
-                        let rgba =
-                            self.color_mapping
-                                .get(color_index)
-                                .copied()
-                                .ok_or_else(|| AlgorithmError::InvalidTileIndex {
-                                    index: tile_ref as usize,
-                                    max_tiles: self.color_mapping.len() + 1,
-                                })?;
-                        Rgba([rgba[0], rgba[1], rgba[2], rgba[3]])
+                        self.color_mapping
+                            .get(color_index)
+                            .copied()
+                            .ok_or_else(|| AlgorithmError::InvalidTileIndex {
+                                index: tile_ref as usize,
+                                max_tiles: self.color_mapping.len() + 1,
+                            })?
                     }
                 };
-
-                img.put_pixel(col as u32, row as u32, color);
+                canvas.cell(0, 0, [row, col], color, origin);
             }
         }
 
-        Ok(Frame::from_parts(
-            img,
-            0,
-            0,
-            image::Delay::from_numer_denom_ms(delay_ms, 1),
-        ))
+        if let Some(latest) = frame.latest {
+            canvas.highlight(0, 0, latest);
+        }
+        canvas.caption(frame.iteration);
+
+        Ok(canvas.into_image())
     }
 
     /// Returns the total number of placement events
@@ -279,3 +532,182 @@ impl VisualizationCapture {
         self.placements.len()
     }
 }
+
+// Grid shown by a visualization frame, with the cell of the latest placement
+// and the iteration it was made in
+struct FrameState {
+    grid: Vec<Vec<(u32, Option<CellOrigin>)>>,
+    latest: Option<[usize; 2]>,
+    iteration: usize,
+}
+
+// Wraps an encoder's error as an export failure of the given format
+fn export_error(
+    path: &Path,
+    format: ImageFormat,
+    error: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+) -> AlgorithmError {
+    AlgorithmError::ImageExport {
+        path: path.to_path_buf(),
+        source: image::ImageError::Encoding(image::error::EncodingError::new(format.into(), error)),
+    }
+}
+
+fn create_file(path: &Path) -> Result<std::io::BufWriter<std::fs::File>> {
+    std::fs::File::create(path)
+        .map(std::io::BufWriter::new)
+        .map_err(|e| AlgorithmError::FileSystem {
+            path: path.to_path_buf(),
+            operation: "create file",
+            source: e,
+        })
+}
+
+// APNG delays are fractions of a second with 16 bit terms, so long delays
+// lose precision
+fn apng_delay(delay_ms: f64) -> (u16, u16) {
+    [1000u16, 100, 10, 1]
+        .into_iter()
+        .map(|denominator| {
+            let numerator = (delay_ms * f64::from(denominator) / 1000.0).round();
+            (numerator, denominator)
+        })
+        .find(|&(numerator, _)| numerator <= f64::from(u16::MAX))
+        .map_or((u16::MAX, 1), |(numerator, denominator)| {
+            (numerator as u16, denominator)
+        })
+}
+
+// Animated WebP holds each frame as the VP8L bitstream of a lossless still
+// image, inside an ANMF chunk giving its placement and duration. Chunks are
+// written as frames arrive and the RIFF size is filled in once all are known.
+struct WebpWriter<'a> {
+    path: &'a Path,
+    file: std::io::BufWriter<std::fs::File>,
+    canvas: [u8; 6],
+    riff_size: usize,
+}
+
+impl<'a> WebpWriter<'a> {
+    fn create(path: &'a Path, width: u32, height: u32) -> Result<Self> {
+        let error = |reason: &str| export_error(path, ImageFormat::WebP, reason.to_string());
+        let (Some([w0, w1, w2]), Some([h0, h1, h2])) = (
+            webp_dimension(width.saturating_sub(1)),
+            webp_dimension(height.saturating_sub(1)),
+        ) else {
+            return Err(error("Dimension exceeds 24 bits"));
+        };
+        let canvas = [w0, w1, w2, h0, h1, h2];
+
+        let mut writer = Self {
+            path,
+            file: create_file(path)?,
+            canvas,
+            riff_size: 4,
+        };
+        let mut header = b"RIFF\0\0\0\0WEBP".to_vec();
+        // Animation and alpha flags, then the canvas size
+        let mut vp8x = vec![0x12, 0, 0, 0];
+        vp8x.extend_from_slice(&canvas);
+        push_chunk(&mut header, *b"VP8X", &vp8x);
+        // Transparent background, looping forever
+        push_chunk(&mut header, *b"ANIM", &[0, 0, 0, 0, 0, 0]);
+        writer.riff_size += header.len() - 12;
+        writer.write(&header)?;
+        Ok(writer)
+    }
+
+    fn write_frame(&mut self, frame: &RgbaImage, delay_ms: f64) -> Result<()> {
+        let mut still = Vec::new();
+        image::codecs::webp::WebPEncoder::new_lossless(&mut still)
+            .write_image(
+                frame.as_raw(),
+                frame.width(),
+                frame.height(),
+                image::ExtendedColorType::Rgba8,
+            )
+            .map_err(|e| AlgorithmError::ImageExport {
+                path: self.path.to_path_buf(),
+                source: e,
+            })?;
+        let bitstream = vp8l_bitstream(&still).ok_or_else(|| {
+            export_error(
+                self.path,
+                ImageFormat::WebP,
+                "Unexpected WebP layout".to_string(),
+            )
+        })?;
+
+        let mut anmf = vec![0; 6];
+        anmf.extend_from_slice(&self.canvas);
+        let [low, middle, high, _] = (delay_ms.round() as u32).clamp(1, 0xFF_FFFF).to_le_bytes();
+        anmf.extend_from_slice(&[low, middle, high]);
+        // Replace the canvas rather than blending, and keep it for the next frame
+        anmf.push(0b10);
+        push_chunk(&mut anmf, *b"VP8L", bitstream);
+
+        let mut chunk = Vec::with_capacity(anmf.len() + 9);
+        push_chunk(&mut chunk, *b"ANMF", &anmf);
+        self.riff_size += chunk.len();
+        self.write(&chunk)
+    }
+
+    fn finish(mut self) -> Result<()> {
+        use std::io::{Seek, SeekFrom};
+
+        let riff_size = u32::try_from(self.riff_size).map_err(|_overflow| {
+            export_error(
+                self.path,
+                ImageFormat::WebP,
+                "File exceeds 4 GiB".to_string(),
+            )
+        })?;
+        let path = self.path;
+        self.file
+            .seek(SeekFrom::Start(4))
+            .and_then(|_| self.file.write_all(&riff_size.to_le_bytes()))
+            .and_then(|()| self.file.flush())
+            .map_err(|e| AlgorithmError::FileSystem {
+                path: path.to_path_buf(),
+                operation: "write file",
+                source: e,
+            })
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        self.file
+            .write_all(bytes)
+            .map_err(|e| AlgorithmError::FileSystem {
+                path: self.path.to_path_buf(),
+                operation: "write file",
+                source: e,
+            })
+    }
+}
+
+// Little-endian 24 bit field of a WebP header
+fn webp_dimension(value: u32) -> Option<[u8; 3]> {
+    let [low, middle, high, top] = value.to_le_bytes();
+    (top == 0).then_some([low, middle, high])
+}
+
+// Payload of the VP8L chunk of a still WebP in the simple container
+fn vp8l_bitstream(still: &[u8]) -> Option<&[u8]> {
+    let (header, rest) = still.split_at_checked(20)?;
+    let (tag, length) = header.get(12..)?.split_at(4);
+    if tag != b"VP8L" {
+        return None;
+    }
+    let length = u32::from_le_bytes(length.try_into().ok()?) as usize;
+    rest.get(..length)
+}
+
+// Appends a RIFF chunk, padded to an even length
+fn push_chunk(out: &mut Vec<u8>, tag: [u8; 4], data: &[u8]) {
+    out.extend_from_slice(&tag);
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        out.push(0);
+    }
+}
@@ -2,5 +2,7 @@
 
 /// Cubic spline interpolation for smooth value transitions
 pub mod interpolation;
+/// Smoothing kernels for density estimation
+pub mod kernel;
 /// Probability distributions and statistical functions
 pub mod probability;
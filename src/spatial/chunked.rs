@@ -0,0 +1,262 @@
+//! Sparse chunked storage for unbounded grids
+//!
+//! Cells live in fixed-size square blocks held in a hash map keyed by chunk
+//! coordinate. Extending the grid only moves its logical bounds, so growth
+//! costs O(1) regardless of size and memory is spent only on chunks that
+//! have been written. Full-grid scans look each chunk up once per row it
+//! covers rather than once per cell.
+
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+
+use ndarray::Array2;
+
+use crate::spatial::extension::ExtensionInfo;
+
+/// Side length of a storage chunk in cells
+pub const CHUNK_SIZE: usize = 32;
+
+/// Cells per storage chunk
+pub const CHUNK_AREA: usize = CHUNK_SIZE * CHUNK_SIZE;
+
+/// Chunk coordinate containing a world position
+pub const fn chunk_of(position: [i32; 2]) -> [i32; 2] {
+    [
+        position[0].div_euclid(CHUNK_SIZE as i32),
+        position[1].div_euclid(CHUNK_SIZE as i32),
+    ]
+}
+
+/// Pack a chunk coordinate into a map key
+const fn chunk_key(chunk: [i32; 2]) -> u64 {
+    ((chunk[0] as u32 as u64) << 32) | chunk[1] as u32 as u64
+}
+
+/// Unpack a map key into a chunk coordinate
+const fn key_chunk(key: u64) -> [i32; 2] {
+    [(key >> 32) as u32 as i32, key as u32 as i32]
+}
+
+/// Multiplicative hasher for packed chunk coordinates
+///
+/// Chunk keys are small integers, so the default `SipHash` would dominate
+/// every cell lookup
+#[derive(Default)]
+struct ChunkKeyHasher(u64);
+
+impl Hasher for ChunkKeyHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0.rotate_left(8) ^ u64::from(byte)).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0 = (self.0 ^ value).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    }
+}
+
+/// Two-dimensional grid stored as sparse fixed-size chunks
+///
+/// Indexed like an `Array2` with `[row, col]` grid indices in `0..dim()`.
+/// Unwritten cells read as the padding value; cells inside the initial
+/// dimensions read as the initial value.
+#[derive(Debug, Clone)]
+pub struct ChunkedGrid<T> {
+    /// Allocated chunks keyed by packed chunk coordinate
+    chunks: HashMap<u64, Box<[T]>, BuildHasherDefault<ChunkKeyHasher>>,
+    /// Storage coordinate of grid index `[0, 0]`
+    origin: [i64; 2],
+    /// Current dimensions (rows, cols)
+    dims: (usize, usize),
+    /// Value of cells that have never been written
+    padding: T,
+    /// A chunk row of padding, read in place of unwritten chunks
+    padding_row: Box<[T]>,
+}
+
+impl<T: Copy + PartialEq> ChunkedGrid<T> {
+    /// Create a grid whose cells hold `initial` and whose extensions hold `padding`
+    pub fn new(rows: usize, cols: usize, initial: T, padding: T) -> Self {
+        let mut grid = Self {
+            chunks: HashMap::default(),
+            origin: [0, 0],
+            dims: (rows, cols),
+            padding,
+            padding_row: vec![padding; CHUNK_SIZE].into_boxed_slice(),
+        };
+        if initial != padding {
+            grid.fill(initial);
+        }
+        grid
+    }
+
+    /// Current dimensions (rows, cols)
+    pub const fn dim(&self) -> (usize, usize) {
+        self.dims
+    }
+
+    /// Number of rows
+    pub const fn nrows(&self) -> usize {
+        self.dims.0
+    }
+
+    /// Number of columns
+    pub const fn ncols(&self) -> usize {
+        self.dims.1
+    }
+
+    /// Anchor storage so chunk coordinates follow world coordinates
+    ///
+    /// Grid index `i` holds world position `i - system_offset`. Cells already
+    /// written are relabelled rather than moved, so anchor before writing.
+    #[must_use]
+    pub const fn anchored(mut self, system_offset: [i32; 2]) -> Self {
+        self.origin = [-(system_offset[0] as i64), -(system_offset[1] as i64)];
+        self
+    }
+
+    /// Number of chunks holding written cells
+    pub fn allocated_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Coordinates of every chunk holding written cells
+    pub fn chunk_coordinates(&self) -> impl Iterator<Item = [i32; 2]> + '_ {
+        self.chunks.keys().map(|&key| key_chunk(key))
+    }
+
+    /// Remove a chunk's cells, which then read as padding
+    pub fn take_chunk(&mut self, chunk: [i32; 2]) -> Option<Box<[T]>> {
+        self.chunks.remove(&chunk_key(chunk))
+    }
+
+    /// Store a chunk's cells, replacing any already present
+    ///
+    /// Returns `false` without storing anything if `cells` is not `CHUNK_AREA` long
+    pub fn insert_chunk(&mut self, chunk: [i32; 2], cells: Box<[T]>) -> bool {
+        if cells.len() != CHUNK_AREA {
+            return false;
+        }
+        self.chunks.insert(chunk_key(chunk), cells);
+        true
+    }
+
+    /// Chunk key and offset within the chunk for an in-bounds grid index
+    const fn locate(&self, index: [usize; 2]) -> Option<(u64, usize)> {
+        if index[0] >= self.dims.0 || index[1] >= self.dims.1 {
+            return None;
+        }
+
+        let size = CHUNK_SIZE as i64;
+        let row = self.origin[0] + index[0] as i64;
+        let col = self.origin[1] + index[1] as i64;
+        let key = chunk_key([row.div_euclid(size) as i32, col.div_euclid(size) as i32]);
+        let local = (row.rem_euclid(size) * size + col.rem_euclid(size)) as usize;
+        Some((key, local))
+    }
+
+    /// Reference to a cell, or `None` outside the grid
+    pub fn get(&self, index: [usize; 2]) -> Option<&T> {
+        let (key, local) = self.locate(index)?;
+        self.chunks
+            .get(&key)
+            .map_or(Some(&self.padding), |chunk| chunk.get(local))
+    }
+
+    /// Mutable reference to a cell, allocating its chunk on first write
+    pub fn get_mut(&mut self, index: [usize; 2]) -> Option<&mut T> {
+        let (key, local) = self.locate(index)?;
+        let padding = self.padding;
+        self.chunks
+            .entry(key)
+            .or_insert_with(|| vec![padding; CHUNK_AREA].into_boxed_slice())
+            .get_mut(local)
+    }
+
+    /// Set every cell inside the current dimensions
+    pub fn fill(&mut self, value: T) {
+        let padding = self.padding;
+        for row in 0..self.dims.0 {
+            for (key, start, len) in self.row_runs(row) {
+                if let Some(cells) = self
+                    .chunks
+                    .entry(key)
+                    .or_insert_with(|| vec![padding; CHUNK_AREA].into_boxed_slice())
+                    .get_mut(start..start + len)
+                {
+                    cells.fill(value);
+                }
+            }
+        }
+    }
+
+    /// Runs of one grid row that share a chunk, as the chunk key, the
+    /// offset of the run within the chunk and the run length, left to right
+    fn row_runs(&self, row: usize) -> impl Iterator<Item = (u64, usize, usize)> + use<T> {
+        let size = CHUNK_SIZE as i64;
+        let storage_row = self.origin[0] + row as i64;
+        let chunk_row = storage_row.div_euclid(size) as i32;
+        let row_start = storage_row.rem_euclid(size) as usize * CHUNK_SIZE;
+        let first_col = self.origin[1];
+        let cols = self.dims.1;
+        let mut col = 0;
+        std::iter::from_fn(move || {
+            if col >= cols {
+                return None;
+            }
+            let storage_col = first_col + col as i64;
+            let local_col = storage_col.rem_euclid(size) as usize;
+            let len = (CHUNK_SIZE - local_col).min(cols - col);
+            col += len;
+            let key = chunk_key([chunk_row, storage_col.div_euclid(size) as i32]);
+            Some((key, row_start + local_col, len))
+        })
+    }
+
+    /// Cells of one grid row, one slice per chunk the row crosses
+    fn row_slices(&self, row: usize) -> impl Iterator<Item = &[T]> + '_ {
+        self.row_runs(row).map(move |(key, start, len)| {
+            self.chunks
+                .get(&key)
+                .and_then(|chunk| chunk.get(start..start + len))
+                .or_else(|| self.padding_row.get(..len))
+                .unwrap_or_default()
+        })
+    }
+
+    /// Grow the grid by the padding in `info` without moving any cells
+    pub const fn extend(&mut self, info: &ExtensionInfo) {
+        if !info.needs_extension {
+            return;
+        }
+        self.origin[0] -= info.pad_left as i64;
+        self.origin[1] -= info.pad_top as i64;
+        self.dims.0 += info.pad_left + info.pad_right;
+        self.dims.1 += info.pad_top + info.pad_bottom;
+    }
+
+    /// All cells with their grid indices in row-major order
+    pub fn indexed_iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        (0..self.dims.0).flat_map(move |row| {
+            (0..)
+                .zip(self.row_slices(row).flatten())
+                .map(move |(col, value)| ((row, col), value))
+        })
+    }
+
+    /// All cells in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        (0..self.dims.0).flat_map(move |row| self.row_slices(row).flatten())
+    }
+
+    /// Dense copy of the grid
+    pub fn to_array(&self) -> Array2<T> {
+        Array2::from_shape_vec(self.dims, self.iter().copied().collect())
+            .unwrap_or_else(|_shape| Array2::from_elem(self.dims, self.padding))
+    }
+}
@@ -164,6 +164,12 @@ impl Extendable for f64 {
     }
 }
 
+impl Extendable for i128 {
+    fn padding_value() -> Self {
+        0
+    }
+}
+
 impl Extendable for u32 {
     fn padding_value() -> Self {
         1
@@ -0,0 +1,75 @@
+//! Incrementally maintained index of open positions next to placed tiles
+//!
+//! Position selection only ever samples from the cells with the highest
+//! adjacency weights. Rather than scanning the whole grid each step, the
+//! index is refreshed around each placement or removal and queried in
+//! descending adjacency order.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Open positions with adjacency above the baseline, bucketed by adjacency weight
+///
+/// Positions are stored in world coordinates so grid extension does not
+/// invalidate them. Within a bucket positions are ordered row-major, which
+/// matches the tie order of a full grid scan.
+#[derive(Debug, Clone, Default)]
+pub struct FrontierIndex {
+    /// Positions grouped by adjacency weight
+    buckets: BTreeMap<u32, BTreeSet<[i32; 2]>>,
+    /// Current adjacency weight of each indexed position
+    weights: HashMap<[i32; 2], u32>,
+}
+
+impl FrontierIndex {
+    /// Create an empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the adjacency weight of a position, or remove it with `None`
+    pub fn update(&mut self, position: [i32; 2], adjacency: Option<u32>) {
+        let previous = match adjacency {
+            Some(weight) => self.weights.insert(position, weight),
+            None => self.weights.remove(&position),
+        };
+
+        if previous == adjacency {
+            return;
+        }
+
+        if let Some(old_weight) = previous
+            && let Some(bucket) = self.buckets.get_mut(&old_weight)
+        {
+            bucket.remove(&position);
+            if bucket.is_empty() {
+                self.buckets.remove(&old_weight);
+            }
+        }
+
+        if let Some(weight) = adjacency {
+            self.buckets.entry(weight).or_default().insert(position);
+        }
+    }
+
+    /// Adjacency weight of an indexed position
+    pub fn adjacency(&self, position: [i32; 2]) -> Option<u32> {
+        self.weights.get(&position).copied()
+    }
+
+    /// Number of indexed positions
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Whether no positions are indexed
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// Indexed positions from highest to lowest adjacency, row-major within ties
+    pub fn iter_descending(&self) -> impl Iterator<Item = ([i32; 2], u32)> + '_ {
+        self.buckets.iter().rev().flat_map(|(&weight, positions)| {
+            positions.iter().map(move |&position| (position, weight))
+        })
+    }
+}
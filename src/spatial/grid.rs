@@ -4,13 +4,28 @@
 //! probabilities, entropy, adjacency weights, and deadlock resolution counters.
 //! The grid automatically extends when tile placement exceeds current bounds.
 
-use ndarray::{Array2, Array3};
+use ndarray::Array3;
 use num_traits::{NumAssign, One};
+use std::collections::BTreeSet;
 
-use crate::spatial::extension::{
-    Extendable, calculate_extension, extend_array_2d, extend_array_3d,
+use crate::spatial::{
+    chunked::{CHUNK_AREA, CHUNK_SIZE, ChunkedGrid},
+    extension::{Extendable, calculate_extension, extend_array_3d},
+    frontier::FrontierIndex,
 };
 
+/// Fixed-point units per unit of natural logarithm in `log_influence`
+const LOG_INFLUENCE_SCALE: f64 = 4_503_599_627_370_496.0;
+
+/// Accumulated influence contributed by a zero factor
+///
+/// Larger in magnitude than any sum of finite factors, so a cell reads as zero
+/// probability while any zero factor applies to it
+const ZERO_INFLUENCE: i128 = -(1 << 80);
+
+/// Entropy of cells no placement has influenced
+const UNCOVERED_ENTROPY: f64 = 1.0;
+
 /// Axis-aligned bounding box for generation constraints
 #[derive(Debug, Clone)]
 pub struct BoundingBox {
@@ -32,28 +47,40 @@ impl BoundingBox {
 
 /// Grid state containing all wave function collapse data structures
 ///
-/// Maintains separate 2D arrays for different state aspects to improve
+/// Maintains separate 2D grids for different state aspects to improve
 /// cache locality and enable selective updates. The grid coordinates use
-/// an offset system to support negative indices during extension.
+/// an offset system to support negative indices during extension. Grids are
+/// stored as sparse chunks, so extension is O(1) and untouched regions cost
+/// no memory.
 #[derive(Debug, Clone)]
 pub struct GridState {
     /// Probability values for each tile type (indexed by `tile_type`, `row`, `col`)
-    pub tile_probabilities: Vec<Array2<f64>>,
+    pub tile_probabilities: Vec<ChunkedGrid<f64>>,
+
+    /// Accumulated influence on each tile type as fixed-point natural logarithms
+    ///
+    /// Probabilities are exponentials of these sums. Placing a tile adds its
+    /// influence and removing it subtracts the same integers, so an undo
+    /// restores every cell exactly regardless of the order of removals.
+    pub log_influence: Vec<ChunkedGrid<i128>>,
+
+    /// Number of placed tiles whose influence region covers each cell
+    pub influence_coverage: ChunkedGrid<u32>,
 
     /// Shannon entropy calculated from tile probabilities
-    pub entropy: Array2<f64>,
+    pub entropy: ChunkedGrid<f64>,
 
     /// Count of locked adjacent positions (used for propagation ordering)
-    pub adjacency_weights: Array2<u32>,
+    pub adjacency_weights: ChunkedGrid<u32>,
 
     /// Locked positions with tile references (0 = unlocked, 1+ = tile index)
-    pub locked_tiles: Array2<u32>,
+    pub locked_tiles: ChunkedGrid<u32>,
 
     /// Combined score for tile selection prioritization
-    pub feasibility: Array2<f64>,
+    pub feasibility: ChunkedGrid<f64>,
 
     /// Deadlock recovery counter per position
-    pub removal_count: Array2<u8>,
+    pub removal_count: ChunkedGrid<u8>,
 
     /// Number of unique tile types
     pub unique_cell_count: usize,
@@ -63,6 +90,77 @@ pub struct GridState {
 
     /// Optional generation bounds in world coordinates
     pub generation_bounds: Option<BoundingBox>,
+
+    /// World coordinates whose contradiction status may have changed
+    ///
+    /// Kept in world coordinates so grid extension does not invalidate it;
+    /// ordered row-major so checks visit cells in grid order
+    pub dirty_cells: BTreeSet<[i32; 2]>,
+
+    /// Open positions adjacent to placed tiles, ordered for position selection
+    pub frontier: FrontierIndex,
+
+    /// Number of placed tiles inside the generation bounds
+    ///
+    /// Counts every placed tile when there are no bounds
+    pub placed_in_bounds: usize,
+
+    /// Chunks whose cells have been evicted and currently read as padding
+    ///
+    /// Storage is anchored to world coordinates, so chunk `[r, c]` covers world
+    /// rows `r * CHUNK_SIZE..(r + 1) * CHUNK_SIZE` and likewise for columns
+    pub evicted_chunks: BTreeSet<[i32; 2]>,
+}
+
+/// Cells of one world chunk taken out of a grid state
+///
+/// Layers that were never written in the chunk are `None`
+#[derive(Debug, Clone)]
+pub struct GridChunk {
+    /// Chunk coordinate (world position divided by `CHUNK_SIZE`)
+    pub chunk: [i32; 2],
+    /// Probability cells for each tile type
+    pub tile_probabilities: Vec<Option<Box<[f64]>>>,
+    /// Accumulated log influence cells for each tile type
+    pub log_influence: Vec<Option<Box<[i128]>>>,
+    /// Influence coverage cells
+    pub influence_coverage: Option<Box<[u32]>>,
+    /// Entropy cells
+    pub entropy: Option<Box<[f64]>>,
+    /// Adjacency weight cells
+    pub adjacency_weights: Option<Box<[u32]>>,
+    /// Locked tile cells
+    pub locked_tiles: Option<Box<[u32]>>,
+    /// Feasibility cells
+    pub feasibility: Option<Box<[f64]>>,
+    /// Deadlock removal counter cells
+    pub removal_count: Option<Box<[u8]>>,
+}
+
+impl GridChunk {
+    /// World position of the first cell in the chunk
+    pub const fn world_min(&self) -> [i32; 2] {
+        chunk_world_min(self.chunk)
+    }
+
+    /// World positions and locked values of every placed tile in the chunk
+    pub fn placed_tiles(&self) -> impl Iterator<Item = ([i32; 2], u32)> + '_ {
+        let min = self.world_min();
+        self.locked_tiles
+            .iter()
+            .flat_map(|cells| cells.iter().enumerate())
+            .filter(|&(_, &locked)| locked > 1)
+            .map(move |(local, &locked)| {
+                let row = min[0] + (local / CHUNK_SIZE) as i32;
+                let col = min[1] + (local % CHUNK_SIZE) as i32;
+                ([row, col], locked)
+            })
+    }
+}
+
+/// World position of the first cell of a chunk
+pub const fn chunk_world_min(chunk: [i32; 2]) -> [i32; 2] {
+    [chunk[0] * CHUNK_SIZE as i32, chunk[1] * CHUNK_SIZE as i32]
 }
 
 impl GridState {
@@ -74,18 +172,23 @@ impl GridState {
         let dimensions = (rows, cols);
 
         let mut tile_probabilities = Vec::with_capacity(unique_cell_count);
+        let mut log_influence = Vec::with_capacity(unique_cell_count);
         for _ in 0..unique_cell_count {
-            tile_probabilities.push(Array2::ones((rows, cols)));
+            tile_probabilities.push(ChunkedGrid::new(rows, cols, 1.0, f64::padding_value()));
+            log_influence.push(ChunkedGrid::new(rows, cols, 0, i128::padding_value()));
         }
 
-        let entropy = Array2::ones((rows, cols));
-        let adjacency_weights = Array2::ones((rows, cols));
-        let locked_tiles = Array2::ones((rows, cols));
-        let feasibility = Array2::ones((rows, cols));
-        let removal_count = Array2::zeros((rows, cols));
+        let influence_coverage = ChunkedGrid::new(rows, cols, 0, 0);
+        let entropy = ChunkedGrid::new(rows, cols, UNCOVERED_ENTROPY, UNCOVERED_ENTROPY);
+        let adjacency_weights = ChunkedGrid::new(rows, cols, 1, u32::padding_value());
+        let locked_tiles = ChunkedGrid::new(rows, cols, 1, u32::padding_value());
+        let feasibility = ChunkedGrid::new(rows, cols, 1.0, f64::padding_value());
+        let removal_count = ChunkedGrid::new(rows, cols, 0, u8::padding_value());
 
         Self {
             tile_probabilities,
+            log_influence,
+            influence_coverage,
             entropy,
             adjacency_weights,
             locked_tiles,
@@ -94,9 +197,354 @@ impl GridState {
             unique_cell_count,
             dimensions,
             generation_bounds: None,
+            dirty_cells: BTreeSet::new(),
+            frontier: FrontierIndex::new(),
+            placed_in_bounds: 0,
+            evicted_chunks: BTreeSet::new(),
         }
     }
 
+    /// Whether a world position lies inside the generation bounds, if any
+    pub fn in_generation_bounds(&self, position: [i32; 2]) -> bool {
+        self.generation_bounds
+            .as_ref()
+            .is_none_or(|bounds| bounds.contains(position))
+    }
+
+    /// Replace the generation bounds, recounting placed tiles and rebuilding the frontier
+    ///
+    /// Scans the new bounds, or the whole grid when unbounded
+    pub fn set_generation_bounds(&mut self, bounds: Option<BoundingBox>, system_offset: [i32; 2]) {
+        self.generation_bounds = bounds;
+        self.frontier = FrontierIndex::new();
+        self.placed_in_bounds = 0;
+
+        let grid_min = [-system_offset[0], -system_offset[1]];
+        let grid_max = [
+            grid_min[0] + self.rows() as i32 - 1,
+            grid_min[1] + self.cols() as i32 - 1,
+        ];
+        let (min, max) = self
+            .generation_bounds
+            .as_ref()
+            .map_or((grid_min, grid_max), |b| {
+                (
+                    [b.min[0].max(grid_min[0]), b.min[1].max(grid_min[1])],
+                    [b.max[0].min(grid_max[0]), b.max[1].min(grid_max[1])],
+                )
+            });
+
+        for row in min[0]..=max[0] {
+            for col in min[1]..=max[1] {
+                let index = [
+                    (row + system_offset[0]) as usize,
+                    (col + system_offset[1]) as usize,
+                ];
+                if self
+                    .locked_tiles
+                    .get(index)
+                    .is_some_and(|&locked| locked > 1)
+                {
+                    self.placed_in_bounds += 1;
+                }
+                self.refresh_frontier_cell([row, col], system_offset);
+            }
+        }
+    }
+
+    /// Take every layer of a world chunk out of the grid
+    ///
+    /// The chunk's cells then read as padding until it is restored, and
+    /// leave the frontier, the dirty set and the placed count
+    pub fn evict_chunk(&mut self, chunk: [i32; 2], system_offset: [i32; 2]) -> GridChunk {
+        let mut grid_chunk = GridChunk {
+            chunk,
+            tile_probabilities: Vec::new(),
+            log_influence: Vec::new(),
+            influence_coverage: None,
+            entropy: None,
+            adjacency_weights: None,
+            locked_tiles: self.locked_tiles.take_chunk(chunk),
+            feasibility: None,
+            removal_count: None,
+        };
+
+        let placed_in_bounds = grid_chunk
+            .placed_tiles()
+            .filter(|&(position, _)| self.in_generation_bounds(position))
+            .count();
+        self.placed_in_bounds = self.placed_in_bounds.saturating_sub(placed_in_bounds);
+
+        grid_chunk.tile_probabilities = self
+            .tile_probabilities
+            .iter_mut()
+            .map(|probabilities| probabilities.take_chunk(chunk))
+            .collect();
+        grid_chunk.log_influence = self
+            .log_influence
+            .iter_mut()
+            .map(|influence| influence.take_chunk(chunk))
+            .collect();
+        grid_chunk.influence_coverage = self.influence_coverage.take_chunk(chunk);
+        grid_chunk.entropy = self.entropy.take_chunk(chunk);
+        grid_chunk.adjacency_weights = self.adjacency_weights.take_chunk(chunk);
+        grid_chunk.feasibility = self.feasibility.take_chunk(chunk);
+        grid_chunk.removal_count = self.removal_count.take_chunk(chunk);
+
+        let min = chunk_world_min(chunk);
+        let size = CHUNK_SIZE as i32;
+        self.dirty_cells.retain(|&[row, col]| {
+            row < min[0] || row >= min[0] + size || col < min[1] || col >= min[1] + size
+        });
+        self.refresh_frontier_rect(min, [min[0] + size - 1, min[1] + size - 1], system_offset);
+        self.evicted_chunks.insert(chunk);
+
+        grid_chunk
+    }
+
+    /// Put an evicted chunk back into the grid
+    ///
+    /// The grid must already cover the chunk. Returns `false` without changing
+    /// anything if the chunk has the wrong number of layers or cells.
+    pub fn restore_chunk(&mut self, grid_chunk: GridChunk, system_offset: [i32; 2]) -> bool {
+        let valid = grid_chunk.tile_probabilities.len() == self.tile_probabilities.len()
+            && grid_chunk
+                .tile_probabilities
+                .iter()
+                .all(|cells| is_whole_chunk(cells.as_deref()))
+            && grid_chunk.log_influence.len() == self.log_influence.len()
+            && grid_chunk
+                .log_influence
+                .iter()
+                .all(|cells| is_whole_chunk(cells.as_deref()))
+            && is_whole_chunk(grid_chunk.influence_coverage.as_deref())
+            && is_whole_chunk(grid_chunk.entropy.as_deref())
+            && is_whole_chunk(grid_chunk.adjacency_weights.as_deref())
+            && is_whole_chunk(grid_chunk.locked_tiles.as_deref())
+            && is_whole_chunk(grid_chunk.feasibility.as_deref())
+            && is_whole_chunk(grid_chunk.removal_count.as_deref());
+        if !valid {
+            return false;
+        }
+
+        let chunk = grid_chunk.chunk;
+        let placed_in_bounds = grid_chunk
+            .placed_tiles()
+            .filter(|&(position, _)| self.in_generation_bounds(position))
+            .count();
+        self.placed_in_bounds += placed_in_bounds;
+
+        let GridChunk {
+            tile_probabilities,
+            log_influence,
+            influence_coverage,
+            entropy,
+            adjacency_weights,
+            locked_tiles,
+            feasibility,
+            removal_count,
+            ..
+        } = grid_chunk;
+
+        for (layer, cells) in self.tile_probabilities.iter_mut().zip(tile_probabilities) {
+            restore_layer(layer, chunk, cells);
+        }
+        for (layer, cells) in self.log_influence.iter_mut().zip(log_influence) {
+            restore_layer(layer, chunk, cells);
+        }
+        restore_layer(&mut self.influence_coverage, chunk, influence_coverage);
+        restore_layer(&mut self.entropy, chunk, entropy);
+        restore_layer(&mut self.adjacency_weights, chunk, adjacency_weights);
+        restore_layer(&mut self.locked_tiles, chunk, locked_tiles);
+        restore_layer(&mut self.feasibility, chunk, feasibility);
+        restore_layer(&mut self.removal_count, chunk, removal_count);
+
+        let min = chunk_world_min(chunk);
+        let max = [
+            min[0] + CHUNK_SIZE as i32 - 1,
+            min[1] + CHUNK_SIZE as i32 - 1,
+        ];
+        self.refresh_frontier_rect(min, max, system_offset);
+        for row in min[0]..=max[0] {
+            for col in min[1]..=max[1] {
+                self.dirty_cells.insert([row, col]);
+            }
+        }
+        self.evicted_chunks.remove(&chunk);
+
+        true
+    }
+
+    /// Apply one placement's influence to a cell
+    ///
+    /// `factors` multiplies the probability of each tile type in turn; the
+    /// cell's probabilities and entropy are then refreshed from the sums
+    pub fn apply_influence(&mut self, index: [usize; 2], factors: impl IntoIterator<Item = f64>) {
+        for (tile_type, factor) in factors.into_iter().enumerate() {
+            self.shift_influence(tile_type, index, log_factor(factor));
+        }
+        if let Some(coverage) = self.influence_coverage.get_mut(index) {
+            *coverage += 1;
+        }
+        self.refresh_entropy(index);
+    }
+
+    /// Remove one placement's influence from a cell
+    ///
+    /// Exactly reverses `apply_influence` with the same factors, leaving the
+    /// cell as if the placement never happened
+    pub fn remove_influence(&mut self, index: [usize; 2], factors: impl IntoIterator<Item = f64>) {
+        for (tile_type, factor) in factors.into_iter().enumerate() {
+            self.shift_influence(tile_type, index, -log_factor(factor));
+        }
+        if let Some(coverage) = self.influence_coverage.get_mut(index) {
+            *coverage = coverage.saturating_sub(1);
+        }
+        self.refresh_entropy(index);
+    }
+
+    /// Add to a tile type's accumulated influence and refresh its probability
+    fn shift_influence(&mut self, tile_type: usize, index: [usize; 2], delta: i128) {
+        let Some(sum) = self
+            .log_influence
+            .get_mut(tile_type)
+            .and_then(|influence| influence.get_mut(index))
+        else {
+            return;
+        };
+        *sum += delta;
+        let probability = influence_probability(*sum);
+
+        if let Some(prob) = self
+            .tile_probabilities
+            .get_mut(tile_type)
+            .and_then(|probs| probs.get_mut(index))
+        {
+            *prob = probability;
+        }
+    }
+
+    /// Recalculate a cell's entropy from its tile probabilities
+    ///
+    /// Cells no placement covers keep the initial entropy
+    fn refresh_entropy(&mut self, index: [usize; 2]) {
+        let covered = self
+            .influence_coverage
+            .get(index)
+            .is_some_and(|&coverage| coverage > 0);
+        let entropy = if covered {
+            self.probability_entropy(index)
+        } else {
+            UNCOVERED_ENTROPY
+        };
+
+        if let Some(entropy_val) = self.entropy.get_mut(index) {
+            *entropy_val = entropy;
+        }
+    }
+
+    /// Entropy of a cell's tile probabilities
+    fn probability_entropy(&self, index: [usize; 2]) -> f64 {
+        let probability = |color: usize| {
+            self.tile_probabilities
+                .get(color)
+                .and_then(|probs| probs.get(index))
+                .copied()
+                .unwrap_or(0.0)
+        };
+
+        let sum: f64 = (0..self.unique_cell_count).map(probability).sum();
+
+        // Mean normalization prevents numerical instability in entropy calculation
+        let mean_prob = sum / self.unique_cell_count as f64;
+        if mean_prob <= 0.0 {
+            return 0.0;
+        }
+
+        let mut entropy_sum = 0.0;
+        for color in 0..self.unique_cell_count {
+            let p = probability(color) / mean_prob;
+            if p > 0.0 {
+                entropy_sum += p * p.ln();
+            }
+        }
+        entropy_sum
+    }
+
+    /// Shannon entropy of a cell's normalized tile probabilities, in nats
+    ///
+    /// Returns `None` when no tile has any probability at the cell
+    pub fn shannon_entropy(&self, index: [usize; 2]) -> Option<f64> {
+        let probabilities: Vec<f64> = self
+            .tile_probabilities
+            .iter()
+            .map(|probs| probs.get(index).copied().unwrap_or(0.0))
+            .collect();
+        let total: f64 = probabilities.iter().sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        Some(
+            probabilities
+                .into_iter()
+                .map(|probability| probability / total)
+                .filter(|&p| p > 0.0)
+                .map(|p| -p * p.ln())
+                .sum(),
+        )
+    }
+
+    /// Mark every cell within `radius` of a world position for rechecking
+    pub fn mark_dirty(&mut self, center: [i32; 2], radius: i32) {
+        for row in center[0] - radius..=center[0] + radius {
+            for col in center[1] - radius..=center[1] + radius {
+                self.dirty_cells.insert([row, col]);
+            }
+        }
+    }
+
+    /// Re-read adjacency and lock state into the frontier index around a world position
+    ///
+    /// Positions join the index while they are unplaced, inside the generation
+    /// bounds and adjacent to a placed tile, and leave it otherwise
+    pub fn refresh_frontier(&mut self, center: [i32; 2], radius: i32, system_offset: [i32; 2]) {
+        self.refresh_frontier_rect(
+            [center[0] - radius, center[1] - radius],
+            [center[0] + radius, center[1] + radius],
+            system_offset,
+        );
+    }
+
+    /// Refresh the frontier index over an inclusive world rectangle
+    fn refresh_frontier_rect(&mut self, min: [i32; 2], max: [i32; 2], system_offset: [i32; 2]) {
+        for row in min[0]..=max[0] {
+            for col in min[1]..=max[1] {
+                self.refresh_frontier_cell([row, col], system_offset);
+            }
+        }
+    }
+
+    /// Refresh the frontier index entry of a single world position
+    fn refresh_frontier_cell(&mut self, position: [i32; 2], system_offset: [i32; 2]) {
+        let index = [
+            position[0] + system_offset[0],
+            position[1] + system_offset[1],
+        ];
+
+        let adjacency = (index[0] >= 0 && index[1] >= 0 && self.in_generation_bounds(position))
+            .then(|| [index[0] as usize, index[1] as usize])
+            .filter(|&index| {
+                self.locked_tiles
+                    .get(index)
+                    .is_some_and(|&locked| locked <= 1)
+            })
+            .and_then(|index| self.adjacency_weights.get(index).copied())
+            .filter(|&adjacency| adjacency > 1);
+
+        self.frontier.update(position, adjacency);
+    }
+
     /// Get the number of rows in the grid
     pub const fn rows(&self) -> usize {
         self.dimensions.0
@@ -110,8 +558,8 @@ impl GridState {
     /// Extend the grid if needed to accommodate a position plus radius
     ///
     /// Returns the new offset and whether extension occurred. Extension preserves
-    /// all existing data while adding padding with appropriate default values.
-    /// The offset is adjusted to maintain consistent coordinate mapping.
+    /// all existing data while adding padding with appropriate default values,
+    /// without copying any cells. The offset is adjusted to maintain consistent coordinate mapping.
     pub fn extend_if_needed(
         &mut self,
         offset: [i32; 2],
@@ -130,23 +578,19 @@ impl GridState {
             return (offset, false);
         }
 
-        // Probability matrices use 1.0 padding (maximum uncertainty)
+        // Chunked storage only moves its bounds; new cells read as padding
         for prob_matrix in &mut self.tile_probabilities {
-            *prob_matrix = extend_array_2d(prob_matrix, &extension_info, f64::padding_value());
+            prob_matrix.extend(&extension_info);
         }
-
-        self.entropy = extend_array_2d(&self.entropy, &extension_info, f64::padding_value());
-        self.adjacency_weights = extend_array_2d(
-            &self.adjacency_weights,
-            &extension_info,
-            u32::padding_value(),
-        );
-        self.locked_tiles =
-            extend_array_2d(&self.locked_tiles, &extension_info, u32::padding_value());
-        self.feasibility =
-            extend_array_2d(&self.feasibility, &extension_info, f64::padding_value());
-        self.removal_count =
-            extend_array_2d(&self.removal_count, &extension_info, u8::padding_value());
+        for influence in &mut self.log_influence {
+            influence.extend(&extension_info);
+        }
+        self.influence_coverage.extend(&extension_info);
+        self.entropy.extend(&extension_info);
+        self.adjacency_weights.extend(&extension_info);
+        self.locked_tiles.extend(&extension_info);
+        self.feasibility.extend(&extension_info);
+        self.removal_count.extend(&extension_info);
 
         let new_height = self.rows() + extension_info.pad_left + extension_info.pad_right;
         let new_width = self.cols() + extension_info.pad_top + extension_info.pad_bottom;
@@ -224,6 +668,43 @@ impl GridState {
     }
 }
 
+/// Fixed-point natural logarithm of an influence factor
+///
+/// Zero, negative and NaN factors map to `ZERO_INFLUENCE`
+fn log_factor(factor: f64) -> i128 {
+    if factor > 0.0 {
+        (factor.min(f64::MAX).ln() * LOG_INFLUENCE_SCALE).round() as i128
+    } else {
+        ZERO_INFLUENCE
+    }
+}
+
+/// Probability for an accumulated log influence
+fn influence_probability(sum: i128) -> f64 {
+    if sum <= ZERO_INFLUENCE / 2 {
+        0.0
+    } else {
+        (sum as f64 / LOG_INFLUENCE_SCALE).exp()
+    }
+}
+
+/// Whether an evicted layer is absent or holds exactly one chunk of cells
+fn is_whole_chunk<T>(cells: Option<&[T]>) -> bool {
+    cells.is_none_or(|cells| cells.len() == CHUNK_AREA)
+}
+
+/// Store an evicted layer chunk, leaving the layer untouched if it was never written
+fn restore_layer<T: Copy + PartialEq>(
+    layer: &mut ChunkedGrid<T>,
+    chunk: [i32; 2],
+    cells: Option<Box<[T]>>,
+) {
+    layer.take_chunk(chunk);
+    if let Some(cells) = cells {
+        layer.insert_chunk(chunk, cells);
+    }
+}
+
 /// Get region spans for a given position and radius
 ///
 /// Converts world coordinates to grid indices and returns ranges for
@@ -5,8 +5,12 @@
 //! - Grid state management
 //! - Tile data structures and extraction
 
+/// Sparse chunked grid storage
+pub mod chunked;
 /// Grid extension utilities
 pub mod extension;
+/// Incremental index of open positions next to placed tiles
+pub mod frontier;
 /// Grid state management and manipulation functions
 pub mod grid;
 /// Tile extraction and pattern matching utilities
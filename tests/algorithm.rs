@@ -40,7 +40,7 @@ fn test_bitset_empty_intersection() {
     let intersection = set1.intersection(&set2);
     assert!(intersection.is_empty());
     assert_eq!(intersection.count(), 0);
-    assert_eq!(intersection.to_vec(), vec![]);
+    assert_eq!(intersection.to_vec(), Vec::<usize>::new());
 }
 
 // Tests cache returns consistent results and tracks hit/miss statistics
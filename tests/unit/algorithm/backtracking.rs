@@ -0,0 +1,300 @@
+//! Tests for the decision stack and backtracking contradiction resolution
+
+#[cfg(test)]
+mod tests {
+    use greedytile::algorithm::backtracking::{
+        Backtrack, DEFAULT_BACKTRACK_BUDGET, DeadlockStrategy, DecisionStack,
+    };
+    use greedytile::algorithm::executor::{AlgorithmConfig, GreedyStochastic, RandomSelector};
+    use greedytile::algorithm::positioning::{PositionContext, PositionSelector};
+    use greedytile::analysis::patterns::ImageProcessor;
+    use greedytile::io::configuration::MAX_SELECTION_CONTRADICTIONS;
+    use greedytile::io::error::AlgorithmError;
+    use greedytile::io::events::{EventLog, GenerationEvent};
+
+    // Always selects the same grid index
+    #[derive(Debug)]
+    struct PinnedSelector([usize; 2]);
+
+    impl PositionSelector for PinnedSelector {
+        fn select(
+            &mut self,
+            _context: &PositionContext<'_>,
+            _random: &mut RandomSelector,
+        ) -> Option<[usize; 2]> {
+            Some(self.0)
+        }
+    }
+
+    fn bounded_executor(seed: u64, size: usize, strategy: DeadlockStrategy) -> GreedyStochastic {
+        let config = AlgorithmConfig {
+            bounds: Some((size, size)),
+            deadlock_strategy: strategy,
+            ..AlgorithmConfig::default()
+        };
+        let processor = ImageProcessor::from_png_file("data/a.png").unwrap();
+        GreedyStochastic::from_image_processor(processor, config, seed).unwrap()
+    }
+
+    fn locked_values(executor: &GreedyStochastic) -> Vec<u32> {
+        let grid = &executor.grid_state;
+        (0..grid.rows())
+            .flat_map(|row| (0..grid.cols()).map(move |col| [row, col]))
+            .map(|index| *grid.locked_tiles.get(index).unwrap())
+            .collect()
+    }
+
+    fn backtracks(executor: &GreedyStochastic) -> usize {
+        executor
+            .decision_stack
+            .as_ref()
+            .map_or(0, DecisionStack::backtracks)
+    }
+
+    // Tests a conflict unwinds to the most recent decision with a placement
+    // near it, undoing every later placement and banning that choice
+    // Verified by unwinding to the top decision regardless of distance
+    #[test]
+    fn test_backtrack_targets_decision_affecting_conflict() {
+        let mut stack = DecisionStack::new(10);
+        stack.record_placement([0, 0]);
+        stack.push_decision([0, 5], 2);
+        stack.record_placement([0, 5]);
+        stack.record_placement([0, 6]);
+        stack.push_decision([10, 10], 3);
+        stack.record_placement([10, 10]);
+
+        let backtrack = stack.backtrack([0, 8]).unwrap();
+
+        assert_eq!(
+            backtrack,
+            Backtrack {
+                undone: vec![[10, 10], [0, 6], [0, 5]],
+                banned: ([0, 5], 2),
+            }
+        );
+        assert_eq!(stack.depth(), 0);
+        assert!(stack.is_banned([0, 5], 2));
+        assert!(!stack.is_banned([0, 5], 3));
+        assert_eq!(stack.backtracks(), 1);
+    }
+
+    // Tests a ban only lasts while the decisions made before it stand
+    // Verified by keeping every ban at the root
+    #[test]
+    fn test_bans_are_dropped_with_their_parent_decision() {
+        let mut stack = DecisionStack::new(10);
+        stack.push_decision([0, 0], 2);
+        stack.record_placement([0, 0]);
+        stack.push_decision([0, 1], 3);
+        stack.record_placement([0, 1]);
+
+        assert_eq!(stack.backtrack([0, 1]).unwrap().banned, ([0, 1], 3));
+        assert!(stack.is_banned([0, 1], 3));
+        assert_eq!(stack.depth(), 1);
+
+        assert_eq!(stack.backtrack([0, 0]).unwrap().banned, ([0, 0], 2));
+        assert!(!stack.is_banned([0, 1], 3));
+        assert!(stack.is_banned([0, 0], 2));
+    }
+
+    // Tests conflicts away from every decision and conflicts past the budget
+    // leave the stack unchanged
+    // Verified by ignoring the budget
+    #[test]
+    fn test_backtrack_respects_budget_and_distance() {
+        let mut stack = DecisionStack::new(1);
+        stack.push_decision([0, 0], 2);
+        stack.record_placement([0, 0]);
+        stack.push_decision([0, 1], 2);
+        stack.record_placement([0, 1]);
+
+        assert!(stack.backtrack([20, 20]).is_none());
+        assert_eq!(stack.depth(), 2);
+
+        assert!(stack.backtrack([0, 1]).is_some());
+        assert!(stack.is_exhausted());
+        assert!(stack.backtrack([0, 0]).is_none());
+        assert_eq!(stack.depth(), 1);
+    }
+
+    // Tests forgetting a removed region keeps the decisions elsewhere open
+    // with their trail and bans, while closing decisions whose tile was
+    // removed and lifting bans near the region
+    // Verified by leaving trail indices unchanged when placements are forgotten
+    #[test]
+    fn test_forget_keeps_decisions_outside_region() {
+        let mut stack = DecisionStack::new(10);
+        stack.push_decision([0, 0], 2);
+        stack.record_placement([0, 0]);
+        stack.record_placement([0, 1]);
+        stack.push_decision([20, 20], 3);
+        stack.record_placement([20, 20]);
+        stack.push_decision([20, 21], 2);
+        stack.record_placement([20, 21]);
+        stack.push_decision([0, 3], 4);
+        stack.record_placement([0, 3]);
+        assert_eq!(stack.backtrack([0, 3]).unwrap().banned, ([0, 3], 4));
+        assert_eq!(stack.backtrack([20, 21]).unwrap().banned, ([20, 21], 2));
+
+        stack.forget(&[[0, 1]]);
+        assert_eq!(stack.depth(), 2);
+        assert!(stack.is_banned([20, 21], 2));
+        assert!(!stack.is_banned([0, 3], 4));
+
+        let backtrack = stack.backtrack([20, 20]).unwrap();
+        assert_eq!(backtrack.undone, vec![[20, 20]]);
+        assert!(stack.is_banned([20, 20], 3));
+
+        stack.forget(&[[0, 0]]);
+        assert_eq!(stack.depth(), 0);
+        assert!(!stack.is_banned([20, 20], 3));
+        assert_eq!(stack.backtracks(), 3);
+    }
+
+    // Tests a contradiction that neither backtracking nor removal can clear
+    // is logged as a fallback and fails selection at the retry cap instead of
+    // retrying forever
+    // Verified by retrying selection without a limit
+    #[test]
+    fn test_unresolvable_selection_fails_after_fallback() {
+        let mut executor = bounded_executor(1, 12, DeadlockStrategy::Backtrack(0));
+        executor.run_iteration().unwrap();
+        executor.deadlock_policy.max_radius = 0;
+        executor.events = Some(EventLog::new());
+
+        let offset = executor.system_offset;
+        let grid_index = |world: [i32; 2]| {
+            [
+                (world[0] + offset[0]) as usize,
+                (world[1] + offset[1]) as usize,
+            ]
+        };
+        let unknown_tile = executor.step_data.unique_cell_count as u32 + 2;
+        *executor
+            .grid_state
+            .locked_tiles
+            .get_mut(grid_index([3, 3]))
+            .unwrap() = unknown_tile;
+        executor.position_selector = Box::new(PinnedSelector(grid_index([3, 4])));
+
+        let mut result = Ok(true);
+        for _ in 0..100 {
+            result = executor.run_iteration();
+            if result.is_err() {
+                break;
+            }
+        }
+
+        assert!(matches!(
+            result,
+            Err(AlgorithmError::SelectionExhausted {
+                contradictions: MAX_SELECTION_CONTRADICTIONS,
+                position: [3, 4],
+                ..
+            })
+        ));
+        let events = executor.events.as_ref().unwrap().events();
+        let fallbacks = events
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    GenerationEvent::Fallback {
+                        position: [3, 4],
+                        backtracks: 0,
+                        exhausted: true,
+                        ..
+                    }
+                )
+            })
+            .count();
+        assert_eq!(fallbacks, MAX_SELECTION_CONTRADICTIONS);
+    }
+
+    // Tests strategies parse from and display as the command line forms
+    // Verified by dropping the budget from the display string
+    #[test]
+    fn test_strategy_round_trip() {
+        assert_eq!(
+            "removal".parse::<DeadlockStrategy>().unwrap(),
+            DeadlockStrategy::Removal
+        );
+        assert_eq!(
+            "Backtrack".parse::<DeadlockStrategy>().unwrap(),
+            DeadlockStrategy::Backtrack(DEFAULT_BACKTRACK_BUDGET)
+        );
+        for strategy in [DeadlockStrategy::Removal, DeadlockStrategy::Backtrack(25)] {
+            assert_eq!(
+                strategy.to_string().parse::<DeadlockStrategy>().unwrap(),
+                strategy
+            );
+        }
+        assert!("backtrack:many".parse::<DeadlockStrategy>().is_err());
+        assert!(DeadlockStrategy::Removal.decision_stack().is_none());
+    }
+
+    // Tests a backtracking fill completes with every window from the source
+    // and a tally matching the placed tiles, and that an iteration which
+    // backtracks removes at least the tile it replaces
+    // Verified by leaving undone tiles locked
+    #[test]
+    fn test_backtracking_fill_stays_consistent() {
+        let mut executor = bounded_executor(1, 12, DeadlockStrategy::Backtrack(5000));
+        let mut complete = false;
+        for _ in 0..20_000 {
+            let before = (backtracks(&executor), executor.grid_state.placed_in_bounds);
+            if !executor.run_iteration().unwrap() {
+                complete = true;
+                break;
+            }
+            if backtracks(&executor) > before.0 {
+                assert!(executor.grid_state.placed_in_bounds <= before.1);
+            }
+        }
+
+        assert!(complete);
+        assert!(backtracks(&executor) > 0);
+
+        let mut placed = vec![0; executor.selection_tally.len()];
+        for locked in locked_values(&executor) {
+            if let Some(count) = placed.get_mut((locked as usize).wrapping_sub(2)) {
+                *count += 1;
+            }
+        }
+        assert_eq!(placed, executor.selection_tally);
+
+        let grid = &executor.grid_state;
+        for row in 0..grid.rows() - 2 {
+            for col in 0..grid.cols() - 2 {
+                let mut window = [[0usize; 3]; 3];
+                let mut complete_window = true;
+                for (di, window_row) in window.iter_mut().enumerate() {
+                    for (dj, cell) in window_row.iter_mut().enumerate() {
+                        let locked = *grid.locked_tiles.get([row + di, col + dj]).unwrap();
+                        complete_window &= locked > 1;
+                        *cell = locked as usize - 1;
+                    }
+                }
+                if complete_window {
+                    assert!(executor.step_data.source_tiles.contains(&window));
+                }
+            }
+        }
+    }
+
+    // Tests a spent budget leaves generation identical to removal
+    // Verified by backtracking without checking the budget
+    #[test]
+    fn test_zero_budget_matches_removal() {
+        let mut removal = bounded_executor(1, 12, DeadlockStrategy::Removal);
+        let mut backtracking = bounded_executor(1, 12, DeadlockStrategy::Backtrack(0));
+        for _ in 0..400 {
+            removal.run_iteration().unwrap();
+            backtracking.run_iteration().unwrap();
+        }
+
+        assert_eq!(locked_values(&removal), locked_values(&backtracking));
+        assert_eq!(backtracking.decision_stack.unwrap().backtracks(), 0);
+    }
+}
@@ -0,0 +1,180 @@
+//! Tests for stopping rules when running until the bounds are filled
+
+#[cfg(test)]
+mod tests {
+    use greedytile::algorithm::backtracking::DeadlockStrategy;
+    use greedytile::algorithm::completion::{RunLimits, StallDetector, run};
+    use greedytile::algorithm::executor::{AlgorithmConfig, GreedyStochastic};
+    use greedytile::analysis::patterns::ImageProcessor;
+    use greedytile::io::configuration::{
+        DEFAULT_STALL_DEADLOCKS, UNTIL_COMPLETE_ITERATIONS_PER_CELL,
+    };
+    use greedytile::io::error::AlgorithmError;
+    use greedytile::io::prefill::PrefillData;
+    use greedytile::spatial::grid::BoundingBox;
+    use std::collections::{HashMap, VecDeque};
+
+    const SIZE: usize = 12;
+
+    fn bounded_executor(seed: u64) -> GreedyStochastic {
+        bounded_executor_with(seed, DeadlockStrategy::default())
+    }
+
+    fn bounded_executor_with(seed: u64, deadlock_strategy: DeadlockStrategy) -> GreedyStochastic {
+        let config = AlgorithmConfig {
+            bounds: Some((SIZE, SIZE)),
+            deadlock_strategy,
+            ..AlgorithmConfig::default()
+        };
+        let processor = ImageProcessor::from_png_file("data/a.png").unwrap();
+        GreedyStochastic::from_image_processor(processor, config, seed).unwrap()
+    }
+
+    // Tests a stall needs the full window of deadlocks since the last new
+    // high in placed tiles
+    // Verified by keeping the deadlock count from the first observation
+    #[test]
+    fn test_stall_detector_resets_on_progress() {
+        let mut detector = StallDetector::new(3);
+        assert!(!detector.observe(10, 0));
+        assert!(!detector.observe(8, 2));
+        assert!(!detector.observe(12, 2));
+        assert!(!detector.observe(12, 4));
+        assert!(detector.observe(11, 5));
+        assert!(!detector.observe(13, 6));
+    }
+
+    // Tests the limits for fixed and until-complete runs
+    // Verified by leaving stall detection off until complete
+    #[test]
+    fn test_limits() {
+        let fixed = RunLimits::iterations(50);
+        assert_eq!(fixed.cap_for_cells(Some(SIZE * SIZE)), 50);
+        assert!(fixed.stall_deadlocks.is_none());
+        assert!(!fixed.require_complete);
+
+        let complete = RunLimits::until_complete();
+        assert_eq!(
+            complete.cap_for_cells(Some(SIZE * SIZE)),
+            SIZE * SIZE * UNTIL_COMPLETE_ITERATIONS_PER_CELL
+        );
+        assert_eq!(complete.stall_deadlocks, Some(DEFAULT_STALL_DEADLOCKS));
+        assert!(complete.require_complete);
+
+        let adjusted = complete.with_max_iterations(7).with_stall_deadlocks(None);
+        assert_eq!(adjusted.cap_for_cells(Some(SIZE * SIZE)), 7);
+        assert!(adjusted.stall_deadlocks.is_none());
+    }
+
+    // Tests the cap per cell counts the bounds after a prefill expands them
+    // Verified by capping from the requested bounds
+    #[test]
+    fn test_cap_follows_expanded_bounds() {
+        let mut executor = bounded_executor(1);
+        executor
+            .apply_prefill(PrefillData {
+                placement_queue: VecDeque::new(),
+                protected_positions: HashMap::new(),
+                bounds: BoundingBox {
+                    min: [-10, -10],
+                    max: [9, 9],
+                },
+            })
+            .unwrap();
+
+        assert_eq!(executor.cells_in_bounds(), Some(400));
+        assert_eq!(
+            RunLimits::until_complete().iteration_cap(&executor),
+            400 * UNTIL_COMPLETE_ITERATIONS_PER_CELL
+        );
+    }
+
+    // Tests running until complete fills the bounds within the cap, and a
+    // fixed count stops short without failing
+    // Verified by completing after the first iteration
+    #[test]
+    fn test_run_until_complete_fills_bounds() {
+        let mut executor = bounded_executor(1);
+        let mut calls = 0;
+        let outcome = run(&mut executor, &RunLimits::until_complete(), |iteration| {
+            calls += 1;
+            assert_eq!(iteration, calls);
+        })
+        .unwrap();
+        assert!(outcome.error.is_none());
+        assert_eq!(executor.unfilled_in_bounds(), Some(0));
+        assert_eq!(outcome.iterations, calls);
+
+        let mut short = bounded_executor(1);
+        let fixed = run(&mut short, &RunLimits::iterations(20), |_| {}).unwrap();
+        assert!(fixed.error.is_none());
+        assert_eq!(fixed.iterations, 20);
+        assert!(
+            short
+                .unfilled_in_bounds()
+                .is_some_and(|unfilled| unfilled > 0)
+        );
+    }
+
+    // Tests hitting the cap before filling the bounds reports the unfilled
+    // cells when completion is required
+    // Verified by not requiring completion at the cap
+    #[test]
+    fn test_cap_reports_unfilled_cells() {
+        let mut executor = bounded_executor(1);
+        let limits = RunLimits::until_complete().with_max_iterations(20);
+        let outcome = run(&mut executor, &limits, |_| {}).unwrap();
+
+        let unfilled = executor.unfilled_in_bounds().unwrap();
+        assert!(unfilled > 0);
+        assert!(matches!(
+            outcome.error,
+            Some(AlgorithmError::Incomplete {
+                unfilled: reported,
+                iterations: 20,
+                stalled: false,
+            }) if reported == unfilled
+        ));
+    }
+
+    // Tests deadlocks that undo progress stop the run as stalled
+    // Verified by not marking the run as stalled when the detector fires
+    #[test]
+    fn test_deadlock_churn_stalls() {
+        let mut executor = bounded_executor(1);
+        let limits = RunLimits::until_complete().with_stall_deadlocks(Some(1));
+        let outcome = run(&mut executor, &limits, |_| {}).unwrap();
+
+        assert_eq!(executor.deadlock_stats().deadlocks, 1);
+        assert!(matches!(
+            outcome.error,
+            Some(AlgorithmError::Incomplete { stalled: true, .. })
+        ));
+        assert!(
+            outcome
+                .error
+                .is_some_and(|error| error.is_generation_failure())
+        );
+    }
+
+    // Tests backtracks that undo progress count towards a stall
+    // Verified by observing removals alone
+    #[test]
+    fn test_backtracking_churn_stalls() {
+        let mut executor = bounded_executor_with(1, DeadlockStrategy::Backtrack(10_000));
+        let limits = RunLimits::until_complete().with_stall_deadlocks(Some(1));
+        let outcome = run(&mut executor, &limits, |_| {}).unwrap();
+
+        assert_eq!(executor.deadlock_stats().deadlocks, 0);
+        assert!(
+            executor
+                .decision_stack
+                .as_ref()
+                .is_some_and(|stack| stack.backtracks() >= 1)
+        );
+        assert!(matches!(
+            outcome.error,
+            Some(AlgorithmError::Incomplete { stalled: true, .. })
+        ));
+    }
+}
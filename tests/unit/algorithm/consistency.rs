@@ -0,0 +1,251 @@
+//! Tests for arc consistency propagation over frontier domains
+
+#[cfg(test)]
+mod tests {
+    use greedytile::algorithm::cache::ViableTilesCache;
+    use greedytile::algorithm::consistency::{ArcConsistency, PropagationMode};
+    use greedytile::algorithm::executor::{AlgorithmConfig, GreedyStochastic};
+    use greedytile::algorithm::propagation::{StepData, update_grid_state};
+    use greedytile::algorithm::selection::compute_viable_tiles_at_position;
+    use greedytile::analysis::patterns::ImageProcessor;
+    use greedytile::io::events::DecisionSource;
+    use greedytile::spatial::GridState;
+    use greedytile::spatial::tiles::TileExtractor;
+    use ndarray::Array2;
+
+    const SIZE: usize = 12;
+
+    // Vertical stripes cycling through the tiles 1, 2, 3 from left to right
+    fn stripe_step_data() -> StepData {
+        let source = Array2::from_shape_fn((6, 9), |(_, col)| col % 3 + 1);
+        let mut extractor = TileExtractor::extract_tiles(&source, 3, false, false);
+        extractor.build_boolean_reference_rules(3);
+        StepData {
+            source_ratios: vec![1.0 / 3.0; 3],
+            unique_cell_count: 3,
+            grid_extension_radius: 2,
+            density_correction_threshold: 0.1,
+            density_correction_steepness: 0.05,
+            density_minimum_strength: 0.1,
+            source_tiles: extractor.source_tiles().to_vec(),
+            tile_compatibility_rules: extractor.get_boolean_reference_rules().clone(),
+        }
+    }
+
+    fn stripe_grid(placements: &[([i32; 2], usize)]) -> (GridState, ArcConsistency) {
+        let mut grid_state = GridState::new(SIZE, SIZE, 3);
+        let mut arc_consistency = ArcConsistency::new();
+        for &(position, tile_reference) in placements {
+            update_grid_state(
+                &mut grid_state,
+                tile_reference,
+                position,
+                [0, 0],
+                &mut None,
+                0,
+                DecisionSource::Selected,
+            );
+            arc_consistency.record_placement(position);
+        }
+        (grid_state, arc_consistency)
+    }
+
+    fn executor(seed: u64) -> GreedyStochastic {
+        let config = AlgorithmConfig {
+            bounds: Some((SIZE, SIZE)),
+            propagation_mode: PropagationMode::ArcConsistency,
+            ..AlgorithmConfig::default()
+        };
+        let processor = ImageProcessor::from_png_file("data/a.png").unwrap();
+        GreedyStochastic::from_image_processor(processor, config, seed).unwrap()
+    }
+
+    // Whether every value of every kept domain has a source tile in each
+    // window covering its cell that agrees with the locks and other domains
+    fn is_arc_consistent(executor: &GreedyStochastic) -> bool {
+        let grid = &executor.grid_state;
+        let offset = executor.system_offset;
+        let Some(arc_consistency) = &executor.arc_consistency else {
+            return false;
+        };
+        let locked = |world: [i32; 2]| {
+            let row = usize::try_from(world[0] + offset[0]).ok()?;
+            let col = usize::try_from(world[1] + offset[1]).ok()?;
+            (row < grid.rows() && col < grid.cols())
+                .then(|| *grid.locked_tiles.get([row, col]).unwrap())
+        };
+
+        for row in -offset[0]..grid.rows() as i32 - offset[0] {
+            for col in -offset[1]..grid.cols() as i32 - offset[1] {
+                let cell = [row, col];
+                let Some(domain) = arc_consistency.domain(cell) else {
+                    continue;
+                };
+                for value in domain.to_vec() {
+                    for top in (row - 2..=row).flat_map(|r| (col - 2..=col).map(move |c| [r, c])) {
+                        let window: Option<Vec<([i32; 2], u32)>> = (0..3)
+                            .flat_map(|di| (0..3).map(move |dj| [top[0] + di, top[1] + dj]))
+                            .map(|position| Some((position, locked(position)?)))
+                            .collect();
+                        let Some(window) = window else {
+                            continue;
+                        };
+                        let supported = executor.step_data.source_tiles.iter().any(|tile| {
+                            tile.iter().flatten().zip(&window).all(
+                                |(&source, &(position, lock))| {
+                                    if lock > 1 {
+                                        source == lock as usize - 1
+                                    } else if position == cell {
+                                        source == value
+                                    } else {
+                                        arc_consistency
+                                            .domain(position)
+                                            .is_none_or(|other| other.contains(source))
+                                    }
+                                },
+                            )
+                        });
+                        if !supported {
+                            return false;
+                        }
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    // Tests modes parse from and display as the command line forms
+    // Verified by displaying arc consistency as "arc"
+    #[test]
+    fn test_mode_round_trip() {
+        for mode in [PropagationMode::Neighbours, PropagationMode::ArcConsistency] {
+            assert_eq!(mode.to_string().parse::<PropagationMode>().unwrap(), mode);
+        }
+        assert_eq!(
+            "AC3".parse::<PropagationMode>().unwrap(),
+            PropagationMode::ArcConsistency
+        );
+        assert!("ac4".parse::<PropagationMode>().is_err());
+        assert!(PropagationMode::Neighbours.arc_consistency().is_none());
+    }
+
+    // Tests a placement forces every frontier cell of its stripe pattern,
+    // not only its eight neighbours
+    // Verified by reporting only cells narrowed by revision
+    #[test]
+    fn test_singletons_beyond_neighbours_are_forced() {
+        let step_data = stripe_step_data();
+        let (grid_state, mut arc_consistency) = stripe_grid(&[([5, 2], 1)]);
+
+        let propagation = arc_consistency.propagate(
+            &grid_state,
+            [0, 0],
+            &step_data,
+            &mut ViableTilesCache::new(),
+        );
+
+        assert!(propagation.contradiction.is_none());
+        assert_eq!(propagation.forced.len(), 24);
+        for forced in &propagation.forced {
+            let expected = (forced.coordinates[1] + 1).rem_euclid(3) as usize;
+            assert_eq!(forced.tile_reference, expected + 1, "{forced:?}");
+        }
+        assert!(
+            propagation
+                .forced
+                .iter()
+                .any(|forced| forced.coordinates == [3, 4])
+        );
+    }
+
+    // Tests two placements out of phase with each other empty a domain
+    // between them, though each open cell still has a viable tile
+    // Verified by treating open cells as unconstrained within windows
+    #[test]
+    fn test_contradiction_between_open_cells() {
+        let step_data = stripe_step_data();
+        let (grid_state, mut arc_consistency) = stripe_grid(&[([5, 2], 1), ([5, 7], 1)]);
+        let mut cache = ViableTilesCache::new();
+
+        for col in 3..7 {
+            let viable = compute_viable_tiles_at_position(
+                &grid_state,
+                [5, col],
+                [0, 0],
+                &step_data.source_tiles,
+                &step_data,
+                &mut cache,
+            );
+            assert!(!viable.is_empty());
+        }
+
+        let propagation = arc_consistency.propagate(&grid_state, [0, 0], &step_data, &mut cache);
+        assert!(propagation.contradiction.is_some());
+        assert!(propagation.forced.is_empty());
+
+        arc_consistency.clear();
+        assert!(arc_consistency.is_empty());
+    }
+
+    // Tests domains stay arc consistent throughout a generation, narrower
+    // than the viable tiles somewhere, and the fill completes with every
+    // window from the source
+    // Verified by not revising cells next to newly seeded domains
+    #[test]
+    fn test_generation_keeps_domains_consistent() {
+        let mut executor = executor(3);
+        let mut narrowed = false;
+        let mut complete = false;
+        for iteration in 0..5000 {
+            if !executor.run_iteration().unwrap() {
+                complete = true;
+                break;
+            }
+            if iteration % 5 != 0 {
+                continue;
+            }
+            assert!(is_arc_consistent(&executor), "iteration {iteration}");
+
+            let offset = executor.system_offset;
+            let arc_consistency = executor.arc_consistency.as_ref().unwrap();
+            for row in 0..executor.grid_state.rows() {
+                for col in 0..executor.grid_state.cols() {
+                    let world = [row as i32 - offset[0], col as i32 - offset[1]];
+                    if let Some(domain) = arc_consistency.domain(world) {
+                        let viable = compute_viable_tiles_at_position(
+                            &executor.grid_state,
+                            world,
+                            offset,
+                            &executor.step_data.source_tiles,
+                            &executor.step_data,
+                            &mut ViableTilesCache::new(),
+                        );
+                        assert!(domain.to_vec().iter().all(|tile| viable.contains(tile)));
+                        narrowed |= domain.count() < viable.len();
+                    }
+                }
+            }
+        }
+
+        assert!(complete);
+        assert!(narrowed);
+        let grid = &executor.grid_state;
+        for row in 0..grid.rows() - 2 {
+            for col in 0..grid.cols() - 2 {
+                let mut window = [[0usize; 3]; 3];
+                let mut complete_window = true;
+                for (di, window_row) in window.iter_mut().enumerate() {
+                    for (dj, cell) in window_row.iter_mut().enumerate() {
+                        let locked = *grid.locked_tiles.get([row + di, col + dj]).unwrap();
+                        complete_window &= locked > 1;
+                        *cell = locked as usize - 1;
+                    }
+                }
+                if complete_window {
+                    assert!(executor.step_data.source_tiles.contains(&window));
+                }
+            }
+        }
+    }
+}
@@ -2,13 +2,176 @@
 
 #[cfg(test)]
 mod tests {
-    use greedytile::algorithm::deadlock::resolve_spatial_deadlock;
+    use greedytile::algorithm::deadlock::{
+        DeadlockPolicy, DeadlockStats, RemovalGrowth, RemovalShape, resolve_spatial_deadlock,
+        unlock_tiles,
+    };
+    use greedytile::algorithm::executor::{AlgorithmConfig, GreedyStochastic};
     use greedytile::algorithm::feasibility::FeasibilityCountLayer;
-    use greedytile::algorithm::propagation::StepData;
-    use greedytile::io::configuration::ADJACENCY_LEVELS;
+    use greedytile::algorithm::fronts::GrowthFronts;
+    use greedytile::algorithm::propagation::{
+        StepData, update_feasibility_counts, update_grid_state, update_probabilities_and_entropy,
+    };
+    use greedytile::analysis::patterns::ImageProcessor;
+    use greedytile::io::configuration::{ADJACENCY_LEVELS, DEFAULT_RECENT_PLACEMENTS};
+    use greedytile::io::error::AlgorithmError;
+    use greedytile::io::events::DecisionSource;
     use greedytile::spatial::GridState;
     use ndarray::Array4;
-    use std::collections::HashMap;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+    use std::collections::{HashMap, VecDeque};
+
+    const SIZE: usize = 24;
+    const TILE_TYPES: usize = 3;
+
+    fn place(
+        grid_state: &mut GridState,
+        feasibility_layer: &mut FeasibilityCountLayer,
+        matrices: &Array4<f64>,
+        step_data: &StepData,
+        position: [usize; 2],
+        tile_reference: usize,
+    ) {
+        let world = [position[0] as i32, position[1] as i32];
+        update_probabilities_and_entropy(
+            grid_state,
+            matrices,
+            tile_reference,
+            world,
+            [0, 0],
+            step_data,
+        );
+        update_grid_state(
+            grid_state,
+            tile_reference,
+            world,
+            [0, 0],
+            &mut None,
+            0,
+            DecisionSource::Selected,
+        );
+        update_feasibility_counts(grid_state, feasibility_layer, world, [0, 0], step_data);
+    }
+
+    // Grid of `size` cells all holding the first tile, with neutral influence
+    fn filled_grid(size: usize) -> (GridState, FeasibilityCountLayer, StepData, Array4<f64>) {
+        let step_data = StepData {
+            source_ratios: vec![0.5, 0.5],
+            unique_cell_count: 2,
+            grid_extension_radius: 2,
+            density_correction_threshold: 0.1,
+            density_correction_steepness: 0.05,
+            density_minimum_strength: 0.1,
+            source_tiles: vec![[[1, 1, 1], [1, 1, 1], [1, 1, 1]]],
+            tile_compatibility_rules: HashMap::new(),
+        };
+        let matrices = Array4::<f64>::ones((2, 2, 5, 5));
+        let mut grid_state = GridState::new(size, size, 2);
+        let mut feasibility_layer = FeasibilityCountLayer::new(size, size, 1);
+        for row in 0..size {
+            for col in 0..size {
+                place(
+                    &mut grid_state,
+                    &mut feasibility_layer,
+                    &matrices,
+                    &step_data,
+                    [row, col],
+                    1,
+                );
+            }
+        }
+        (grid_state, feasibility_layer, step_data, matrices)
+    }
+
+    // Unlock around the centre of a filled 15x15 grid after `repeats` earlier
+    // removals there
+    fn removed_tiles(
+        policy: &DeadlockPolicy,
+        recent: &[[i32; 2]],
+        fronts: Option<&GrowthFronts>,
+        repeats: u8,
+    ) -> Vec<[usize; 2]> {
+        let (mut grid_state, mut feasibility_layer, step_data, matrices) = filled_grid(15);
+        *grid_state.removal_count.get_mut([7, 7]).unwrap() = repeats;
+        let recent_placements: VecDeque<[i32; 2]> = recent.iter().copied().collect();
+        let mut tally = vec![225, 0];
+
+        let result = resolve_spatial_deadlock(
+            &mut grid_state,
+            &mut feasibility_layer,
+            [7, 7],
+            [0, 0],
+            &mut tally,
+            &step_data,
+            &matrices,
+            policy,
+            &recent_placements,
+            fronts,
+            &mut None,
+            0,
+        );
+
+        assert_eq!(tally, vec![225 - result.tiles_unlocked, 0]);
+        for &index in &result.unlocked_positions {
+            assert_eq!(grid_state.locked_tiles.get(index).copied(), Some(1));
+        }
+        result.unlocked_positions
+    }
+
+    fn bounded_executor(seed: u64, policy: DeadlockPolicy) -> GreedyStochastic {
+        let config = AlgorithmConfig {
+            bounds: Some((12, 12)),
+            deadlock_policy: policy,
+            ..AlgorithmConfig::default()
+        };
+        let processor = ImageProcessor::from_png_file("data/a.png").unwrap();
+        GreedyStochastic::from_image_processor(processor, config, seed).unwrap()
+    }
+
+    // Run until complete, stopping at the first error
+    fn run_to_completion(executor: &mut GreedyStochastic) -> Result<(), AlgorithmError> {
+        let mut iterations = 0;
+        while executor.run_iteration()? {
+            iterations += 1;
+            assert!(iterations < 20_000, "generation did not complete");
+        }
+        Ok(())
+    }
+
+    // Every layer an undo must restore, as raw bits per cell
+    fn cell_bits(
+        grid_state: &GridState,
+        feasibility_layer: &FeasibilityCountLayer,
+    ) -> Vec<Vec<i128>> {
+        let mut cells = Vec::new();
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                let index = [row, col];
+                let mut cell = Vec::new();
+                for tile_type in 0..TILE_TYPES {
+                    let probabilities = grid_state.tile_probabilities.get(tile_type).unwrap();
+                    let influence = grid_state.log_influence.get(tile_type).unwrap();
+                    cell.push(i128::from(probabilities.get(index).unwrap().to_bits()));
+                    cell.push(*influence.get(index).unwrap());
+                }
+                cell.push(i128::from(grid_state.entropy.get(index).unwrap().to_bits()));
+                cell.push(i128::from(
+                    *grid_state.influence_coverage.get(index).unwrap(),
+                ));
+                cell.push(i128::from(
+                    *grid_state.adjacency_weights.get(index).unwrap(),
+                ));
+                cell.push(i128::from(*grid_state.locked_tiles.get(index).unwrap()));
+                cell.push(i128::from(
+                    feasibility_layer.get_fraction(row, col).to_bits(),
+                ));
+                cells.push(cell);
+            }
+        }
+        cells
+    }
 
     // Complex test verifying deadlock resolution unlocks tiles, reverts probability mutations, and resets adjacency weights
     // Verified by removing tile unlocking logic during deadlock resolution
@@ -91,34 +254,10 @@ mod tests {
             }
         }
 
-        if let Some(probs) = grid_state.tile_probabilities.get_mut(0) {
-            if let Some(val) = probs.get_mut([1, 1]) {
-                *val *= 1.5;
-            }
-            if let Some(val) = probs.get_mut([1, 2]) {
-                *val *= 0.5;
-            }
-            if let Some(val) = probs.get_mut([2, 1]) {
-                *val *= 1.5;
-            }
-            if let Some(val) = probs.get_mut([2, 2]) {
-                *val *= 0.5;
-            }
-        }
-        if let Some(probs) = grid_state.tile_probabilities.get_mut(1) {
-            if let Some(val) = probs.get_mut([1, 1]) {
-                *val *= 0.5;
-            }
-            if let Some(val) = probs.get_mut([1, 2]) {
-                *val *= 1.5;
-            }
-            if let Some(val) = probs.get_mut([2, 1]) {
-                *val *= 0.5;
-            }
-            if let Some(val) = probs.get_mut([2, 2]) {
-                *val *= 1.5;
-            }
-        }
+        grid_state.apply_influence([1, 1], [1.5, 0.5]);
+        grid_state.apply_influence([1, 2], [0.5, 1.5]);
+        grid_state.apply_influence([2, 1], [1.5, 0.5]);
+        grid_state.apply_influence([2, 2], [0.5, 1.5]);
 
         let contradiction_pos = [2, 2];
         let system_offset = [0, 0];
@@ -131,6 +270,9 @@ mod tests {
             &mut selection_tally,
             &step_data,
             &probability_influence_matrices,
+            &DeadlockPolicy::default(),
+            &VecDeque::new(),
+            None,
             &mut None,
             0,
         );
@@ -231,4 +373,328 @@ mod tests {
             "Probability at [2,2] for color 1 should be ~2.667 after reverting"
         );
     }
+
+    // Tests placing random tiles over an existing layout and unlocking them in
+    // a shuffled order restores every probability, log sum, entropy, weight
+    // and pattern count bit for bit, including zero influence factors
+    // Verified by updating cached probabilities multiplicatively instead of
+    // from the accumulated sums
+    #[test]
+    fn test_place_then_unlock_restores_state_exactly() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let step_data = StepData {
+            source_ratios: vec![0.3, 0.3, 0.4],
+            unique_cell_count: TILE_TYPES,
+            grid_extension_radius: 3,
+            density_correction_threshold: 0.1,
+            density_correction_steepness: 0.05,
+            density_minimum_strength: 0.1,
+            source_tiles: vec![[[1, 2, 3], [2, 3, 1], [3, 1, 2]]],
+            tile_compatibility_rules: HashMap::new(),
+        };
+        let matrices = Array4::from_shape_fn((TILE_TYPES, TILE_TYPES, 7, 7), |_| {
+            if rng.random_bool(0.1) {
+                0.0
+            } else {
+                rng.random_range(-3.0..3.0_f64).exp()
+            }
+        });
+
+        let mut grid_state = GridState::new(SIZE, SIZE, TILE_TYPES);
+        let mut feasibility_layer = FeasibilityCountLayer::new(SIZE, SIZE, TILE_TYPES);
+        let mut open: Vec<[usize; 2]> = (0..SIZE)
+            .flat_map(|row| (0..SIZE).map(move |col| [row, col]))
+            .collect();
+        open.shuffle(&mut rng);
+
+        for position in open.split_off(open.len() - 60) {
+            let tile_reference = rng.random_range(1..=TILE_TYPES);
+            place(
+                &mut grid_state,
+                &mut feasibility_layer,
+                &matrices,
+                &step_data,
+                position,
+                tile_reference,
+            );
+        }
+
+        // Bring every pattern count up to date so counts depend only on locks
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                let world = [row as i32, col as i32];
+                update_feasibility_counts(
+                    &mut grid_state,
+                    &mut feasibility_layer,
+                    world,
+                    [0, 0],
+                    &step_data,
+                );
+            }
+        }
+
+        for _ in 0..30 {
+            let before = cell_bits(&grid_state, &feasibility_layer);
+            let mut tally = vec![5; TILE_TYPES];
+
+            open.shuffle(&mut rng);
+            let mut placed = Vec::new();
+            for &position in open.iter().take(rng.random_range(1..10)) {
+                let tile_reference = rng.random_range(1..=TILE_TYPES);
+                place(
+                    &mut grid_state,
+                    &mut feasibility_layer,
+                    &matrices,
+                    &step_data,
+                    position,
+                    tile_reference,
+                );
+                placed.push((position, tile_reference as u32));
+            }
+            placed.shuffle(&mut rng);
+
+            let min = placed.iter().fold([i32::MAX; 2], |min, &([row, col], _)| {
+                [min[0].min(row as i32), min[1].min(col as i32)]
+            });
+            let max = placed.iter().fold([i32::MIN; 2], |max, &([row, col], _)| {
+                [max[0].max(row as i32), max[1].max(col as i32)]
+            });
+            unlock_tiles(
+                &mut grid_state,
+                &mut feasibility_layer,
+                &placed,
+                min,
+                max,
+                [0, 0],
+                &mut tally,
+                &step_data,
+                &matrices,
+                &mut None,
+                0,
+            );
+
+            assert_eq!(cell_bits(&grid_state, &feasibility_layer), before);
+        }
+    }
+
+    // Tests a disk removes only the cells within the radius by Euclidean
+    // distance while a square removes the whole square
+    // Verified by building the disk from the square without filtering
+    #[test]
+    fn test_disk_and_square_shapes() {
+        let square = removed_tiles(&DeadlockPolicy::default(), &[], None, 2);
+        let disk = removed_tiles(
+            &DeadlockPolicy::default().with_shape(RemovalShape::Disk),
+            &[],
+            None,
+            2,
+        );
+
+        assert_eq!(square.len(), 49);
+        assert_eq!(disk.len(), 29);
+        assert!(disk.iter().all(|index| square.contains(index)));
+        assert!(disk.contains(&[4, 7]));
+        assert!(disk.contains(&[5, 5]));
+        assert!(!disk.contains(&[4, 5]));
+    }
+
+    // Tests the recent shape removes only recent placements connected to the
+    // contradiction within the radius, and falls back to a square when none are
+    // Verified by removing every recent placement within the radius
+    #[test]
+    fn test_recent_shape_floods_from_contradiction() {
+        let policy = DeadlockPolicy::default().with_shape(RemovalShape::Recent(16));
+        let recent = [
+            [7, 5],
+            [7, 6],
+            [7, 7],
+            [6, 8],
+            [5, 9],
+            [5, 10],
+            [9, 4],
+            [7, 11],
+        ];
+
+        assert_eq!(
+            removed_tiles(&policy, &recent, None, 2),
+            vec![[5, 9], [5, 10], [6, 8], [7, 5], [7, 6], [7, 7]]
+        );
+        assert_eq!(removed_tiles(&policy, &[[0, 0]], None, 2).len(), 49);
+    }
+
+    // Tests the first removal on the seam between two fronts clears only the
+    // side with fewer tiles in the square, and a repeat clears both
+    // Verified by removing the whole square on seams
+    #[test]
+    fn test_seam_removes_one_front() {
+        // Columns up to 7 grow from a seed at column 0, the rest from column 14
+        let mut fronts = GrowthFronts::new();
+        fronts.start_front([7, 0]);
+        fronts.start_front([7, 14]);
+        let rows: Vec<i32> = (0..7).rev().chain(8..15).collect();
+        for col in [0, 14] {
+            for &row in &rows {
+                fronts.record_placement([row, col]);
+            }
+        }
+        for col in (8..14).rev().chain(1..8) {
+            for row in (0..15).map(|row| (row + 7) % 15) {
+                fronts.record_placement([row, col]);
+            }
+        }
+        assert_eq!(fronts.merges(), 1);
+
+        let policy = DeadlockPolicy::default().with_radius(2, 6);
+        let removed = removed_tiles(&policy, &[], Some(&fronts), 0);
+        assert_eq!(removed.len(), 21);
+        assert!(removed.iter().all(|&[_, col]| (8..=10).contains(&col)));
+        assert_eq!(removed_tiles(&policy, &[], Some(&fronts), 1).len(), 81);
+    }
+
+    // Tests each growth schedule's radius on repeats, capped at the maximum
+    // Verified by doubling from the first repeat's radius of two
+    #[test]
+    fn test_growth_schedules() {
+        let radii = |growth| {
+            let policy = DeadlockPolicy::default()
+                .with_growth(growth)
+                .with_radius(1, 9);
+            (0..6)
+                .map(|repeats| policy.radius(repeats))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(radii(RemovalGrowth::Fixed), vec![1, 1, 1, 1, 1, 1]);
+        assert_eq!(radii(RemovalGrowth::Linear(2)), vec![1, 3, 5, 7, 9, 9]);
+        assert_eq!(radii(RemovalGrowth::Doubling), vec![1, 2, 3, 5, 9, 9]);
+        assert_eq!(
+            DeadlockPolicy::default()
+                .with_growth(RemovalGrowth::Doubling)
+                .radius(u8::MAX),
+            DeadlockPolicy::default().max_radius
+        );
+    }
+
+    // Tests shapes and growth schedules parse from and display as the command
+    // line forms, and invalid policies are rejected
+    // Verified by accepting a zero decay interval
+    #[test]
+    fn test_policy_parsing_and_validation() {
+        assert_eq!(
+            "recent".parse::<RemovalShape>().unwrap(),
+            RemovalShape::Recent(DEFAULT_RECENT_PLACEMENTS)
+        );
+        assert_eq!(
+            "Linear".parse::<RemovalGrowth>().unwrap(),
+            RemovalGrowth::Linear(1)
+        );
+        for shape in [
+            RemovalShape::Square,
+            RemovalShape::Disk,
+            RemovalShape::Recent(12),
+        ] {
+            assert_eq!(shape.to_string().parse::<RemovalShape>().unwrap(), shape);
+        }
+        for growth in [
+            RemovalGrowth::Fixed,
+            RemovalGrowth::Linear(3),
+            RemovalGrowth::Doubling,
+        ] {
+            assert_eq!(growth.to_string().parse::<RemovalGrowth>().unwrap(), growth);
+        }
+        assert!("recent:0".parse::<RemovalShape>().is_err());
+        assert!("linear:-1".parse::<RemovalGrowth>().is_err());
+
+        assert!(DeadlockPolicy::default().validate().is_ok());
+        assert!(
+            DeadlockPolicy::default()
+                .with_radius(3, 2)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            DeadlockPolicy::default()
+                .with_radius(-1, 2)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            DeadlockPolicy::default()
+                .with_decay_interval(0)
+                .validate()
+                .is_err()
+        );
+    }
+
+    // Tests stats count removals and tiles, merge, and rank hotspots by count
+    // with ties in row-major order
+    // Verified by ranking hotspots from fewest removals
+    #[test]
+    fn test_stats_rank_hotspots() {
+        let mut stats = DeadlockStats::default();
+        stats.record([2, 3], 4);
+        stats.record([0, 1], 2);
+        let mut other = DeadlockStats::default();
+        other.record([2, 3], 5);
+        other.record([-1, 0], 1);
+        stats.merge(&other);
+
+        assert_eq!(stats.deadlocks, 4);
+        assert_eq!(stats.tiles_removed, 12);
+        assert_eq!(stats.top_hotspots(2), vec![([2, 3], 2), ([-1, 0], 1)]);
+        assert_eq!(stats.top_hotspots(10).len(), 3);
+    }
+
+    // Tests a run stops with a typed error once the removal limit is reached,
+    // after recording exactly that many removals
+    // Verified by allowing one removal past the limit
+    #[test]
+    fn test_removal_limit_is_unsolvable() {
+        let mut executor = bounded_executor(1, DeadlockPolicy::default().with_max_attempts(1));
+        let result = run_to_completion(&mut executor);
+
+        assert!(matches!(
+            result,
+            Err(AlgorithmError::Unsolvable { deadlocks: 1, .. })
+        ));
+        let stats = executor.deadlock_stats();
+        assert_eq!(stats.deadlocks, 1);
+        assert_eq!(stats.hotspots.values().sum::<usize>(), 1);
+        assert!(stats.tiles_removed > 0);
+    }
+
+    // Tests removal counts at every hotspot decay back to zero once removals
+    // stop, while without decay they keep their counts
+    // Verified by skipping the decay
+    #[test]
+    fn test_removal_counts_decay() {
+        let hotspot_counts = |executor: &GreedyStochastic| {
+            let offset = executor.system_offset;
+            executor
+                .deadlock_stats()
+                .hotspots
+                .keys()
+                .map(|position| {
+                    let index = [
+                        usize::try_from(position[0] + offset[0]).unwrap(),
+                        usize::try_from(position[1] + offset[1]).unwrap(),
+                    ];
+                    *executor.grid_state.removal_count.get(index).unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut kept = bounded_executor(1, DeadlockPolicy::default());
+        assert!(run_to_completion(&mut kept).is_ok());
+        assert!(hotspot_counts(&kept).iter().any(|&count| count > 0));
+
+        let mut decayed = bounded_executor(1, DeadlockPolicy::default().with_decay_interval(2));
+        assert!(run_to_completion(&mut decayed).is_ok());
+        for _ in 0..2 * usize::from(u8::MAX) {
+            assert!(!decayed.run_iteration().unwrap());
+        }
+        let counts = hotspot_counts(&decayed);
+        assert!(!counts.is_empty());
+        assert!(counts.iter().all(|&count| count == 0));
+    }
 }
@@ -3,9 +3,10 @@
 #[cfg(test)]
 mod tests {
     use greedytile::algorithm::cache::ViableTilesCache;
-    use greedytile::algorithm::executor::GreedyStochastic;
+    use greedytile::algorithm::executor::{AlgorithmConfig, GreedyStochastic};
     use greedytile::algorithm::propagation::detect_forced_positions;
     use greedytile::algorithm::selection::compute_viable_tiles_at_position;
+    use greedytile::analysis::patterns::ImageProcessor;
     use std::collections::HashSet;
 
     // Verifies forced positions are detected during iterations
@@ -251,4 +252,38 @@ mod tests {
             "No forced positions were detected during the test"
         );
     }
+
+    // Tests odd bounds keep every requested row and column, centred with
+    // the extra cell after the origin
+    // Verified by building the bounds maximum from half the size
+    #[test]
+    fn test_odd_bounds_keep_requested_size() {
+        let config = AlgorithmConfig {
+            bounds: Some((5, 8)),
+            ..AlgorithmConfig::default()
+        };
+        let processor = ImageProcessor::from_png_file("data/a.png").unwrap();
+        let executor = GreedyStochastic::from_image_processor(processor, config, 1).unwrap();
+
+        let bounds = executor.grid_state.generation_bounds.as_ref().unwrap();
+        assert_eq!(bounds.min, [-2, -4]);
+        assert_eq!(bounds.max, [2, 3]);
+        assert_eq!(executor.cells_in_bounds(), Some(40));
+    }
+
+    // Tests tiles are read by world position, with off-grid cells as None
+    // Verified by returning the raw lock value instead of the tile reference
+    #[test]
+    fn test_tile_at_world_position() {
+        let mut executor = GreedyStochastic::new(1).expect("Failed to create executor");
+        executor.run_iteration().expect("Failed to run iteration");
+
+        let placed = executor.selection_coordinates;
+        assert_eq!(
+            executor.tile_at(placed),
+            Some(executor.selected_cell_reference as u32)
+        );
+        assert_eq!(executor.tile_at([10_000, 10_000]), None);
+        assert_eq!(executor.tile_at([-10_000, 0]), None);
+    }
 }
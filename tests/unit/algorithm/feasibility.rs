@@ -48,6 +48,25 @@ mod tests {
         assert!((layer.get_fraction(0, 0) - original_fraction).abs() < f64::EPSILON);
     }
 
+    // Tests growing towards negative coordinates moves existing counts with
+    // their cells and leaves the new rows and columns fully feasible
+    // Verified by extending without shifting the existing counts
+    #[test]
+    fn test_extend_with_shift_moves_counts() {
+        let mut layer = FeasibilityCountLayer::new(2, 2, 10);
+        let source_tiles: Vec<Tile> = vec![[[1; 3]; 3]];
+        let tile_grid = [[2; 3]; 3];
+        layer.update_count(1, 0, &tile_grid, &source_tiles, &HashMap::new(), 10);
+        assert!(layer.get_fraction(1, 0).abs() < f64::EPSILON);
+
+        layer.extend_with_shift(5, 4, [2, 1]);
+
+        assert!(layer.get_fraction(3, 1).abs() < f64::EPSILON);
+        for index in [[1, 0], [0, 0], [2, 1], [4, 3]] {
+            assert!((layer.get_fraction(index[0], index[1]) - 1.0).abs() < f64::EPSILON);
+        }
+    }
+
     // Tests update count with all matching tiles
     // Verified by commenting out the count increment
     #[test]
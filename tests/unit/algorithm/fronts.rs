@@ -0,0 +1,238 @@
+//! Tests for growth from several seed points
+
+#[cfg(test)]
+mod tests {
+    use greedytile::algorithm::completion::{RunLimits, run};
+    use greedytile::algorithm::executor::{AlgorithmConfig, GreedyStochastic};
+    use greedytile::algorithm::fronts::{GrowthFronts, GrowthSeeds};
+    use greedytile::algorithm::origin::BoundsAnchor;
+    use greedytile::analysis::patterns::ImageProcessor;
+    use greedytile::io::configuration::{DEFAULT_SEED_SPACING, MAX_SEED_PIXELS};
+    use greedytile::io::error::Result;
+    use greedytile::io::prefill::{PrefillData, PrefillPlacement};
+    use greedytile::spatial::grid::BoundingBox;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::collections::{HashMap, VecDeque};
+
+    const SIZE: usize = 16;
+
+    fn seeded_executor(
+        seeds: GrowthSeeds,
+        bounds: Option<(usize, usize)>,
+    ) -> Result<GreedyStochastic> {
+        seeded_executor_with(seeds, bounds, 1)
+    }
+
+    fn seeded_executor_with(
+        seeds: GrowthSeeds,
+        bounds: Option<(usize, usize)>,
+        seed: u64,
+    ) -> Result<GreedyStochastic> {
+        let config = AlgorithmConfig {
+            bounds,
+            bounds_anchor: BoundsAnchor::TopLeft,
+            growth_seeds: seeds,
+            ..AlgorithmConfig::default()
+        };
+        let processor = ImageProcessor::from_png_file("data/a.png")?;
+        GreedyStochastic::from_image_processor(processor, config, seed)
+    }
+
+    // Tests seeds parse from and display as the command line forms
+    // Verified by parsing a bare count as a single seed
+    #[test]
+    fn test_round_trip() {
+        for seeds in [
+            GrowthSeeds::Single,
+            GrowthSeeds::Sampled {
+                count: 4,
+                min_spacing: 6,
+            },
+            GrowthSeeds::pixels(&[[1, 2], [10, 3]]).unwrap(),
+        ] {
+            assert_eq!(seeds.to_string().parse::<GrowthSeeds>().unwrap(), seeds);
+        }
+        assert_eq!(
+            "3".parse::<GrowthSeeds>().unwrap(),
+            GrowthSeeds::Sampled {
+                count: 3,
+                min_spacing: DEFAULT_SEED_SPACING,
+            }
+        );
+        assert_eq!("1".parse::<GrowthSeeds>().unwrap(), GrowthSeeds::Single);
+        assert!("0".parse::<GrowthSeeds>().is_err());
+        assert!("2,3;x".parse::<GrowthSeeds>().is_err());
+        assert!(GrowthSeeds::pixels(&[[0, 0]; MAX_SEED_PIXELS + 1]).is_err());
+    }
+
+    // Tests pixels map into the bounds without duplicates, sampled seeds keep
+    // their spacing from the start, and several seeds need bounds
+    // Verified by sampling without the spacing check
+    #[test]
+    fn test_seed_world_positions() {
+        let bounds = BoundsAnchor::Center.bounds(20, 20);
+        let mut rng = StdRng::seed_from_u64(5);
+
+        let pixels = GrowthSeeds::pixels(&[[0, 0], [19, 19], [0, 0]])
+            .unwrap()
+            .world_positions([0, 0], Some(&bounds), &mut rng)
+            .unwrap();
+        assert_eq!(pixels, vec![[-10, -10], [9, 9]]);
+
+        let sampled = GrowthSeeds::Sampled {
+            count: 4,
+            min_spacing: 6,
+        }
+        .world_positions([0, 0], Some(&bounds), &mut rng)
+        .unwrap();
+        assert_eq!(sampled.len(), 4);
+        assert_eq!(sampled.first(), Some(&[0, 0]));
+        for a in &sampled {
+            assert!(bounds.contains(*a));
+            for b in sampled.iter().filter(|&b| b != a) {
+                let (dr, dc) = (a[0] - b[0], a[1] - b[1]);
+                assert!(dr * dr + dc * dc >= 36);
+            }
+        }
+
+        let crowded = GrowthSeeds::Sampled {
+            count: 50,
+            min_spacing: 15,
+        };
+        assert!(
+            crowded
+                .world_positions([0, 0], Some(&bounds), &mut rng)
+                .is_err()
+        );
+        assert!(
+            GrowthSeeds::pixels(&[[0, 0]])
+                .unwrap()
+                .world_positions([0, 0], None, &mut rng)
+                .is_err()
+        );
+        assert_eq!(
+            GrowthSeeds::Single
+                .world_positions([2, 3], None, &mut rng)
+                .unwrap(),
+            vec![[2, 3]]
+        );
+    }
+
+    // Tests tiles join the neighbouring front with most tiles, meeting fronts
+    // are recorded once, and removal forgets the label
+    // Verified by joining the lowest numbered neighbouring front
+    #[test]
+    fn test_front_labels_and_merges() {
+        let mut fronts = GrowthFronts::new();
+        assert_eq!(fronts.start_front([0, 0]), 0);
+        assert_eq!(fronts.start_front([0, 4]), 1);
+
+        fronts.record_placement([0, 1]);
+        fronts.record_placement([0, 3]);
+        fronts.record_placement([1, 3]);
+        assert_eq!(fronts.front_at([1, 3]), Some(1));
+        assert_eq!(fronts.merges(), 0);
+
+        fronts.record_placement([1, 2]);
+        assert_eq!(fronts.front_at([1, 2]), Some(1));
+        assert_eq!(fronts.fronts_around([0, 2]), vec![0, 1]);
+        assert_eq!(fronts.merges(), 1);
+        fronts.record_placement([0, 2]);
+        assert_eq!(fronts.merges(), 1);
+
+        fronts.record_placement([9, 9]);
+        assert_eq!(fronts.front_at([9, 9]), None);
+        fronts.record_removal([1, 2]);
+        assert_eq!(fronts.front_at([1, 2]), None);
+        assert_eq!(fronts.fronts(), 2);
+    }
+
+    // Tests the seam side is the front with fewer tiles in the removal, and
+    // contradictions inside one front have no seam side
+    // Verified by removing the front that started first
+    #[test]
+    fn test_seam_side() {
+        let mut fronts = GrowthFronts::new();
+        fronts.start_front([0, 0]);
+        fronts.start_front([0, 3]);
+        for col in [1, 2] {
+            fronts.record_placement([0, col]);
+        }
+
+        let removal = [[0, 0], [0, 1], [0, 2], [0, 3], [0, 4]];
+        assert_eq!(fronts.front_at([0, 1]), Some(0));
+        assert_eq!(fronts.front_at([0, 2]), Some(0));
+        assert_eq!(fronts.seam_side([1, 2], &removal), Some(1));
+        assert_eq!(fronts.seam_side([1, 0], &removal), None);
+    }
+
+    // Tests every seed is placed before growth, each starting a front, and the
+    // fronts meet as the bounds fill
+    // Verified by growing only from the first seed
+    #[test]
+    fn test_seeds_placed_first_and_merge() {
+        let seeds = GrowthSeeds::pixels(&[[1, 1], [14, 14], [1, 14]]).unwrap();
+        let mut executor = seeded_executor_with(seeds, Some((SIZE, SIZE)), 56).unwrap();
+        for _ in 0..3 {
+            executor.run_iteration().unwrap();
+        }
+        for seed in [[1, 1], [14, 14], [1, 14]] {
+            assert!(executor.tile_at(seed).is_some());
+        }
+        assert_eq!(executor.grid_state.placed_in_bounds, 3);
+        assert_eq!(executor.growth_fronts.as_ref().unwrap().fronts(), 3);
+
+        let outcome = run(&mut executor, &RunLimits::until_complete(), |_| {}).unwrap();
+        assert!(
+            outcome.error.is_none(),
+            "{:?} {:?}",
+            outcome.error,
+            executor.unfilled_in_bounds()
+        );
+        assert_eq!(executor.unfilled_in_bounds(), Some(0));
+        assert!(executor.growth_fronts.as_ref().unwrap().merges() >= 2);
+
+        assert!(seeded_executor(GrowthSeeds::pixels(&[[0, 0]]).unwrap(), None).is_err());
+        assert!(
+            seeded_executor(GrowthSeeds::Single, Some((SIZE, SIZE)))
+                .unwrap()
+                .growth_fronts
+                .is_none()
+        );
+    }
+
+    // Tests a prefill in place of the initial placement still places every
+    // seed, the first included, each starting a front
+    // Verified by popping the first seed when the executor is created
+    #[test]
+    fn test_seeds_placed_after_prefill() {
+        let seeds = GrowthSeeds::pixels(&[[1, 1], [14, 14]]).unwrap();
+        let mut executor = seeded_executor(seeds, Some((SIZE, SIZE))).unwrap();
+        let tile_reference = executor.selected_cell_reference;
+        executor
+            .apply_prefill(PrefillData {
+                placement_queue: VecDeque::from([PrefillPlacement {
+                    world_position: [8, 8],
+                    tile_reference,
+                }]),
+                protected_positions: HashMap::new(),
+                bounds: BoundingBox {
+                    min: [8, 8],
+                    max: [8, 8],
+                },
+            })
+            .unwrap();
+
+        for _ in 0..3 {
+            executor.run_iteration().unwrap();
+        }
+        for position in [[8, 8], [1, 1], [14, 14]] {
+            assert!(
+                executor.tile_at(position).is_some(),
+                "{position:?} is empty"
+            );
+        }
+        assert_eq!(executor.growth_fronts.as_ref().unwrap().fronts(), 2);
+    }
+}
@@ -1,7 +1,17 @@
+pub mod backtracking;
 pub mod bitset;
 pub mod cache;
+pub mod completion;
+pub mod consistency;
 pub mod deadlock;
 pub mod executor;
 pub mod feasibility;
+pub mod fronts;
+pub mod origin;
+pub mod positioning;
 pub mod propagation;
+pub mod replay;
+pub mod restart;
+pub mod seeded;
 pub mod selection;
+pub mod streaming;
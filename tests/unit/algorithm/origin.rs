@@ -0,0 +1,149 @@
+//! Tests for anchoring the bounds and placing the first tile
+
+#[cfg(test)]
+mod tests {
+    use greedytile::algorithm::executor::{AlgorithmConfig, GreedyStochastic};
+    use greedytile::algorithm::origin::{BoundsAnchor, StartPosition};
+    use greedytile::analysis::patterns::ImageProcessor;
+    use greedytile::io::error::Result;
+    use greedytile::spatial::grid::BoundingBox;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn executor(
+        bounds: Option<(usize, usize)>,
+        anchor: BoundsAnchor,
+        start: StartPosition,
+    ) -> Result<GreedyStochastic> {
+        let config = AlgorithmConfig {
+            bounds,
+            bounds_anchor: anchor,
+            start_position: start,
+            ..AlgorithmConfig::default()
+        };
+        let processor = ImageProcessor::from_png_file("data/a.png")?;
+        GreedyStochastic::from_image_processor(processor, config, 1)
+    }
+
+    // Tests every anchor keeps the requested size with the origin at the
+    // anchored cell
+    // Verified by anchoring the top right like the top left
+    #[test]
+    fn test_anchor_bounds() {
+        let expected = [
+            (BoundsAnchor::Center, [-2, -2], [2, 1]),
+            (BoundsAnchor::TopLeft, [0, 0], [4, 3]),
+            (BoundsAnchor::TopRight, [0, -3], [4, 0]),
+            (BoundsAnchor::BottomLeft, [-4, 0], [0, 3]),
+            (BoundsAnchor::BottomRight, [-4, -3], [0, 0]),
+        ];
+        for (anchor, min, max) in expected {
+            let bounds = anchor.bounds(5, 4);
+            assert_eq!((bounds.min, bounds.max), (min, max), "{anchor}");
+            assert_eq!(anchor.position_in(&bounds), [0, 0], "{anchor}");
+        }
+    }
+
+    // Tests anchors and starts parse from and display as the command line forms
+    // Verified by displaying pixels as column then row
+    #[test]
+    fn test_round_trip() {
+        for anchor in BoundsAnchor::ALL {
+            assert_eq!(anchor.to_string().parse::<BoundsAnchor>().unwrap(), anchor);
+        }
+        for start in [
+            StartPosition::Origin,
+            StartPosition::Random,
+            StartPosition::Anchor(BoundsAnchor::BottomLeft),
+            StartPosition::Pixel([3, 7]),
+        ] {
+            assert_eq!(start.to_string().parse::<StartPosition>().unwrap(), start);
+        }
+        assert_eq!(
+            "2, 5".parse::<StartPosition>().unwrap(),
+            StartPosition::Pixel([2, 5])
+        );
+        assert!("middle".parse::<BoundsAnchor>().is_err());
+        assert!("-1,2".parse::<StartPosition>().is_err());
+    }
+
+    // Tests starts resolve inside the bounds, and pixels outside them or
+    // starts away from the origin without bounds are rejected
+    // Verified by accepting pixels outside the bounds
+    #[test]
+    fn test_start_world_positions() {
+        let bounds = BoundsAnchor::Center.bounds(6, 6);
+        let mut rng = StdRng::seed_from_u64(3);
+
+        let resolve = |start: StartPosition, source: &mut StdRng| {
+            start.world_position(Some(&bounds), source).unwrap()
+        };
+        assert_eq!(resolve(StartPosition::Origin, &mut rng), [0, 0]);
+        assert_eq!(
+            resolve(StartPosition::Anchor(BoundsAnchor::TopLeft), &mut rng),
+            [-3, -3]
+        );
+        assert_eq!(resolve(StartPosition::Pixel([5, 1]), &mut rng), [2, -2]);
+        for _ in 0..50 {
+            assert!(bounds.contains(resolve(StartPosition::Random, &mut rng)));
+        }
+
+        assert!(
+            StartPosition::Pixel([6, 0])
+                .world_position(Some(&bounds), &mut rng)
+                .is_err()
+        );
+        assert!(
+            StartPosition::Random
+                .world_position(None, &mut rng)
+                .is_err()
+        );
+        assert_eq!(
+            StartPosition::Origin
+                .world_position(None, &mut rng)
+                .unwrap(),
+            [0, 0]
+        );
+
+        let single = BoundingBox {
+            min: [4, 4],
+            max: [4, 4],
+        };
+        assert_eq!(
+            StartPosition::Random
+                .world_position(Some(&single), &mut rng)
+                .unwrap(),
+            [4, 4]
+        );
+    }
+
+    // Tests the first tile lands at the chosen start inside anchored bounds
+    // Verified by always placing the first tile at the origin
+    #[test]
+    fn test_first_tile_at_start() {
+        let mut corner = executor(
+            Some((9, 7)),
+            BoundsAnchor::TopLeft,
+            StartPosition::Anchor(BoundsAnchor::BottomRight),
+        )
+        .unwrap();
+        let bounds = corner.grid_state.generation_bounds.clone().unwrap();
+        assert_eq!((bounds.min, bounds.max), ([0, 0], [8, 6]));
+
+        corner.run_iteration().unwrap();
+        assert!(corner.tile_at([8, 6]).is_some());
+        assert_eq!(corner.tile_at([0, 0]), None);
+        assert_eq!(corner.grid_state.placed_in_bounds, 1);
+
+        let mut pixel = executor(
+            Some((9, 7)),
+            BoundsAnchor::Center,
+            StartPosition::Pixel([0, 6]),
+        )
+        .unwrap();
+        pixel.run_iteration().unwrap();
+        assert!(pixel.tile_at([-4, 3]).is_some());
+
+        assert!(executor(None, BoundsAnchor::Center, StartPosition::Random).is_err());
+    }
+}
@@ -0,0 +1,303 @@
+//! Tests for position selection strategies
+
+#[cfg(test)]
+mod tests {
+    use greedytile::algorithm::executor::{GreedyStochastic, RandomSelector};
+    use greedytile::algorithm::positioning::{
+        MinimumEntropySelector, PositionContext, PositionSelector, PositionStrategy,
+        RandomFrontierSelector, ScanlineSelector, SpiralSelector, WeightedSelector,
+    };
+    use greedytile::spatial::grid::BoundingBox;
+
+    fn select_world(
+        executor: &mut GreedyStochastic,
+        selector: &mut dyn PositionSelector,
+    ) -> [i32; 2] {
+        let context = PositionContext {
+            grid_state: &executor.grid_state,
+            selection_tally: &executor.selection_tally,
+            step_data: &executor.step_data,
+            system_offset: executor.system_offset,
+        };
+        let index = selector
+            .select(&context, &mut executor.random_selector)
+            .expect("a position should be available");
+        context.world_position(index)
+    }
+
+    fn grid_index(executor: &GreedyStochastic, world: [i32; 2]) -> [usize; 2] {
+        [
+            (world[0] + executor.system_offset[0]) as usize,
+            (world[1] + executor.system_offset[1]) as usize,
+        ]
+    }
+
+    // Tests every strategy drives the executor without running out of positions
+    // Verified by returning None from the spiral selector after the first ring
+    #[test]
+    fn test_all_strategies_run() {
+        for strategy in [
+            PositionStrategy::Weighted,
+            PositionStrategy::MinimumEntropy,
+            PositionStrategy::Scanline,
+            PositionStrategy::Spiral,
+            PositionStrategy::RandomFrontier,
+        ] {
+            let mut executor = GreedyStochastic::new(7).expect("Failed to create executor");
+            executor.position_selector = strategy.selector();
+
+            for i in 0..40 {
+                executor
+                    .run_iteration()
+                    .unwrap_or_else(|e| unreachable!("{strategy} failed at iteration {i}: {e}"));
+            }
+
+            let placed = executor
+                .grid_state
+                .locked_tiles
+                .iter()
+                .filter(|&&locked| locked > 1)
+                .count();
+            assert!(placed > 10, "{strategy} placed only {placed} tiles");
+        }
+    }
+
+    // Tests the spiral starts at the origin and continues clockwise from the top
+    // Verified by ordering rings by Manhattan instead of Chebyshev distance
+    #[test]
+    fn test_spiral_order() {
+        let mut executor = GreedyStochastic::new(1).expect("Failed to create executor");
+
+        assert_eq!(select_world(&mut executor, &mut SpiralSelector), [0, 0]);
+
+        let mut order = Vec::new();
+        for _ in 0..9 {
+            let world = select_world(&mut executor, &mut SpiralSelector);
+            order.push(world);
+            let index = grid_index(&executor, world);
+            *executor.grid_state.locked_tiles.get_mut(index).unwrap() = 2;
+        }
+
+        assert_eq!(
+            order,
+            vec![
+                [0, 0],
+                [-1, 0],
+                [-1, 1],
+                [0, 1],
+                [1, 1],
+                [1, 0],
+                [1, -1],
+                [0, -1],
+                [-1, -1],
+            ]
+        );
+    }
+
+    // Tests the frontier path picks the same position as the full-grid scan
+    // at every step, bounded and unbounded, from the same random state
+    // Verified by sorting the weighted candidates instead of keeping heap order
+    #[test]
+    fn test_weighted_selection_matches_full_scan() {
+        for (seed, bounds) in [
+            (3, None),
+            (11, None),
+            (
+                5,
+                Some(BoundingBox {
+                    min: [-4, -6],
+                    max: [5, 3],
+                }),
+            ),
+        ] {
+            let mut executor = GreedyStochastic::new(seed).expect("Failed to create executor");
+            let offset = executor.system_offset;
+            executor.grid_state.set_generation_bounds(bounds, offset);
+            let mut selector = WeightedSelector::default();
+
+            for step in 0..80 {
+                let context = PositionContext {
+                    grid_state: &executor.grid_state,
+                    selection_tally: &executor.selection_tally,
+                    step_data: &executor.step_data,
+                    system_offset: executor.system_offset,
+                };
+                let indexed = selector.select(&context, &mut RandomSelector::new(step));
+                let scanned =
+                    selector.select_by_full_scan(&context, &mut RandomSelector::new(step));
+                assert_eq!(indexed, scanned, "seed {seed} diverged at step {step}");
+
+                if executor.grid_state.placed_in_bounds >= 90 {
+                    break;
+                }
+                executor.run_iteration().expect("Failed to run iteration");
+            }
+        }
+    }
+
+    // Tests the scanline and spiral selectors and the frontier candidates
+    // match a scan over every open position throughout a run
+    // Verified by sizing the scanline square without the next tile
+    #[test]
+    fn test_selectors_match_full_grid_scan() {
+        let mut executor = GreedyStochastic::new(4).expect("Failed to create executor");
+
+        for step in 0..60 {
+            let context = PositionContext {
+                grid_state: &executor.grid_state,
+                selection_tally: &executor.selection_tally,
+                step_data: &executor.step_data,
+                system_offset: executor.system_offset,
+            };
+            let open: Vec<[usize; 2]> = context.open_positions().collect();
+
+            let placed = context
+                .grid_state
+                .locked_tiles
+                .iter()
+                .filter(|&&locked| locked > 1)
+                .count();
+            let half_width = ((placed + 1) as f64).sqrt().ceil() as i32 / 2;
+            let scanned_scanline = open.iter().copied().find(|&index| {
+                let [row, col] = context.world_position(index);
+                row.abs() <= half_width && col.abs() <= half_width
+            });
+
+            let spiral_key = |index: [usize; 2]| {
+                let [row, col] = context.world_position(index);
+                let angle = f64::from(col)
+                    .atan2(-f64::from(row))
+                    .rem_euclid(std::f64::consts::TAU);
+                (row.abs().max(col.abs()), angle)
+            };
+            let scanned_spiral = open.iter().copied().min_by(|&a, &b| {
+                let (ring_a, angle_a) = spiral_key(a);
+                let (ring_b, angle_b) = spiral_key(b);
+                ring_a.cmp(&ring_b).then(angle_a.total_cmp(&angle_b))
+            });
+
+            let scanned_frontier: Vec<[usize; 2]> = open
+                .iter()
+                .copied()
+                .filter(|&index| {
+                    context
+                        .grid_state
+                        .adjacency_weights
+                        .get(index)
+                        .is_some_and(|&adjacency| adjacency > 1)
+                })
+                .collect();
+
+            let mut random = RandomSelector::new(step);
+            assert_eq!(
+                ScanlineSelector.select(&context, &mut random),
+                scanned_scanline,
+                "scanline diverged at step {step}"
+            );
+            assert_eq!(
+                SpiralSelector.select(&context, &mut random),
+                scanned_spiral,
+                "spiral diverged at step {step}"
+            );
+            assert_eq!(
+                context.frontier_positions(),
+                scanned_frontier,
+                "frontier diverged at step {step}"
+            );
+
+            executor.run_iteration().expect("Failed to run iteration");
+        }
+    }
+
+    // Tests scanline starts at the top-left corner of the generation bounds
+    // Verified by scanning in column-major order
+    #[test]
+    fn test_scanline_follows_bounds() {
+        let mut executor = GreedyStochastic::new(1).expect("Failed to create executor");
+        executor.grid_state.generation_bounds = Some(BoundingBox {
+            min: [-2, -2],
+            max: [2, 2],
+        });
+
+        assert_eq!(select_world(&mut executor, &mut ScanlineSelector), [-2, -2]);
+
+        let index = grid_index(&executor, [-2, -2]);
+        *executor.grid_state.locked_tiles.get_mut(index).unwrap() = 2;
+        assert_eq!(select_world(&mut executor, &mut ScanlineSelector), [-2, -1]);
+    }
+
+    // Tests minimum entropy picks a cell dominated by one tile before uniform cells
+    // Verified by selecting the maximum entropy instead
+    #[test]
+    fn test_minimum_entropy_selection() {
+        let mut executor = GreedyStochastic::new(1).expect("Failed to create executor");
+        let target = [1, -1];
+        let index = grid_index(&executor, target);
+        for (tile, layer) in executor
+            .grid_state
+            .tile_probabilities
+            .iter_mut()
+            .enumerate()
+        {
+            layer.fill(1.0);
+            *layer.get_mut(index).unwrap() = if tile == 0 { 100.0 } else { 0.01 };
+        }
+
+        assert_eq!(
+            select_world(&mut executor, &mut MinimumEntropySelector),
+            target
+        );
+
+        *executor.grid_state.locked_tiles.get_mut(index).unwrap() = 2;
+        assert_ne!(
+            select_world(&mut executor, &mut MinimumEntropySelector),
+            target
+        );
+    }
+
+    // Tests random frontier only picks positions adjacent to placed tiles
+    // Verified by dropping the adjacency filter
+    #[test]
+    fn test_random_frontier_prefers_adjacent_positions() {
+        let mut executor = GreedyStochastic::new(1).expect("Failed to create executor");
+        executor.grid_state.adjacency_weights.fill(1);
+        let frontier = [[0, 1], [2, 0]];
+        for world in frontier {
+            let index = grid_index(&executor, world);
+            *executor
+                .grid_state
+                .adjacency_weights
+                .get_mut(index)
+                .unwrap() = 3;
+        }
+        let offset = executor.system_offset;
+        executor.grid_state.set_generation_bounds(None, offset);
+
+        for _ in 0..20 {
+            let chosen = select_world(&mut executor, &mut RandomFrontierSelector);
+            assert!(
+                frontier.contains(&chosen),
+                "{chosen:?} is not on the frontier"
+            );
+        }
+    }
+
+    // Tests strategies parse from their command-line spellings
+    // Verified by removing the 'frontier' arm
+    #[test]
+    fn test_position_strategy_from_str() {
+        for strategy in [
+            PositionStrategy::Weighted,
+            PositionStrategy::MinimumEntropy,
+            PositionStrategy::Scanline,
+            PositionStrategy::Spiral,
+            PositionStrategy::RandomFrontier,
+        ] {
+            assert_eq!(
+                strategy.to_string().parse::<PositionStrategy>().unwrap(),
+                strategy
+            );
+        }
+        assert!("diagonal".parse::<PositionStrategy>().is_err());
+    }
+}
@@ -3,10 +3,15 @@
 #[cfg(test)]
 mod tests {
 
+    use greedytile::algorithm::cache::ViableTilesCache;
+    use greedytile::algorithm::executor::GreedyStochastic;
     use greedytile::algorithm::propagation::{
-        ForcedPipeline, ForcedPosition, StepData, update_probabilities_and_entropy,
+        ForcedPipeline, ForcedPosition, StepData, check_for_contradiction,
+        update_probabilities_and_entropy,
     };
+    use greedytile::algorithm::selection::compute_viable_tiles_at_position;
     use greedytile::spatial::GridState;
+    use greedytile::spatial::grid::BoundingBox;
     use ndarray::Array4;
     use std::collections::HashMap;
 
@@ -64,10 +69,10 @@ mod tests {
         for row in 0..3 {
             for col in 0..3 {
                 for color in 0..2 {
-                    if let Some(prob) = grid_state.tile_probabilities.get_mut(color) {
-                        if let Some(val) = prob.get_mut([row, col]) {
-                            *val = 1.0;
-                        }
+                    if let Some(prob) = grid_state.tile_probabilities.get_mut(color)
+                        && let Some(val) = prob.get_mut([row, col])
+                    {
+                        *val = 1.0;
                     }
                 }
                 if let Some(val) = grid_state.entropy.get_mut([row, col]) {
@@ -136,24 +141,7 @@ mod tests {
 
         for row in 0..5 {
             for col in 0..5 {
-                if let Some(prob) = grid_state.tile_probabilities.get_mut(0) {
-                    if let Some(val) = prob.get_mut([row, col]) {
-                        *val = 0.8;
-                    }
-                }
-                if let Some(prob) = grid_state.tile_probabilities.get_mut(1) {
-                    if let Some(val) = prob.get_mut([row, col]) {
-                        *val = 1.2;
-                    }
-                }
-                if let Some(prob) = grid_state.tile_probabilities.get_mut(2) {
-                    if let Some(val) = prob.get_mut([row, col]) {
-                        *val = 0.6;
-                    }
-                }
-                if let Some(val) = grid_state.entropy.get_mut([row, col]) {
-                    *val = 0.0;
-                }
+                grid_state.apply_influence([row, col], [0.8, 1.2, 0.6]);
             }
         }
 
@@ -260,19 +248,7 @@ mod tests {
 
         for row in 0..3 {
             for col in 0..3 {
-                if let Some(prob) = grid_state.tile_probabilities.get_mut(0) {
-                    if let Some(val) = prob.get_mut([row, col]) {
-                        *val = 1e-20;
-                    }
-                }
-                if let Some(prob) = grid_state.tile_probabilities.get_mut(1) {
-                    if let Some(val) = prob.get_mut([row, col]) {
-                        *val = 1e-20;
-                    }
-                }
-                if let Some(val) = grid_state.entropy.get_mut([row, col]) {
-                    *val = 0.0;
-                }
+                grid_state.apply_influence([row, col], [1e-20, 1e-20]);
             }
         }
 
@@ -316,4 +292,123 @@ mod tests {
                 .is_some_and(|&v| v.abs() < f64::EPSILON)
         );
     }
+
+    fn full_scan_contradictions(executor: &GreedyStochastic) -> Vec<[i32; 2]> {
+        let grid = &executor.grid_state;
+        let offset = executor.system_offset;
+        let mut cache = ViableTilesCache::new();
+        let mut found = Vec::new();
+
+        for ((i, j), &locked) in grid.locked_tiles.indexed_iter() {
+            let frontier = grid.adjacency_weights.get([i, j]).copied().unwrap_or(0) > 1;
+            if locked > 1 || !frontier {
+                continue;
+            }
+            let coords = [i as i32 - offset[0], j as i32 - offset[1]];
+            let viable = compute_viable_tiles_at_position(
+                grid,
+                coords,
+                offset,
+                &executor.step_data.source_tiles,
+                &executor.step_data,
+                &mut cache,
+            );
+            if viable.is_empty() {
+                found.push(coords);
+            }
+        }
+        found
+    }
+
+    // Tests every contradiction a full-grid scan would find is caught from the dirty set
+    // Verified by not marking cells dirty when a tile is placed
+    #[test]
+    fn test_dirty_cells_cover_all_contradictions() {
+        // Seed 8 hits contradictions at iterations 10 and 63
+        let mut executor = GreedyStochastic::new(8).expect("Failed to create executor");
+
+        for _ in 0..70 {
+            executor.run_iteration().expect("Failed to run iteration");
+
+            for coords in full_scan_contradictions(&executor) {
+                assert!(
+                    executor.grid_state.dirty_cells.contains(&coords),
+                    "Contradiction at {coords:?} is not marked dirty"
+                );
+            }
+        }
+
+        let cell_count = executor.grid_state.rows() * executor.grid_state.cols();
+        let offset = executor.system_offset;
+        let mut cache = ViableTilesCache::new();
+        check_for_contradiction(
+            &mut executor.grid_state,
+            offset,
+            &executor.step_data,
+            &mut cache,
+        );
+        assert!(
+            executor.grid_state.dirty_cells.len() < cell_count / 4,
+            "Clean cells should be cleared from the dirty set"
+        );
+    }
+
+    // Tests contradictions at cells outside the generation bounds are not
+    // reported, since those cells are never filled, while the same cells
+    // inside the bounds are
+    // Verified by checking every dirty cell regardless of the bounds
+    #[test]
+    fn test_contradictions_outside_bounds_are_skipped() {
+        let mut executor = GreedyStochastic::new(1).expect("Failed to create executor");
+        for _ in 0..10 {
+            executor.run_iteration().expect("Failed to run iteration");
+        }
+
+        // A tile no source window holds next to the latest placement leaves
+        // its open neighbours without a viable tile
+        let offset = executor.system_offset;
+        let [row, col] = executor.selection_coordinates;
+        let blocker = [row - 1, row, row + 1]
+            .into_iter()
+            .flat_map(|r| [col - 1, col, col + 1].map(|c| [r, c]))
+            .find(|&[r, c]| {
+                let index = [(r + offset[0]) as usize, (c + offset[1]) as usize];
+                *executor.grid_state.locked_tiles.get(index).unwrap() <= 1
+            })
+            .expect("the latest placement should have an open neighbour");
+        let index = [
+            (blocker[0] + offset[0]) as usize,
+            (blocker[1] + offset[1]) as usize,
+        ];
+        *executor.grid_state.locked_tiles.get_mut(index).unwrap() =
+            executor.step_data.unique_cell_count as u32 + 2;
+        let contradictions = full_scan_contradictions(&executor);
+        assert!(!contradictions.is_empty());
+
+        let mut cache = ViableTilesCache::new();
+        executor.grid_state.mark_dirty(blocker, 2);
+        executor.grid_state.generation_bounds = Some(BoundingBox {
+            min: [1000, 1000],
+            max: [1010, 1010],
+        });
+        let outside = check_for_contradiction(
+            &mut executor.grid_state,
+            offset,
+            &executor.step_data,
+            &mut cache,
+        );
+        assert_eq!(outside, None);
+
+        executor.grid_state.generation_bounds = None;
+        for coords in &contradictions {
+            executor.grid_state.mark_dirty(*coords, 0);
+        }
+        let inside = check_for_contradiction(
+            &mut executor.grid_state,
+            offset,
+            &executor.step_data,
+            &mut cache,
+        );
+        assert!(inside.is_some());
+    }
 }
@@ -0,0 +1,216 @@
+//! Tests for rebuilding generation state from event logs
+
+#[cfg(test)]
+mod tests {
+    use greedytile::algorithm::backtracking::DeadlockStrategy;
+    use greedytile::algorithm::executor::{AlgorithmConfig, GreedyStochastic};
+    use greedytile::algorithm::replay::first_divergence;
+    use greedytile::analysis::patterns::ImageProcessor;
+    use greedytile::io::events::{DecisionSource, EventLog, GenerationEvent};
+    use greedytile::io::fields::{FieldSchedule, FieldSnapshot};
+
+    fn backtracking_executor(seed: u64) -> GreedyStochastic {
+        let config = AlgorithmConfig {
+            bounds: Some((12, 12)),
+            deadlock_strategy: DeadlockStrategy::Backtrack(5000),
+            ..AlgorithmConfig::default()
+        };
+        let processor = ImageProcessor::from_png_file("data/a.png").unwrap();
+        GreedyStochastic::from_image_processor(processor, config, seed).unwrap()
+    }
+
+    // Every field of the executor's grid, with floats as bits so NaN compares equal
+    fn fields(executor: &GreedyStochastic) -> (Vec<u64>, Vec<u32>, Vec<u8>) {
+        let snapshot = FieldSnapshot::capture(
+            &executor.grid_state,
+            executor.system_offset,
+            executor.iteration,
+        );
+        let floats = snapshot
+            .tile_probabilities
+            .iter()
+            .chain(&snapshot.entropy)
+            .chain(&snapshot.feasibility)
+            .map(|value| value.to_bits())
+            .collect();
+        let integers = snapshot
+            .adjacency_weights
+            .iter()
+            .chain(&snapshot.locked_tiles)
+            .copied()
+            .collect();
+        let removals = executor
+            .grid_state
+            .removal_count
+            .indexed_iter()
+            .map(|(_, &count)| count)
+            .collect();
+        (floats, integers, removals)
+    }
+
+    // Runs an executor with its event log enabled for some iterations
+    fn recorded(mut executor: GreedyStochastic, iterations: usize) -> GreedyStochastic {
+        executor.enable_event_log();
+        for _ in 0..iterations {
+            executor.run_iteration().unwrap();
+        }
+        executor
+    }
+
+    // Tests replaying a log through its removals rebuilds every field, the
+    // tally and the removal statistics, from the log read back from JSON
+    // Verified by not counting the removal at the contradiction
+    #[test]
+    fn test_replay_rebuilds_state() {
+        // Seed 26 resolves deadlocks at iterations 24, 34, 42 and 47
+        let original = recorded(GreedyStochastic::new(26).unwrap(), 50);
+        let log = EventLog::from_jsonl(&original.events.as_ref().unwrap().to_jsonl()).unwrap();
+        assert_eq!(log.events(), original.events.as_ref().unwrap().events());
+
+        let mut replayed = GreedyStochastic::new(26).unwrap();
+        let applied = replayed.replay_events(log.events(), None).unwrap();
+        assert_eq!(applied, log.events().len());
+        assert_eq!(replayed.iteration, original.iteration);
+        assert_eq!(replayed.system_offset, original.system_offset);
+        assert_eq!(fields(&replayed), fields(&original));
+        assert_eq!(replayed.selection_tally, original.selection_tally);
+        assert_eq!(
+            replayed.deadlock_stats().deadlocks,
+            original.deadlock_stats().deadlocks
+        );
+        assert_eq!(
+            replayed.deadlock_stats().tiles_removed,
+            original.deadlock_stats().tiles_removed
+        );
+    }
+
+    // Tests replaying up to an iteration matches a run stopped there, and
+    // backtracks are replayed as well as placements
+    // Verified by applying the events of the stopping iteration's successor
+    #[test]
+    fn test_replay_until_iteration() {
+        let original = recorded(backtracking_executor(1), 150);
+        let events = original.events.as_ref().unwrap().events();
+        assert!(
+            events
+                .iter()
+                .any(|event| matches!(event, GenerationEvent::Backtrack { .. }))
+        );
+
+        for until in [1, 60, 150] {
+            let stopped = recorded(backtracking_executor(1), until);
+            let mut replayed = backtracking_executor(1);
+            replayed.replay_events(events, Some(until)).unwrap();
+            assert_eq!(replayed.iteration, until);
+            assert_eq!(fields(&replayed), fields(&stopped), "iteration {until}");
+        }
+    }
+
+    // Tests replayed runs fill enabled captures as the original did, so the
+    // replay's own log and snapshots match
+    // Verified by recording no snapshot for the last replayed iteration
+    #[test]
+    fn test_replay_fills_captures() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut original = GreedyStochastic::new(26).unwrap();
+        original.enable_field_capture(FieldSchedule::Every(10), temp_dir.path().join("original"));
+        original.enable_visualization(50);
+        let original = recorded(original, 50);
+
+        let mut replayed = GreedyStochastic::new(26).unwrap();
+        replayed.enable_event_log();
+        replayed.enable_field_capture(FieldSchedule::Every(10), temp_dir.path().join("replayed"));
+        replayed.enable_visualization(50);
+        replayed
+            .replay_events(original.events.as_ref().unwrap().events(), None)
+            .unwrap();
+
+        assert_eq!(
+            replayed.events.as_ref().unwrap().events(),
+            original.events.as_ref().unwrap().events()
+        );
+        let iterations = |executor: &GreedyStochastic| -> Vec<usize> {
+            executor
+                .fields
+                .as_ref()
+                .unwrap()
+                .snapshots()
+                .iter()
+                .map(|snapshot| snapshot.iteration)
+                .collect()
+        };
+        assert_eq!(iterations(&replayed), vec![10, 20, 30, 40, 50]);
+        assert_eq!(
+            replayed
+                .visualization
+                .as_ref()
+                .unwrap()
+                .get_placements()
+                .len(),
+            original
+                .visualization
+                .as_ref()
+                .unwrap()
+                .get_placements()
+                .len()
+        );
+    }
+
+    // Tests events that do not fit the rebuilt state are rejected
+    // Verified by placing over occupied positions
+    #[test]
+    fn test_replay_rejects_mismatched_log() {
+        let placement = |iteration, position, tile| GenerationEvent::Placement {
+            iteration,
+            position,
+            tile,
+            source: DecisionSource::Selected,
+            candidates: Vec::new(),
+        };
+        let replays = |events: &[GenerationEvent]| {
+            GreedyStochastic::new(1)
+                .unwrap()
+                .replay_events(events, None)
+                .is_ok()
+        };
+
+        assert!(replays(&[placement(1, [0, 0], 1), placement(2, [0, 1], 2)]));
+        assert!(!replays(&[placement(1, [0, 0], 999)]));
+        assert!(!replays(&[
+            placement(1, [0, 0], 1),
+            placement(2, [0, 0], 2)
+        ]));
+        assert!(!replays(&[
+            placement(2, [0, 0], 1),
+            placement(1, [0, 1], 2)
+        ]));
+        assert!(!replays(&[GenerationEvent::Removal {
+            iteration: 1,
+            position: [0, 0],
+            radius: 1,
+            removed: vec![[5, 5]],
+        }]));
+    }
+
+    // Tests logs of runs with the same seed agree and the first differing
+    // event of another seed or a shorter log is found
+    // Verified by comparing only the shared prefix
+    #[test]
+    fn test_first_divergence() {
+        let first = recorded(GreedyStochastic::new(3).unwrap(), 30);
+        let second = recorded(GreedyStochastic::new(3).unwrap(), 30);
+        let other = recorded(GreedyStochastic::new(4).unwrap(), 30);
+        let events = |executor: &GreedyStochastic| executor.events.clone().unwrap();
+
+        let recorded_events = events(&first);
+        let recorded_events = recorded_events.events();
+        assert_eq!(
+            first_divergence(recorded_events, events(&second).events()),
+            None
+        );
+        assert!(first_divergence(recorded_events, events(&other).events()).is_some());
+
+        let (prefix, _) = recorded_events.split_at(10);
+        assert_eq!(first_divergence(recorded_events, prefix), Some(10));
+    }
+}
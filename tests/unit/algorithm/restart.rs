@@ -0,0 +1,175 @@
+//! Tests for restarting failed generations with derived seeds
+
+#[cfg(test)]
+mod tests {
+    use greedytile::algorithm::completion::RunLimits;
+    use greedytile::algorithm::deadlock::DeadlockPolicy;
+    use greedytile::algorithm::executor::{AlgorithmConfig, GreedyStochastic};
+    use greedytile::algorithm::restart::{
+        AttemptScore, attempt_seed, ratio_deviation, run_with_restarts,
+    };
+    use greedytile::analysis::patterns::ImageProcessor;
+    use greedytile::io::error::{AlgorithmError, Result};
+    use std::cmp::Ordering;
+    use std::collections::{BTreeSet, HashSet};
+
+    const LIMITS: RunLimits = RunLimits::iterations(20_000);
+
+    // Source that fails on its first deadlock
+    fn fragile_executor(seed: u64) -> Result<GreedyStochastic> {
+        let config = AlgorithmConfig {
+            bounds: Some((12, 12)),
+            deadlock_policy: DeadlockPolicy::default().with_max_attempts(1),
+            ..AlgorithmConfig::default()
+        };
+        let processor = ImageProcessor::from_png_file("data/a.png")?;
+        GreedyStochastic::from_image_processor(processor, config, seed)
+    }
+
+    // Tests the first attempt keeps the base seed and later attempts derive
+    // distinct seeds from it
+    // Verified by deriving a seed for the first attempt too
+    #[test]
+    fn test_attempt_seeds() {
+        assert_eq!(attempt_seed(5, 0), 5);
+        let seeds: HashSet<u64> = (0..50).map(|attempt| attempt_seed(5, attempt)).collect();
+        assert_eq!(seeds.len(), 50);
+        assert_ne!(attempt_seed(5, 1), attempt_seed(6, 1));
+    }
+
+    // Tests the distance is zero for matching proportions, half the moved
+    // mass otherwise, and maximal for an empty tally
+    // Verified by not halving the summed differences
+    #[test]
+    fn test_ratio_deviation() {
+        assert!(ratio_deviation(&[2, 2], &[0.5, 0.5]).abs() < 1e-12);
+        assert!((ratio_deviation(&[4, 0], &[0.5, 0.5]) - 0.5).abs() < 1e-12);
+        assert!((ratio_deviation(&[1, 3], &[0.5, 0.5]) - 0.25).abs() < 1e-12);
+        assert!((ratio_deviation(&[0, 0], &[0.5, 0.5]) - 1.0).abs() < 1e-12);
+    }
+
+    // Tests completeness outranks proportions, which outrank deadlocks
+    // Verified by preferring the larger ratio deviation
+    #[test]
+    fn test_rank_is_lexicographic() {
+        let score = |completeness, ratio_deviation, deadlocks| AttemptScore {
+            completeness,
+            ratio_deviation,
+            deadlocks,
+        };
+
+        assert_eq!(
+            score(0.9, 0.5, 9).rank(&score(0.8, 0.0, 0)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            score(0.9, 0.1, 9).rank(&score(0.9, 0.2, 0)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            score(0.9, 0.1, 1).rank(&score(0.9, 0.1, 2)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            score(0.9, 0.1, 1).rank(&score(0.9, 0.1, 1)),
+            Ordering::Equal
+        );
+        assert!(score(1.0, 0.3, 0).is_complete());
+        assert!(!score(0.99, 0.0, 0).is_complete());
+    }
+
+    // Tests a failed first attempt is retried and the first completed
+    // attempt ends the run with its seed recorded
+    // Verified by continuing after a completed attempt
+    #[test]
+    fn test_restart_stops_at_first_success() {
+        let mut attempts_seen = BTreeSet::new();
+        let best = run_with_restarts(73, 3, &LIMITS, fragile_executor, |attempt, _| {
+            attempts_seen.insert(attempt);
+        })
+        .unwrap();
+
+        assert!(best.succeeded());
+        assert!(best.error.is_none());
+        assert_eq!(best.attempt, 1);
+        assert_eq!(best.attempts, 2);
+        assert_eq!(best.seed, attempt_seed(73, 1));
+        assert_eq!(attempts_seen.into_iter().collect::<Vec<_>>(), vec![0, 1]);
+    }
+
+    // Tests the most complete attempt is kept when every attempt fails
+    // Verified by keeping the first attempt
+    #[test]
+    fn test_best_failed_attempt_is_kept() {
+        let best = run_with_restarts(2, 5, &LIMITS, fragile_executor, |_, _| {}).unwrap();
+
+        assert!(!best.succeeded());
+        assert!(
+            best.error
+                .as_ref()
+                .is_some_and(AlgorithmError::is_generation_failure)
+        );
+        assert_eq!(best.attempts, 6);
+        for attempt in 0..6 {
+            let single = run_with_restarts(
+                attempt_seed(2, attempt),
+                0,
+                &LIMITS,
+                fragile_executor,
+                |_, _| {},
+            )
+            .unwrap();
+            assert_ne!(best.score.rank(&single.score), Ordering::Less);
+        }
+        assert_eq!(best.seed, attempt_seed(2, best.attempt));
+        assert_ne!(best.attempt, 0);
+    }
+
+    // Tests without restarts the failure is returned with the attempt, and
+    // errors other than generation failures abort the run
+    // Verified by retrying after a failure to build the executor
+    #[test]
+    fn test_failures_without_restarts() {
+        let single = run_with_restarts(1, 0, &LIMITS, fragile_executor, |_, _| {}).unwrap();
+        assert_eq!(single.attempts, 1);
+        assert_eq!(single.seed, 1);
+        assert!(matches!(
+            single.error,
+            Some(AlgorithmError::Unsolvable { .. })
+        ));
+
+        let mut builds = 0;
+        let result = run_with_restarts(
+            1,
+            3,
+            &RunLimits::iterations(100),
+            |_| {
+                builds += 1;
+                ImageProcessor::from_png_file("data/missing.png").and_then(|_| fragile_executor(1))
+            },
+            |_, _| {},
+        );
+        assert!(result.is_err());
+        assert_eq!(builds, 1);
+    }
+
+    // Tests the winning seed and score are saved as JSON
+    // Verified by omitting the attempt count
+    #[test]
+    fn test_export_metadata() {
+        let best = run_with_restarts(73, 3, &LIMITS, fragile_executor, |_, _| {}).unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("nested").join("a_result.json");
+        best.export_metadata(path.to_str().unwrap()).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let field = |name: &str| json.get(name).unwrap();
+        assert_eq!(field("seed"), attempt_seed(73, 1));
+        assert_eq!(field("attempt"), 1);
+        assert_eq!(field("attempts"), 2);
+        assert_eq!(field("complete"), true);
+        assert_eq!(field("completeness"), 1.0);
+        assert_eq!(field("deadlocks"), best.score.deadlocks);
+    }
+}
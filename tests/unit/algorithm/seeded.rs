@@ -0,0 +1,142 @@
+//! Tests for order-independent generation from per-chunk seeds
+
+#[cfg(test)]
+mod tests {
+    use greedytile::algorithm::executor::{AlgorithmConfig, GreedyStochastic};
+    use greedytile::algorithm::seeded::{SeededWorld, chunk_class, chunk_dependencies, chunk_seed};
+    use greedytile::analysis::patterns::ImageProcessor;
+    use greedytile::io::configuration::SEAM_REPAIR_WIDTH;
+
+    const CHUNK_SIZE: usize = 11;
+
+    // Source whose chunks fill within a few attempts, keeping the tests fast
+    fn executor(seed: u64) -> GreedyStochastic {
+        let processor = ImageProcessor::from_png_file("data/d.png").unwrap();
+        GreedyStochastic::from_image_processor(processor, AlgorithmConfig::default(), seed).unwrap()
+    }
+
+    fn world(executor_seed: u64, seed: u64) -> SeededWorld {
+        SeededWorld::new(executor(executor_seed), seed, CHUNK_SIZE).unwrap()
+    }
+
+    fn chunk_tiles(world: &SeededWorld, chunk: [i32; 2]) -> Vec<Option<usize>> {
+        let bounds = world.chunk_bounds(chunk);
+        (bounds.min[0]..=bounds.max[0])
+            .flat_map(|row| (bounds.min[1]..=bounds.max[1]).map(move |col| [row, col]))
+            .map(|position| world.tile(position))
+            .collect()
+    }
+
+    // Tests two clients requesting chunks in different orders, with different
+    // executor seeds and unrelated history, generate identical tiles
+    // Verified by generating a chunk before its lower-class neighbours
+    #[test]
+    fn test_chunks_are_independent_of_request_order() {
+        let mut direct = world(1, 7);
+        assert!(direct.generate_chunk([0, 1]).unwrap());
+
+        let mut reordered = world(2, 7);
+        for chunk in [[4, 4], [0, 2], [0, 0], [0, 1]] {
+            assert!(reordered.generate_chunk(chunk).unwrap());
+        }
+
+        for chunk in [[0, 0], [0, 1], [0, 2]] {
+            assert!(direct.is_generated(chunk));
+            let tiles = chunk_tiles(&direct, chunk);
+            assert!(tiles.iter().all(Option::is_some));
+            assert_eq!(tiles, chunk_tiles(&reordered, chunk), "chunk {chunk:?}");
+        }
+        assert!(!direct.is_generated([4, 4]));
+    }
+
+    // Tests the seams a chunk repairs in its neighbours are written back, so
+    // every window across them appears in the source
+    // Verified by copying back only the chunk's own tiles
+    #[test]
+    fn test_repaired_seams_match_source() {
+        let mut world = world(1, 7);
+        assert!(world.generate_chunk([0, 1]).unwrap());
+
+        let source_tiles = &world.executor().step_data.source_tiles;
+        let width = 3 * CHUNK_SIZE as i32;
+        for row in 0..CHUNK_SIZE as i32 - 2 {
+            for col in 0..width - 2 {
+                let mut window = [[0usize; 3]; 3];
+                for (di, window_row) in window.iter_mut().enumerate() {
+                    for (dj, cell) in window_row.iter_mut().enumerate() {
+                        *cell = world.tile([row + di as i32, col + dj as i32]).unwrap();
+                    }
+                }
+                assert!(
+                    source_tiles.contains(&window),
+                    "window at {:?} does not appear in the source",
+                    [row, col]
+                );
+            }
+        }
+    }
+
+    // Tests the global seed changes a chunk's content
+    // Verified by deriving chunk seeds without the global seed
+    #[test]
+    fn test_global_seed_changes_content() {
+        let mut first = world(1, 7);
+        let mut second = world(1, 8);
+        assert!(first.generate_chunk([0, 0]).unwrap());
+        assert!(second.generate_chunk([0, 0]).unwrap());
+
+        assert_ne!(chunk_tiles(&first, [0, 0]), chunk_tiles(&second, [0, 0]));
+        assert_ne!(chunk_seed(7, [0, 0], 0), chunk_seed(7, [0, 1], 0));
+        assert_ne!(chunk_seed(7, [0, 0], 0), chunk_seed(7, [0, 0], 1));
+    }
+
+    // Tests exactly one of every pair of neighbouring chunks, diagonals
+    // included, depends on the other
+    // Verified by summing the coordinate parities as the class
+    #[test]
+    fn test_neighbours_are_ordered_by_class() {
+        for row in -3..3 {
+            for col in -3..3 {
+                let chunk = [row, col];
+                assert!((0..4).contains(&chunk_class(chunk)));
+                for dependency in chunk_dependencies(chunk) {
+                    assert!(chunk_class(dependency) < chunk_class(chunk));
+                }
+                for neighbour in [
+                    [row, col + 1],
+                    [row + 1, col - 1],
+                    [row + 1, col],
+                    [row + 1, col + 1],
+                ] {
+                    let forward = chunk_dependencies(neighbour).any(|other| other == chunk);
+                    let backward = chunk_dependencies(chunk).any(|other| other == neighbour);
+                    assert!(forward != backward, "{chunk:?} and {neighbour:?}");
+                }
+            }
+        }
+    }
+
+    // Tests chunks too narrow to keep non-neighbours apart are rejected
+    // Verified by leaving the repair bands out of the minimum size
+    #[test]
+    fn test_rejects_narrow_chunks() {
+        let minimum = executor(1).seam_margin() + 2 * SEAM_REPAIR_WIDTH;
+        let minimum = usize::try_from(minimum).unwrap();
+
+        assert!(SeededWorld::new(executor(1), 7, minimum - 1).is_err());
+        assert!(SeededWorld::new(executor(1), 7, minimum).is_ok());
+    }
+
+    // Tests a chunk only counts as settled once all eight neighbours exist
+    // Verified by treating a generated chunk as settled
+    #[test]
+    fn test_settled_after_all_neighbours() {
+        let mut world = world(1, 7);
+        assert!(world.generate_chunk([0, 1]).unwrap());
+        assert!(!world.is_settled([0, 1]));
+
+        assert!(world.settle_chunk([0, 1]).unwrap());
+        assert!(world.is_settled([0, 1]));
+        assert!(!world.is_settled([0, 3]));
+    }
+}
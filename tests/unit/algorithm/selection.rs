@@ -4,9 +4,11 @@
 mod tests {
 
     use greedytile::algorithm::cache::ViableTilesCache;
+    use greedytile::algorithm::executor::RandomSelector;
     use greedytile::algorithm::propagation::StepData;
     use greedytile::algorithm::selection::{
-        compute_viable_tiles_at_position, optimal_density_correction,
+        DensityCorrectedChooser, SoftmaxChooser, TileChoiceContext, TileChooser, TileStrategy,
+        UniformChooser, compute_viable_tiles_at_position, optimal_density_correction,
     };
     use greedytile::spatial::GridState;
     use greedytile::spatial::tiles::Tile;
@@ -210,4 +212,88 @@ mod tests {
             "Larger deviation should produce larger correction magnitude"
         );
     }
+
+    fn choice_counts(chooser: &mut dyn TileChooser, draws: usize) -> Vec<usize> {
+        let viable_tiles = [1, 2, 3];
+        let probabilities = [0.7, 0.2, 0.1];
+        let selection_tally = [0, 0, 0];
+        let source_ratios = [0.7, 0.2, 0.1];
+        let context = TileChoiceContext {
+            viable_tiles: &viable_tiles,
+            probabilities: &probabilities,
+            selection_tally: &selection_tally,
+            source_ratios: &source_ratios,
+        };
+
+        let mut random = RandomSelector::new(11);
+        let mut counts = vec![0; viable_tiles.len()];
+        for _ in 0..draws {
+            let index = chooser.choose(&context, &mut random);
+            if let Some(count) = counts.get_mut(index) {
+                *count += 1;
+            }
+        }
+        counts
+    }
+
+    // Tests softmax temperature moves the choice between argmax and uniform
+    // Verified by multiplying instead of dividing log probabilities by the temperature
+    #[test]
+    fn test_softmax_temperature_controls_sharpness() {
+        let argmax = choice_counts(&mut SoftmaxChooser { temperature: 0.0 }, 100);
+        assert_eq!(argmax, vec![100, 0, 0]);
+
+        let proportional = choice_counts(&mut SoftmaxChooser { temperature: 1.0 }, 3000);
+        assert!(proportional.is_sorted_by(|a, b| a > b), "{proportional:?}");
+
+        let flat = choice_counts(
+            &mut SoftmaxChooser {
+                temperature: 1000.0,
+            },
+            3000,
+        );
+        assert!(
+            flat.iter().all(|&count| (800..1200).contains(&count)),
+            "High temperature should be near uniform: {flat:?}"
+        );
+    }
+
+    // Tests uniform and density-corrected choosers return indices into the viable tiles
+    // Verified by returning the tile reference instead of its index from the uniform chooser
+    #[test]
+    fn test_uniform_and_density_choosers() {
+        let uniform = choice_counts(&mut UniformChooser, 3000);
+        assert!(uniform.iter().all(|&count| (800..1200).contains(&count)));
+
+        let corrected = choice_counts(&mut DensityCorrectedChooser::default(), 3000);
+        assert_eq!(corrected.iter().sum::<usize>(), 3000);
+        assert!(corrected.first() > corrected.last());
+    }
+
+    // Tests tile strategies parse from their command-line spellings
+    // Verified by accepting negative temperatures
+    #[test]
+    fn test_tile_strategy_from_str() {
+        assert_eq!(
+            "density".parse::<TileStrategy>().unwrap(),
+            TileStrategy::DensityCorrected
+        );
+        assert_eq!(
+            "argmax".parse::<TileStrategy>().unwrap(),
+            TileStrategy::Softmax(0.0)
+        );
+        assert_eq!(
+            "softmax:0.5".parse::<TileStrategy>().unwrap(),
+            TileStrategy::Softmax(0.5)
+        );
+        assert_eq!(
+            TileStrategy::Softmax(2.0)
+                .to_string()
+                .parse::<TileStrategy>()
+                .unwrap(),
+            TileStrategy::Softmax(2.0)
+        );
+        assert!("softmax:-1".parse::<TileStrategy>().is_err());
+        assert!("greedy".parse::<TileStrategy>().is_err());
+    }
 }
@@ -0,0 +1,216 @@
+//! Tests for on-demand region generation and chunk eviction
+
+#[cfg(test)]
+mod tests {
+    use greedytile::algorithm::executor::GreedyStochastic;
+    use greedytile::algorithm::streaming::ChunkSnapshot;
+    use greedytile::spatial::grid::BoundingBox;
+
+    fn feasibility_at(executor: &GreedyStochastic, position: [i32; 2]) -> f64 {
+        executor.feasibility_layer.get_fraction(
+            (position[0] + executor.system_offset[0]) as usize,
+            (position[1] + executor.system_offset[1]) as usize,
+        )
+    }
+
+    // Every fully placed 3x3 window inside `area` must be one of the source patterns
+    fn assert_windows_match_source(executor: &GreedyStochastic, area: &BoundingBox) {
+        for row in area.min[0]..area.max[0] - 1 {
+            for col in area.min[1]..area.max[1] - 1 {
+                let mut window = [[0usize; 3]; 3];
+                let mut complete = true;
+                for (di, window_row) in window.iter_mut().enumerate() {
+                    for (dj, cell) in window_row.iter_mut().enumerate() {
+                        let tile = executor.tile_at([row + di as i32, col + dj as i32]);
+                        complete &= tile.is_some();
+                        *cell = tile.unwrap_or(0) as usize;
+                    }
+                }
+                if complete {
+                    assert!(
+                        executor.step_data.source_tiles.contains(&window),
+                        "window at {:?} does not appear in the source",
+                        [row, col]
+                    );
+                }
+            }
+        }
+    }
+
+    fn placed_count(executor: &GreedyStochastic, area: &BoundingBox) -> usize {
+        (area.min[0]..=area.max[0])
+            .flat_map(|row| (area.min[1]..=area.max[1]).map(move |col| [row, col]))
+            .filter(|&position| executor.tile_at(position).is_some())
+            .count()
+    }
+
+    // Tests a region and its repair band are filled without placing tiles further out,
+    // and an adjacent region joins it seamlessly
+    // Verified by generating regions without the repair band
+    #[test]
+    fn test_adjacent_regions_share_a_consistent_seam() {
+        let mut executor = GreedyStochastic::new(4).expect("Failed to create executor");
+        let first = BoundingBox {
+            min: [2, 2],
+            max: [9, 9],
+        };
+        let second = BoundingBox {
+            min: [2, 10],
+            max: [9, 17],
+        };
+        let first_band = BoundingBox {
+            min: [0, 0],
+            max: [11, 11],
+        };
+        let both_bands = BoundingBox {
+            min: [0, 0],
+            max: [11, 19],
+        };
+        let surroundings = BoundingBox {
+            min: [-4, -4],
+            max: [15, 23],
+        };
+
+        assert!(executor.generate_region(&first, 1000).unwrap());
+        assert_eq!(placed_count(&executor, &first_band), 144);
+        assert_eq!(placed_count(&executor, &surroundings), 144);
+
+        let fixed: Vec<Option<u32>> = (0..12)
+            .flat_map(|row| (0..8).map(move |col| [row, col]))
+            .map(|position| executor.tile_at(position))
+            .collect();
+
+        assert!(executor.generate_region(&second, 1000).unwrap());
+        let after: Vec<Option<u32>> = (0..12)
+            .flat_map(|row| (0..8).map(move |col| [row, col]))
+            .map(|position| executor.tile_at(position))
+            .collect();
+        assert_eq!(after, fixed, "tiles outside the second band changed");
+        assert_eq!(placed_count(&executor, &both_bands), 240);
+        assert_eq!(placed_count(&executor, &surroundings), 240);
+        assert_windows_match_source(&executor, &surroundings);
+    }
+
+    // Tests evicted chunks are released, block nearby generation, and restore exactly
+    // Verified by skipping the feasibility counts when restoring a chunk
+    #[test]
+    fn test_evict_and_restore_round_trip() {
+        let mut executor = GreedyStochastic::new(5).expect("Failed to create executor");
+        let mut resident = GreedyStochastic::new(5).expect("Failed to create executor");
+        let home = BoundingBox {
+            min: [2, 2],
+            max: [9, 9],
+        };
+        let home_band = BoundingBox {
+            min: [0, 0],
+            max: [11, 11],
+        };
+        let away = BoundingBox {
+            min: [162, 2],
+            max: [169, 9],
+        };
+        let beside = BoundingBox {
+            min: [2, 10],
+            max: [9, 17],
+        };
+
+        assert!(executor.generate_region(&home, 1000).unwrap());
+        assert!(resident.generate_region(&home, 1000).unwrap());
+        let home_tiles: Vec<Option<u32>> = (0..12)
+            .flat_map(|row| (0..12).map(move |col| [row, col]))
+            .map(|position| executor.tile_at(position))
+            .collect();
+        assert!(
+            executor.evict_chunk([0, 0]).is_err(),
+            "active chunk evicted"
+        );
+
+        assert!(executor.generate_region(&away, 1000).unwrap());
+        assert!(resident.generate_region(&away, 1000).unwrap());
+        assert_eq!(executor.loaded_chunks(), vec![[0, 0], [5, 0]]);
+        let tally_before: usize = executor.selection_tally.iter().sum();
+
+        let bytes = executor.evict_chunk([0, 0]).unwrap();
+        assert_eq!(executor.loaded_chunks(), vec![[5, 0]]);
+        assert_eq!(placed_count(&executor, &home_band), 0);
+        assert_eq!(
+            executor.selection_tally.iter().sum::<usize>(),
+            tally_before - 144
+        );
+        assert!(executor.generate_region(&beside, 1000).is_err());
+
+        // A second executor can take the chunk over as well
+        let mut other = GreedyStochastic::new(9).expect("Failed to create executor");
+        assert_eq!(other.restore_chunk(&bytes).unwrap(), [0, 0]);
+        assert_eq!(placed_count(&other, &home_band), 144);
+
+        assert_eq!(executor.restore_chunk(&bytes).unwrap(), [0, 0]);
+        assert!(executor.restore_chunk(&bytes).is_err(), "restored twice");
+        let restored: Vec<Option<u32>> = (0..12)
+            .flat_map(|row| (0..12).map(move |col| [row, col]))
+            .map(|position| executor.tile_at(position))
+            .collect();
+        assert_eq!(restored, home_tiles);
+        for row in 0..12 {
+            for col in 0..12 {
+                assert!(
+                    (feasibility_at(&executor, [row, col]) - feasibility_at(&resident, [row, col]))
+                        .abs()
+                        < f64::EPSILON,
+                    "feasibility differs at {:?}",
+                    [row, col]
+                );
+            }
+        }
+        assert_eq!(executor.selection_tally.iter().sum::<usize>(), tally_before);
+        assert_eq!(ChunkSnapshot::from_bytes(&bytes).unwrap().to_bytes(), bytes);
+
+        // Generation continues exactly as if the chunk had never left memory
+        assert!(executor.generate_region(&beside, 1000).unwrap());
+        assert!(resident.generate_region(&beside, 1000).unwrap());
+        let surroundings = BoundingBox {
+            min: [-4, -4],
+            max: [15, 23],
+        };
+        for row in surroundings.min[0]..=surroundings.max[0] {
+            for col in surroundings.min[1]..=surroundings.max[1] {
+                assert_eq!(
+                    executor.tile_at([row, col]),
+                    resident.tile_at([row, col]),
+                    "restored executor diverged at {:?}",
+                    [row, col]
+                );
+            }
+        }
+        assert_windows_match_source(&executor, &surroundings);
+    }
+
+    // Tests malformed snapshot bytes are rejected
+    // Verified by ignoring trailing bytes
+    #[test]
+    fn test_snapshot_rejects_malformed_bytes() {
+        let mut executor = GreedyStochastic::new(5).expect("Failed to create executor");
+        let home = BoundingBox {
+            min: [2, 2],
+            max: [5, 5],
+        };
+        assert!(executor.generate_region(&home, 500).unwrap());
+        assert!(
+            executor
+                .generate_region(
+                    &BoundingBox {
+                        min: [100, 100],
+                        max: [101, 101],
+                    },
+                    500,
+                )
+                .unwrap()
+        );
+        let bytes = executor.evict_chunk([0, 0]).unwrap();
+
+        assert!(ChunkSnapshot::from_bytes(bytes.split_last().unwrap().1).is_err());
+        assert!(ChunkSnapshot::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+        assert!(ChunkSnapshot::from_bytes(b"not a chunk").is_err());
+        assert!(ChunkSnapshot::from_bytes(&bytes).is_ok());
+    }
+}
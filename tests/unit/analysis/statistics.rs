@@ -1,8 +1,21 @@
-//! Tests for smooth kernel distribution with boundary reflection
+//! Tests for smooth kernel distribution with boundary reflection and anisotropic statistics
 
 #[cfg(test)]
 mod tests {
-    use crate::analysis::statistics::SmoothKernelDistribution;
+    use crate::analysis::statistics::{
+        BandwidthSelection, KernelShape, OffsetSymmetry, Processor, SmoothKernelDistribution,
+    };
+    use crate::io::configuration::{
+        KDE_CROSS_VALIDATION_MAX_POINTS, MAX_KDE_BANDWIDTH, MIN_KDE_BANDWIDTH,
+    };
+    use crate::math::kernel::{DiscreteKernel, GaussianKernel, Kernel};
+    use crate::math::probability::erf;
+    use ndarray::Array2;
+    use std::sync::Arc;
+
+    fn horizontal_stripes() -> Array2<usize> {
+        Array2::from_shape_fn((8, 8), |(i, _)| 1 + i % 2)
+    }
 
     // Tests bounded kernel density estimation with reflection at x=0 using mathematical verification and measurable reflection effects
     // Verified by mathematical verification of reflection implementation with exact expected values
@@ -171,4 +184,345 @@ mod tests {
             "PDF at x=0.1 should be positive, got {pdf_at_positive}"
         );
     }
+
+    // Tests offset images under each symmetry group have the expected size and contents
+    // Verified by rotating offsets clockwise instead of counter-clockwise
+    #[test]
+    fn test_offset_symmetry_images() {
+        assert_eq!(
+            OffsetSymmetry::new(false, false).images([1, 2]),
+            vec![[1, 2]]
+        );
+        assert_eq!(
+            OffsetSymmetry::new(true, false).images([1, 2]),
+            vec![[1, 2], [2, -1], [-1, -2], [-2, 1]]
+        );
+        assert_eq!(
+            OffsetSymmetry::new(false, true).images([1, 2]),
+            vec![[1, 2], [1, -2]]
+        );
+        assert_eq!(OffsetSymmetry::new(true, true).images([1, 2]).len(), 8);
+    }
+
+    // Tests anisotropic matrices favour same-colour neighbours along stripes over across them
+    // Verified by replacing signed offsets with their Euclidean distance
+    #[test]
+    fn test_anisotropic_matrices_preserve_stripe_direction() {
+        let mut processor = Processor::new(horizontal_stripes(), vec![0.5, 0.5], 4, 3)
+            .with_anisotropy(OffsetSymmetry::default());
+
+        let matrices = processor
+            .preprocess_pattern_statistics(&[])
+            .expect("anisotropic preprocessing should succeed");
+
+        assert_eq!(matrices.dim(), (2, 2, 7, 7));
+
+        let along_stripe = matrices.get([0, 0, 3, 4]).copied().unwrap();
+        let across_stripe = matrices.get([0, 0, 4, 3]).copied().unwrap();
+        assert!(
+            along_stripe > across_stripe,
+            "Same colour should be favoured along the stripe ({along_stripe}) over across it ({across_stripe})"
+        );
+
+        let other_across = matrices.get([0, 1, 4, 3]).copied().unwrap();
+        assert!(
+            other_across > across_stripe,
+            "Other colour should be favoured directly across the stripe"
+        );
+    }
+
+    // Tests rotation symmetry makes anisotropic matrices invariant under quarter turns
+    // Verified by ignoring the rotation flag when expanding offsets
+    #[test]
+    fn test_anisotropic_matrices_respect_rotation_symmetry() {
+        let mut processor = Processor::new(horizontal_stripes(), vec![0.5, 0.5], 4, 3)
+            .with_anisotropy(OffsetSymmetry::new(true, false));
+
+        let matrices = processor
+            .preprocess_pattern_statistics(&[])
+            .expect("anisotropic preprocessing should succeed");
+
+        for i in 0..7 {
+            for j in 0..7 {
+                let value = matrices.get([0, 0, i, j]).copied().unwrap();
+                let rotated = matrices.get([0, 0, j, 6 - i]).copied().unwrap();
+                assert!(
+                    (value - rotated).abs() < 1e-9,
+                    "Matrix should be rotation invariant at ({i}, {j}): {value} vs {rotated}"
+                );
+            }
+        }
+    }
+
+    // Tests rules of thumb shrink the bandwidth as the effective sample size grows
+    // Verified by ignoring weights when computing the sample size
+    #[test]
+    fn test_rule_of_thumb_bandwidth_shrinks_with_sample_size() {
+        let points = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let light = vec![1.0; 5];
+        let heavy = vec![50.0; 5];
+
+        for rule in [BandwidthSelection::Silverman, BandwidthSelection::Scott] {
+            let h_light = rule.select_1d(&points, &light, &GaussianKernel);
+            let h_heavy = rule.select_1d(&points, &heavy, &GaussianKernel);
+            assert!(
+                h_heavy < h_light,
+                "{rule} should narrow with more data: {h_heavy} vs {h_light}"
+            );
+            assert!((MIN_KDE_BANDWIDTH..=MAX_KDE_BANDWIDTH).contains(&h_light));
+        }
+
+        let expected_scott = 1.06 * 2.0_f64.sqrt() * 5.0_f64.powf(-0.2);
+        let scott = BandwidthSelection::Scott.select_1d(&points, &light, &GaussianKernel);
+        assert!((scott - expected_scott).abs() < 1e-12);
+
+        let fixed = BandwidthSelection::Fixed(0.6).select_1d(&points, &heavy, &GaussianKernel);
+        assert!((fixed - 0.6).abs() < f64::EPSILON);
+    }
+
+    // Tests cross-validation prefers narrow kernels for tight clusters and wide ones for sparse data
+    // Verified by leaving out a single copy of each point instead of all of its weight
+    #[test]
+    fn test_cross_validation_adapts_to_data() {
+        let dense: Vec<f64> = (1..=5).chain(30..=34).map(f64::from).collect();
+        let h_dense = BandwidthSelection::LikelihoodCrossValidation.select_1d(
+            &dense,
+            &[3.0; 10],
+            &GaussianKernel,
+        );
+
+        let sparse: Vec<f64> = (1..=20).map(|i| f64::from(i) * 3.0).collect();
+        let h_sparse = BandwidthSelection::LikelihoodCrossValidation.select_1d(
+            &sparse,
+            &[3.0; 20],
+            &GaussianKernel,
+        );
+
+        assert!(
+            h_dense < h_sparse,
+            "Clustered data should select a narrower kernel: {h_dense} vs {h_sparse}"
+        );
+        assert!(
+            h_dense > MIN_KDE_BANDWIDTH,
+            "Repeated lattice values should not collapse to the minimum bandwidth"
+        );
+
+        let offsets = vec![[0.0, 1.0], [0.0, -1.0], [1.0, 0.0], [-1.0, 0.0]];
+        let h_2d = BandwidthSelection::LikelihoodCrossValidation.select_2d(
+            &offsets,
+            &[5.0; 4],
+            &GaussianKernel,
+        );
+        assert!((MIN_KDE_BANDWIDTH..=MAX_KDE_BANDWIDTH).contains(&h_2d));
+    }
+
+    // Tests cross-validation on realistic point counts scores an even subsample
+    // Verified by scoring every point, which takes minutes on the offset statistics
+    #[test]
+    fn test_cross_validation_subsamples_large_inputs() {
+        let points: Vec<f64> = (0..5_000).map(|i| f64::from(i).sqrt()).collect();
+        let weights: Vec<f64> = (0..5_000).map(|i| f64::from(1 + i % 7)).collect();
+        let stride = points.len().div_ceil(KDE_CROSS_VALIDATION_MAX_POINTS);
+        let sampled_points: Vec<f64> = points.iter().copied().step_by(stride).collect();
+        let sampled_weights: Vec<f64> = weights.iter().copied().step_by(stride).collect();
+
+        let lcv = BandwidthSelection::LikelihoodCrossValidation;
+        let h_full = lcv.select_1d(&points, &weights, &GaussianKernel);
+        let h_sampled = lcv.select_1d(&sampled_points, &sampled_weights, &GaussianKernel);
+        assert!((h_full - h_sampled).abs() < f64::EPSILON);
+
+        let source = Array2::from_shape_fn((32, 32), |(i, j)| 1 + (i * 7 + j * 3) % 4);
+        let mut processor = Processor::new(source, vec![0.25; 4], 4, 3)
+            .with_anisotropy(OffsetSymmetry::new(true, true))
+            .with_bandwidth_selection(lcv);
+        processor
+            .preprocess_pattern_statistics(&[0.0, 1.0, 2.0, 4.0])
+            .expect("preprocessing should succeed");
+        assert_eq!(processor.selected_bandwidths().len(), 16);
+    }
+
+    // Tests bandwidth strategies parse from their command-line spellings
+    // Verified by removing the 'lcv' alias
+    #[test]
+    fn test_bandwidth_selection_from_str() {
+        assert_eq!(
+            "silverman".parse::<BandwidthSelection>().unwrap(),
+            BandwidthSelection::Silverman
+        );
+        assert_eq!(
+            "Scott".parse::<BandwidthSelection>().unwrap(),
+            BandwidthSelection::Scott
+        );
+        assert_eq!(
+            "lcv".parse::<BandwidthSelection>().unwrap(),
+            BandwidthSelection::LikelihoodCrossValidation
+        );
+        assert_eq!(
+            "0.5".parse::<BandwidthSelection>().unwrap(),
+            BandwidthSelection::Fixed(0.5)
+        );
+        assert!("-1".parse::<BandwidthSelection>().is_err());
+        assert!("wide".parse::<BandwidthSelection>().is_err());
+    }
+
+    // Tests the processor records one selected bandwidth per observed pair
+    // Verified by skipping bandwidth selection when building distributions
+    #[test]
+    fn test_processor_records_selected_bandwidths() {
+        let mut processor = Processor::new(horizontal_stripes(), vec![0.5, 0.5], 4, 3)
+            .with_bandwidth_selection(BandwidthSelection::Silverman);
+
+        processor
+            .preprocess_pattern_statistics(&[0.0, 1.0, 2.0, 4.0])
+            .expect("preprocessing should succeed");
+
+        let bandwidths = processor.selected_bandwidths();
+        assert_eq!(bandwidths.len(), 4);
+        assert!(
+            bandwidths
+                .iter()
+                .all(|pb| (MIN_KDE_BANDWIDTH..=MAX_KDE_BANDWIDTH).contains(&pb.bandwidth))
+        );
+        assert!(
+            bandwidths
+                .iter()
+                .any(|pb| (pb.bandwidth - 1.0).abs() > f64::EPSILON)
+        );
+    }
+
+    // Tests the discrete kernel reproduces the empirical distance histogram
+    // Verified by reflecting the discrete kernel onto positive distances
+    #[test]
+    fn test_discrete_kernel_reproduces_histogram() {
+        let dist = SmoothKernelDistribution::new((1, 2), vec![(1.0, 3.0), (2.0, 1.0)])
+            .with_kernel(Arc::new(DiscreteKernel));
+
+        assert!((dist.pdf(1.0) - 0.75).abs() < f64::EPSILON);
+        assert!((dist.pdf(2.0) - 0.25).abs() < f64::EPSILON);
+        assert!(dist.pdf(3.0).abs() < f64::EPSILON);
+        assert!(dist.pdf(0.0).abs() < f64::EPSILON);
+    }
+
+    // Tests discrete influence matrices hold the exact histogram log-ratio at each lattice offset
+    // Verified by interpolating the discrete log-ratios with the cubic spline
+    #[test]
+    fn test_discrete_kernel_influence_is_exact_at_lattice_offsets() {
+        let influence_distance = 4.0;
+        let radius = 3;
+        let mut processor = Processor::new(horizontal_stripes(), vec![0.5, 0.5], 4, radius)
+            .with_kernel(Arc::new(DiscreteKernel));
+        let matrices = processor
+            .preprocess_pattern_statistics(&[0.0, 1.0, 2.0, 4.0, 8.0])
+            .expect("preprocessing should succeed");
+
+        let pair_distances = processor.calculate_integer_pair_distances();
+        let pdf = |from: usize, to: usize, distance: f64| {
+            let pair = pair_distances
+                .iter()
+                .find(|p| (p.from_value, p.to_value) == (from, to))
+                .unwrap();
+            let total: usize = pair.distances.iter().map(|d| d.frequency).sum();
+            pair.distances
+                .iter()
+                .filter(|d| (d.distance - distance).abs() < 1e-9)
+                .map(|d| d.frequency as f64 / total as f64)
+                .sum::<f64>()
+        };
+        let taper = |x: f64| {
+            let sqrt_k = f64::sqrt(influence_distance);
+            0.5 - erf(sqrt_k / 2.0 - x / sqrt_k) / (2.0 * erf(sqrt_k / 2.0))
+        };
+
+        for [di, dj] in [[0, 1], [1, 1], [1, 2], [2, 0], [2, 2]] {
+            let distance = f64::from(di * di + dj * dj).sqrt();
+            let mixture = f64::midpoint(pdf(1, 1, distance), pdf(1, 2, distance));
+            for to in 1..=2 {
+                let single = pdf(1, to, distance);
+                let ratio = if single > 0.0 && mixture > 0.0 {
+                    (single * 2.0 / mixture).ln() - 4.0_f64.ln()
+                } else {
+                    -4.0_f64.ln()
+                };
+                let t = taper(distance);
+                let expected = ratio.mul_add(1.0 - t, t * 0.5_f64.ln()).exp() / distance;
+                let actual = *matrices
+                    .get([
+                        0,
+                        to - 1,
+                        (radius as i32 + di) as usize,
+                        (radius as i32 + dj) as usize,
+                    ])
+                    .unwrap();
+                assert!(
+                    (actual - expected).abs() < 1e-12,
+                    "1→{to} at {di},{dj}: {actual} vs {expected}"
+                );
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    struct WideBoxKernel;
+
+    impl Kernel for WideBoxKernel {
+        fn density(&self, offset: f64, _bandwidth: f64) -> f64 {
+            if offset.abs() < 5.0 { 0.1 } else { 0.0 }
+        }
+    }
+
+    // Tests a user-supplied kernel reaches the processor's pair distributions
+    // Verified by building distributions without passing the processor kernel
+    #[test]
+    fn test_processor_uses_custom_kernel() {
+        let processor = Processor::new(horizontal_stripes(), vec![0.5, 0.5], 4, 3)
+            .with_kernel(Arc::new(WideBoxKernel));
+
+        let pair_distances = processor.calculate_integer_pair_distances();
+        let distributions = processor.create_smooth_kernel_distributions(&pair_distances);
+        assert!(!distributions.is_empty());
+
+        for dist in &distributions {
+            let far = dist.data_points.iter().fold(0.0_f64, |a, &b| a.max(b)) + 4.0;
+            assert!(
+                dist.pdf(far) > 0.0,
+                "Wide box should reach beyond the Gaussian's effective support"
+            );
+            assert!(dist.pdf(far + 2.0).abs() < f64::EPSILON);
+        }
+    }
+
+    // Tests a user-supplied kernel reaches the anisotropic offset distributions
+    // Verified by building the two-dimensional pdf from a fixed Gaussian
+    #[test]
+    fn test_processor_uses_custom_kernel_for_offsets() {
+        let processor = Processor::new(horizontal_stripes(), vec![0.5, 0.5], 4, 3)
+            .with_anisotropy(OffsetSymmetry::new(false, false))
+            .with_kernel(Arc::new(WideBoxKernel));
+
+        let pair_offsets = processor.calculate_integer_pair_offsets();
+        let distributions = processor.create_smooth_kernel_distributions_2d(&pair_offsets);
+        assert!(!distributions.is_empty());
+
+        for dist in &distributions {
+            let far = dist.data_points.iter().fold(0.0_f64, |a, p| a.max(p[0])) + 4.0;
+            assert!(dist.pdf([far, 0.0]) > 0.0);
+            assert!(dist.pdf([far + 2.0, 0.0]).abs() < f64::EPSILON);
+        }
+    }
+
+    // Tests kernel shapes parse from their command-line spellings
+    // Verified by removing the 'discrete' arm
+    #[test]
+    fn test_kernel_shape_from_str() {
+        for shape in [
+            KernelShape::Gaussian,
+            KernelShape::Epanechnikov,
+            KernelShape::Triangular,
+            KernelShape::Discrete,
+        ] {
+            assert_eq!(shape.to_string().parse::<KernelShape>().unwrap(), shape);
+        }
+        assert!("box".parse::<KernelShape>().is_err());
+        assert!(KernelShape::Discrete.kernel().density(0.7, 1.0).abs() < f64::EPSILON);
+    }
 }
@@ -238,4 +238,42 @@ mod tests {
         let expected = vec![5.0, 4.0, 3.0];
         assert_eq!(values, expected);
     }
+
+    // Tests frontier candidates and weights agree with the full-grid calculation
+    // Verified by ranking frontier buckets in ascending adjacency order
+    #[test]
+    fn test_frontier_selection_matches_full_scan() {
+        use greedytile::algorithm::executor::GreedyStochastic;
+        use greedytile::analysis::weights::{
+            DensityBias, calculate_position_selection, position_weight, top_k_frontier_indices,
+        };
+
+        let mut executor = GreedyStochastic::new(5).expect("Failed to create executor");
+        for _ in 0..60 {
+            executor.run_iteration().expect("Failed to run iteration");
+        }
+
+        let grid_state = &executor.grid_state;
+        let full = calculate_position_selection(
+            grid_state,
+            &executor.selection_tally,
+            &executor.step_data,
+            executor.system_offset,
+        );
+
+        let frontier = top_k_frontier_indices(grid_state, executor.system_offset, 20);
+        let scanned = top_k_valid_indices(&full.adjacency_matrix, &full.validity_matrix, 20);
+        assert_eq!(frontier.len(), 20);
+        assert_eq!(frontier, scanned);
+
+        let density = DensityBias::new(&executor.selection_tally, &executor.step_data);
+        for index in frontier {
+            let expected = full.weight_matrix.get(index).copied().unwrap();
+            let actual = position_weight(grid_state, index, &density);
+            assert!(
+                (expected - actual).abs() <= 1e-12 * expected.abs(),
+                "weight at {index:?}: {actual} != {expected}"
+            );
+        }
+    }
 }
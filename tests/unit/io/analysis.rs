@@ -1,7 +1,12 @@
 //! Tests for analysis capture with configurable recording radius
 
+use greedytile::analysis::statistics::{BandwidthSelection, PairBandwidth};
 use greedytile::io::analysis::AnalysisCapture;
+use greedytile::io::events::DecisionSource;
+use greedytile::io::render::RenderOptions;
+use greedytile::io::visualization::VisualizationCapture;
 use greedytile::spatial::GridState;
+use image::AnimationDecoder;
 
 // Verifies AnalysisCapture construction and recording functionality with different capture radii
 // Verified by breaking capture radius calculations to verify radius affects captured data
@@ -113,3 +118,175 @@ fn test_analysis_capture_empty_color_mapping() {
     let grid_state = GridState::new(2, 2, 1);
     analysis.record_region(0, 0, &grid_state, [0, 0], 0);
 }
+
+// Tests metadata export includes the bandwidth strategy and every pair bandwidth
+// Verified by dropping the bandwidth list from the exported JSON
+#[test]
+fn test_analysis_metadata_includes_bandwidths() {
+    let mut analysis = AnalysisCapture::new(vec![[255, 0, 0, 255], [0, 0, 255, 255]], 2);
+    analysis.set_bandwidths(
+        BandwidthSelection::Scott,
+        vec![
+            PairBandwidth {
+                from_value: 1,
+                to_value: 2,
+                bandwidth: 0.75,
+            },
+            PairBandwidth {
+                from_value: 2,
+                to_value: 1,
+                bandwidth: 1.5,
+            },
+        ],
+    );
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("nested").join("metadata.json");
+    analysis.export_metadata(path.to_str().unwrap()).unwrap();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(json.get("bandwidth_selection").unwrap(), "scott");
+    assert_eq!(
+        json.get("bandwidths").unwrap(),
+        &serde_json::json!([
+            {"from": 1, "to": 2, "bandwidth": 0.75},
+            {"from": 2, "to": 1, "bandwidth": 1.5},
+        ])
+    );
+    assert_eq!(analysis.pair_bandwidths().len(), 2);
+}
+
+// Tests cells recorded twice in an iteration count once and export with their
+// latest values, one frame per iteration plus the held final frame
+// Verified by keeping the first value recorded for a cell in an iteration
+#[test]
+fn test_analysis_frames_aggregate_per_iteration() {
+    let mut analysis = AnalysisCapture::new(vec![[255, 0, 0, 255]], 0);
+    let visualization = VisualizationCapture::new(1, 1, vec![[255, 0, 0, 255]], 1);
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("analysis.gif");
+    assert!(
+        analysis
+            .export_analysis(
+                &visualization,
+                path.to_str().unwrap(),
+                10,
+                RenderOptions::default(),
+            )
+            .is_err()
+    );
+
+    let mut grid_state = GridState::new(2, 1, 1);
+    grid_state.entropy.fill(0.0);
+    for (iteration, entropy) in [(1, 0.0), (1, 1.0), (2, 1.0)] {
+        if let Some(cell) = grid_state.entropy.get_mut([0, 0]) {
+            *cell = entropy;
+        }
+        analysis.record_region(0, 0, &grid_state, [0, 0], iteration);
+        analysis.record_region(1, 0, &grid_state, [0, 0], iteration);
+    }
+    assert_eq!(analysis.event_count(), 4);
+
+    analysis
+        .export_analysis(
+            &visualization,
+            path.to_str().unwrap(),
+            10,
+            RenderOptions::default(),
+        )
+        .unwrap();
+    let decoder = image::codecs::gif::GifDecoder::new(std::io::BufReader::new(
+        std::fs::File::open(&path).unwrap(),
+    ))
+    .unwrap();
+    let frames: Vec<_> = decoder
+        .into_frames()
+        .map(|frame| frame.unwrap().into_buffer())
+        .collect();
+    assert_eq!(frames.len(), 4);
+    // Entropy panel sits below the two rows of the color panel and padding
+    let after_first = frames.get(1).unwrap();
+    assert_eq!(after_first.get_pixel(0, 4).0, [255, 255, 255, 255]);
+    assert_eq!(after_first.get_pixel(0, 5).0, [0, 0, 0, 255]);
+}
+
+// Tests analysis panels are upscaled with gridlines, keeping the padding
+// between them in proportion, with the iteration captioned below
+// Verified by drawing the analysis panels without scaling
+#[test]
+fn test_analysis_render_options() {
+    let mut analysis = AnalysisCapture::new(vec![[255, 0, 0, 255]], 0);
+    let mut visualization = VisualizationCapture::new(1, 1, vec![[255, 0, 0, 255]], 1);
+    visualization.record_placement(0, 0, 2, 1, DecisionSource::Forced);
+    let grid_state = GridState::new(1, 1, 1);
+    analysis.record_region(0, 0, &grid_state, [0, 0], 1);
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("analysis.gif");
+    let options = RenderOptions {
+        scale: 2,
+        gridlines: true,
+        caption: true,
+        ..RenderOptions::default()
+    };
+    analysis
+        .export_analysis(&visualization, path.to_str().unwrap(), 10, options)
+        .unwrap();
+
+    let decoder = image::codecs::gif::GifDecoder::new(std::io::BufReader::new(
+        std::fs::File::open(&path).unwrap(),
+    ))
+    .unwrap();
+    let last = decoder.into_frames().last().unwrap().unwrap().into_buffer();
+    // Panels of one 2 pixel cell with 4 pixels of padding, above the caption
+    assert_eq!(last.dimensions(), (8, 15));
+    assert_eq!(last.get_pixel(6, 0).0, [255, 0, 0, 255]);
+    assert_eq!(last.get_pixel(7, 0).0, [96, 96, 96, 255]);
+    assert_eq!(last.get_pixel(3, 0).0, [128, 128, 128, 255]);
+    assert_eq!(last.get_pixel(2, 9).0, [255, 255, 255, 255]);
+}
+
+// Tests export includes the frame still being recorded and leaves it open,
+// so a later export sees cells recorded after the first
+// Verified by exporting only the closed frames
+#[test]
+fn test_analysis_export_includes_open_frame() {
+    let mut analysis = AnalysisCapture::new(vec![[255, 0, 0, 255]], 0);
+    let visualization = VisualizationCapture::new(1, 1, vec![[255, 0, 0, 255]], 1);
+    let mut grid_state = GridState::new(2, 1, 1);
+    grid_state.entropy.fill(1.0);
+    analysis.record_region(1, 0, &grid_state, [0, 0], 1);
+    analysis.record_region(0, 0, &grid_state, [0, 0], 2);
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("analysis.gif");
+    let last_frame = |capture: &mut AnalysisCapture| {
+        capture
+            .export_analysis(
+                &visualization,
+                path.to_str().unwrap(),
+                10,
+                RenderOptions::default(),
+            )
+            .unwrap();
+        let decoder = image::codecs::gif::GifDecoder::new(std::io::BufReader::new(
+            std::fs::File::open(&path).unwrap(),
+        ))
+        .unwrap();
+        decoder.into_frames().last().unwrap().unwrap().into_buffer()
+    };
+
+    // Entropy panel sits below the two rows of the color panel and padding
+    assert_eq!(
+        last_frame(&mut analysis).get_pixel(0, 4).0,
+        [255, 255, 255, 255]
+    );
+
+    analysis.record_region(1, 0, &grid_state, [0, 0], 2);
+    assert_eq!(analysis.event_count(), 3);
+    assert_eq!(
+        last_frame(&mut analysis).get_pixel(0, 5).0,
+        [255, 255, 255, 255]
+    );
+}
@@ -5,6 +5,7 @@ mod tests {
     use clap::Parser;
     use greedytile::io::cli::Cli;
     use greedytile::io::configuration::{DEFAULT_MAX_ITERATIONS, DEFAULT_SEED};
+    use greedytile::io::render::RenderOptions;
     use std::path::PathBuf;
 
     // Tests CLI parsing with only required target file argument
@@ -218,6 +219,29 @@ mod tests {
         Cli::parse_from(args)
     }
 
+    // Tests render flags reach the options of visualization and analysis frames
+    // Verified by mapping --highlight to the overlays option
+    #[test]
+    fn test_cli_render_options() {
+        let plain = Cli::parse_from(["program", "test.png"]).render_options();
+        assert_eq!(plain, RenderOptions::default());
+
+        let cli = Cli::parse_from([
+            "program",
+            "test.png",
+            "--scale",
+            "4",
+            "--gridlines",
+            "--highlight",
+            "--caption",
+        ]);
+        let options = cli.render_options();
+        assert_eq!(options.scale, 4);
+        assert!(options.gridlines && options.highlight_latest && options.caption);
+        assert!(!options.overlays);
+        assert_eq!(cli.animation_options().render, options);
+    }
+
     // Tests width/height CLI arguments create proper bounds configuration
     // Verified by changing short flag from 'w' to 'x'
     #[test]
@@ -268,4 +292,185 @@ mod tests {
         assert!(cli_short.rotate);
         assert!(cli_short.mirror);
     }
+
+    // Tests bandwidth strategy parsing and its fixed default
+    // Verified by changing the default bandwidth strategy
+    #[test]
+    fn test_cli_bandwidth_argument() {
+        use greedytile::analysis::statistics::BandwidthSelection;
+
+        let cli_default = Cli::parse_from(vec!["program", "test.png"]);
+        assert_eq!(cli_default.bandwidth, BandwidthSelection::Fixed(1.0));
+        assert!(!cli_default.anisotropic);
+
+        let cli_scott = Cli::parse_from(vec![
+            "program",
+            "test.png",
+            "--bandwidth",
+            "scott",
+            "--anisotropic",
+        ]);
+        assert_eq!(cli_scott.bandwidth, BandwidthSelection::Scott);
+        assert!(cli_scott.anisotropic);
+
+        assert!(Cli::try_parse_from(vec!["program", "test.png", "--bandwidth", "x"]).is_err());
+    }
+
+    // Tests the deadlock policy takes its radii from the command line, and
+    // radii the policy rejects are reported when it is validated
+    // Verified by building the policy with the default radii
+    #[test]
+    fn test_cli_deadlock_policy() {
+        use greedytile::algorithm::deadlock::DeadlockPolicy;
+
+        let cli_default = Cli::parse_from(vec!["program", "test.png"]);
+        assert_eq!(cli_default.deadlock_policy(), DeadlockPolicy::default());
+
+        let cli = Cli::parse_from(vec![
+            "program",
+            "test.png",
+            "--removal-radius",
+            "2",
+            "--max-removal-radius",
+            "9",
+            "--max-deadlocks",
+            "40",
+        ]);
+        let policy = cli.deadlock_policy();
+        assert_eq!((policy.base_radius, policy.max_radius), (2, 9));
+        assert_eq!(policy.max_attempts, Some(40));
+        assert!(policy.validate().is_ok());
+
+        let inverted = Cli::parse_from(vec![
+            "program",
+            "test.png",
+            "--removal-radius",
+            "5",
+            "--max-removal-radius",
+            "3",
+        ]);
+        assert!(inverted.deadlock_policy().validate().is_err());
+    }
+
+    // Tests one given side makes square bounds
+    // Verified by swapping height and width in the returned bounds
+    #[test]
+    fn test_cli_bounds() {
+        let square = Cli::parse_from(vec!["program", "test.png", "-w", "8"]);
+        assert_eq!(square.bounds(), Some((8, 8)));
+
+        let both = Cli::parse_from(vec!["program", "test.png", "-w", "8", "-H", "5"]);
+        assert_eq!(both.bounds(), Some((5, 8)));
+
+        assert_eq!(Cli::parse_from(vec!["program", "test.png"]).bounds(), None);
+    }
+
+    // Tests running until complete needs bounds, and with them fills the
+    // output even when the iteration count alone would stop short
+    // Verified by ignoring --until-complete when choosing the iteration limit
+    #[test]
+    fn test_until_complete() {
+        let temp_dir = TempDir::new().unwrap();
+        let input_file = temp_dir.path().join("a.png");
+        fs::copy("data/a.png", &input_file).unwrap();
+        let input = input_file.to_str().unwrap();
+
+        let unbounded = Cli::parse_from(vec!["program", input, "--until-complete", "-q"]);
+        assert!(FileProcessor::new(unbounded).process().is_err());
+
+        let bounded = Cli::parse_from(vec![
+            "program",
+            input,
+            "--until-complete",
+            "-w",
+            "12",
+            "-i",
+            "10",
+            "-s",
+            "1",
+            "-q",
+        ]);
+        FileProcessor::new(bounded).process().unwrap();
+
+        let output = image::open(temp_dir.path().join("a_result.png"))
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(output.dimensions(), (12, 12));
+        assert!(output.pixels().all(|pixel| pixel.0[3] > 0));
+    }
+
+    // Tests the until-complete cap and stall window come from their flags,
+    // which are rejected without --until-complete
+    // Verified by keeping the cap per cell when --max-iterations is given
+    #[test]
+    fn test_until_complete_limit_flags() {
+        let temp_dir = TempDir::new().unwrap();
+        let input_file = temp_dir.path().join("a.png");
+        fs::copy("data/a.png", &input_file).unwrap();
+        let input = input_file.to_str().unwrap();
+        let run = |extra: &[&str]| {
+            let mut args = vec!["program", input, "-w", "12", "-s", "1", "-q", "--no-skip"];
+            args.extend_from_slice(extra);
+            FileProcessor::new(Cli::parse_from(args)).process()
+        };
+
+        assert!(run(&["--until-complete", "--max-iterations", "20"]).is_err());
+        assert!(run(&["--max-iterations", "2000"]).is_err());
+        assert!(run(&["--stall-deadlocks", "5"]).is_err());
+        run(&["--until-complete", "--stall-deadlocks", "0"]).unwrap();
+    }
+
+    // Tests a recorded run verifies against a rerun with its seed but not
+    // another, and replays to the same output, to scaled frames or to an
+    // earlier iteration
+    // Verified by replaying every event regardless of --replay-until
+    #[test]
+    fn test_replay_and_verify_events() {
+        let temp_dir = TempDir::new().unwrap();
+        let input_file = temp_dir.path().join("a.png");
+        fs::copy("data/a.png", &input_file).unwrap();
+        let input = input_file.to_str().unwrap();
+        let output = temp_dir.path().join("a_result.png");
+        let run = |extra: &[&str]| {
+            let mut args = vec!["program", input, "-w", "12", "-i", "60", "-q", "--no-skip"];
+            args.extend_from_slice(extra);
+            FileProcessor::new(Cli::parse_from(args)).process()
+        };
+
+        run(&["--events"]).unwrap();
+        assert!(temp_dir.path().join("a_events.jsonl").exists());
+        let generated = fs::read(&output).unwrap();
+
+        run(&["--verify-events"]).unwrap();
+        assert!(run(&["--verify-events", "--seed", "7"]).is_err());
+
+        run(&["--replay", "--visualize", "--frame-delay", "40"]).unwrap();
+        assert_eq!(fs::read(&output).unwrap(), generated);
+        let (width, height) = image::open(temp_dir.path().join("a_visualization.gif"))
+            .unwrap()
+            .to_rgba8()
+            .dimensions();
+
+        run(&[
+            "--replay",
+            "--visualize",
+            "--animation",
+            "frames",
+            "--scale",
+            "2",
+        ])
+        .unwrap();
+        let first_frame = temp_dir
+            .path()
+            .join("a_visualization")
+            .join("frame_000000.png");
+        assert_eq!(
+            image::open(first_frame).unwrap().to_rgba8().dimensions(),
+            (2 * width, 2 * height)
+        );
+
+        run(&["--replay-until", "5"]).unwrap();
+        let early = image::open(&output).unwrap().to_rgba8();
+        assert_eq!(early.pixels().filter(|pixel| pixel.0[3] > 0).count(), 5);
+    }
 }
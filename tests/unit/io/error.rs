@@ -33,6 +33,85 @@ mod tests {
         assert!(message.contains("10x20"));
     }
 
+    // Tests Unsolvable error reports the removals, position and iteration
+    // Verified by omitting the position from the message
+    #[test]
+    fn test_unsolvable_error() {
+        let error = AlgorithmError::Unsolvable {
+            deadlocks: 12,
+            position: [-3, 5],
+            iteration: 340,
+        };
+
+        let message = error.to_string();
+        assert!(message.contains("12 deadlock removals"));
+        assert!(message.contains("(-3, 5)"));
+        assert!(message.contains("iteration 340"));
+    }
+
+    // Tests SelectionExhausted error names the retry cap, position and iteration
+    // Verified by misreporting the contradiction count
+    #[test]
+    fn test_selection_exhausted_error() {
+        let error = AlgorithmError::SelectionExhausted {
+            contradictions: 100,
+            position: [4, -2],
+            iteration: 57,
+        };
+
+        let message = error.to_string();
+        assert!(message.contains("retry cap"));
+        assert!(message.contains("100 contradictions"));
+        assert!(message.contains("(4, -2)"));
+        assert!(message.contains("iteration 57"));
+        assert!(error.is_generation_failure());
+    }
+
+    // Tests Incomplete error reports the unfilled cells and why the run stopped
+    // Verified by describing every stop as the iteration cap
+    #[test]
+    fn test_incomplete_error() {
+        let capped = AlgorithmError::Incomplete {
+            unfilled: 17,
+            iterations: 400,
+            stalled: false,
+        };
+        let stalled = AlgorithmError::Incomplete {
+            unfilled: 3,
+            iterations: 90,
+            stalled: true,
+        };
+
+        let message = capped.to_string();
+        assert!(message.contains("17 cells unfilled"));
+        assert!(message.contains("400 iterations"));
+        assert!(message.contains("iteration cap"));
+        assert!(stalled.to_string().contains("stalled"));
+        assert!(stalled.is_generation_failure());
+    }
+
+    // Tests only failures of generation itself are worth retrying
+    // Verified by treating every error as a generation failure
+    #[test]
+    fn test_generation_failures() {
+        let unsolvable = AlgorithmError::Unsolvable {
+            deadlocks: 1,
+            position: [0, 0],
+            iteration: 1,
+        };
+        let no_positions = AlgorithmError::NoValidPositions {
+            iteration: 1,
+            grid_dimensions: (1, 1),
+        };
+        let invalid = AlgorithmError::InvalidSourceData {
+            reason: "empty".to_string(),
+        };
+
+        assert!(unsolvable.is_generation_failure());
+        assert!(no_positions.is_generation_failure());
+        assert!(!invalid.is_generation_failure());
+    }
+
     // Tests InvalidParameter error contains all fields
     // Verified by omitting value from message
     #[test]
@@ -0,0 +1,302 @@
+//! Tests for the structured generation event log
+
+#[cfg(test)]
+mod tests {
+    use greedytile::algorithm::backtracking::DeadlockStrategy;
+    use greedytile::algorithm::executor::{AlgorithmConfig, GreedyStochastic};
+    use greedytile::analysis::patterns::ImageProcessor;
+    use greedytile::io::events::{Candidate, DecisionSource, EventLog, GenerationEvent};
+    use std::collections::HashMap;
+
+    fn backtracking_executor() -> GreedyStochastic {
+        let config = AlgorithmConfig {
+            bounds: Some((12, 12)),
+            deadlock_strategy: DeadlockStrategy::Backtrack(5000),
+            ..AlgorithmConfig::default()
+        };
+        let processor = ImageProcessor::from_png_file("data/a.png").unwrap();
+        GreedyStochastic::from_image_processor(processor, config, 1).unwrap()
+    }
+
+    // Tiles left standing after applying every event in order
+    fn replay(events: &[GenerationEvent]) -> HashMap<[i32; 2], usize> {
+        let mut tiles = HashMap::new();
+        for event in events {
+            match event {
+                GenerationEvent::Placement { position, tile, .. } => {
+                    tiles.insert(*position, *tile);
+                }
+                GenerationEvent::Removal { removed, .. }
+                | GenerationEvent::Backtrack { removed, .. } => {
+                    for position in removed {
+                        tiles.remove(position);
+                    }
+                }
+                GenerationEvent::Fallback { .. } => {}
+            }
+        }
+        tiles
+    }
+
+    // Tiles locked in the executor's grid, keyed by world position
+    fn locked(executor: &GreedyStochastic) -> HashMap<[i32; 2], usize> {
+        executor
+            .grid_state
+            .locked_tiles
+            .indexed_iter()
+            .filter(|&(_, &locked)| locked > 1)
+            .map(|((row, col), &locked)| {
+                (
+                    [
+                        row as i32 - executor.system_offset[0],
+                        col as i32 - executor.system_offset[1],
+                    ],
+                    locked as usize - 1,
+                )
+            })
+            .collect()
+    }
+
+    // Tests each event serializes to one JSON object with its fields
+    // Verified by leaving the decision source unquoted
+    #[test]
+    fn test_event_json() {
+        let placement = GenerationEvent::Placement {
+            iteration: 3,
+            position: [-1, 2],
+            tile: 4,
+            source: DecisionSource::Selected,
+            candidates: vec![
+                Candidate {
+                    tile: 2,
+                    weight: 0.25,
+                },
+                Candidate {
+                    tile: 4,
+                    weight: 0.75,
+                },
+            ],
+        };
+        assert_eq!(
+            placement.to_json(),
+            "{\"event\": \"placement\", \"iteration\": 3, \"position\": [-1, 2], \"tile\": 4, \"source\": \"selected\", \"candidates\": [{\"tile\": 2, \"weight\": 0.25}, {\"tile\": 4, \"weight\": 0.75}]}"
+        );
+
+        let removal = GenerationEvent::Removal {
+            iteration: 9,
+            position: [0, 0],
+            radius: 2,
+            removed: vec![[0, 1], [1, 0]],
+        };
+        assert_eq!(
+            removal.to_json(),
+            "{\"event\": \"removal\", \"iteration\": 9, \"position\": [0, 0], \"radius\": 2, \"removed\": [[0, 1], [1, 0]]}"
+        );
+
+        let backtrack = GenerationEvent::Backtrack {
+            iteration: 12,
+            position: [5, 5],
+            removed: Vec::new(),
+            banned: ([4, 5], 3),
+        };
+        assert_eq!(backtrack.iteration(), 12);
+        assert_eq!(
+            backtrack.to_json(),
+            "{\"event\": \"backtrack\", \"iteration\": 12, \"position\": [5, 5], \"removed\": [], \"banned\": {\"position\": [4, 5], \"tile\": 3}}"
+        );
+
+        let fallback = GenerationEvent::Fallback {
+            iteration: 14,
+            position: [5, 5],
+            backtracks: 8,
+            exhausted: true,
+        };
+        assert_eq!(
+            fallback.to_json(),
+            "{\"event\": \"fallback\", \"iteration\": 14, \"position\": [5, 5], \"backtracks\": 8, \"exhausted\": true}"
+        );
+        assert_eq!(
+            GenerationEvent::from_json(&fallback.to_json()).unwrap(),
+            fallback
+        );
+    }
+
+    // Tests events are read from any valid JSON, with escaped strings, other
+    // field orders and no spacing
+    // Verified by reading with the previous parser, which rejected escapes
+    #[test]
+    fn test_event_from_escaped_json() {
+        let removal = GenerationEvent::from_json(
+            "{\"iteration\":9,\"event\":\"remov\\u0061l\",\"radius\":2,\"position\":[0,-1],\"removed\":[[1,1]]}",
+        )
+        .unwrap();
+        assert_eq!(
+            removal,
+            GenerationEvent::Removal {
+                iteration: 9,
+                position: [0, -1],
+                radius: 2,
+                removed: vec![[1, 1]],
+            }
+        );
+        assert!(GenerationEvent::from_json("{\"event\": \"removal\"}").is_err());
+        assert!(GenerationEvent::from_json("{\"event\": \"teleport\", \"iteration\": 1}").is_err());
+    }
+
+    // Tests weights JSON cannot hold are written as null and extreme weights
+    // in exponent form
+    // Verified by writing infinite weights as they display
+    #[test]
+    fn test_candidate_weights() {
+        let json = |weight: f64| {
+            GenerationEvent::Placement {
+                iteration: 0,
+                position: [0, 0],
+                tile: 2,
+                source: DecisionSource::Initial,
+                candidates: vec![Candidate { tile: 2, weight }],
+            }
+            .to_json()
+        };
+        assert!(json(f64::NAN).contains("\"weight\": null"));
+        assert!(json(f64::INFINITY).contains("\"weight\": null"));
+        assert!(json(0.0).contains("\"weight\": 0}"));
+        assert!(json(1e-9).contains("\"weight\": 1e-9}"));
+        assert!(json(0.5).contains("\"weight\": 0.5}"));
+    }
+
+    // Tests the executor logs the initial draw over the source ratios, then
+    // selected tiles with their candidates and forced tiles without, and
+    // only once the log is enabled
+    // Verified by logging forced tiles as selected
+    #[test]
+    fn test_executor_decision_sources() {
+        let mut executor = GreedyStochastic::new(1).unwrap();
+        assert!(executor.export_events("unused.jsonl").is_err());
+
+        executor.enable_event_log();
+        for _ in 0..40 {
+            executor.run_iteration().unwrap();
+        }
+        let events = executor.events.as_ref().unwrap().events();
+
+        let placements: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                GenerationEvent::Placement {
+                    iteration,
+                    tile,
+                    source,
+                    candidates,
+                    ..
+                } => Some((*iteration, *tile, *source, candidates)),
+                _ => None,
+            })
+            .collect();
+        let (first, rest) = placements.split_first().unwrap();
+        assert_eq!((first.0, first.2), (1, DecisionSource::Initial));
+        assert_eq!(first.3.len(), executor.step_data.source_ratios.len());
+
+        let mut sources = Vec::new();
+        for &(_, tile, source, candidates) in rest {
+            sources.push(source);
+            if source == DecisionSource::Selected {
+                assert!(candidates.iter().any(|candidate| candidate.tile == tile));
+                assert!(candidates.iter().all(|candidate| candidate.weight >= 0.0));
+            } else {
+                assert_eq!(source, DecisionSource::Forced);
+                assert!(candidates.is_empty());
+            }
+        }
+        assert!(sources.contains(&DecisionSource::Selected));
+        assert!(sources.contains(&DecisionSource::Forced));
+        assert!(events.windows(2).all(|pair| match pair {
+            [earlier, later] => earlier.iteration() <= later.iteration(),
+            _ => false,
+        }));
+    }
+
+    // Tests removals record their radius and cleared cells so that replaying
+    // the log reproduces the grid
+    // Verified by logging removed cells as grid indices
+    #[test]
+    fn test_removals_replay_to_grid() {
+        // Seed 26 resolves deadlocks at iterations 24, 34, 42 and 47
+        let mut executor = GreedyStochastic::new(26).unwrap();
+        executor.enable_event_log();
+        for _ in 0..50 {
+            executor.run_iteration().unwrap();
+        }
+        let events = executor.events.as_ref().unwrap().events();
+
+        let removals: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                GenerationEvent::Removal {
+                    radius, removed, ..
+                } => Some((*radius, removed.len())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(removals.len(), executor.deadlock_stats().deadlocks);
+        assert!(
+            removals
+                .iter()
+                .all(|&(radius, removed)| radius > 0 && removed > 0)
+        );
+        assert_eq!(replay(events), locked(&executor));
+    }
+
+    // Tests backtracks record the tiles they undo and the banned choice so
+    // that replaying the log reproduces the grid
+    // Verified by logging the undone positions that held no tile
+    #[test]
+    fn test_backtracks_replay_to_grid() {
+        let mut executor = backtracking_executor();
+        executor.enable_event_log();
+        while executor.run_iteration().unwrap() {}
+        let events = executor.events.as_ref().unwrap().events();
+
+        let banned: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                GenerationEvent::Backtrack { banned, .. } => Some(*banned),
+                _ => None,
+            })
+            .collect();
+        assert!(!banned.is_empty());
+        assert!(banned.iter().all(|&(_, tile)| tile > 0));
+        assert_eq!(replay(events), locked(&executor));
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("logs").join("a_events.jsonl");
+        executor.export_events(path.to_str().unwrap()).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written.lines().count(), events.len());
+        assert!(
+            written
+                .lines()
+                .all(|line| line.starts_with("{\"event\": \""))
+        );
+    }
+
+    // Tests the log writes one line per event in recording order
+    // Verified by joining events without a trailing newline
+    #[test]
+    fn test_to_jsonl() {
+        let mut log = EventLog::new();
+        assert_eq!(log.to_jsonl(), "");
+        for iteration in [1, 2] {
+            log.record(GenerationEvent::Removal {
+                iteration,
+                position: [0, 0],
+                radius: 1,
+                removed: Vec::new(),
+            });
+        }
+        let jsonl = log.to_jsonl();
+        assert!(jsonl.ends_with("}\n"));
+        assert_eq!(jsonl.lines().count(), 2);
+        assert!(jsonl.lines().last().unwrap().contains("\"iteration\": 2"));
+    }
+}
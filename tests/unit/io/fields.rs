@@ -0,0 +1,218 @@
+//! Tests for numeric export of grid state fields
+
+#[cfg(test)]
+mod tests {
+    use greedytile::algorithm::executor::GreedyStochastic;
+    use greedytile::io::fields::{
+        FIELDS_METADATA_FILE, FieldCapture, FieldSchedule, FieldSnapshot, encode_npy,
+    };
+    use greedytile::spatial::GridState;
+    use greedytile::spatial::grid::BoundingBox;
+
+    const COLORS: [[u8; 4]; 2] = [[255, 0, 0, 255], [0, 0, 255, 255]];
+
+    // Header dictionary and data bytes of an encoded NPY array
+    fn split_npy(bytes: &[u8]) -> (String, &[u8]) {
+        let (preamble, rest) = bytes.split_at(10);
+        let (magic, length) = preamble.split_at(8);
+        assert_eq!(magic, b"\x93NUMPY\x01\x00");
+        let header_len = u16::from_le_bytes(length.try_into().unwrap()) as usize;
+        let (header, data) = rest.split_at(header_len);
+        (String::from_utf8(header.to_vec()).unwrap(), data)
+    }
+
+    // Tests arrays encode with a padded header describing type and shape,
+    // followed by little endian data, and mismatched shapes are rejected
+    // Verified by leaving the header unpadded
+    #[test]
+    fn test_encode_npy() {
+        let bytes = encode_npy(&[2, 3], &[0.0, 1.0, 2.0, 3.0, 4.0, 5.5]).unwrap();
+        let (header, data) = split_npy(&bytes);
+        assert_eq!((10 + header.len()) % 64, 0);
+        assert!(header.ends_with('\n'));
+        assert!(header.starts_with("{'descr': '<f8', 'fortran_order': False, 'shape': (2, 3), }"));
+        assert_eq!(data.len(), 48);
+        assert_eq!(data.get(40..).unwrap(), 5.5f64.to_le_bytes());
+
+        let integers = encode_npy(&[2], &[7u32, 9]).unwrap();
+        let (integer_header, values) = split_npy(&integers);
+        assert!(integer_header.contains("'descr': '<u4'"));
+        assert!(integer_header.contains("'shape': (2,)"));
+        assert_eq!(values, [7, 0, 0, 0, 9, 0, 0, 0]);
+
+        assert!(encode_npy(&[2, 2], &[1u32, 2, 3]).is_err());
+    }
+
+    // Tests schedules parse from and display as the command line forms and
+    // select the right iterations
+    // Verified by capturing every interval from iteration zero
+    #[test]
+    fn test_schedule() {
+        for schedule in [
+            FieldSchedule::Final,
+            FieldSchedule::Every(25),
+            FieldSchedule::Iterations(vec![3, 40]),
+        ] {
+            assert_eq!(
+                schedule.to_string().parse::<FieldSchedule>().unwrap(),
+                schedule
+            );
+        }
+        assert_eq!(
+            "40, 3,40".parse::<FieldSchedule>().unwrap(),
+            FieldSchedule::Iterations(vec![3, 40])
+        );
+        for invalid in ["every:0", "0", "every", "1,x"] {
+            assert!(invalid.parse::<FieldSchedule>().is_err(), "{invalid}");
+        }
+
+        let every = FieldSchedule::Every(10);
+        assert!(every.includes(20));
+        assert!(!every.includes(25));
+        assert!(!FieldSchedule::Final.includes(10));
+        assert!(FieldSchedule::Iterations(vec![3, 40]).includes(3));
+    }
+
+    // Tests a bounded snapshot covers the bounds from their minimum, reading
+    // NaN and zero past the grid, and an unbounded one the whole grid
+    // Verified by reading cells past the grid as open
+    #[test]
+    fn test_capture_over_bounds() {
+        let mut grid_state = GridState::new(4, 4, 2);
+        *grid_state.locked_tiles.get_mut([0, 0]).unwrap() = 3;
+        *grid_state.entropy.get_mut([0, 0]).unwrap() = 0.25;
+        *grid_state
+            .tile_probabilities
+            .last_mut()
+            .and_then(|layer| layer.get_mut([3, 2]))
+            .unwrap() = 0.75;
+
+        let unbounded = FieldSnapshot::capture(&grid_state, [1, 1], 4);
+        assert_eq!(unbounded.offset, [-1, -1]);
+        assert_eq!(
+            (unbounded.rows, unbounded.cols, unbounded.tiles()),
+            (4, 4, 2)
+        );
+        assert_eq!(unbounded.locked_tiles.first().copied(), Some(3));
+
+        grid_state.set_generation_bounds(
+            Some(BoundingBox {
+                min: [-1, -1],
+                max: [3, 1],
+            }),
+            [1, 1],
+        );
+        let bounded = FieldSnapshot::capture(&grid_state, [1, 1], 7);
+        assert_eq!((bounded.iteration, bounded.offset), (7, [-1, -1]));
+        assert_eq!((bounded.rows, bounded.cols), (5, 3));
+        assert_eq!(bounded.tile_probabilities.len(), 30);
+        assert_eq!(bounded.entropy.first().copied(), Some(0.25));
+        // Second tile layer, row 3 and column 2 of the bounds
+        assert_eq!(
+            bounded.tile_probabilities.get(15 + 3 * 3 + 2).copied(),
+            Some(0.75)
+        );
+        assert_eq!(bounded.locked_tiles.first().copied(), Some(3));
+        assert_eq!(bounded.locked_tiles.get(4 * 3).copied(), Some(0));
+        assert!(
+            bounded
+                .feasibility
+                .get(4 * 3)
+                .is_some_and(|value| value.is_nan())
+        );
+    }
+
+    // Tests scheduled snapshots are written when taken, then moved beside
+    // the final state with metadata naming their files, offset and palette,
+    // and the staging directory goes with the capture
+    // Verified by writing the scheduled snapshots only on export
+    #[test]
+    fn test_export_fields() {
+        let grid_state = GridState::new(3, 2, 2);
+        let temp_dir = tempfile::tempdir().unwrap();
+        let staging = temp_dir.path().join("staging");
+        let mut capture = FieldCapture::new(FieldSchedule::Every(5), staging.clone());
+        for iteration in 1..=10 {
+            capture.record(&grid_state, [0, 0], iteration).unwrap();
+        }
+        assert_eq!(capture.snapshots().len(), 2);
+        assert!(staging.join("iteration_10_entropy.npy").exists());
+
+        let directory = temp_dir.path().join("fields");
+        let final_snapshot = FieldSnapshot::capture(&grid_state, [0, 0], 12);
+        capture
+            .export(&final_snapshot, &COLORS, &directory)
+            .unwrap();
+
+        for prefix in ["iteration_5", "iteration_10", "final"] {
+            for field in [
+                "tile_probabilities",
+                "entropy",
+                "feasibility",
+                "adjacency_weights",
+                "locked_tiles",
+            ] {
+                assert!(directory.join(format!("{prefix}_{field}.npy")).exists());
+            }
+        }
+        assert!(!staging.join("iteration_10_entropy.npy").exists());
+        let bytes = std::fs::read(directory.join("final_tile_probabilities.npy")).unwrap();
+        assert!(split_npy(&bytes).0.contains("'shape': (2, 3, 2)"));
+
+        let metadata: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(directory.join(FIELDS_METADATA_FILE)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(metadata.get("schedule").unwrap(), "every:5");
+        assert_eq!(
+            metadata.get("palette").unwrap(),
+            &serde_json::json!([[255, 0, 0, 255], [0, 0, 255, 255]])
+        );
+        let snapshots = metadata.get("snapshots").unwrap().as_array().unwrap();
+        assert_eq!(snapshots.len(), 3);
+        let last = snapshots.last().unwrap();
+        assert_eq!(last.get("iteration").unwrap(), 12);
+        assert_eq!(last.get("final").unwrap(), true);
+        assert_eq!(last.get("offset").unwrap(), &serde_json::json!([0, 0]));
+        assert_eq!(
+            snapshots.first().unwrap().pointer("/files/entropy").unwrap(),
+            "iteration_5_entropy.npy"
+        );
+
+        drop(capture);
+        assert!(!staging.exists());
+    }
+
+    // Tests the executor snapshots its fields on schedule and exports only
+    // once capture is enabled
+    // Verified by recording before the placement of each iteration
+    #[test]
+    fn test_executor_field_capture() {
+        let mut executor = GreedyStochastic::new(1).unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert!(executor.export_fields(temp_dir.path()).is_err());
+
+        let staging = temp_dir.path().join(".staging");
+        executor.enable_field_capture(FieldSchedule::Iterations(vec![2, 4]), staging.clone());
+        for _ in 0..5 {
+            executor.run_iteration().unwrap();
+        }
+        assert_eq!(executor.fields.as_ref().unwrap().snapshots().len(), 2);
+        let bytes = std::fs::read(staging.join("iteration_4_locked_tiles.npy")).unwrap();
+        let placed = split_npy(&bytes)
+            .1
+            .chunks_exact(4)
+            .filter(|value| u32::from_le_bytes((*value).try_into().unwrap()) > 1)
+            .count();
+        assert_eq!(placed, 4);
+
+        executor.export_fields(temp_dir.path()).unwrap();
+        assert!(temp_dir.path().join(FIELDS_METADATA_FILE).exists());
+        assert!(
+            temp_dir
+                .path()
+                .join("iteration_4_locked_tiles.npy")
+                .exists()
+        );
+    }
+}
@@ -3,8 +3,11 @@
 #[cfg(test)]
 mod tests {
 
-    use greedytile::io::image::export_grid_as_png;
+    use greedytile::io::image::{
+        UnfilledFill, export_bounds_as_png, export_grid_as_png, render_bounds,
+    };
     use greedytile::spatial::GridState;
+    use greedytile::spatial::grid::BoundingBox;
     use std::fs;
     use std::path::Path;
 
@@ -77,4 +80,128 @@ mod tests {
             "Should fail when tile index exceeds color mapping"
         );
     }
+
+    const COLORS: [[u8; 4]; 2] = [[255, 0, 0, 255], [0, 255, 0, 255]];
+
+    // Grid of 4x4 cells with world origin at index [1, 1] and two placed
+    // tiles, one at each corner of the bounds that fall inside the grid
+    fn sparse_grid() -> GridState {
+        let mut grid_state = GridState::new(4, 4, 2);
+        *grid_state.locked_tiles.get_mut([0, 0]).unwrap() = 2;
+        *grid_state.locked_tiles.get_mut([3, 2]).unwrap() = 3;
+        grid_state
+    }
+
+    // Tests fills parse from and display as the command line forms
+    // Verified by dropping the alpha channel from the display string
+    #[test]
+    fn test_fill_round_trip() {
+        for fill in [
+            UnfilledFill::Transparent,
+            UnfilledFill::MostProbable,
+            UnfilledFill::Color([1, 20, 200, 128]),
+        ] {
+            assert_eq!(fill.to_string().parse::<UnfilledFill>().unwrap(), fill);
+        }
+        assert_eq!(
+            "#FF8000".parse::<UnfilledFill>().unwrap(),
+            UnfilledFill::Color([255, 128, 0, 255])
+        );
+        for invalid in ["red", "#ff00", "#gg0000", "#+f0000", "ff0000"] {
+            assert!(invalid.parse::<UnfilledFill>().is_err(), "{invalid}");
+        }
+    }
+
+    // Tests bounds render at their exact size anchored at the bounds
+    // minimum, filling empty cells and cells past the grid alike
+    // Verified by skipping the fill for cells outside the grid
+    #[test]
+    fn test_render_bounds_exact_size() {
+        let grid_state = sparse_grid();
+        let bounds = BoundingBox {
+            min: [-1, -1],
+            max: [3, 1],
+        };
+        let gray = [9, 9, 9, 255];
+
+        let img = render_bounds(
+            &grid_state,
+            [1, 1],
+            &bounds,
+            &COLORS,
+            UnfilledFill::Color(gray),
+        )
+        .unwrap();
+
+        assert_eq!(img.dimensions(), (3, 5));
+        assert_eq!(img.get_pixel(0, 0).0, COLORS[0]);
+        assert_eq!(img.get_pixel(2, 3).0, COLORS[1]);
+        assert_eq!(img.get_pixel(1, 1).0, gray);
+        assert_eq!(img.get_pixel(2, 4).0, gray);
+
+        let transparent = render_bounds(
+            &grid_state,
+            [1, 1],
+            &bounds,
+            &COLORS,
+            UnfilledFill::default(),
+        )
+        .unwrap();
+        assert_eq!(transparent.get_pixel(1, 1).0, [0, 0, 0, 0]);
+    }
+
+    // Tests the most probable fill picks the likeliest tile at each cell
+    // Verified by picking the least likely tile
+    #[test]
+    fn test_most_probable_fill() {
+        let mut grid_state = sparse_grid();
+        for (index, probabilities) in [([1, 2], [0.2, 0.8]), ([2, 1], [0.7, 0.3])] {
+            for (layer, probability) in grid_state.tile_probabilities.iter_mut().zip(probabilities)
+            {
+                *layer.get_mut(index).unwrap() = probability;
+            }
+        }
+        let bounds = BoundingBox {
+            min: [0, 0],
+            max: [1, 1],
+        };
+
+        let img = render_bounds(
+            &grid_state,
+            [1, 1],
+            &bounds,
+            &COLORS,
+            UnfilledFill::MostProbable,
+        )
+        .unwrap();
+
+        assert_eq!(img.get_pixel(1, 0).0, COLORS[1]);
+        assert_eq!(img.get_pixel(0, 1).0, COLORS[0]);
+    }
+
+    // Tests bounded export writes the bounds size even with unfilled edges
+    // Verified by saving the image cropped to placed tiles
+    #[test]
+    fn test_export_bounds_as_png() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("nested").join("bounds.png");
+        let bounds = BoundingBox {
+            min: [-1, -1],
+            max: [3, 3],
+        };
+
+        export_bounds_as_png(
+            &sparse_grid(),
+            [1, 1],
+            &bounds,
+            &COLORS,
+            UnfilledFill::default(),
+            path.to_str().unwrap(),
+        )
+        .unwrap();
+
+        let img = image::open(&path).unwrap().to_rgba8();
+        assert_eq!(img.dimensions(), (5, 5));
+        assert_eq!(img.get_pixel(4, 4).0, [0, 0, 0, 0]);
+    }
 }
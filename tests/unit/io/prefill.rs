@@ -108,4 +108,30 @@ mod tests {
         assert_eq!(replacement_next.world_position, [7, 8]);
         assert_eq!(replacement_next.tile_reference, 9);
     }
+
+    // Tests the prefill anchor lands at the origin, centred by default
+    // Verified by centring the image whatever the anchor
+    #[test]
+    fn test_prefill_anchoring() {
+        use crate::algorithm::origin::BoundsAnchor;
+
+        let red = [255, 0, 0, 255];
+        let mut img = image::RgbaImage::new(4, 3);
+        img.put_pixel(3, 2, image::Rgba(red));
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("a_pre.png");
+        img.save(&path).unwrap();
+
+        let centred = PrefillData::from_png(&path, &[red]).unwrap();
+        assert_eq!(centred.bounds.min, [1, 1]);
+
+        let top_left =
+            PrefillData::from_png_anchored(&path, &[red], BoundsAnchor::TopLeft).unwrap();
+        assert_eq!(top_left.bounds.min, [2, 3]);
+        assert_eq!(top_left.is_protected([2, 3]), Some(1));
+
+        let bottom_right =
+            PrefillData::from_png_anchored(&path, &[red], BoundsAnchor::BottomRight).unwrap();
+        assert_eq!(bottom_right.bounds.max, [0, 0]);
+    }
 }
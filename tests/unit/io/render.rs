@@ -0,0 +1,51 @@
+//! Tests for frame render options
+
+#[cfg(test)]
+mod tests {
+    use greedytile::io::events::DecisionSource;
+    use greedytile::io::render::{CellOrigin, RenderOptions};
+
+    // Tests options are rejected when a zero scale or gridlines leave no
+    // pixels for the cells
+    // Verified by allowing gridlines at a scale of 1
+    #[test]
+    fn test_render_options_validate() {
+        let options = |scale, gridlines| RenderOptions {
+            scale,
+            gridlines,
+            ..RenderOptions::default()
+        };
+        assert!(RenderOptions::default().validate().is_ok());
+        assert!(options(0, false).validate().is_err());
+        assert!(options(1, true).validate().is_err());
+        assert!(options(2, true).validate().is_ok());
+    }
+
+    // Tests each decision source maps to the overlay of how its tile was placed
+    // Verified by counting seeds as forced
+    #[test]
+    fn test_cell_origin_of_source() {
+        let origins: Vec<_> = [
+            DecisionSource::Initial,
+            DecisionSource::Seed,
+            DecisionSource::Selected,
+            DecisionSource::Forced,
+            DecisionSource::Prefill,
+            DecisionSource::Fixed,
+        ]
+        .into_iter()
+        .map(CellOrigin::of)
+        .collect();
+        assert_eq!(
+            origins,
+            vec![
+                CellOrigin::Random,
+                CellOrigin::Random,
+                CellOrigin::Random,
+                CellOrigin::Forced,
+                CellOrigin::Prefill,
+                CellOrigin::Prefill,
+            ]
+        );
+    }
+}
@@ -1,8 +1,60 @@
-//! Tests for GIF visualization capture and frame generation
+//! Tests for visualization capture and animation export
 
 #[cfg(test)]
 mod tests {
-    use greedytile::io::visualization::VisualizationCapture;
+    use greedytile::io::events::DecisionSource;
+    use greedytile::io::render::RenderOptions;
+    use greedytile::io::visualization::{AnimationFormat, AnimationOptions, VisualizationCapture};
+    use image::{AnimationDecoder, RgbaImage};
+    use std::fs::File;
+    use std::io::BufReader;
+    use std::path::Path;
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+    // Two placed tiles on the diagonal of a 2x2 grid
+    fn diagonal() -> VisualizationCapture {
+        let mut viz = VisualizationCapture::new(2, 2, vec![RED, BLUE], 2);
+        viz.record_placement(0, 0, 2, 1, DecisionSource::Selected);
+        viz.record_placement(1, 1, 3, 2, DecisionSource::Selected);
+        viz
+    }
+
+    fn options(format: AnimationFormat) -> AnimationOptions {
+        AnimationOptions {
+            format,
+            render: RenderOptions {
+                scale: 3,
+                ..RenderOptions::default()
+            },
+            frame_delay_ms: 100,
+            duration_ms: None,
+        }
+    }
+
+    // Frame images and delays in milliseconds of an animation
+    fn decode(frames: image::Frames<'_>) -> Vec<(RgbaImage, u32)> {
+        frames
+            .map(|frame| {
+                let frame = frame.unwrap();
+                let (numerator, denominator) = frame.delay().numer_denom_ms();
+                (frame.into_buffer(), numerator / denominator)
+            })
+            .collect()
+    }
+
+    // Checks the final frame shows both tiles upscaled with the empty cells between
+    fn check_final_frame(frame: &RgbaImage) {
+        assert_eq!(frame.dimensions(), (6, 6));
+        assert_eq!(frame.get_pixel(2, 2).0, RED);
+        assert_eq!(frame.get_pixel(3, 3).0, BLUE);
+        assert_eq!(frame.get_pixel(5, 0).0, [127, 0, 127, 255]);
+    }
+
+    fn frame_files(directory: &Path) -> usize {
+        std::fs::read_dir(directory).unwrap().count()
+    }
 
     // Tests VisualizationCapture construction
     // Verified by initializing with non-empty placements
@@ -21,10 +73,10 @@ mod tests {
         let color_mapping = vec![[255, 0, 0, 255], [0, 255, 0, 255]];
         let mut viz = VisualizationCapture::new(10, 10, color_mapping, 100);
 
-        viz.record_placement(5, 5, 2, 1);
+        viz.record_placement(5, 5, 2, 1, DecisionSource::Selected);
         assert_eq!(viz.placement_count(), 1);
 
-        viz.record_placement(6, 6, 3, 2);
+        viz.record_placement(6, 6, 3, 2, DecisionSource::Selected);
         assert_eq!(viz.placement_count(), 2);
     }
 
@@ -46,10 +98,10 @@ mod tests {
         let color_mapping = vec![[255, 0, 0, 255], [0, 255, 0, 255]];
         let mut viz = VisualizationCapture::new(5, 5, color_mapping, 100);
 
-        viz.record_placement(2, 3, 1, 1);
-        viz.record_placement(7, 8, 2, 2);
-        viz.record_placement(-2, -3, 1, 3);
-        viz.record_placement(10, 10, 2, 4);
+        viz.record_placement(2, 3, 1, 1, DecisionSource::Selected);
+        viz.record_placement(7, 8, 2, 2, DecisionSource::Selected);
+        viz.record_placement(-2, -3, 1, 3, DecisionSource::Selected);
+        viz.record_placement(10, 10, 2, 4, DecisionSource::Selected);
 
         assert_eq!(viz.placement_count(), 4);
 
@@ -65,11 +117,11 @@ mod tests {
         let color_mapping = vec![[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
         let mut viz = VisualizationCapture::new(10, 10, color_mapping, 100);
 
-        viz.record_placement(-5, -3, 1, 1);
-        viz.record_placement(-10, 5, 2, 2);
-        viz.record_placement(3, -7, 3, 3);
-        viz.record_placement(0, 0, 1, 4);
-        viz.record_placement(5, 7, 2, 5);
+        viz.record_placement(-5, -3, 1, 1, DecisionSource::Selected);
+        viz.record_placement(-10, 5, 2, 2, DecisionSource::Selected);
+        viz.record_placement(3, -7, 3, 3, DecisionSource::Selected);
+        viz.record_placement(0, 0, 1, 4, DecisionSource::Selected);
+        viz.record_placement(5, 7, 2, 5, DecisionSource::Selected);
         viz.record_removal(-2, -2, 6);
 
         assert_eq!(viz.placement_count(), 6);
@@ -86,10 +138,10 @@ mod tests {
         let color_mapping = vec![[255, 0, 0, 255], [0, 255, 0, 255]];
         let mut viz = VisualizationCapture::new(10, 10, color_mapping, 100);
 
-        viz.record_placement(5, 5, 2, 1);
-        viz.record_placement(6, 6, 3, 2);
+        viz.record_placement(5, 5, 2, 1, DecisionSource::Selected);
+        viz.record_placement(6, 6, 3, 2, DecisionSource::Selected);
         viz.record_removal(5, 5, 3);
-        viz.record_placement(7, 7, 2, 4);
+        viz.record_placement(7, 7, 2, 4, DecisionSource::Selected);
         viz.record_removal(8, 8, 5);
 
         assert_eq!(viz.placement_count(), 5);
@@ -106,4 +158,257 @@ mod tests {
         assert_eq!(removal.col, 5);
         assert_eq!(removal.iteration, 3);
     }
+
+    // Tests formats parse from and display as their command line names
+    // Verified by giving frame sequences a file extension
+    #[test]
+    fn test_animation_format() {
+        for format in [
+            AnimationFormat::Gif,
+            AnimationFormat::Apng,
+            AnimationFormat::Webp,
+            AnimationFormat::Frames,
+        ] {
+            assert_eq!(
+                format.to_string().parse::<AnimationFormat>().unwrap(),
+                format
+            );
+        }
+        assert!("mp4".parse::<AnimationFormat>().is_err());
+        assert_eq!(AnimationFormat::Apng.extension(), Some("png"));
+        assert_eq!(AnimationFormat::Frames.extension(), None);
+    }
+
+    // Tests APNG output keeps every frame losslessly at the requested scale
+    // and delay, holding the last one
+    // Verified by writing the image without animation control chunks
+    #[test]
+    fn test_export_apng() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("viz.png");
+        diagonal()
+            .export_animation(path.to_str().unwrap(), &options(AnimationFormat::Apng))
+            .unwrap();
+
+        let decoder =
+            image::codecs::png::PngDecoder::new(BufReader::new(File::open(&path).unwrap()))
+                .unwrap();
+        assert!(decoder.is_apng().unwrap());
+        let frames = decode(decoder.apng().unwrap().into_frames());
+        let delays: Vec<u32> = frames.iter().map(|&(_, delay)| delay).collect();
+        assert_eq!(delays, vec![100, 100, 2500]);
+        check_final_frame(&frames.last().unwrap().0);
+    }
+
+    // Tests animated WebP output decodes to every frame at the requested
+    // scale and delay
+    // Verified by blending frames over the previous canvas
+    #[test]
+    fn test_export_webp() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("nested").join("viz.webp");
+        diagonal()
+            .export_animation(path.to_str().unwrap(), &options(AnimationFormat::Webp))
+            .unwrap();
+
+        let decoder =
+            image::codecs::webp::WebPDecoder::new(BufReader::new(File::open(&path).unwrap()))
+                .unwrap();
+        assert!(decoder.has_animation());
+        let frames = decode(decoder.into_frames());
+        let delays: Vec<u32> = frames.iter().map(|&(_, delay)| delay).collect();
+        assert_eq!(delays, vec![100, 100, 2500]);
+        check_final_frame(&frames.last().unwrap().0);
+        assert!(
+            frames
+                .first()
+                .unwrap()
+                .0
+                .pixels()
+                .all(|pixel| pixel.0 == [127, 0, 127, 255])
+        );
+    }
+
+    // Tests a target duration sets the frame delay, skipping frames shown for
+    // less than the sequence frame rate, and a zero scale is rejected
+    // Verified by ignoring the duration
+    #[test]
+    fn test_export_frames_over_duration() {
+        let mut viz = VisualizationCapture::new(1, 120, vec![RED], 120);
+        for col in 0..120 {
+            viz.record_placement(0, col, 2, col as usize + 1, DecisionSource::Selected);
+        }
+        let temp_dir = tempfile::tempdir().unwrap();
+        let directory = temp_dir.path().join("frames");
+        let one_second = AnimationOptions {
+            format: AnimationFormat::Frames,
+            render: RenderOptions {
+                scale: 1,
+                ..RenderOptions::default()
+            },
+            frame_delay_ms: 1000,
+            duration_ms: Some(1000),
+        };
+        viz.export_animation(directory.to_str().unwrap(), &one_second)
+            .unwrap();
+
+        // Two placements per frame at 60 frames a second, after the empty frame
+        assert_eq!(frame_files(&directory), 61);
+        let last = image::open(directory.join("frame_000060.png"))
+            .unwrap()
+            .to_rgba8();
+        assert!(last.pixels().all(|pixel| pixel.0 == RED));
+
+        let unscaled = AnimationOptions {
+            render: RenderOptions {
+                scale: 0,
+                ..RenderOptions::default()
+            },
+            ..one_second
+        };
+        assert!(
+            viz.export_animation(directory.to_str().unwrap(), &unscaled)
+                .is_err()
+        );
+    }
+
+    // Tests frame sequences repeat frames to play at the sequence frame rate
+    // for the requested duration or frame delay
+    // Verified by writing each rendered frame once
+    #[test]
+    fn test_frames_resampled_to_sequence_rate() {
+        let mut viz = VisualizationCapture::new(1, 100, vec![RED], 100);
+        for col in 0..100 {
+            viz.record_placement(0, col, 2, col as usize + 1, DecisionSource::Selected);
+        }
+        let temp_dir = tempfile::tempdir().unwrap();
+        let ten_seconds = AnimationOptions {
+            format: AnimationFormat::Frames,
+            render: RenderOptions::default(),
+            frame_delay_ms: 5,
+            duration_ms: Some(10_000),
+        };
+        let duration_dir = temp_dir.path().join("duration");
+        viz.export_animation(duration_dir.to_str().unwrap(), &ten_seconds)
+            .unwrap();
+
+        // Six sequence frames for each placement and the empty frame before them
+        assert_eq!(frame_files(&duration_dir), 606);
+        let last = image::open(duration_dir.join("frame_000605.png"))
+            .unwrap()
+            .to_rgba8();
+        assert!(last.pixels().all(|pixel| pixel.0 == RED));
+
+        let delayed = AnimationOptions {
+            frame_delay_ms: 50,
+            duration_ms: None,
+            ..ten_seconds
+        };
+        let delay_dir = temp_dir.path().join("delay");
+        viz.export_animation(delay_dir.to_str().unwrap(), &delayed)
+            .unwrap();
+        assert_eq!(frame_files(&delay_dir), 303);
+    }
+
+    // Tests streamed frames end with one for leftover skipped placements,
+    // holding it, and the WebP size is filled in once every frame is written
+    // Verified by leaving the RIFF size unwritten
+    #[test]
+    fn test_streamed_frame_count() {
+        let mut viz = VisualizationCapture::new(1, 5, vec![RED], 5);
+        for col in 0..5 {
+            viz.record_placement(0, col, 2, col as usize + 1, DecisionSource::Selected);
+        }
+        let temp_dir = tempfile::tempdir().unwrap();
+        let gif_path = temp_dir.path().join("viz.gif");
+        // Under half the viewer minimum, so every third placement is shown
+        let fast = AnimationOptions {
+            format: AnimationFormat::Gif,
+            render: RenderOptions {
+                scale: 1,
+                ..RenderOptions::default()
+            },
+            frame_delay_ms: 20,
+            duration_ms: None,
+        };
+        viz.export_animation(gif_path.to_str().unwrap(), &fast)
+            .unwrap();
+
+        let decoder =
+            image::codecs::gif::GifDecoder::new(BufReader::new(File::open(&gif_path).unwrap()))
+                .unwrap();
+        let frames = decode(decoder.into_frames());
+        let delays: Vec<u32> = frames.iter().map(|&(_, delay)| delay).collect();
+        assert_eq!(delays, vec![60, 60, 1500]);
+        assert!(
+            frames
+                .last()
+                .unwrap()
+                .0
+                .pixels()
+                .all(|pixel| pixel.0 == RED)
+        );
+
+        let webp_path = temp_dir.path().join("viz.webp");
+        viz.export_animation(
+            webp_path.to_str().unwrap(),
+            &AnimationOptions {
+                format: AnimationFormat::Webp,
+                ..fast
+            },
+        )
+        .unwrap();
+        let bytes = std::fs::read(&webp_path).unwrap();
+        let riff_size = u32::from_le_bytes(bytes.get(4..8).unwrap().try_into().unwrap());
+        assert_eq!(riff_size as usize, bytes.len() - 8);
+    }
+
+    // Tests annotated frames tint tiles by source and removed cells, outline
+    // the latest placement, draw gridlines and caption the iteration below,
+    // widening the frame to fit it
+    // Verified by tinting forced tiles with the random overlay
+    #[test]
+    fn test_annotated_frames() {
+        let mut viz = VisualizationCapture::new(2, 2, vec![RED, BLUE], 4);
+        viz.record_placement(0, 0, 2, 1, DecisionSource::Forced);
+        viz.record_placement(0, 1, 3, 2, DecisionSource::Prefill);
+        viz.record_removal(0, 1, 3);
+        viz.record_placement(1, 1, 3, 12, DecisionSource::Selected);
+        let temp_dir = tempfile::tempdir().unwrap();
+        let directory = temp_dir.path().join("frames");
+        let annotated = AnimationOptions {
+            format: AnimationFormat::Frames,
+            render: RenderOptions {
+                scale: 4,
+                gridlines: true,
+                highlight_latest: true,
+                overlays: true,
+                caption: true,
+            },
+            frame_delay_ms: 17,
+            duration_ms: None,
+        };
+        viz.export_animation(directory.to_str().unwrap(), &annotated)
+            .unwrap();
+
+        assert_eq!(frame_files(&directory), 5);
+        let last = image::open(directory.join("frame_000004.png"))
+            .unwrap()
+            .to_rgba8();
+        // Two cells of 4 pixels, widened for the two caption digits, above a
+        // caption strip of 7 pixels
+        assert_eq!(last.dimensions(), (9, 15));
+        let pixel = |x, y| last.get_pixel(x, y).0;
+        // Forced red, removed empty, and random blue, each half tinted
+        assert_eq!(pixel(1, 1), [128, 48, 128, 255]);
+        assert_eq!(pixel(5, 1), [191, 0, 64, 255]);
+        assert_eq!(pixel(5, 5), [128, 100, 128, 255]);
+        assert_eq!(pixel(3, 1), [96, 96, 96, 255]);
+        assert_eq!(pixel(4, 5), [255, 255, 255, 255]);
+        assert_eq!(pixel(8, 0), [0, 0, 0, 0]);
+        // Top row of the glyph for 1, then of the glyph for 2
+        assert_eq!(pixel(1, 9), [0, 0, 0, 255]);
+        assert_eq!(pixel(2, 9), [255, 255, 255, 255]);
+        assert_eq!(pixel(5, 9), [255, 255, 255, 255]);
+    }
 }
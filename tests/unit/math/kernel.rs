@@ -0,0 +1,59 @@
+//! Tests for smoothing kernel normalisation and support
+
+#[cfg(test)]
+mod tests {
+    use crate::math::kernel::{
+        DiscreteKernel, EpanechnikovKernel, GaussianKernel, Kernel, TriangularKernel,
+    };
+
+    fn integrate(kernel: &dyn Kernel, bandwidth: f64) -> f64 {
+        let step = 0.001;
+        (-10_000..=10_000)
+            .map(|i| kernel.density(f64::from(i) * step, bandwidth) * step)
+            .sum()
+    }
+
+    // Tests every continuous built-in kernel integrates to one for several bandwidths
+    // Verified by dropping the 1/bandwidth scaling from the Epanechnikov kernel
+    #[test]
+    fn test_kernels_integrate_to_one() {
+        let kernels: [&dyn Kernel; 3] = [&GaussianKernel, &EpanechnikovKernel, &TriangularKernel];
+
+        for kernel in kernels {
+            for bandwidth in [0.5, 1.0, 2.0] {
+                let area = integrate(kernel, bandwidth);
+                assert!(
+                    (area - 1.0).abs() < 1e-3,
+                    "{kernel:?} with bandwidth {bandwidth} integrates to {area}"
+                );
+            }
+        }
+    }
+
+    // Tests compact kernels vanish beyond one bandwidth and the discrete kernel ignores it
+    // Verified by widening the triangular support check to two bandwidths
+    #[test]
+    fn test_kernel_support() {
+        assert!(EpanechnikovKernel.density(1.5, 1.0).abs() < f64::EPSILON);
+        assert!(TriangularKernel.density(-1.5, 1.0).abs() < f64::EPSILON);
+        assert!((TriangularKernel.density(0.5, 1.0) - 0.5).abs() < f64::EPSILON);
+        assert!(GaussianKernel.density(1.5, 1.0) > 0.0);
+
+        assert!((DiscreteKernel.density(0.0, 3.0) - 1.0).abs() < f64::EPSILON);
+        assert!(DiscreteKernel.density(1.0, 3.0).abs() < f64::EPSILON);
+    }
+
+    // Tests the discrete kernel keeps neighbouring lattice distances apart
+    // Verified by restoring the unit-width box around zero
+    #[test]
+    fn test_discrete_kernel_separates_lattice_distances() {
+        assert!(DiscreteKernel.density(2.0_f64.sqrt() - 1.0, 1.0).abs() < f64::EPSILON);
+        assert!(DiscreteKernel.density(5.0_f64.sqrt() - 2.0, 1.0).abs() < f64::EPSILON);
+        assert!(DiscreteKernel.is_discrete());
+        assert!(!GaussianKernel.is_discrete());
+        assert!(
+            (DiscreteKernel.density_2d([0.0, 0.0], 1.0) - 1.0).abs() < f64::EPSILON,
+            "Lattice offsets carry unit mass in two dimensions"
+        );
+    }
+}
@@ -1,2 +1,3 @@
 pub mod interpolation;
+pub mod kernel;
 pub mod probability;
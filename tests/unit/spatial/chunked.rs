@@ -0,0 +1,97 @@
+//! Tests for sparse chunked grid storage
+
+#[cfg(test)]
+mod tests {
+    use greedytile::spatial::chunked::{CHUNK_SIZE, ChunkedGrid};
+    use greedytile::spatial::extension::calculate_extension;
+
+    // Tests initial cells, padding and out-of-range reads
+    // Verified by returning the initial value for unwritten extension cells
+    #[test]
+    fn test_initial_and_padding_values() {
+        let mut grid = ChunkedGrid::new(3, 4, 0u8, 1u8);
+        assert_eq!(grid.dim(), (3, 4));
+        assert_eq!(grid.get([2, 3]), Some(&0));
+        assert_eq!(grid.get([3, 0]), None);
+        assert_eq!(grid.get_mut([0, 4]), None);
+
+        let info = calculate_extension([3, 4], [0, 0], &[-2, 5], 0);
+        grid.extend(&info);
+        assert_eq!(grid.dim(), (5, 6));
+        assert_eq!(grid.get([0, 0]), Some(&1));
+        assert_eq!(grid.get([2, 0]), Some(&0));
+    }
+
+    // Tests extension shifts existing cells by the padding without allocating
+    // Verified by advancing the origin instead of moving it back on extension
+    #[test]
+    fn test_extension_preserves_cells() {
+        let mut grid = ChunkedGrid::new(4, 4, 1.0, 1.0);
+        *grid.get_mut([1, 2]).unwrap() = 7.0;
+        let chunks = grid.allocated_chunks();
+
+        let info = calculate_extension([4, 4], [0, 0], &[-100, -3], 2);
+        grid.extend(&info);
+
+        assert_eq!(grid.allocated_chunks(), chunks);
+        let shifted = [1 + info.pad_left, 2 + info.pad_top];
+        assert_eq!(grid.get(shifted), Some(&7.0));
+        assert_eq!(grid.iter().filter(|&&value| value > 1.0).count(), 1);
+        assert_eq!(grid.to_array().get(shifted), Some(&7.0));
+    }
+
+    // Tests memory is only spent on chunks that have been written
+    // Verified by allocating every chunk on extension
+    #[test]
+    fn test_allocates_only_touched_chunks() {
+        let mut grid = ChunkedGrid::new(1, 1, 1u32, 1u32);
+        let info = calculate_extension([1, 1], [0, 0], &[0, 0], 50_000);
+        grid.extend(&info);
+        assert_eq!(grid.allocated_chunks(), 0);
+
+        *grid.get_mut([0, 0]).unwrap() = 3;
+        *grid.get_mut([1, 1]).unwrap() = 3;
+        *grid.get_mut([CHUNK_SIZE * 500, 7]).unwrap() = 3;
+        assert_eq!(grid.allocated_chunks(), 2);
+        assert_eq!(grid.get([CHUNK_SIZE * 500, 7]), Some(&3));
+        assert_eq!(grid.get([CHUNK_SIZE * 500, 8]), Some(&1));
+    }
+
+    // Tests row-major scans and fills read and write every cell across chunk
+    // boundaries, with unwritten chunks read as padding
+    // Verified by running each row slice to the end of the row instead of the chunk
+    #[test]
+    fn test_scans_cross_chunk_boundaries() {
+        let mut grid = ChunkedGrid::new(3, 3, 0i32, -1i32).anchored([5, 3]);
+        let info = calculate_extension([3, 3], [5, 3], &[-40, 70], 0);
+        grid.extend(&info);
+        let (rows, cols) = grid.dim();
+        assert!(rows > CHUNK_SIZE && cols > 2 * CHUNK_SIZE);
+        for (row, col) in [(0, 0), (rows - 1, cols - 1), (7, CHUNK_SIZE + 3), (30, 2)] {
+            *grid.get_mut([row, col]).unwrap() = (row * cols + col) as i32;
+        }
+
+        let scanned: Vec<((usize, usize), i32)> = grid
+            .indexed_iter()
+            .map(|(index, &value)| (index, value))
+            .collect();
+        let expected: Vec<((usize, usize), i32)> = (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| (row, col)))
+            .map(|(row, col)| ((row, col), *grid.get([row, col]).unwrap()))
+            .collect();
+        assert_eq!(scanned, expected);
+        assert!(
+            grid.iter()
+                .copied()
+                .eq(expected.iter().map(|&(_, value)| value))
+        );
+        assert_eq!(
+            grid.to_array().get([7, CHUNK_SIZE + 3]),
+            Some(&((7 * cols + CHUNK_SIZE + 3) as i32))
+        );
+
+        grid.fill(4);
+        assert!(grid.iter().all(|&value| value == 4));
+        assert_eq!(grid.iter().count(), rows * cols);
+    }
+}
@@ -0,0 +1,69 @@
+//! Tests for the incremental frontier index
+
+#[cfg(test)]
+mod tests {
+    use greedytile::algorithm::executor::GreedyStochastic;
+    use greedytile::spatial::frontier::FrontierIndex;
+    use std::collections::BTreeMap;
+
+    // Tests positions are returned by descending adjacency, row-major within ties
+    // Verified by iterating buckets in ascending order
+    #[test]
+    fn test_iteration_order() {
+        let mut index = FrontierIndex::new();
+        index.update([1, 0], Some(3));
+        index.update([0, 5], Some(3));
+        index.update([-2, 2], Some(6));
+        index.update([0, 0], Some(2));
+
+        let order: Vec<([i32; 2], u32)> = index.iter_descending().collect();
+        assert_eq!(
+            order,
+            vec![([-2, 2], 6), ([0, 5], 3), ([1, 0], 3), ([0, 0], 2)]
+        );
+    }
+
+    // Tests updating a position moves it between buckets and None removes it
+    // Verified by skipping removal from the previous bucket
+    #[test]
+    fn test_update_and_remove() {
+        let mut index = FrontierIndex::new();
+        index.update([0, 0], Some(2));
+        index.update([0, 0], Some(5));
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.adjacency([0, 0]), Some(5));
+        assert_eq!(index.iter_descending().count(), 1);
+
+        index.update([0, 0], None);
+        assert!(index.is_empty());
+        assert_eq!(index.iter_descending().count(), 0);
+    }
+
+    // Tests the index matches a full grid scan through placements and deadlock removals
+    // Verified by not refreshing the frontier after deadlock removal
+    #[test]
+    fn test_index_matches_full_scan() {
+        // Seed 26 resolves deadlocks at iterations 24, 34, 42 and 47
+        let mut executor = GreedyStochastic::new(26).expect("Failed to create executor");
+
+        for _ in 0..50 {
+            executor.run_iteration().expect("Failed to run iteration");
+
+            let grid_state = &executor.grid_state;
+            let mut expected = BTreeMap::new();
+            for ((row, col), &adjacency) in grid_state.adjacency_weights.indexed_iter() {
+                let locked = grid_state.locked_tiles.get([row, col]).copied();
+                if adjacency > 1 && locked.is_some_and(|locked| locked <= 1) {
+                    let world = [
+                        row as i32 - executor.system_offset[0],
+                        col as i32 - executor.system_offset[1],
+                    ];
+                    expected.insert(world, adjacency);
+                }
+            }
+
+            let indexed: BTreeMap<[i32; 2], u32> = grid_state.frontier.iter_descending().collect();
+            assert_eq!(indexed, expected, "iteration {}", executor.iteration);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+pub mod chunked;
 pub mod extension;
+pub mod frontier;
 pub mod grid;
 pub mod tiles;